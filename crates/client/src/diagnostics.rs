@@ -1,11 +1,20 @@
-//! Client-specific tracy diagnostics (rollback metrics, input sync, chunk colliders).
+//! Client-specific tracy diagnostics (rollback metrics, input sync, chunk colliders),
+//! desync detection against the server's state checksums, and the F5 reconciliation
+//! panel.
 
-use avian3d::prelude::Collider;
+use avian3d::prelude::{Collider, Position};
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
-use lightyear::prelude::{InputTimeline, IsSynced, PredictionMetrics};
-use protocol::diagnostics::plot_action_state;
-use protocol::PlayerActions;
+use lightyear::prelude::{
+    Controlled, InputTimeline, IsSynced, LocalTimeline, MessageReceiver, Predicted,
+    PredictionMetrics, Tick,
+};
+use protocol::diagnostics::{
+    map_state_checksum, plot_action_state, StateChecksum, StateChecksumHistory,
+};
+use protocol::map::MapInstanceId;
+use protocol::{CharacterMarker, Health, PlayerActions};
+use std::collections::VecDeque;
 use tracy_client::plot;
 use voxel_map_engine::prelude::VoxelChunk;
 
@@ -15,8 +24,24 @@ pub struct ClientDiagnosticsPlugin;
 impl Plugin for ClientDiagnosticsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<PrevRollbackMetrics>()
+            .init_resource::<RollbackLog>()
+            .init_resource::<RollbackPanel>()
+            .add_systems(Startup, spawn_rollback_panel)
             .add_systems(FixedUpdate, plot_client_input_state)
-            .add_systems(Last, (plot_rollback_diagnostics, plot_input_sync_status));
+            .add_systems(FixedLast, record_state_checksum)
+            .add_systems(
+                Update,
+                (toggle_rollback_panel, update_rollback_panel).chain(),
+            )
+            .add_systems(
+                Last,
+                (
+                    plot_rollback_diagnostics,
+                    plot_input_sync_status,
+                    check_state_checksums,
+                    record_rollbacks,
+                ),
+            );
     }
 }
 
@@ -27,6 +52,63 @@ struct PrevRollbackMetrics {
     rollback_ticks: u32,
 }
 
+/// Rollbacks kept in [`RollbackLog`].
+pub const ROLLBACK_LOG_LEN: usize = 12;
+
+/// A frame in which lightyear rolled back.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RollbackLogEntry {
+    /// Predicted tick when the rollback was seen.
+    pub tick: Tick,
+    pub rollbacks: u32,
+    /// Ticks resimulated.
+    pub rollback_ticks: u32,
+}
+
+/// Backing data for the reconciliation panel: recent rollbacks, newest last,
+/// and the latest predicted and confirmed ticks.
+#[derive(Resource, Debug, Default)]
+pub struct RollbackLog {
+    pub entries: VecDeque<RollbackLogEntry>,
+    pub predicted_tick: Option<Tick>,
+    /// Tick of the latest server state checksum.
+    pub confirmed_tick: Option<Tick>,
+    /// Whether that checksum disagreed with the predicted state.
+    pub desynced: bool,
+    seen_rollbacks: u32,
+    seen_rollback_ticks: u32,
+}
+
+impl RollbackLog {
+    /// Panel text: ticks, sync status and recent rollbacks, newest first.
+    pub fn summary(&self) -> String {
+        let tick_text = |tick: Option<Tick>| tick.map_or("-".to_string(), |t| t.to_string());
+        let mut text = format!(
+            "Predicted tick: {}\nConfirmed tick: {} ({})\nRecent rollbacks:",
+            tick_text(self.predicted_tick),
+            tick_text(self.confirmed_tick),
+            if self.desynced { "DESYNC" } else { "in sync" },
+        );
+        for entry in self.entries.iter().rev() {
+            text.push_str(&format!(
+                "\n  tick {}: {} rollback(s), {} tick(s) resimulated",
+                entry.tick, entry.rollbacks, entry.rollback_ticks
+            ));
+        }
+        text
+    }
+}
+
+/// Whether the reconciliation panel is shown. Off by default; press F5 to toggle.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RollbackPanel {
+    pub visible: bool,
+}
+
+/// Text node showing [`RollbackLog::summary`].
+#[derive(Component)]
+struct RollbackPanelText;
+
 /// Plots per-tick input state for the client's character.
 fn plot_client_input_state(query: Query<&ActionState<PlayerActions>>) {
     for action_state in &query {
@@ -34,6 +116,26 @@ fn plot_client_input_state(query: Query<&ActionState<PlayerActions>>) {
     }
 }
 
+/// Records this tick's [`map_state_checksum`] of the predicted characters on
+/// the local player's map into [`StateChecksumHistory`]: the set the server
+/// checksums for this client. Runs in `FixedLast`, after physics has settled
+/// the tick.
+pub fn record_state_checksum(
+    timeline: Res<LocalTimeline>,
+    player: Query<&MapInstanceId, (With<Predicted>, With<Controlled>, With<CharacterMarker>)>,
+    characters: Query<
+        (&Position, &Health, &MapInstanceId),
+        (With<Predicted>, With<CharacterMarker>),
+    >,
+    mut history: ResMut<StateChecksumHistory>,
+) {
+    let Ok(map) = player.single() else {
+        trace!("record_state_checksum: no controlled character yet");
+        return;
+    };
+    history.record(timeline.tick().0, map_state_checksum(map, &characters));
+}
+
 /// Plots per-frame rollback deltas and chunk collider insertions.
 fn plot_rollback_diagnostics(
     metrics: Res<PredictionMetrics>,
@@ -53,8 +155,193 @@ fn plot_rollback_diagnostics(
     );
 }
 
+/// Appends a [`RollbackLog`] entry for each frame whose cumulative
+/// [`PredictionMetrics`] grew, and tracks the predicted tick.
+pub fn record_rollbacks(
+    metrics: Res<PredictionMetrics>,
+    timeline: Res<LocalTimeline>,
+    mut log: ResMut<RollbackLog>,
+) {
+    let tick = timeline.tick();
+    log.predicted_tick = Some(tick);
+    let rollbacks = metrics.rollbacks.saturating_sub(log.seen_rollbacks);
+    let rollback_ticks = metrics
+        .rollback_ticks
+        .saturating_sub(log.seen_rollback_ticks);
+    log.seen_rollbacks = metrics.rollbacks;
+    log.seen_rollback_ticks = metrics.rollback_ticks;
+    if rollbacks == 0 {
+        trace!("record_rollbacks: no new rollbacks at {tick:?}");
+        return;
+    }
+    if log.entries.len() == ROLLBACK_LOG_LEN {
+        log.entries.pop_front();
+    }
+    log.entries.push_back(RollbackLogEntry {
+        tick,
+        rollbacks,
+        rollback_ticks,
+    });
+}
+
+/// Spawns the hidden reconciliation panel in the bottom-left corner.
+fn spawn_rollback_panel(mut commands: Commands) {
+    commands.spawn((
+        Text::new(""),
+        TextFont {
+            font_size: 14.0,
+            ..default()
+        },
+        TextColor(Color::WHITE),
+        BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(8.0),
+            bottom: Val::Px(8.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        Visibility::Hidden,
+        RollbackPanelText,
+    ));
+}
+
+/// Toggles the [`RollbackPanel`] when F5 is pressed.
+pub fn toggle_rollback_panel(keys: Res<ButtonInput<KeyCode>>, mut panel: ResMut<RollbackPanel>) {
+    if keys.just_pressed(KeyCode::F5) {
+        panel.visible = !panel.visible;
+    }
+}
+
+/// Shows or hides the panel and refreshes its text while visible.
+fn update_rollback_panel(
+    panel: Res<RollbackPanel>,
+    log: Res<RollbackLog>,
+    mut query: Query<(&mut Text, &mut Visibility), With<RollbackPanelText>>,
+) {
+    for (mut text, mut visibility) in &mut query {
+        if !panel.visible {
+            *visibility = Visibility::Hidden;
+            continue;
+        }
+        *visibility = Visibility::Visible;
+        text.0 = log.summary();
+    }
+}
+
 /// Plots whether the input timeline is synced (required for input delivery).
 fn plot_input_sync_status(query: Query<Has<IsSynced<InputTimeline>>, With<InputTimeline>>) {
     let synced = query.iter().any(|has| has);
     plot!("cli_input_timeline_synced", if synced { 1.0 } else { 0.0 });
 }
+
+/// Compares server state checksums against the predicted state recorded for the
+/// same tick. Both cover the characters on this client's map.
+fn check_state_checksums(
+    history: Res<StateChecksumHistory>,
+    mut log: ResMut<RollbackLog>,
+    mut receivers: Query<&mut MessageReceiver<StateChecksum>>,
+) {
+    for mut receiver in &mut receivers {
+        for server in receiver.receive() {
+            let Some(local) = history.get(server.tick) else {
+                trace!(
+                    "check_state_checksums: tick {} not in local history",
+                    server.tick
+                );
+                continue;
+            };
+            let desynced = local != server.checksum;
+            log.confirmed_tick = Some(server.tick);
+            log.desynced = desynced;
+            if desynced {
+                warn!(
+                    "State desync at tick {}: server {:#018x}, client {:#018x}",
+                    server.tick, server.checksum, local
+                );
+            }
+            plot!("cli_state_desync", if desynced { 1.0 } else { 0.0 });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recorded_checksum_covers_predicted_characters_on_the_players_map() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(LocalTimeline::default());
+        app.init_resource::<StateChecksumHistory>();
+        app.add_systems(FixedLast, record_state_checksum);
+        let character = |position: Vec3, map: MapInstanceId| {
+            (CharacterMarker, Position(position), Health::new(100.0), map)
+        };
+        app.world_mut().spawn((
+            character(Vec3::ZERO, MapInstanceId::Overworld),
+            Predicted,
+            Controlled,
+        ));
+        app.world_mut()
+            .spawn((character(Vec3::X, MapInstanceId::Overworld), Predicted));
+
+        let mut server = World::new();
+        server.spawn(character(Vec3::ZERO, MapInstanceId::Overworld));
+        server.spawn(character(Vec3::X, MapInstanceId::Overworld));
+        let expected =
+            protocol::diagnostics::world_state_checksum(&mut server, &MapInstanceId::Overworld);
+        // Neither the unpredicted copy nor another map's character count.
+        app.world_mut()
+            .spawn(character(Vec3::Y * 9.0, MapInstanceId::Overworld));
+        app.world_mut().spawn((
+            character(Vec3::Y, MapInstanceId::Homebase { owner: 1 }),
+            Predicted,
+        ));
+
+        app.world_mut().run_schedule(FixedLast);
+
+        let tick = app.world().resource::<LocalTimeline>().tick().0;
+        assert_eq!(
+            app.world().resource::<StateChecksumHistory>().get(tick),
+            Some(expected)
+        );
+    }
+
+    #[test]
+    fn rollback_is_recorded_in_log() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<PredictionMetrics>();
+        app.insert_resource(LocalTimeline::default());
+        app.init_resource::<RollbackLog>();
+        app.add_systems(Last, record_rollbacks);
+
+        app.update();
+        assert!(app.world().resource::<RollbackLog>().entries.is_empty());
+
+        {
+            let mut metrics = app.world_mut().resource_mut::<PredictionMetrics>();
+            metrics.rollbacks += 1;
+            metrics.rollback_ticks += 4;
+        }
+        app.update();
+        app.update();
+
+        let log = app.world().resource::<RollbackLog>();
+        let tick = app.world().resource::<LocalTimeline>().tick();
+        assert_eq!(
+            log.entries,
+            [RollbackLogEntry {
+                tick,
+                rollbacks: 1,
+                rollback_ticks: 4,
+            }],
+            "one entry per rollback, not per frame"
+        );
+        assert_eq!(log.predicted_tick, Some(tick));
+        let summary = log.summary();
+        assert!(summary.contains("1 rollback(s), 4 tick(s) resimulated"));
+    }
+}
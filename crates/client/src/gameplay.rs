@@ -2,36 +2,70 @@ use avian3d::prelude::*;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 use lightyear::prelude::client::input::InputSystems;
-use lightyear::prelude::{Controlled, Interpolated, Predicted, Replicated};
+use lightyear::prelude::{Controlled, Interpolated, MessageSender, Predicted, Replicated};
+use protocol::ability::{InputDeviceChannel, InputDeviceReport};
 use protocol::*;
 use render::CameraOrbitState;
 
+use crate::keybinds::Keybinds;
+use crate::latency::{delay_local_inputs, restore_live_inputs, SimulatedLatency};
 use crate::world_object::{
-    init_default_vox_model_material, on_visual_kind_changed, on_world_object_replicated,
+    init_default_vox_model_material, on_map_prop_replicated, on_visual_kind_changed,
+    on_world_object_replicated,
 };
 
+/// Stick deflection that counts as the player using a gamepad.
+const GAMEPAD_STICK_ACTIVITY: f32 = 0.3;
+
+/// Display name to ask the server for once our character spawns, from `--name`.
+/// `None` keeps the server's default.
+#[derive(Resource, Debug, Clone, Default, PartialEq)]
+pub struct RequestedPlayerName(pub Option<String>);
+
 pub struct ClientGameplayPlugin;
 
 impl Plugin for ClientGameplayPlugin {
     fn build(&self, app: &mut App) {
         let ready = in_state(AppState::Ready);
+        app.init_resource::<Keybinds>();
+        app.init_resource::<RequestedPlayerName>();
         app.add_systems(Startup, init_default_vox_model_material);
         app.add_systems(Update, handle_new_character);
-        // detect_grounded must run before handle_character_movement and
-        // ability_activation so the IsGrounded gate sees fresh state.
+        app.add_systems(Update, protocol::world_time::sync_world_time);
+        // detect_grounded and detect_wall_contact must run before
+        // handle_character_movement and ability_activation so the IsGrounded
+        // gate, wall jumps and step-up see fresh state.
         app.add_systems(
             FixedUpdate,
-            (protocol::detect_grounded, handle_character_movement)
+            (
+                (protocol::detect_grounded, protocol::detect_wall_contact),
+                handle_character_movement,
+                protocol::apply_dodge,
+                protocol::step_up_obstacles,
+                protocol::apply_ground_drag,
+            )
                 .chain()
                 .before(protocol::ability::ability_activation),
         );
+        app.add_systems(FixedUpdate, protocol::expire_invulnerability);
+        app.init_resource::<SimulatedLatency>();
         app.add_systems(
             FixedPreUpdate,
-            sync_camera_yaw_to_input.before(InputSystems::BufferClientInputs),
+            (sync_camera_yaw_to_input, delay_local_inputs)
+                .chain()
+                .before(InputSystems::BufferClientInputs),
         );
+        app.add_systems(Last, restore_live_inputs);
         app.add_systems(
             Update,
-            (on_world_object_replicated, on_visual_kind_changed).run_if(ready),
+            (
+                on_world_object_replicated,
+                on_visual_kind_changed,
+                on_map_prop_replicated,
+                report_input_device,
+                request_player_name,
+            )
+                .run_if(ready),
         );
 
         app.add_observer(on_respawn_timer_added);
@@ -43,7 +77,7 @@ fn handle_new_character(
     mut commands: Commands,
     confirmed_query: Query<(Entity, Has<Controlled>), (Added<Replicated>, With<CharacterMarker>)>,
     character_query: Query<
-        Entity,
+        (Entity, &CharacterDimensions),
         (
             Or<(Added<Predicted>, Added<Interpolated>)>,
             With<CharacterMarker>,
@@ -51,28 +85,19 @@ fn handle_new_character(
     >,
     registry: Res<MapRegistry>,
     map_ids: Query<&MapInstanceId>,
+    keybinds: Res<Keybinds>,
+    movement_config: Res<MovementConfig>,
 ) {
     for (entity, is_controlled) in &confirmed_query {
         if is_controlled {
             trace!("Adding InputMap to controlled and predicted entity {entity:?}");
-            commands.entity(entity).insert(
-                InputMap::new([(PlayerActions::Jump, KeyCode::Space)])
-                    .with(PlayerActions::Jump, GamepadButton::South)
-                    .with_dual_axis(PlayerActions::Move, GamepadStick::LEFT)
-                    .with_dual_axis(PlayerActions::Move, VirtualDPad::wasd())
-                    .with(PlayerActions::PlaceVoxel, MouseButton::Left)
-                    .with(PlayerActions::RemoveVoxel, MouseButton::Right)
-                    .with(PlayerActions::Ability1, KeyCode::Digit1)
-                    .with(PlayerActions::Ability2, KeyCode::Digit2)
-                    .with(PlayerActions::Ability3, KeyCode::Digit3)
-                    .with(PlayerActions::Ability4, KeyCode::Digit4),
-            );
+            commands.entity(entity).insert(keybinds.input_map());
         } else {
             trace!("Remote character predicted for us: {entity:?}");
         }
     }
 
-    for entity in &character_query {
+    for (entity, dimensions) in &character_query {
         if let Ok(mid) = map_ids.get(entity) {
             if !registry.0.contains_key(mid) {
                 trace!("Despawning stale character {entity:?} from map {mid:?}");
@@ -83,14 +108,25 @@ fn handle_new_character(
         trace!(?entity, "Adding physics to predicted character");
         commands
             .entity(entity)
-            .insert(CharacterPhysicsBundle::default());
+            .insert(CharacterPhysicsBundle::new(&movement_config, dimensions));
     }
 }
 
 fn handle_character_movement(
     time: Res<Time>,
+    config: Res<MovementConfig>,
+    locks: MovementLocks,
+    contacts: CharacterContacts,
     mut query: Query<
-        (&ActionState<PlayerActions>, &ComputedMass, Forces),
+        (
+            Entity,
+            &ActionState<PlayerActions>,
+            &ComputedMass,
+            Has<IsGrounded>,
+            Option<&TouchingWall>,
+            Option<&ActiveBuffs>,
+            Forces,
+        ),
         (
             With<Predicted>,
             With<CharacterMarker>,
@@ -98,8 +134,22 @@ fn handle_character_movement(
         ),
     >,
 ) {
-    for (action_state, mass, mut forces) in &mut query {
-        apply_movement(mass, time.delta_secs(), action_state, &mut forces);
+    for (entity, action_state, mass, grounded, wall, buffs, mut forces) in &mut query {
+        if locks.is_locked(entity) {
+            trace!("Movement input ignored for {entity:?}: locked by an ability or stun");
+            continue;
+        }
+        apply_movement(
+            mass,
+            time.delta_secs(),
+            action_state,
+            config.move_deadzone,
+            buffs,
+            grounded,
+            wall,
+            &contacts.blocked_directions(entity),
+            &mut forces,
+        );
     }
 }
 
@@ -151,6 +201,78 @@ fn set_descendants_visibility(
     }
 }
 
+/// Sends the `RequestedPlayerName` whenever our controlled character replicates,
+/// so the name is reapplied after reconnects and map transitions.
+fn request_player_name(
+    requested: Res<RequestedPlayerName>,
+    characters: Query<
+        (),
+        (
+            Added<Replicated>,
+            With<Controlled>,
+            With<CharacterMarker>,
+            Without<Minion>,
+        ),
+    >,
+    mut senders: Query<&mut MessageSender<PlayerNameRequest>>,
+) {
+    let Some(name) = &requested.0 else {
+        trace!("request_player_name: no name requested");
+        return;
+    };
+    if characters.is_empty() {
+        trace!("request_player_name: no newly replicated controlled character");
+        return;
+    }
+    let Ok(mut sender) = senders.single_mut() else {
+        trace!("request_player_name: no PlayerNameRequest sender");
+        return;
+    };
+    sender.send::<PlayerNameChannel>(PlayerNameRequest { name: name.clone() });
+}
+
+/// Tells the server when the player switches between keyboard/mouse and a
+/// gamepad, so projectile aim assist only applies to gamepad play. The last
+/// device is sent again whenever our character replicates, since a respawned or
+/// reconnected character starts without the server-side tag.
+fn report_input_device(
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    gamepads: Query<&Gamepad>,
+    new_characters: Query<(), (Added<Controlled>, With<CharacterMarker>, Without<Minion>)>,
+    mut senders: Query<&mut MessageSender<InputDeviceReport>>,
+    mut current: Local<Option<bool>>,
+) {
+    let gamepad_used = gamepads.iter().any(|gamepad| {
+        gamepad.get_just_pressed().next().is_some()
+            || gamepad.left_stick().length() > GAMEPAD_STICK_ACTIVITY
+            || gamepad.right_stick().length() > GAMEPAD_STICK_ACTIVITY
+    });
+    let keyboard_used =
+        keys.get_just_pressed().next().is_some() || mouse.get_just_pressed().next().is_some();
+    let switched = match (gamepad_used, keyboard_used) {
+        (true, false) => Some(true),
+        (false, true) => Some(false),
+        _ => None,
+    };
+    let changed = switched.is_some_and(|gamepad| *current != Some(gamepad));
+    if let Some(gamepad) = switched {
+        *current = Some(gamepad);
+    }
+    if !changed && new_characters.is_empty() {
+        return;
+    }
+    let Some(gamepad) = *current else {
+        trace!("report_input_device: no input yet, nothing to report");
+        return;
+    };
+    let Ok(mut sender) = senders.single_mut() else {
+        trace!("report_input_device: no InputDeviceReport sender");
+        return;
+    };
+    sender.send::<InputDeviceChannel>(InputDeviceReport { gamepad });
+}
+
 /// Writes the camera's target yaw angle into the player's ActionState for replication.
 fn sync_camera_yaw_to_input(
     camera_query: Query<&CameraOrbitState>,
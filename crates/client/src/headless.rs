@@ -0,0 +1,72 @@
+use bevy::ecs::message::MessageWriter;
+use bevy::ecs::query::QuerySingleError;
+use bevy::prelude::*;
+use lightyear::prelude::client::*;
+use ui::{ClientState, MapTransitionState};
+
+/// How long a headless client waits for its `Client` entity before exiting.
+const CLIENT_ENTITY_TIMEOUT_SECS: f32 = 5.0;
+
+/// Stands in for `UiPlugin` when the client runs without a window, for bots
+/// and load testing: owns the [`ClientState`] machine, connects as soon as the
+/// client entity exists and exits the app once the connection is lost.
+pub struct HeadlessClientPlugin;
+
+impl Plugin for HeadlessClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<ClientState>();
+        app.add_sub_state::<MapTransitionState>();
+        app.add_systems(
+            Update,
+            connect_once_client_spawned.run_if(in_state(ClientState::MainMenu)),
+        );
+        app.add_observer(enter_game_on_connected);
+        app.add_observer(exit_on_disconnected);
+    }
+}
+
+/// Connects with the netcode client `ClientNetworkPlugin` built from the
+/// configured address and id; there is no menu to pick another server. Retries
+/// each frame until the client entity is spawned, and exits with an error if it
+/// isn't within [`CLIENT_ENTITY_TIMEOUT_SECS`] or there is more than one.
+fn connect_once_client_spawned(
+    mut commands: Commands,
+    client_query: Query<Entity, With<Client>>,
+    time: Res<Time>,
+    mut next_state: ResMut<NextState<ClientState>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    let client_entity = match client_query.single() {
+        Ok(entity) => entity,
+        Err(QuerySingleError::NoEntities(_))
+            if time.elapsed_secs() < CLIENT_ENTITY_TIMEOUT_SECS =>
+        {
+            trace!("Headless client waiting for its Client entity");
+            return;
+        }
+        Err(err) => {
+            error!("Headless client cannot connect, exiting: {err}");
+            exit.write(AppExit::error());
+            return;
+        }
+    };
+    info!("Headless client connecting");
+    next_state.set(ClientState::Connecting);
+    commands.trigger(Connect {
+        entity: client_entity,
+    });
+}
+
+fn enter_game_on_connected(
+    _trigger: On<Add, Connected>,
+    mut next_state: ResMut<NextState<ClientState>>,
+) {
+    info!("Headless client connected, transitioning to InGame state");
+    next_state.set(ClientState::InGame);
+}
+
+/// A headless client has nothing to fall back to once disconnected, so it quits.
+fn exit_on_disconnected(_trigger: On<Add, Disconnected>, mut exit: MessageWriter<AppExit>) {
+    warn!("Headless client disconnected, exiting");
+    exit.write(AppExit::error());
+}
@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use protocol::PlayerActions;
+use std::collections::HashMap;
+
+/// Keyboard bindings for the remappable button actions. Movement, mouse and
+/// gamepad bindings are fixed and added on top in [`Keybinds::input_map`].
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Keybinds {
+    pub keys: HashMap<PlayerActions, KeyCode>,
+}
+
+impl Default for Keybinds {
+    fn default() -> Self {
+        Self {
+            keys: HashMap::from([
+                (PlayerActions::Jump, KeyCode::Space),
+                (PlayerActions::Dodge, KeyCode::ShiftLeft),
+                (PlayerActions::Ability1, KeyCode::Digit1),
+                (PlayerActions::Ability2, KeyCode::Digit2),
+                (PlayerActions::Ability3, KeyCode::Digit3),
+                (PlayerActions::Ability4, KeyCode::Digit4),
+                (PlayerActions::SwapLoadout, KeyCode::KeyR),
+            ]),
+        }
+    }
+}
+
+/// How [`Keybinds::rebind`] handles a key that is already bound to another action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RebindPolicy {
+    /// Leave the bindings untouched and report the conflict.
+    Reject,
+    /// Give the other action the rebound action's previous key.
+    Swap,
+}
+
+/// A key bound to more than one action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeybindConflict {
+    pub key: KeyCode,
+    pub actions: Vec<PlayerActions>,
+}
+
+impl Keybinds {
+    /// Every key bound to more than one action, with actions in a stable order.
+    pub fn conflicts(&self) -> Vec<KeybindConflict> {
+        let mut by_key: HashMap<KeyCode, Vec<PlayerActions>> = HashMap::new();
+        for (action, key) in &self.keys {
+            by_key.entry(*key).or_default().push(*action);
+        }
+        let mut conflicts: Vec<_> = by_key
+            .into_iter()
+            .filter(|(_, actions)| actions.len() > 1)
+            .map(|(key, mut actions)| {
+                actions.sort_by_key(|a| format!("{a:?}"));
+                KeybindConflict { key, actions }
+            })
+            .collect();
+        conflicts.sort_by_key(|c| format!("{:?}", c.key));
+        conflicts
+    }
+
+    /// Binds `action` to `key`. If another action already uses `key`, resolves
+    /// it per `policy`: `Reject` returns the conflict unchanged, `Swap` moves
+    /// `action`'s old key to the other action (or unbinds it if `action` had none).
+    pub fn rebind(
+        &mut self,
+        action: PlayerActions,
+        key: KeyCode,
+        policy: RebindPolicy,
+    ) -> Result<(), KeybindConflict> {
+        let holder = self
+            .keys
+            .iter()
+            .find(|(other, bound)| **other != action && **bound == key)
+            .map(|(other, _)| *other);
+
+        if let Some(other) = holder {
+            warn!("{key:?} is already bound to {other:?} (rebinding {action:?}, {policy:?})");
+            match policy {
+                RebindPolicy::Reject => {
+                    return Err(KeybindConflict {
+                        key,
+                        actions: vec![other, action],
+                    });
+                }
+                RebindPolicy::Swap => match self.keys.get(&action).copied() {
+                    Some(old_key) => {
+                        self.keys.insert(other, old_key);
+                    }
+                    None => {
+                        self.keys.remove(&other);
+                    }
+                },
+            }
+        }
+
+        self.keys.insert(action, key);
+        debug_assert!(self.conflicts().is_empty(), "rebind left a conflict");
+        Ok(())
+    }
+
+    /// Full input map for the local character: remappable keys plus fixed bindings.
+    pub fn input_map(&self) -> InputMap<PlayerActions> {
+        let mut map = InputMap::default()
+            .with(PlayerActions::Jump, GamepadButton::South)
+            .with(PlayerActions::Dodge, GamepadButton::East)
+            .with(PlayerActions::SwapLoadout, GamepadButton::North)
+            .with_dual_axis(PlayerActions::Move, GamepadStick::LEFT)
+            .with_dual_axis(PlayerActions::Move, VirtualDPad::wasd())
+            .with(PlayerActions::PlaceVoxel, MouseButton::Left)
+            .with(PlayerActions::RemoveVoxel, MouseButton::Right);
+        for (action, key) in &self.keys {
+            map.insert(*action, *key);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_bindings_have_no_conflicts() {
+        assert!(Keybinds::default().conflicts().is_empty());
+    }
+
+    #[test]
+    fn default_bindings_avoid_fixed_movement_and_camera_keys() {
+        // WASD moves; Q/E rotate the camera in `render::camera`.
+        let fixed = [
+            KeyCode::KeyW,
+            KeyCode::KeyA,
+            KeyCode::KeyS,
+            KeyCode::KeyD,
+            KeyCode::KeyQ,
+            KeyCode::KeyE,
+        ];
+        for (action, key) in &Keybinds::default().keys {
+            assert!(
+                !fixed.contains(key),
+                "{action:?} is bound to fixed key {key:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn conflicting_bindings_are_flagged() {
+        let mut keybinds = Keybinds::default();
+        keybinds
+            .keys
+            .insert(PlayerActions::Ability2, KeyCode::Digit1);
+
+        assert_eq!(
+            keybinds.conflicts(),
+            vec![KeybindConflict {
+                key: KeyCode::Digit1,
+                actions: vec![PlayerActions::Ability1, PlayerActions::Ability2],
+            }]
+        );
+    }
+
+    #[test]
+    fn reject_policy_keeps_existing_binding() {
+        let mut keybinds = Keybinds::default();
+        let result = keybinds.rebind(PlayerActions::Jump, KeyCode::Digit1, RebindPolicy::Reject);
+
+        assert_eq!(
+            result,
+            Err(KeybindConflict {
+                key: KeyCode::Digit1,
+                actions: vec![PlayerActions::Ability1, PlayerActions::Jump],
+            })
+        );
+        assert_eq!(keybinds, Keybinds::default());
+    }
+
+    #[test]
+    fn swap_policy_exchanges_keys() {
+        let mut keybinds = Keybinds::default();
+        keybinds
+            .rebind(PlayerActions::Jump, KeyCode::Digit1, RebindPolicy::Swap)
+            .expect("swap always resolves the conflict");
+
+        assert_eq!(keybinds.keys[&PlayerActions::Jump], KeyCode::Digit1);
+        assert_eq!(keybinds.keys[&PlayerActions::Ability1], KeyCode::Space);
+        assert!(keybinds.conflicts().is_empty());
+    }
+}
@@ -0,0 +1,167 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use protocol::{PlayerActions, FIXED_TIMESTEP_HZ};
+use std::collections::VecDeque;
+
+/// Debug setting that holds the local player's inputs back by `extra_ms` before
+/// lightyear buffers and sends them, to test prediction and rollback feel without
+/// a bad network. Zero (the default) disables it.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SimulatedLatency {
+    pub extra_ms: u32,
+}
+
+impl SimulatedLatency {
+    /// `extra_ms` rounded to whole fixed ticks.
+    pub fn delay_ticks(&self) -> usize {
+        (self.extra_ms as f64 * FIXED_TIMESTEP_HZ / 1000.0).round() as usize
+    }
+}
+
+/// Per-character input buffer for [`SimulatedLatency`].
+#[derive(Component, Default)]
+pub struct DelayedInputs {
+    /// Live states captured each tick, oldest first.
+    queue: VecDeque<ActionState<PlayerActions>>,
+    /// The live state leafwing produced this frame, restored by
+    /// [`restore_live_inputs`] so leafwing never diffs against a delayed state.
+    live: Option<ActionState<PlayerActions>>,
+}
+
+/// Swaps each locally controlled character's `ActionState` for the one captured
+/// `delay_ticks` ago, or a neutral state while the buffer fills. Runs before
+/// lightyear buffers inputs, so local prediction and the server both see the
+/// delayed input.
+pub fn delay_local_inputs(
+    latency: Res<SimulatedLatency>,
+    mut commands: Commands,
+    mut query: Query<
+        (
+            Entity,
+            &mut ActionState<PlayerActions>,
+            Option<&mut DelayedInputs>,
+        ),
+        With<InputMap<PlayerActions>>,
+    >,
+) {
+    let delay = latency.delay_ticks();
+    for (entity, mut action_state, delayed) in &mut query {
+        let Some(mut delayed) = delayed else {
+            if delay > 0 {
+                commands.entity(entity).insert(DelayedInputs::default());
+            }
+            continue;
+        };
+        let live = delayed
+            .live
+            .get_or_insert_with(|| action_state.clone())
+            .clone();
+        delayed.queue.push_back(live);
+        while delayed.queue.len() > delay + 1 {
+            delayed.queue.pop_front();
+        }
+        *action_state = if delayed.queue.len() == delay + 1 {
+            delayed
+                .queue
+                .pop_front()
+                .expect("queue holds delay + 1 states")
+        } else {
+            ActionState::default()
+        };
+    }
+}
+
+/// Puts back the live `ActionState` stashed by [`delay_local_inputs`] at the end
+/// of the frame.
+pub fn restore_live_inputs(
+    mut query: Query<(&mut ActionState<PlayerActions>, &mut DelayedInputs)>,
+) {
+    for (mut action_state, mut delayed) in &mut query {
+        if let Some(live) = delayed.live.take() {
+            *action_state = live;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Whether the last fixed tick's (possibly delayed) input had Jump held.
+    #[derive(Resource, Default)]
+    struct JumpSeen(bool);
+
+    fn record_jump(query: Query<&ActionState<PlayerActions>>, mut seen: ResMut<JumpSeen>) {
+        seen.0 = query
+            .single()
+            .expect("one character")
+            .pressed(&PlayerActions::Jump);
+    }
+
+    fn latency_app(extra_ms: u32) -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(bevy::time::TimeUpdateStrategy::FixedTimesteps(1));
+        app.insert_resource(SimulatedLatency { extra_ms });
+        app.init_resource::<JumpSeen>();
+        app.add_systems(FixedPreUpdate, delay_local_inputs);
+        app.add_systems(FixedUpdate, record_jump);
+        app.add_systems(Last, restore_live_inputs);
+        let entity = app
+            .world_mut()
+            .spawn((
+                ActionState::<PlayerActions>::default(),
+                InputMap::<PlayerActions>::default(),
+            ))
+            .id();
+        // Lets the buffer component be inserted.
+        app.update();
+        (app, entity)
+    }
+
+    /// Runs one tick with Jump live-held or released, returning whether the
+    /// tick's gameplay saw Jump held.
+    fn tick_with_jump(app: &mut App, entity: Entity, held: bool) -> bool {
+        let mut action_state = app
+            .world_mut()
+            .get_mut::<ActionState<PlayerActions>>(entity)
+            .unwrap();
+        if held {
+            action_state.press(&PlayerActions::Jump);
+        } else {
+            action_state.release(&PlayerActions::Jump);
+        }
+        app.update();
+        app.world().resource::<JumpSeen>().0
+    }
+
+    #[test]
+    fn delay_rounds_milliseconds_to_ticks() {
+        assert_eq!(SimulatedLatency { extra_ms: 0 }.delay_ticks(), 0);
+        assert_eq!(SimulatedLatency { extra_ms: 50 }.delay_ticks(), 3);
+        assert_eq!(SimulatedLatency { extra_ms: 1000 }.delay_ticks(), 64);
+    }
+
+    #[test]
+    fn input_is_applied_after_configured_ticks() {
+        let (mut app, entity) = latency_app(50);
+        let seen: Vec<bool> = (0..6)
+            .map(|tick| tick_with_jump(&mut app, entity, tick == 0))
+            .collect();
+
+        assert_eq!(seen, vec![false, false, false, true, false, false]);
+        assert!(
+            app.world()
+                .get::<ActionState<PlayerActions>>(entity)
+                .unwrap()
+                .released(&PlayerActions::Jump),
+            "live state is restored at the end of each frame"
+        );
+    }
+
+    #[test]
+    fn zero_latency_passes_input_through() {
+        let (mut app, entity) = latency_app(0);
+        assert!(tick_with_jump(&mut app, entity, true));
+    }
+}
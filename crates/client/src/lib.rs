@@ -1,5 +1,10 @@
+pub mod diagnostics;
 pub mod gameplay;
+pub mod headless;
+pub mod keybinds;
+pub mod latency;
 pub mod map;
+pub mod plugins;
 pub mod transition;
 pub mod world_object;
 
@@ -1,63 +1,81 @@
-pub mod diagnostics;
-pub mod gameplay;
-pub mod map;
-pub mod transition;
-pub mod world_object;
-
 use bevy::prelude::*;
-use client_lightyear::{ClientNetworkConfig, ClientNetworkPlugin};
-use dev::DevPlugin;
-use diagnostics::ClientDiagnosticsPlugin;
-use gameplay::ClientGameplayPlugin;
-use lightyear::prelude::client::*;
-use map::ClientMapPlugin;
-use protocol::diagnostics::SharedDiagnosticsPlugin;
-use protocol::*;
-use render::RenderPlugin;
-use std::time::Duration;
-use ui::{UiClientConfig, UiPlugin};
+use client::latency::SimulatedLatency;
+use client::plugins::{GameClientConfig, GameClientPlugins};
+use client::ClientNetworkConfig;
+use protocol::settings::Settings;
 
 fn main() {
-    let client_id = parse_client_id();
-
-    let network_config = ClientNetworkConfig {
-        client_id,
+    let network = ClientNetworkConfig {
+        client_id: parse_client_id(),
         certificate_digest: include_str!("../../../certificates/digest.txt")
             .trim()
             .to_string(),
         ..Default::default()
     };
 
-    // Create UI config from network config to keep them in sync
-    let ui_config = UiClientConfig {
-        server_addr: network_config.server_addr,
-        client_id: network_config.client_id,
-        protocol_id: network_config.protocol_id,
-        private_key: network_config.private_key,
+    let headless = parse_headless();
+    // Headless bots and load-test clients don't share the player's settings file.
+    let settings_path = if headless {
+        None
+    } else {
+        Settings::default_path()
+    };
+
+    let config = GameClientConfig {
+        network,
+        settings: settings_path
+            .as_deref()
+            .map(Settings::load)
+            .unwrap_or_default(),
+        settings_path,
+        latency: SimulatedLatency {
+            extra_ms: parse_latency_ms(),
+        },
+        practice_mode: parse_practice_mode(),
+        player_name: parse_player_name(),
+        headless,
     };
 
-    App::new()
-        .add_plugins(DefaultPlugins.set(AssetPlugin {
-            file_path: concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets").to_string(),
-            ..default()
-        }))
-        .add_plugins(ClientPlugins {
-            tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
-        })
-        .add_plugins(SharedGameplayPlugin)
-        .add_plugins(ClientNetworkPlugin {
-            config: network_config,
-        })
-        .insert_resource(ui_config) // Override default UiClientConfig
-        .add_plugins(ClientGameplayPlugin)
-        .add_plugins(ClientMapPlugin)
-        .add_plugins(transition::ClientTransitionPlugin)
-        .add_plugins(RenderPlugin)
-        .add_plugins(UiPlugin)
-        .add_plugins(DevPlugin)
-        .add_plugins(SharedDiagnosticsPlugin)
-        .add_plugins(ClientDiagnosticsPlugin)
-        .run();
+    let mut app = App::new();
+    if config.headless {
+        add_headless_base_plugins(&mut app);
+    } else {
+        add_windowed_base_plugins(&mut app);
+    }
+    app.add_plugins(GameClientPlugins { config }).run();
+}
+
+fn asset_plugin() -> AssetPlugin {
+    AssetPlugin {
+        file_path: concat!(env!("CARGO_MANIFEST_DIR"), "/../../assets").to_string(),
+        ..default()
+    }
+}
+
+/// Window, rendering, audio and input: everything the rendered client runs on.
+fn add_windowed_base_plugins(app: &mut App) {
+    app.add_plugins(DefaultPlugins.set(asset_plugin()));
+}
+
+/// The windowless subset the gameplay and network plugins still need, mirroring
+/// the server's: assets, transforms, states, input and the mesh and material
+/// asset types voxel maps and world objects create.
+fn add_headless_base_plugins(app: &mut App) {
+    app.add_plugins(MinimalPlugins)
+        .add_plugins(bevy::app::TerminalCtrlCHandlerPlugin)
+        .add_plugins(bevy::state::app::StatesPlugin)
+        .add_plugins(bevy::log::LogPlugin::default())
+        .add_plugins(bevy::input::InputPlugin)
+        .add_plugins(asset_plugin())
+        .add_plugins(bevy::transform::TransformPlugin)
+        .add_plugins(bevy::scene::ScenePlugin)
+        .add_message::<bevy::asset::AssetEvent<bevy::prelude::Mesh>>()
+        .init_asset::<bevy::prelude::Mesh>()
+        .init_asset::<bevy::pbr::StandardMaterial>()
+        .init_asset::<bevy::shader::Shader>()
+        .add_message::<bevy::asset::AssetEvent<bevy::shader::Shader>>()
+        .init_asset::<bevy::image::Image>()
+        .add_message::<bevy::asset::AssetEvent<bevy::image::Image>>();
 }
 
 fn parse_client_id() -> u64 {
@@ -71,3 +89,34 @@ fn parse_client_id() -> u64 {
     }
     0
 }
+
+/// Extra input delay for `SimulatedLatency`, from `--latency-ms <N>`.
+fn parse_latency_ms() -> u32 {
+    let args: Vec<String> = std::env::args().collect();
+    for i in 0..args.len() {
+        if args[i] == "--latency-ms" {
+            if let Some(ms_str) = args.get(i + 1) {
+                return ms_str.parse().expect("Invalid latency in ms");
+            }
+        }
+    }
+    0
+}
+
+/// Predict casts without cooldowns to match a `--practice` server.
+fn parse_practice_mode() -> bool {
+    std::env::args().any(|arg| arg == "--practice")
+}
+
+/// Display name to request from the server, from `--name <NAME>`.
+fn parse_player_name() -> Option<String> {
+    let args: Vec<String> = std::env::args().collect();
+    args.iter()
+        .position(|arg| arg == "--name")
+        .and_then(|i| args.get(i + 1).cloned())
+}
+
+/// Run without a window for bots and load testing, from `--headless`.
+fn parse_headless() -> bool {
+    std::env::args().any(|arg| arg == "--headless")
+}
@@ -159,7 +159,7 @@ pub fn handle_unload_column(
 }
 
 /// Applies voxel edit broadcasts from the server, skipping positions with pending predictions.
-fn handle_voxel_broadcasts(
+pub fn handle_voxel_broadcasts(
     mut receiver: Query<&mut MessageReceiver<VoxelEditBroadcast>>,
     player_query: Query<&ChunkTicket, (With<Predicted>, With<Controlled>, With<CharacterMarker>)>,
     mut voxel_world: VoxelWorld,
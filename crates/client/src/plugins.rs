@@ -0,0 +1,116 @@
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+use client_lightyear::{ClientNetworkConfig, ClientNetworkPlugin};
+use dev::DevPlugin;
+use lightyear::prelude::client::ClientPlugins;
+use protocol::diagnostics::SharedDiagnosticsPlugin;
+use protocol::settings::{Settings, SettingsPath, SettingsPlugin};
+use protocol::{PracticeMode, SharedGameplayPlugin, FIXED_TIMESTEP_HZ};
+use render::RenderPlugin;
+use std::path::PathBuf;
+use std::time::Duration;
+use ui::{UiClientConfig, UiPlugin};
+
+use crate::diagnostics::ClientDiagnosticsPlugin;
+use crate::gameplay::{ClientGameplayPlugin, RequestedPlayerName};
+use crate::headless::HeadlessClientPlugin;
+use crate::latency::SimulatedLatency;
+use crate::map::ClientMapPlugin;
+use crate::transition::ClientTransitionPlugin;
+
+/// Settings for [`GameClientPlugins`].
+#[derive(Clone)]
+pub struct GameClientConfig {
+    pub network: ClientNetworkConfig,
+    /// Player settings to start from, including the main menu's reconnect target.
+    pub settings: Settings,
+    /// File `settings` are loaded from and saved back to; `None` keeps them in
+    /// memory only.
+    pub settings_path: Option<PathBuf>,
+    pub latency: SimulatedLatency,
+    /// Predict casts without cooldowns, matching a practice-mode server.
+    pub practice_mode: bool,
+    pub player_name: Option<String>,
+    /// Run without a window, rendering, UI or dev tools; see
+    /// [`HeadlessClientPlugin`].
+    pub headless: bool,
+}
+
+impl Default for GameClientConfig {
+    fn default() -> Self {
+        Self {
+            network: ClientNetworkConfig::default(),
+            settings: Settings::default(),
+            settings_path: None,
+            latency: SimulatedLatency::default(),
+            practice_mode: false,
+            player_name: None,
+            headless: false,
+        }
+    }
+}
+
+impl GameClientConfig {
+    /// UI connection settings kept in sync with `network`.
+    pub fn ui_config(&self) -> UiClientConfig {
+        UiClientConfig {
+            server_addr: self.network.server_addr,
+            client_id: self.network.client_id,
+            protocol_id: self.network.protocol_id,
+            private_key: self.network.private_key,
+        }
+    }
+}
+
+/// The whole game client: networking, protocol, gameplay, maps, rendering, UI,
+/// dev tools and diagnostics. Expects `DefaultPlugins` to already be added.
+///
+/// With [`GameClientConfig::headless`], [`HeadlessClientPlugin`] replaces the
+/// rendering, UI and dev tools, and only a windowless base set is expected
+/// (see the client binary's `--headless`).
+#[derive(Default)]
+pub struct GameClientPlugins {
+    pub config: GameClientConfig,
+}
+
+impl PluginGroup for GameClientPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        let headless = self.config.headless;
+        let group = PluginGroupBuilder::start::<Self>()
+            .add(GameClientConfigPlugin(self.config.clone()))
+            .add(ClientPlugins {
+                tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+            })
+            .add(SharedGameplayPlugin)
+            .add(SettingsPlugin)
+            .add(ClientNetworkPlugin {
+                config: self.config.network,
+            })
+            .add(ClientGameplayPlugin)
+            .add(ClientMapPlugin)
+            .add(ClientTransitionPlugin);
+        let group = if headless {
+            group.add(HeadlessClientPlugin)
+        } else {
+            group.add(RenderPlugin).add(UiPlugin).add(DevPlugin)
+        };
+        group
+            .add(SharedDiagnosticsPlugin)
+            .add(ClientDiagnosticsPlugin)
+    }
+}
+
+/// Inserts the resources [`GameClientConfig`] sets. Added first, so the later
+/// plugins' `init_resource` calls keep these values.
+struct GameClientConfigPlugin(GameClientConfig);
+
+impl Plugin for GameClientConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.0.ui_config())
+            .insert_resource(self.0.settings)
+            .insert_resource(SettingsPath(self.0.settings_path.clone()))
+            .insert_resource(self.0.latency)
+            .insert_resource(PracticeMode(self.0.practice_mode))
+            .insert_resource(RequestedPlayerName(self.0.player_name.clone()));
+    }
+}
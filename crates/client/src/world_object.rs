@@ -6,7 +6,7 @@ use protocol::vox_model::{VoxModelAsset, VoxModelRegistry};
 use protocol::world_object::{
     apply_object_components, VisualKind, WorldObjectDef, WorldObjectDefRegistry, WorldObjectId,
 };
-use protocol::{MapInstanceId, MapRegistry};
+use protocol::{prop_physics, MapInstanceId, MapProp, MapRegistry};
 
 /// Shared PBR material for all vox model meshes.
 ///
@@ -83,6 +83,24 @@ pub fn on_world_object_replicated(
     }
 }
 
+/// Gives a replicated map prop its static collider and a solid-colored mesh.
+pub fn on_map_prop_replicated(
+    query: Query<(Entity, &MapProp, Option<&Position>, Option<&Rotation>), Added<Replicated>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut commands: Commands,
+) {
+    for (entity, prop, pos, rot) in &query {
+        commands.entity(entity).insert((
+            prop_physics(&prop.shape),
+            transform_from_physics(pos, rot),
+            Visibility::default(),
+            Mesh3d(meshes.add(prop.shape.mesh())),
+            MeshMaterial3d(materials.add(prop.color)),
+        ));
+    }
+}
+
 /// Clones the definition's reflected components for insertion.
 ///
 /// When `filter_collider_constructor` is true, `ColliderConstructor` is excluded
@@ -1,10 +1,14 @@
+use avian3d::prelude::{Position, Rotation};
 use bevy::prelude::*;
 use client_lightyear::ClientNetworkPlugin;
 use lightyear::prelude::client as lightyear_client;
 use lightyear::prelude::*;
 use lightyear_client::*;
+use protocol::test_utils::TestHarness;
 use protocol::*;
 
+const MAX_WAIT_TICKS: usize = 50;
+
 #[test]
 fn test_client_network_plugin_registers_observers() {
     let mut app = App::new();
@@ -66,3 +70,235 @@ fn test_client_network_plugin_disconnected_observer() {
         "Observer should process Disconnected component"
     );
 }
+
+#[test]
+fn protocol_plugin_applies_component_sync_config() {
+    let config = ComponentSyncConfig {
+        position: SyncMode::INTERPOLATED,
+        rotation: SyncMode::PREDICTED,
+        velocity: SyncMode {
+            prediction: false,
+            interpolation: false,
+        },
+    };
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins::default());
+    app.insert_resource(config);
+    app.add_plugins(ProtocolPlugin);
+    app.finish();
+    app.update();
+
+    let applied = *app.world().resource::<ComponentSyncConfig>();
+    assert_eq!(
+        applied, config,
+        "pre-inserted config is kept, not overwritten"
+    );
+    assert!(
+        !applied.position.prediction,
+        "position is interpolation-only"
+    );
+    assert!(applied.rotation.prediction && !applied.rotation.interpolation);
+}
+
+/// Whether client `index`'s predicted copy of the server's entity keeps a
+/// prediction history for `Position`, i.e. whether that client predicts it.
+fn predicts_position(harness: &mut TestHarness, index: usize) -> bool {
+    let world = harness.clients[index].app.world_mut();
+    world
+        .query_filtered::<Has<PredictionHistory<Position>>, With<Predicted>>()
+        .iter(world)
+        .any(|has_history| has_history)
+}
+
+#[test]
+fn component_sync_config_changes_what_a_client_predicts() {
+    let mut harness = TestHarness::server_only(|_| {});
+    let predicting = harness.add_client(|_| {});
+    let interpolating = harness.add_client_with_config(
+        ComponentSyncConfig {
+            position: SyncMode::INTERPOLATED,
+            ..default()
+        },
+        |_| {},
+    );
+    harness.server.world_mut().spawn((
+        Position(Vec3::X),
+        Rotation::default(),
+        Replicate::to_clients(NetworkTarget::All),
+        PredictionTarget::to_clients(NetworkTarget::All),
+    ));
+
+    for index in [predicting, interpolating] {
+        assert!(
+            harness.tick_until_on(index, MAX_WAIT_TICKS, |world| {
+                world
+                    .query_filtered::<(), (With<Predicted>, With<Position>)>()
+                    .iter(world)
+                    .next()
+                    .is_some()
+            }),
+            "client {index} should receive the predicted entity"
+        );
+    }
+    assert!(
+        predicts_position(&mut harness, predicting),
+        "the default config predicts Position"
+    );
+    assert!(
+        !predicts_position(&mut harness, interpolating),
+        "an interpolation-only Position is not predicted"
+    );
+}
+
+#[test]
+fn protocol_plugin_defaults_to_predicting_physics() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins::default());
+    app.add_plugins(ProtocolPlugin);
+
+    assert_eq!(
+        *app.world().resource::<ComponentSyncConfig>(),
+        ComponentSyncConfig::default()
+    );
+    assert_eq!(
+        ComponentSyncConfig::default().position,
+        SyncMode::PREDICTED_AND_INTERPOLATED
+    );
+}
+
+#[test]
+fn protocol_plugin_can_disable_input_rebroadcast() {
+    let config = InputRebroadcastConfig {
+        rebroadcast_inputs: false,
+    };
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins::default());
+    app.insert_resource(config);
+    app.add_plugins(ProtocolPlugin);
+    app.finish();
+    app.update();
+
+    let applied = *app.world().resource::<InputRebroadcastConfig>();
+    assert_eq!(
+        applied, config,
+        "pre-inserted config is kept, not overwritten"
+    );
+    let input_config = player_input_config(&applied);
+    assert!(!input_config.rebroadcast_inputs);
+    // Only the relay to other clients is off; owned inputs are still sent with
+    // the usual redundancy.
+    assert_eq!(
+        input_config.packet_redundancy,
+        player_input_config(&InputRebroadcastConfig::default()).packet_redundancy
+    );
+    assert!(player_input_config(&InputRebroadcastConfig::default()).rebroadcast_inputs);
+}
+
+#[test]
+fn headless_client_plugins_omit_render_and_ui() {
+    use client::gameplay::ClientGameplayPlugin;
+    use client::headless::HeadlessClientPlugin;
+    use client::plugins::{GameClientConfig, GameClientPlugins};
+
+    let headless = GameClientPlugins {
+        config: GameClientConfig {
+            headless: true,
+            ..default()
+        },
+    }
+    .build();
+    assert!(!headless.contains::<render::RenderPlugin>());
+    assert!(!headless.contains::<ui::UiPlugin>());
+    assert!(!headless.contains::<dev::DevPlugin>());
+    assert!(headless.contains::<HeadlessClientPlugin>());
+    assert!(headless.contains::<SharedGameplayPlugin>());
+    assert!(headless.contains::<ClientNetworkPlugin>());
+    assert!(headless.contains::<ClientGameplayPlugin>());
+
+    let windowed = GameClientPlugins::default().build();
+    assert!(windowed.contains::<render::RenderPlugin>());
+    assert!(windowed.contains::<ui::UiPlugin>());
+    assert!(!windowed.contains::<HeadlessClientPlugin>());
+}
+
+/// The windowless base plugins the client binary's `--headless` runs on.
+fn headless_host_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(bevy::state::app::StatesPlugin);
+    app.add_plugins(bevy::input::InputPlugin);
+    app.add_plugins(AssetPlugin::default());
+    app.add_plugins(bevy::transform::TransformPlugin);
+    app.add_plugins(bevy::scene::ScenePlugin);
+    app.add_message::<AssetEvent<Mesh>>();
+    app.init_asset::<Mesh>();
+    app.init_asset::<StandardMaterial>();
+    app.init_asset::<bevy::shader::Shader>();
+    app.add_message::<AssetEvent<bevy::shader::Shader>>();
+    app.init_asset::<Image>();
+    app.add_message::<AssetEvent<Image>>();
+    app
+}
+
+#[test]
+fn headless_game_client_plugins_build_and_run() {
+    use client::gameplay::RequestedPlayerName;
+    use client::plugins::{GameClientConfig, GameClientPlugins};
+
+    let mut app = headless_host_app();
+    app.add_plugins(
+        GameClientPlugins {
+            config: GameClientConfig {
+                headless: true,
+                practice_mode: true,
+                player_name: Some("bot".into()),
+                ..default()
+            },
+        }
+        .build()
+        // Connecting to a real server is out of scope for a unit test.
+        .disable::<ClientNetworkPlugin>(),
+    );
+    app.update();
+    app.update();
+
+    let world = app.world();
+    assert_eq!(world.resource::<PracticeMode>(), &PracticeMode(true));
+    assert_eq!(
+        world.resource::<RequestedPlayerName>(),
+        &RequestedPlayerName(Some("bot".into()))
+    );
+    assert!(world.contains_resource::<State<AppState>>());
+    assert!(world.contains_resource::<MovementConfig>());
+}
+
+#[test]
+fn headless_client_exits_with_an_error_when_no_client_entity_appears() {
+    use client::plugins::{GameClientConfig, GameClientPlugins};
+
+    let mut app = headless_host_app();
+    app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+        std::time::Duration::from_secs(1),
+    ));
+    app.add_plugins(
+        GameClientPlugins {
+            config: GameClientConfig {
+                headless: true,
+                ..default()
+            },
+        }
+        .build()
+        // Without it no `Client` entity is ever spawned.
+        .disable::<ClientNetworkPlugin>(),
+    );
+    app.update();
+    assert_eq!(app.should_exit(), None, "the client waits for its entity");
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(app.should_exit(), Some(AppExit::error()));
+}
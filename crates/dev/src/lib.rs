@@ -1,8 +1,12 @@
 //! Development-only tooling: physics debug rendering and runtime debug toggles.
 
-use avian3d::prelude::{PhysicsDebugPlugin, PhysicsGizmos};
+use avian3d::prelude::{
+    Collider, PhysicsDebugPlugin, PhysicsGizmoExt, PhysicsGizmos, Position, Rotation,
+};
 use bevy::gizmos::config::GizmoConfigStore;
 use bevy::prelude::*;
+use protocol::ability::{AbilityBulletOf, AoEHitbox, MeleeHitbox};
+use protocol::CharacterMarker;
 
 /// Adds physics debug rendering and keybindings for toggling debug views at runtime.
 pub struct DevPlugin;
@@ -10,11 +14,28 @@ pub struct DevPlugin;
 impl Plugin for DevPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(PhysicsDebugPlugin::default())
+            .init_resource::<HitboxOverlay>()
             .add_systems(Startup, hide_physics_debug)
-            .add_systems(Update, toggle_physics_debug);
+            .add_systems(
+                Update,
+                (
+                    toggle_physics_debug,
+                    toggle_hitbox_overlay,
+                    draw_hitbox_overlay.run_if(|overlay: Res<HitboxOverlay>| overlay.enabled),
+                )
+                    .chain(),
+            );
     }
 }
 
+/// Whether the hitbox overlay is drawn: wireframes of melee and AoE hitboxes,
+/// projectile colliders and character capsules only, unlike the F3 view of every
+/// collider. Off by default; press F4 to toggle.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HitboxOverlay {
+    pub enabled: bool,
+}
+
 /// Hides the avian physics debug gizmos by default; press F3 at runtime to reveal.
 fn hide_physics_debug(mut store: ResMut<GizmoConfigStore>) {
     let (config, _) = store.config_mut::<PhysicsGizmos>();
@@ -28,3 +49,75 @@ fn toggle_physics_debug(keys: Res<ButtonInput<KeyCode>>, mut store: ResMut<Gizmo
         config.enabled = !config.enabled;
     }
 }
+
+/// Toggles the [`HitboxOverlay`] when F4 is pressed.
+pub fn toggle_hitbox_overlay(keys: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<HitboxOverlay>) {
+    if keys.just_pressed(KeyCode::F4) {
+        overlay.enabled = !overlay.enabled;
+    }
+}
+
+/// Draws the collider of every hitbox, bullet and character, colored by kind.
+fn draw_hitbox_overlay(
+    mut gizmos: Gizmos,
+    query: Query<
+        (
+            &Collider,
+            &Position,
+            &Rotation,
+            Has<MeleeHitbox>,
+            Has<AoEHitbox>,
+            Has<AbilityBulletOf>,
+        ),
+        Or<(
+            With<MeleeHitbox>,
+            With<AoEHitbox>,
+            With<AbilityBulletOf>,
+            With<CharacterMarker>,
+        )>,
+    >,
+) {
+    for (collider, position, rotation, melee, aoe, bullet) in &query {
+        let color = match (melee, aoe, bullet) {
+            (true, _, _) => Color::srgb(1.0, 0.2, 0.2),
+            (_, true, _) => Color::srgb(1.0, 0.6, 0.0),
+            (_, _, true) => Color::srgb(1.0, 1.0, 0.2),
+            _ => Color::srgb(0.2, 0.8, 1.0),
+        };
+        gizmos.draw_collider(collider, *position, *rotation, color);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press_f4(app: &mut App) {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release(KeyCode::F4);
+        keys.clear();
+        keys.press(KeyCode::F4);
+        app.update();
+    }
+
+    #[test]
+    fn f4_toggles_hitbox_overlay() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_resource::<HitboxOverlay>();
+        app.add_systems(Update, toggle_hitbox_overlay);
+
+        app.update();
+        assert!(
+            !app.world().resource::<HitboxOverlay>().enabled,
+            "off by default"
+        );
+
+        press_f4(&mut app);
+        assert!(app.world().resource::<HitboxOverlay>().enabled);
+
+        press_f4(&mut app);
+        assert!(!app.world().resource::<HitboxOverlay>().enabled);
+    }
+}
@@ -1,11 +1,22 @@
-use super::loader::{apply_ability_archetype, extract_conditional_effects, extract_phases};
+use super::loader::{
+    apply_ability_archetype, extract_conditional_effects, extract_cost, extract_max_charges,
+    extract_max_range, extract_phases, extract_reset_cooldown_on_kill,
+};
 use super::loading::DefaultAbilitySlots;
 use super::types::{
-    AbilityAsset, AbilityCooldowns, AbilityDefs, AbilityPhase, AbilityPhases, AbilitySlots,
-    ActiveAbility, Condition, OnHitEffectDefs, OnHitEffects, TickEffect,
+    AbilityAsset, AbilityAvailability, AbilityCastEvent, AbilityCharges, AbilityCooldowns,
+    AbilityDefs, AbilityId, AbilityLoadouts, AbilityLockouts, AbilityPhase, AbilityPhases,
+    AbilitySlots, ActiveAbility, CastState, Channeled, Condition, DamagedThisTick,
+    LoadoutSwapConfig, OnHitEffectDefs, OnHitEffects, PracticeMode, ResourcePool, Stunned,
+    TickEffect,
 };
-use crate::character::IsGrounded;
-use crate::{PlayerActions, PlayerId};
+use crate::character::{IsGrounded, RespawnTimer};
+use crate::map::MapInstanceId;
+use crate::{DeathEvent, Health, PlayerActions, PlayerId};
+use avian3d::prelude::Position;
+use bevy::ecs::entity::EntityHashMap;
+use bevy::ecs::message::{MessageReader, MessageWriter};
+use bevy::ecs::query::QueryFilter;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 use lightyear::prelude::LocalTimeline;
@@ -33,32 +44,88 @@ pub fn slot_to_ability_action(slot: usize) -> Option<PlayerActions> {
     ABILITY_ACTIONS.get(slot).copied()
 }
 
+/// On a `SwapLoadout` press off cooldown, cycles the character to its next
+/// [`AbilityLoadouts`] preset and makes it the live [`AbilitySlots`]. Runs
+/// before [`ability_activation`], so a cast pressed on the same tick uses the
+/// new preset.
+pub fn swap_loadout(
+    config: Res<LoadoutSwapConfig>,
+    timeline: Res<LocalTimeline>,
+    mut query: Query<(
+        Entity,
+        &ActionState<PlayerActions>,
+        &mut AbilityLoadouts,
+        &mut AbilitySlots,
+    )>,
+) {
+    let tick = timeline.tick();
+    for (entity, action_state, mut loadouts, mut slots) in &mut query {
+        if !action_state.just_pressed(&PlayerActions::SwapLoadout) {
+            continue;
+        }
+        if loadouts.presets.len() < 2 {
+            trace!("Loadout swap ignored for {entity:?}: nothing to swap to");
+            continue;
+        }
+        if loadouts.is_on_cooldown(tick, config.cooldown_ticks) {
+            trace!("Loadout swap ignored for {entity:?}: on cooldown");
+            continue;
+        }
+        loadouts.active = (loadouts.active + 1) % loadouts.presets.len();
+        loadouts.last_swap = Some(tick);
+        *slots = loadouts.presets[loadouts.active].clone();
+    }
+}
+
 pub fn ability_activation(
     mut commands: Commands,
     ability_defs: Res<AbilityDefs>,
     ability_assets: Res<Assets<AbilityAsset>>,
     registry: Res<AppTypeRegistry>,
     default_slots: Res<DefaultAbilitySlots>,
+    practice: Res<PracticeMode>,
+    availability: Res<AbilityAvailability>,
     timeline: Res<LocalTimeline>,
     mut query: Query<(
         Entity,
         &ActionState<PlayerActions>,
         Option<&AbilitySlots>,
         &mut AbilityCooldowns,
+        &mut AbilityCharges,
+        Option<&AbilityLockouts>,
+        Option<&mut ResourcePool>,
         &PlayerId,
     )>,
     server_query: Query<&ControlledBy>,
     grounded_query: Query<(), With<IsGrounded>>,
+    stunned_query: Query<(), With<Stunned>>,
     // `Without<PredictionDisable>` filters out client-predicted corpses: lightyear's
     // `prediction_despawn()` doesn't immediately remove a predicted entity, it tags it
     // `PredictionDisable` and leaves it in the world until the server's confirmed despawn
     // arrives. Without this filter the dedup check below would treat those tombstones as
     // still-active abilities and silently reject every re-cast for the rollback window.
     active_abilities: Query<&ActiveAbility, Without<PredictionDisable>>,
+    caster_query: Query<(&Position, Option<&MapInstanceId>)>,
+    target_query: Query<(Entity, &Position, Option<&MapInstanceId>), RangeTargetFilter>,
+    mut cast_events: MessageWriter<AbilityCastEvent>,
 ) {
     let tick = timeline.tick();
 
-    for (entity, action_state, slots_opt, mut cooldowns, player_id) in &mut query {
+    for (
+        entity,
+        action_state,
+        slots_opt,
+        mut cooldowns,
+        mut charges,
+        lockouts,
+        mut pool,
+        player_id,
+    ) in &mut query
+    {
+        if stunned_query.contains(entity) {
+            trace!("Ability input ignored for {entity:?}: stunned");
+            continue;
+        }
         let slots = slots_opt.unwrap_or(&default_slots.0);
         for (slot_idx, action) in ABILITY_ACTIONS.iter().enumerate() {
             if !action_state.just_pressed(action) {
@@ -67,6 +134,17 @@ pub fn ability_activation(
             let Some(ref ability_id) = slots.0[slot_idx] else {
                 continue;
             };
+            if !availability.is_available(ability_id) {
+                trace!("Ability {:?} refused: unavailable", ability_id);
+                continue;
+            }
+            if lockouts.is_some_and(|lockouts| lockouts.is_locked(slot_idx, tick)) {
+                trace!(
+                    "Ability {:?} refused: slot {slot_idx} interrupted",
+                    ability_id
+                );
+                continue;
+            }
             let Some(handle) = ability_defs.get(ability_id) else {
                 warn!("Ability {:?} not found in defs", ability_id);
                 continue;
@@ -79,7 +157,14 @@ pub fn ability_activation(
                 warn!("Ability {:?} missing AbilityPhases component", ability_id);
                 continue;
             };
-            if cooldowns.is_on_cooldown(slot_idx, tick, phases.cooldown) {
+            let max_charges = extract_max_charges(asset).filter(|_| !practice.0);
+            if let Some(max_charges) = max_charges {
+                charges.refill(slot_idx, tick, max_charges.refill_ticks);
+                if charges.remaining(slot_idx, max_charges.charges) == 0 {
+                    trace!("Ability {:?} refused: no charges left", ability_id);
+                    continue;
+                }
+            } else if !practice.0 && cooldowns.is_on_cooldown(slot_idx, tick, phases.cooldown) {
                 continue;
             }
 
@@ -102,6 +187,18 @@ pub fn ability_activation(
                 continue;
             }
 
+            if let Some(max_range) = extract_max_range(asset) {
+                if !has_target_in_range(entity, max_range.range, &caster_query, &target_query) {
+                    trace!(
+                        "Ability {:?} refused: no target within {} of {:?}",
+                        ability_id,
+                        max_range.range,
+                        entity
+                    );
+                    continue;
+                }
+            }
+
             // Evaluate ConditionalEffects against the caster's current state. If
             // the asset declares conditions but none match, refuse the cast: no
             // spawn, no cooldown consumption.
@@ -130,6 +227,24 @@ pub fn ability_activation(
                 continue;
             }
 
+            // Practice mode has infinite resources: costs neither gate nor drain.
+            let cost = extract_cost(asset).filter(|_| !practice.0);
+            if let (Some(cost), Some(pool)) = (cost, pool.as_deref_mut()) {
+                if !pool.try_spend(cost.cost) {
+                    trace!(
+                        "Ability {:?} refused: costs {} but {:?} has {}",
+                        ability_id,
+                        cost.cost,
+                        entity,
+                        pool.current
+                    );
+                    continue;
+                }
+            }
+
+            if max_charges.is_some() {
+                charges.spend(slot_idx, tick);
+            }
             cooldowns.last_used[slot_idx] = Some(tick);
             if let Some(client) = TracyClient::running() {
                 client.message(
@@ -150,6 +265,7 @@ pub fn ability_activation(
                         phase_start_tick: tick,
                         ability_slot: slot_idx as u8,
                         depth: 0,
+                        landed_hit: false,
                     },
                     PreSpawned::default_with_salt(salt),
                     Name::new("ActiveAbility"),
@@ -171,10 +287,37 @@ pub fn ability_activation(
                     *controlled_by,
                 ));
             }
+
+            cast_events.write(AbilityCastEvent {
+                caster: entity,
+                ability_id: ability_id.clone(),
+                slot: slot_idx as u8,
+                tick,
+            });
         }
     }
 }
 
+/// Targets that satisfy a `MaxRange` gate: living damageable entities.
+type RangeTargetFilter = (With<Health>, Without<RespawnTimer>);
+
+/// Whether any target other than `caster`, on the caster's map, is within `range`.
+fn has_target_in_range(
+    caster: Entity,
+    range: f32,
+    caster_query: &Query<(&Position, Option<&MapInstanceId>)>,
+    target_query: &Query<(Entity, &Position, Option<&MapInstanceId>), RangeTargetFilter>,
+) -> bool {
+    let Ok((caster_pos, caster_map)) = caster_query.get(caster) else {
+        warn!("MaxRange gate: caster {:?} has no Position", caster);
+        return false;
+    };
+    let range_sq = range * range;
+    target_query.iter().any(|(target, pos, map)| {
+        target != caster && map == caster_map && pos.0.distance_squared(caster_pos.0) <= range_sq
+    })
+}
+
 fn advance_ability_phase(
     commands: &mut Commands,
     entity: Entity,
@@ -212,11 +355,23 @@ pub fn update_active_abilities(
         &mut ActiveAbility,
         &AbilityPhases,
         Option<&OnHitEffectDefs>,
+        Has<Channeled>,
     )>,
+    action_query: Query<&ActionState<PlayerActions>>,
+    damaged_query: Query<(), With<DamagedThisTick>>,
 ) {
     let tick = timeline.tick();
 
-    for (entity, mut active, phases, on_hit_defs) in &mut query {
+    for (entity, mut active, phases, on_hit_defs, channeled) in &mut query {
+        if channeled && channel_broken(&active, &action_query, &damaged_query) {
+            trace!(
+                "Channel of {:?} broken on {:?}",
+                active.def_id,
+                active.caster
+            );
+            commands.entity(entity).prediction_despawn();
+            continue;
+        }
         let prev_phase = active.phase.clone();
         advance_ability_phase(&mut commands, entity, &mut active, phases, tick);
 
@@ -224,9 +379,11 @@ pub fn update_active_abilities(
             if let Some(defs) = on_hit_defs {
                 if !defs.0.is_empty() {
                     commands.entity(entity).insert(OnHitEffects {
+                        ability_id: active.def_id.clone(),
                         effects: defs.0.clone(),
                         caster: active.caster,
                         original_caster: active.original_caster,
+                        ability_slot: active.ability_slot,
                         depth: active.depth,
                     });
                 }
@@ -238,3 +395,142 @@ pub fn update_active_abilities(
         }
     }
 }
+
+/// Whether a channeled `active`, still in Startup or Active, must end: its
+/// caster was hit or no longer holds the slot's action. Casters without
+/// input (e.g. minions), and slots with no action, only break on hits.
+fn channel_broken(
+    active: &ActiveAbility,
+    action_query: &Query<&ActionState<PlayerActions>>,
+    damaged_query: &Query<(), With<DamagedThisTick>>,
+) -> bool {
+    if active.phase == AbilityPhase::Recovery {
+        return false;
+    }
+    if damaged_query.contains(active.caster) {
+        return true;
+    }
+    let Some(action) = slot_to_ability_action(active.ability_slot as usize) else {
+        trace!(
+            "channel_broken: slot {} of {:?} has no action to hold",
+            active.ability_slot,
+            active.def_id
+        );
+        return false;
+    };
+    action_query
+        .get(active.caster)
+        .is_ok_and(|action_state| !action_state.pressed(&action))
+}
+
+/// Fraction of its current phase `active` has completed by `tick`. A
+/// zero-length phase counts as complete.
+fn phase_progress(active: &ActiveAbility, phases: &AbilityPhases, tick: Tick) -> f32 {
+    let duration = phases.phase_duration(&active.phase);
+    if duration == 0 {
+        return 1.0;
+    }
+    let elapsed = (tick - active.phase_start_tick).max(0);
+    (elapsed as f32 / duration as f32).min(1.0)
+}
+
+/// Mirrors each caster's top-level `ActiveAbility` into its [`CastState`],
+/// and removes the `CastState` of casters no longer casting. Sub-abilities
+/// (depth above zero) don't count as casts. A caster with several top-level
+/// abilities active shows the one in the lowest slot, then the lowest id, so
+/// server and client agree regardless of query order. An unchanged
+/// `CastState` is left untouched and doesn't trigger change detection.
+pub fn update_cast_states(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    ability_query: Query<(&ActiveAbility, &AbilityPhases), Without<PredictionDisable>>,
+    mut cast_state_query: Query<(Entity, &mut CastState)>,
+) {
+    let tick = timeline.tick();
+    let mut casts = EntityHashMap::<(&ActiveAbility, &AbilityPhases)>::default();
+    for (active, phases) in &ability_query {
+        if active.depth != 0 {
+            continue;
+        }
+        let outranked = casts.get(&active.caster).is_some_and(|(shown, _)| {
+            (shown.ability_slot, &shown.def_id.0) <= (active.ability_slot, &active.def_id.0)
+        });
+        if !outranked {
+            casts.insert(active.caster, (active, phases));
+        }
+    }
+    let cast_state = |active: &ActiveAbility, phases: &AbilityPhases| CastState {
+        ability_id: active.def_id.clone(),
+        phase: active.phase.clone(),
+        phase_progress: phase_progress(active, phases, tick),
+    };
+
+    for (entity, mut current) in &mut cast_state_query {
+        match casts.remove(&entity) {
+            Some((active, phases)) => {
+                current.set_if_neq(cast_state(active, phases));
+            }
+            None => {
+                commands.entity(entity).remove::<CastState>();
+            }
+        }
+    }
+    for (caster, (active, phases)) in casts {
+        commands
+            .entity(caster)
+            .try_insert(cast_state(active, phases));
+    }
+}
+
+/// Ability ids of every `ActiveAbility` cast by `caster`, sub-abilities
+/// included, in query order. Pass a `Without<PredictionDisable>` filter to
+/// skip abilities rolled back on the client.
+pub fn active_abilities_of<F: QueryFilter>(
+    caster: Entity,
+    ability_query: &Query<&ActiveAbility, F>,
+) -> Vec<AbilityId> {
+    ability_query
+        .iter()
+        .filter(|active| active.caster == caster)
+        .map(|active| active.def_id.clone())
+        .collect()
+}
+
+/// Clears the killer's cooldown for the slot a lethal hit was cast from, when
+/// that ability has [`ResetCooldownOnKill`](super::types::ResetCooldownOnKill).
+pub fn reset_cooldown_on_kill(
+    mut deaths: MessageReader<DeathEvent>,
+    ability_defs: Res<AbilityDefs>,
+    ability_assets: Res<Assets<AbilityAsset>>,
+    mut cooldown_query: Query<&mut AbilityCooldowns>,
+) {
+    for kill in deaths.read().filter_map(|death| death.killed_by.as_ref()) {
+        let Some(asset) = ability_defs
+            .get(&kill.ability_id)
+            .and_then(|handle| ability_assets.get(handle))
+        else {
+            trace!("reset_cooldown_on_kill: no asset for {:?}", kill.ability_id);
+            continue;
+        };
+        if extract_reset_cooldown_on_kill(asset).is_none() {
+            trace!(
+                "reset_cooldown_on_kill: {:?} does not reset on kill",
+                kill.ability_id
+            );
+            continue;
+        }
+        let Ok(mut cooldowns) = cooldown_query.get_mut(kill.original_caster) else {
+            trace!(
+                "reset_cooldown_on_kill: killer {:?} has no cooldowns",
+                kill.original_caster
+            );
+            continue;
+        };
+        let slot = kill.ability_slot as usize;
+        debug_assert!(
+            slot < cooldowns.last_used.len(),
+            "ability slot {slot} out of range"
+        );
+        cooldowns.last_used[slot] = None;
+    }
+}
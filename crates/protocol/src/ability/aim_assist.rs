@@ -0,0 +1,121 @@
+use super::types::{AbilityBulletOf, AbilityProjectileSpawn, StuckTo};
+use crate::map::MapInstanceId;
+use crate::{CharacterMarker, Health, RespawnTimer};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Tuning for projectile aim assist. Only bullets fired by a character with
+/// [`GamepadAiming`] are steered. A `strength` of zero disables it.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct AimAssistConfig {
+    /// Maximum turn rate toward the target, in radians per second.
+    pub strength: f32,
+    /// Half-angle of the cone around the bullet's heading that targets must be in.
+    pub cone_half_angle: f32,
+    /// Farthest a target may be from the bullet.
+    pub range: f32,
+}
+
+impl Default for AimAssistConfig {
+    fn default() -> Self {
+        Self {
+            strength: 1.5,
+            cone_half_angle: 12f32.to_radians(),
+            range: 25.0,
+        }
+    }
+}
+
+/// Marker on a character whose player is aiming with a gamepad. Inserted by the
+/// server from [`InputDeviceReport`] and replicated so prediction steers the same
+/// bullets.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct GamepadAiming;
+
+/// Channel for client input-device reports.
+pub struct InputDeviceChannel;
+
+/// Sent by a client whenever its active input device switches between
+/// keyboard/mouse and gamepad.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputDeviceReport {
+    pub gamepad: bool,
+}
+
+/// `velocity` turned toward `to_target` by at most `max_angle` radians, keeping
+/// its speed. Returns `velocity` unchanged if either vector is zero.
+pub fn steer_toward(velocity: Vec3, to_target: Vec3, max_angle: f32) -> Vec3 {
+    let (Some(heading), Some(desired)) = (velocity.try_normalize(), to_target.try_normalize())
+    else {
+        return velocity;
+    };
+    let angle = heading.angle_between(desired);
+    if angle <= max_angle {
+        return desired * velocity.length();
+    }
+    let rotation = Quat::from_rotation_arc(heading, desired);
+    let (axis, _) = rotation.to_axis_angle();
+    Quat::from_axis_angle(axis, max_angle) * velocity
+}
+
+/// Nudges bullets fired by [`GamepadAiming`] characters toward the nearest live
+/// character inside the configured cone and range, at up to `strength` radians
+/// per second.
+pub fn aim_assist_projectiles(
+    time: Res<Time>,
+    config: Res<AimAssistConfig>,
+    mut bullets: Query<
+        (
+            &Position,
+            &mut LinearVelocity,
+            &AbilityBulletOf,
+            &MapInstanceId,
+        ),
+        Without<StuckTo>,
+    >,
+    spawns: Query<&AbilityProjectileSpawn>,
+    aiming: Query<(), With<GamepadAiming>>,
+    targets: Query<
+        (Entity, &Position, &MapInstanceId),
+        (With<CharacterMarker>, With<Health>, Without<RespawnTimer>),
+    >,
+) {
+    if config.strength <= 0.0 {
+        trace!("aim_assist_projectiles: disabled");
+        return;
+    }
+    let max_turn = config.strength * time.delta_secs();
+    for (position, mut velocity, bullet_of, map_id) in &mut bullets {
+        let Ok(spawn) = spawns.get(bullet_of.0) else {
+            trace!(
+                "aim_assist_projectiles: bullet spawn {:?} is gone",
+                bullet_of.0
+            );
+            continue;
+        };
+        if !aiming.contains(spawn.shooter) {
+            trace!(
+                "aim_assist_projectiles: shooter {:?} is not on a gamepad",
+                spawn.shooter
+            );
+            continue;
+        }
+        let Some(heading) = velocity.0.try_normalize() else {
+            trace!("aim_assist_projectiles: bullet has no velocity to steer");
+            continue;
+        };
+        let target = targets
+            .iter()
+            .filter(|(entity, _, target_map)| *entity != spawn.shooter && *target_map == map_id)
+            .map(|(_, target_pos, _)| target_pos.0 - position.0)
+            .filter(|offset| {
+                offset.length() <= config.range
+                    && heading.angle_between(*offset) <= config.cone_half_angle
+            })
+            .min_by(|a, b| a.length().total_cmp(&b.length()));
+        if let Some(to_target) = target {
+            velocity.0 = steer_toward(velocity.0, to_target, max_turn);
+        }
+    }
+}
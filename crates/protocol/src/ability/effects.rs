@@ -1,16 +1,28 @@
-use super::spawn::{spawn_aoe_hitbox, spawn_melee_hitbox, spawn_sub_ability};
+use super::spawn::{spawn_aoe_hitbox, spawn_melee_hitbox, spawn_minion, spawn_sub_ability};
 use super::types::{
-    AbilityAsset, AbilityDefs, AbilityEffect, AbilityPhase, ActiveAbility, ActiveShield,
-    EffectTarget, ForceFrame, OnEndEffects, OnHitEffects, OnInputEffects, OnTickEffects,
-    ProjectileSpawnEffect, WhileActiveEffects,
+    by_priority, AbilityAsset, AbilityDefs, AbilityEffect, AbilityId, AbilityInterpolation,
+    AbilityPhase, ActiveAbility, ActiveBuffs, ActiveShield, AoEHitbox, EffectTarget, ForceFrame,
+    GrappleAnchor, MeleeArc, OnCastEffects, OnEndEffects, OnHitEffects, OnInputEffects,
+    OnTickEffects, ProjectileSpawnEffect, StickyProjectile, Stunned, VoxelWallEvent,
+    WhileActiveEffects, HEALING_STAT,
 };
+use crate::hit_detection::{queue_effects_in_radius, MELEE_ARC_DEFAULT_RANGE};
 use crate::map::MapInstanceId;
-use crate::{PlayerActions, PlayerId};
+use crate::{CharacterDimensions, GameLayer, Health, PlayerActions, PlayerId};
 use avian3d::prelude::*;
+use bevy::ecs::message::MessageWriter;
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 use lightyear::prelude::{ControlledBy, LocalTimeline, Tick};
 
+/// Horizontal distance from the caster's center to the middle of a `VoxelWall`.
+const VOXEL_WALL_DISTANCE: f32 = 3.0;
+/// Gap left between the caster's capsule and a grapple anchor when the pull ends.
+const GRAPPLE_STOP_MARGIN: f32 = 0.5;
+/// How close to the stopping distance counts as arrived.
+const GRAPPLE_ARRIVAL_TOLERANCE: f32 = 0.05;
+
 fn resolve_caster_target(target: &EffectTarget, active: &ActiveAbility) -> Entity {
     match target {
         EffectTarget::Caster => active.caster,
@@ -25,112 +37,269 @@ fn resolve_caster_target(target: &EffectTarget, active: &ActiveAbility) -> Entit
     }
 }
 
+/// The `AllInRadius` radius and `include_caster` of an effect that supports
+/// that target.
+fn all_in_radius(effect: &AbilityEffect) -> Option<(f32, bool)> {
+    let target = match effect {
+        AbilityEffect::Damage { target, .. }
+        | AbilityEffect::Heal { target, .. }
+        | AbilityEffect::ApplyForce { target, .. }
+        | AbilityEffect::Buff { target, .. } => target,
+        _ => return None,
+    };
+    match target {
+        EffectTarget::AllInRadius {
+            radius,
+            include_caster,
+        } => Some((*radius, *include_caster)),
+        _ => None,
+    }
+}
+
+/// Scale a base heal amount by the `healing` stat buffs on `healer`.
+pub(crate) fn apply_healing_buffs(
+    base: f32,
+    healer: Entity,
+    buff_query: &Query<&ActiveBuffs>,
+) -> f32 {
+    buff_query
+        .get(healer)
+        .map_or(base, |buffs| base * buffs.multiplier(HEALING_STAT))
+}
+
+/// Heals a caster-context `target` of `active` by `amount`, scaled by the
+/// caster's `healing` buffs.
+fn apply_caster_heal(
+    health_query: &mut Query<&mut Health>,
+    buff_query: &Query<&ActiveBuffs>,
+    active: &ActiveAbility,
+    target: &EffectTarget,
+    amount: f32,
+) {
+    let target_entity = resolve_caster_target(target, active);
+    let amount = apply_healing_buffs(amount, active.caster, buff_query);
+    let Ok(mut health) = health_query.get_mut(target_entity) else {
+        warn!("Heal target {:?} has no Health", target_entity);
+        return;
+    };
+    health.heal(amount);
+}
+
+/// Sets the horizontal part of `velocity` to `speed` along `rotation`'s facing.
+fn set_facing_velocity(velocity: &mut LinearVelocity, rotation: &Rotation, speed: f32) {
+    let direction = super::types::facing_direction(rotation);
+    velocity.x = direction.x * speed;
+    velocity.z = direction.z * speed;
+}
+
+/// Moves `position` `distance` along `rotation`'s facing.
+fn teleport_forward(position: &mut Position, rotation: &Rotation, distance: f32) {
+    position.0 += super::types::facing_direction(rotation) * distance;
+}
+
+/// What the caster-context effects every trigger shares need: `AllInRadius`
+/// effects, sub-abilities, shields, heals, buffs and stuns.
+#[derive(SystemParam)]
+pub struct CasterEffects<'w, 's> {
+    commands: Commands<'w, 's>,
+    ability_defs: Res<'w, AbilityDefs>,
+    ability_assets: Res<'w, Assets<AbilityAsset>>,
+    registry: Res<'w, AppTypeRegistry>,
+    timeline: Res<'w, LocalTimeline>,
+    server_query: Query<'w, 's, &'static ControlledBy>,
+    player_id_query: Query<'w, 's, &'static PlayerId>,
+    health_query: Query<'w, 's, &'static mut Health>,
+    buff_query: Query<'w, 's, &'static ActiveBuffs>,
+}
+
+impl CasterEffects<'_, '_> {
+    /// Applies `effect` from `active` if every trigger handles it the same way.
+    /// Returns `false` for effects the calling trigger must handle itself.
+    fn apply(&mut self, effect: &AbilityEffect, active: &ActiveAbility) -> bool {
+        let tick = self.timeline.tick();
+        if let Some((radius, include_caster)) = all_in_radius(effect) {
+            let on_hit = OnHitEffects::from_ability(active, vec![effect.clone()]);
+            queue_effects_in_radius(&mut self.commands, on_hit, radius, include_caster);
+            return true;
+        }
+        match effect {
+            AbilityEffect::Ability { id, target } => {
+                spawn_sub_ability(
+                    &mut self.commands,
+                    &self.ability_defs,
+                    &self.ability_assets,
+                    &self.registry.0,
+                    id,
+                    resolve_caster_target(target, active),
+                    active.original_caster,
+                    active.ability_slot,
+                    active.depth,
+                    tick,
+                    &self.server_query,
+                    &self.player_id_query,
+                );
+            }
+            AbilityEffect::Shield { absorb } => {
+                self.commands
+                    .entity(active.caster)
+                    .insert(ActiveShield { remaining: *absorb });
+            }
+            AbilityEffect::Heal { amount, target } => {
+                apply_caster_heal(
+                    &mut self.health_query,
+                    &self.buff_query,
+                    active,
+                    target,
+                    *amount,
+                );
+            }
+            AbilityEffect::Buff {
+                stat,
+                multiplier,
+                duration_ticks,
+                target,
+            } => {
+                apply_buff(
+                    &mut self.commands,
+                    resolve_caster_target(target, active),
+                    stat,
+                    *multiplier,
+                    *duration_ticks,
+                    tick,
+                    active.def_id.clone(),
+                );
+            }
+            AbilityEffect::Stun {
+                duration_ticks,
+                target,
+            } => {
+                apply_stun(
+                    &mut self.commands,
+                    resolve_caster_target(target, active),
+                    *duration_ticks,
+                    tick,
+                );
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
 pub fn apply_on_tick_effects(
-    mut commands: Commands,
-    ability_defs: Res<AbilityDefs>,
-    ability_assets: Res<Assets<AbilityAsset>>,
-    registry: Res<AppTypeRegistry>,
-    timeline: Res<LocalTimeline>,
-    server_query: Query<&ControlledBy>,
-    player_id_query: Query<&PlayerId>,
+    mut caster_effects: CasterEffects,
     query: Query<(
         Entity,
         &OnTickEffects,
         &ActiveAbility,
         Option<&OnHitEffects>,
+        Option<&AbilityInterpolation>,
     )>,
     mut caster_set: ParamSet<(
         Query<(&mut Position, &Rotation, &MapInstanceId)>,
         Query<Forces>,
     )>,
 ) {
-    let tick = timeline.tick();
-    for (entity, effects, active, on_hit_effects) in &query {
+    let tick = caster_effects.timeline.tick();
+    for (entity, effects, active, on_hit_effects, interpolation) in &query {
         if active.phase != AbilityPhase::Active {
             continue;
         }
 
         let active_offset = (tick - active.phase_start_tick) as u16;
-        for tick_effect in &effects.0 {
-            if tick_effect.tick != active_offset {
+        let due = effects
+            .0
+            .iter()
+            .filter(|tick_effect| tick_effect.tick == active_offset)
+            .map(|tick_effect| &tick_effect.effect);
+        for effect in by_priority(due) {
+            if caster_effects.apply(effect, active) {
                 continue;
             }
-            match &tick_effect.effect {
-                AbilityEffect::Melee { .. } => {
+            let commands = &mut caster_effects.commands;
+            match effect {
+                AbilityEffect::Melee {
+                    affects,
+                    arc_degrees,
+                    range,
+                    ..
+                } => {
                     let caster_query = caster_set.p0();
+                    let arc = arc_degrees.map(|degrees| {
+                        MeleeArc::new(degrees, range.unwrap_or(MELEE_ARC_DEFAULT_RANGE))
+                    });
                     spawn_melee_hitbox(
-                        &mut commands,
+                        commands,
                         entity,
                         active,
                         on_hit_effects,
+                        interpolation,
+                        *affects,
+                        arc,
                         &caster_query,
                     );
                 }
                 AbilityEffect::AreaOfEffect {
                     radius,
                     duration_ticks,
+                    affects,
+                    max_targets,
+                    falloff,
                     ..
                 } => {
                     let caster_query = caster_set.p0();
                     spawn_aoe_hitbox(
-                        &mut commands,
+                        commands,
                         entity,
                         active,
                         on_hit_effects,
+                        interpolation,
+                        *affects,
                         &caster_query,
-                        *radius,
-                        tick,
-                        duration_ticks.unwrap_or(1),
+                        AoEHitbox {
+                            spawn_tick: tick,
+                            duration_ticks: duration_ticks.unwrap_or(1),
+                            max_targets: *max_targets,
+                            radius: *radius,
+                            falloff: *falloff,
+                        },
                     );
                 }
                 AbilityEffect::Projectile {
                     speed,
                     lifetime_ticks,
+                    radius,
+                    stick,
+                    fuse_ticks,
+                    clash,
+                    homing,
                     ..
                 } => {
                     commands.entity(entity).insert(ProjectileSpawnEffect {
                         speed: *speed,
                         lifetime_ticks: *lifetime_ticks,
+                        radius: *radius,
+                        sticky: stick.then_some(StickyProjectile {
+                            fuse_ticks: *fuse_ticks,
+                        }),
+                        clash: *clash,
+                        homing: *homing,
                     });
                 }
-                AbilityEffect::Ability { id, target } => {
-                    let target_entity = resolve_caster_target(target, active);
-                    spawn_sub_ability(
-                        &mut commands,
-                        ability_defs.as_ref(),
-                        ability_assets.as_ref(),
-                        &registry.0,
-                        id,
-                        target_entity,
-                        active.original_caster,
-                        active.ability_slot,
-                        active.depth,
-                        tick,
-                        &server_query,
-                        &player_id_query,
-                    );
-                }
                 AbilityEffect::Teleport { distance } => {
                     apply_teleport(&mut caster_set.p0(), active.caster, *distance);
                 }
-                AbilityEffect::Shield { absorb } => {
-                    commands
-                        .entity(active.caster)
-                        .insert(ActiveShield { remaining: *absorb });
-                }
-                AbilityEffect::Buff {
-                    stat,
-                    multiplier,
-                    duration_ticks,
-                    target,
-                } => {
-                    apply_buff(
-                        &mut commands,
-                        resolve_caster_target(target, active),
-                        stat,
-                        *multiplier,
-                        *duration_ticks,
+                AbilityEffect::Summon { id, lifetime_ticks } => {
+                    spawn_minion(
+                        commands,
+                        active,
+                        id,
+                        *lifetime_ticks,
                         tick,
+                        interpolation,
+                        &caster_set.p0(),
+                        &caster_effects.server_query,
+                        &caster_effects.player_id_query,
                     );
                 }
                 AbilityEffect::ApplyForce {
@@ -153,7 +322,7 @@ pub fn apply_on_tick_effects(
                     );
                 }
                 _ => {
-                    warn!("Unhandled OnTick effect: {:?}", tick_effect.effect);
+                    warn!("Unhandled OnTick effect: {:?}", effect);
                 }
             }
         }
@@ -188,6 +357,76 @@ fn apply_caster_force(
     forces.apply_linear_impulse(world_force);
 }
 
+/// Fires `OnCastEffects` on the tick the ability enters Active. Unlike
+/// `OnTick { tick: 0 }`, this is keyed to the phase transition itself.
+pub fn apply_on_cast_effects(
+    mut caster_effects: CasterEffects,
+    query: Query<(&OnCastEffects, &ActiveAbility)>,
+    mut caster_query: Query<(&mut Position, &Rotation, &mut LinearVelocity)>,
+    wall_caster_query: Query<(&MapInstanceId, &CharacterDimensions)>,
+    mut wall_events: MessageWriter<VoxelWallEvent>,
+) {
+    let tick = caster_effects.timeline.tick();
+    for (effects, active) in &query {
+        if active.phase != AbilityPhase::Active || active.phase_start_tick != tick {
+            continue;
+        }
+        for effect in by_priority(&effects.0) {
+            if caster_effects.apply(effect, active) {
+                continue;
+            }
+            match effect {
+                AbilityEffect::SetVelocity { speed, target } => {
+                    let target_entity = resolve_caster_target(target, active);
+                    if let Ok((_, rotation, mut velocity)) = caster_query.get_mut(target_entity) {
+                        set_facing_velocity(&mut velocity, rotation, *speed);
+                    }
+                }
+                AbilityEffect::Teleport { distance } => {
+                    if let Ok((mut position, rotation, _)) = caster_query.get_mut(active.caster) {
+                        teleport_forward(&mut position, rotation, *distance);
+                    } else {
+                        warn!(
+                            "Teleport: caster {:?} missing Position/Rotation",
+                            active.caster
+                        );
+                    }
+                }
+                AbilityEffect::VoxelWall {
+                    length,
+                    height,
+                    material,
+                    duration_ticks,
+                } => {
+                    let (Ok((position, rotation, _)), Ok((map_id, dimensions))) = (
+                        caster_query.get(active.caster),
+                        wall_caster_query.get(active.caster),
+                    ) else {
+                        warn!("VoxelWall: caster {:?} is not a character", active.caster);
+                        continue;
+                    };
+                    let feet = position.0 - Vec3::Y * dimensions.feet_offset();
+                    wall_events.write(VoxelWallEvent {
+                        caster: active.caster,
+                        map_id: map_id.clone(),
+                        cells: voxel_wall_cells(
+                            feet,
+                            super::types::facing_direction(rotation),
+                            *length,
+                            *height,
+                        ),
+                        material: *material,
+                        expires_tick: tick + *duration_ticks as i16,
+                    });
+                }
+                _ => {
+                    warn!("Unhandled OnCast effect: {:?}", effect);
+                }
+            }
+        }
+    }
+}
+
 pub fn apply_while_active_effects(
     query: Query<(&WhileActiveEffects, &ActiveAbility)>,
     mut caster_query: Query<(&Rotation, &mut LinearVelocity)>,
@@ -196,16 +435,16 @@ pub fn apply_while_active_effects(
         if active.phase != AbilityPhase::Active {
             continue;
         }
-        for effect in &effects.0 {
+        for effect in by_priority(&effects.0) {
             match effect {
                 AbilityEffect::SetVelocity { speed, target } => {
-                    let target_entity = resolve_caster_target(&target, active);
+                    let target_entity = resolve_caster_target(target, active);
                     if let Ok((rotation, mut velocity)) = caster_query.get_mut(target_entity) {
-                        let direction = super::types::facing_direction(rotation);
-                        velocity.x = direction.x * speed;
-                        velocity.z = direction.z * speed;
+                        set_facing_velocity(&mut velocity, rotation, *speed);
                     }
                 }
+                // Needs a spatial query; applied by `apply_grapple_effects`.
+                AbilityEffect::Grapple { .. } => {}
                 _ => {
                     warn!("Unhandled WhileActive effect: {:?}", effect);
                 }
@@ -214,54 +453,141 @@ pub fn apply_while_active_effects(
     }
 }
 
-pub fn apply_on_end_effects(
+/// Latches each Active `Grapple` ability onto the first character, terrain or
+/// damageable object its caster faces within `max_distance`, then sets the
+/// caster's velocity toward that point every tick until it arrives.
+pub fn apply_grapple_effects(
     mut commands: Commands,
-    ability_defs: Res<AbilityDefs>,
-    ability_assets: Res<Assets<AbilityAsset>>,
-    registry: Res<AppTypeRegistry>,
-    timeline: Res<LocalTimeline>,
-    server_query: Query<&ControlledBy>,
-    player_id_query: Query<&PlayerId>,
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    map_ids: Query<&MapInstanceId>,
+    query: Query<(
+        Entity,
+        &WhileActiveEffects,
+        &ActiveAbility,
+        Option<&GrappleAnchor>,
+    )>,
+    mut caster_query: Query<(
+        &Position,
+        &Rotation,
+        &mut LinearVelocity,
+        &CharacterDimensions,
+    )>,
+) {
+    for (entity, effects, active, anchor) in &query {
+        if active.phase != AbilityPhase::Active {
+            continue;
+        }
+        let Some((speed, max_distance)) = effects.0.iter().find_map(|effect| match effect {
+            AbilityEffect::Grapple {
+                speed,
+                max_distance,
+            } => Some((*speed, *max_distance)),
+            _ => None,
+        }) else {
+            continue;
+        };
+        let Ok((position, rotation, mut velocity, dimensions)) =
+            caster_query.get_mut(active.caster)
+        else {
+            warn!("Grapple: caster {:?} is not a character", active.caster);
+            continue;
+        };
+
+        let point = match anchor {
+            Some(GrappleAnchor(point)) => *point,
+            None => {
+                let point = cast_grapple_ray(
+                    &spatial_query,
+                    &map_ids,
+                    active.caster,
+                    position.0,
+                    super::types::facing_direction(rotation),
+                    max_distance,
+                );
+                if point.is_none() {
+                    trace!(
+                        "Grapple from {:?} found nothing to latch onto",
+                        active.caster
+                    );
+                }
+                commands.entity(entity).insert(GrappleAnchor(point));
+                point
+            }
+        };
+        let Some(point) = point else {
+            continue;
+        };
+
+        let offset = point - position.0;
+        let remaining = offset.length() - (dimensions.radius + GRAPPLE_STOP_MARGIN);
+        if remaining <= GRAPPLE_ARRIVAL_TOLERANCE {
+            velocity.0 = Vec3::ZERO;
+            commands.entity(entity).insert(GrappleAnchor(None));
+            continue;
+        }
+        // Never overshoot the stopping distance within one tick.
+        let step_speed = speed.min(remaining / time.delta_secs());
+        velocity.0 = offset.normalize() * step_speed;
+    }
+}
+
+/// First hit point of a ray from `origin` along `direction`, ignoring the caster,
+/// hitboxes, projectiles and anything on another map.
+fn cast_grapple_ray(
+    spatial_query: &SpatialQuery,
+    map_ids: &Query<&MapInstanceId>,
+    caster: Entity,
+    origin: Vec3,
+    direction: Vec3,
+    max_distance: f32,
+) -> Option<Vec3> {
+    let direction = Dir3::new(direction).ok()?;
+    let filter = SpatialQueryFilter::from_mask([
+        GameLayer::Character,
+        GameLayer::Terrain,
+        GameLayer::Damageable,
+    ])
+    .with_excluded_entities([caster]);
+    let caster_map = map_ids.get(caster).ok();
+    let hit = spatial_query.cast_ray_predicate(
+        origin,
+        direction,
+        max_distance,
+        true,
+        &filter,
+        &|hit_entity| match (caster_map, map_ids.get(hit_entity).ok()) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        },
+    )?;
+    Some(origin + *direction * hit.distance)
+}
+
+pub fn apply_on_end_effects(
+    mut caster_effects: CasterEffects,
     query: Query<(Entity, &OnEndEffects, &ActiveAbility)>,
     mut caster_query: Query<(&mut Position, &Rotation, &mut LinearVelocity)>,
 ) {
-    let tick = timeline.tick();
+    let tick = caster_effects.timeline.tick();
     for (_entity, effects, active) in &query {
         if active.phase != AbilityPhase::Recovery || active.phase_start_tick != tick {
             continue;
         }
-        for effect in &effects.0 {
+        for effect in by_priority(&effects.0) {
+            if caster_effects.apply(effect, active) {
+                continue;
+            }
             match effect {
                 AbilityEffect::SetVelocity { speed, target } => {
                     let target_entity = resolve_caster_target(target, active);
                     if let Ok((_, rotation, mut velocity)) = caster_query.get_mut(target_entity) {
-                        let direction = super::types::facing_direction(rotation);
-                        velocity.x = direction.x * speed;
-                        velocity.z = direction.z * speed;
+                        set_facing_velocity(&mut velocity, rotation, *speed);
                     }
                 }
-                AbilityEffect::Ability { id, target } => {
-                    let target_entity = resolve_caster_target(target, active);
-                    spawn_sub_ability(
-                        &mut commands,
-                        ability_defs.as_ref(),
-                        ability_assets.as_ref(),
-                        &registry.0,
-                        id,
-                        target_entity,
-                        active.original_caster,
-                        active.ability_slot,
-                        active.depth,
-                        tick,
-                        &server_query,
-                        &player_id_query,
-                    );
-                }
                 AbilityEffect::Teleport { distance } => {
-                    let target_entity = resolve_caster_target(&EffectTarget::Caster, active);
-                    if let Ok((mut position, rotation, _)) = caster_query.get_mut(target_entity) {
-                        let direction = super::types::facing_direction(rotation);
-                        position.0 += direction * *distance;
+                    if let Ok((mut position, rotation, _)) = caster_query.get_mut(active.caster) {
+                        teleport_forward(&mut position, rotation, *distance);
                     } else {
                         warn!(
                             "Teleport: caster {:?} missing Position/Rotation",
@@ -269,26 +595,6 @@ pub fn apply_on_end_effects(
                         );
                     }
                 }
-                AbilityEffect::Shield { absorb } => {
-                    commands
-                        .entity(active.caster)
-                        .insert(ActiveShield { remaining: *absorb });
-                }
-                AbilityEffect::Buff {
-                    stat,
-                    multiplier,
-                    duration_ticks,
-                    target,
-                } => {
-                    apply_buff(
-                        &mut commands,
-                        resolve_caster_target(target, active),
-                        stat,
-                        *multiplier,
-                        *duration_ticks,
-                        tick,
-                    );
-                }
                 _ => {
                     warn!("Unhandled OnEnd effect: {:?}", effect);
                 }
@@ -346,34 +652,114 @@ pub fn apply_on_input_effects(
     }
 }
 
+/// Voxel cells of a `length`×`height` wall standing on the voxel layer at `feet`,
+/// centered `VOXEL_WALL_DISTANCE` ahead along `facing` and running across it.
+pub fn voxel_wall_cells(feet: Vec3, facing: Vec3, length: u32, height: u32) -> Vec<IVec3> {
+    let forward = Vec3::new(facing.x, 0.0, facing.z).normalize_or(Vec3::NEG_Z);
+    let across = Vec3::new(-forward.z, 0.0, forward.x);
+    let center = feet + forward * VOXEL_WALL_DISTANCE;
+    let base_y = feet.y.round() as i32;
+    let mut cells = Vec::new();
+    for i in 0..length {
+        let along = center + across * (i as f32 - (length as f32 - 1.0) / 2.0);
+        for y in 0..height as i32 {
+            let cell = IVec3::new(along.x.floor() as i32, base_y + y, along.z.floor() as i32);
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+    }
+    cells
+}
+
 fn apply_teleport(
     caster_query: &mut Query<(&mut Position, &Rotation, &MapInstanceId)>,
     caster: Entity,
     distance: f32,
 ) {
     if let Ok((mut position, rotation, _)) = caster_query.get_mut(caster) {
-        let direction = super::types::facing_direction(rotation);
-        position.0 += direction * distance;
+        teleport_forward(&mut position, rotation, distance);
     } else {
         warn!("Teleport: caster {:?} missing Position/Rotation", caster);
     }
 }
 
-fn apply_buff(
+/// Stuns `target_entity` until `duration_ticks` from `tick`, keeping any
+/// existing stun that lasts longer.
+pub(crate) fn apply_stun(
+    commands: &mut Commands,
+    target_entity: Entity,
+    duration_ticks: u16,
+    tick: Tick,
+) {
+    let expires_tick = tick + duration_ticks as i16;
+    commands
+        .entity(target_entity)
+        .queue(
+            move |mut entity: EntityWorldMut| match entity.get_mut::<Stunned>() {
+                Some(mut stunned) => {
+                    let extends: i16 = expires_tick - stunned.expires_tick;
+                    if extends > 0 {
+                        stunned.expires_tick = expires_tick;
+                    }
+                }
+                None => {
+                    entity.insert(Stunned { expires_tick });
+                }
+            },
+        );
+}
+
+/// Adds a buff from `source` to the target's `ActiveBuffs`, refreshing one
+/// `source` already applied to `stat`. `max_health` buffs also rescale
+/// `Health.max`, recording the unbuffed max in `BaseMaxHealth` on first use.
+pub(crate) fn apply_buff(
     commands: &mut Commands,
     target_entity: Entity,
     stat: &str,
     multiplier: f32,
     duration_ticks: u16,
     tick: Tick,
+    source: AbilityId,
 ) {
-    use super::types::{ActiveBuff, ActiveBuffs};
-    let expires_tick = tick + duration_ticks as i16;
+    use super::types::{ActiveBuff, ActiveBuffs, BaseMaxHealth, MAX_HEALTH_STAT};
+    let buff = ActiveBuff {
+        stat: stat.to_string(),
+        multiplier,
+        expires_tick: tick + duration_ticks as i16,
+        source,
+    };
     commands
         .entity(target_entity)
-        .insert(ActiveBuffs(vec![ActiveBuff {
-            stat: stat.to_string(),
-            multiplier,
-            expires_tick,
-        }]));
+        .queue(move |mut entity: EntityWorldMut| {
+            let is_max_health = buff.stat == MAX_HEALTH_STAT;
+            match entity.get_mut::<ActiveBuffs>() {
+                Some(mut buffs) => buffs.add(buff),
+                None => {
+                    entity.insert(ActiveBuffs(vec![buff]));
+                }
+            }
+            if !is_max_health {
+                return;
+            }
+            let Some(current_max) = entity.get::<Health>().map(|h| h.max) else {
+                warn!("max_health buff on {:?} without Health", entity.id());
+                return;
+            };
+            let base = match entity.get::<BaseMaxHealth>() {
+                Some(base) => base.0,
+                None => {
+                    entity.insert(BaseMaxHealth(current_max));
+                    current_max
+                }
+            };
+            let multiplier = entity
+                .get::<ActiveBuffs>()
+                .expect("ActiveBuffs inserted above")
+                .multiplier(MAX_HEALTH_STAT);
+            entity
+                .get_mut::<Health>()
+                .expect("Health checked above")
+                .set_max_preserving_fraction(base * multiplier);
+        });
 }
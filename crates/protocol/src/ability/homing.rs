@@ -0,0 +1,110 @@
+use super::aim_assist::steer_toward;
+use super::types::{
+    AbilityBulletOf, AbilityProjectileSpawn, HomingProjectile, HomingTarget, Minion, StuckTo,
+};
+use crate::hit_detection::{relation_to, GameLayer};
+use crate::map::MapInstanceId;
+use crate::{CharacterMarker, Health, RespawnTimer};
+use avian3d::prelude::*;
+use bevy::prelude::*;
+
+/// Steers [`HomingProjectile`] bullets toward their [`HomingTarget`] at up to
+/// `turn_rate` radians per second. A target that dies, leaves range, stops
+/// matching `affects` or goes behind terrain is dropped and the nearest valid
+/// character is locked instead.
+pub fn home_projectiles(
+    time: Res<Time>,
+    spatial_query: SpatialQuery,
+    mut bullets: Query<
+        (
+            &Position,
+            &mut LinearVelocity,
+            &HomingProjectile,
+            &mut HomingTarget,
+            &AbilityBulletOf,
+            &MapInstanceId,
+        ),
+        Without<StuckTo>,
+    >,
+    spawns: Query<&AbilityProjectileSpawn>,
+    map_ids: Query<&MapInstanceId>,
+    minion_query: Query<&Minion>,
+    targets: Query<
+        (Entity, &Position, &Health, &MapInstanceId),
+        (With<CharacterMarker>, Without<RespawnTimer>),
+    >,
+) {
+    for (position, mut velocity, homing, mut target, bullet_of, map_id) in &mut bullets {
+        let Ok(spawn) = spawns.get(bullet_of.0) else {
+            trace!("home_projectiles: bullet spawn {:?} is gone", bullet_of.0);
+            continue;
+        };
+        // The target's entity and offset from the bullet, if it is valid.
+        let valid_offset = |(entity, target_pos, health, target_map): (
+            Entity,
+            &Position,
+            &Health,
+            &MapInstanceId,
+        )| {
+            let offset = target_pos.0 - position.0;
+            let valid = health.current > 0.0
+                && target_map == map_id
+                && offset.length() <= homing.range
+                && homing
+                    .affects
+                    .allows(relation_to(entity, spawn.shooter, &minion_query))
+                && (!homing.line_of_sight
+                    || has_line_of_sight(&spatial_query, &map_ids, map_id, position.0, offset));
+            valid.then_some((entity, offset))
+        };
+
+        let locked = target
+            .0
+            .and_then(|entity| targets.get(entity).ok())
+            .and_then(valid_offset);
+        let acquired = locked.or_else(|| {
+            targets
+                .iter()
+                .filter_map(valid_offset)
+                .min_by(|(_, a), (_, b)| a.length().total_cmp(&b.length()))
+        });
+        let Some((entity, to_target)) = acquired else {
+            if target.0.is_some() {
+                trace!("home_projectiles: no valid target left, flying straight");
+                target.0 = None;
+            }
+            continue;
+        };
+        target.0 = Some(entity);
+        velocity.0 = steer_toward(velocity.0, to_target, homing.turn_rate * time.delta_secs());
+    }
+}
+
+/// Whether no terrain on `map_id` lies between `origin` and `origin + offset`.
+fn has_line_of_sight(
+    spatial_query: &SpatialQuery,
+    map_ids: &Query<&MapInstanceId>,
+    map_id: &MapInstanceId,
+    origin: Vec3,
+    offset: Vec3,
+) -> bool {
+    let Ok(direction) = Dir3::new(offset) else {
+        trace!("has_line_of_sight: zero offset counts as visible");
+        return true;
+    };
+    let filter = SpatialQueryFilter::from_mask([GameLayer::Terrain]);
+    spatial_query
+        .cast_ray_predicate(
+            origin,
+            direction,
+            offset.length(),
+            true,
+            &filter,
+            &|hit_entity| {
+                !map_ids
+                    .get(hit_entity)
+                    .is_ok_and(|hit_map| hit_map != map_id)
+            },
+        )
+        .is_none()
+}
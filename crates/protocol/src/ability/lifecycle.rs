@@ -1,26 +1,163 @@
 use super::types::{
-    AbilityBulletOf, AbilityProjectileSpawn, ActiveAbility, ActiveBuffs, AoEHitbox,
+    AbilityBulletOf, AbilityMissEvent, AbilityProjectileSpawn, ActiveAbility, ActiveBuffs,
+    ActiveDots, ActiveShield, AoEHitbox, BaseMaxHealth, DamagedThisTick, Minion, Resistances,
+    ResourcePool, StuckTo, Stunned, MAX_HEALTH_STAT,
 };
+use crate::hit_detection::deal_damage;
+use crate::{DeathEvent, Health, HealthRegen, Invulnerable, LastDamagedTick, RespawnTimer};
+use avian3d::prelude::Position;
+use bevy::ecs::message::MessageWriter;
 use bevy::prelude::*;
 use lightyear::prelude::LocalTimeline;
 
+/// Drops expired buffs. When a `max_health` buff expires, `Health.max` is
+/// recomputed from `BaseMaxHealth`, keeping the current health fraction.
 pub fn expire_buffs(
     mut commands: Commands,
     timeline: Res<LocalTimeline>,
-    mut query: Query<(Entity, &mut ActiveBuffs)>,
+    mut query: Query<(
+        Entity,
+        &mut ActiveBuffs,
+        Option<&mut Health>,
+        Option<&BaseMaxHealth>,
+    )>,
 ) {
     let tick = timeline.tick();
-    for (entity, mut buffs) in &mut query {
+    for (entity, mut buffs, health, base_max) in &mut query {
+        let had_max_health = buffs.has(MAX_HEALTH_STAT);
         buffs.0.retain(|b| {
             let remaining: i16 = b.expires_tick - tick;
             remaining > 0
         });
+        if let (true, Some(mut health), Some(base_max)) = (had_max_health, health, base_max) {
+            health.set_max_preserving_fraction(base_max.0 * buffs.multiplier(MAX_HEALTH_STAT));
+            if !buffs.has(MAX_HEALTH_STAT) {
+                commands.entity(entity).remove::<BaseMaxHealth>();
+            }
+        }
         if buffs.0.is_empty() {
             commands.entity(entity).remove::<ActiveBuffs>();
         }
     }
 }
 
+/// Refills each `ResourcePool` by its `regen_per_tick`, clamped to `max`.
+pub fn regenerate_resource_pools(mut query: Query<&mut ResourcePool>) {
+    for mut pool in &mut query {
+        if pool.current < pool.max {
+            pool.current = (pool.current + pool.regen_per_tick).min(pool.max);
+        }
+    }
+}
+
+/// Restores `HealthRegen::per_tick` health each tick to living entities that
+/// have gone `combat_delay_ticks` since their `LastDamagedTick`, dropping that
+/// marker once the delay has passed so old ticks never wrap around into a new
+/// pause. Dead and respawning entities are left to the respawn flow.
+pub fn regenerate_health(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    mut query: Query<
+        (Entity, &HealthRegen, &mut Health, Option<&LastDamagedTick>),
+        Without<RespawnTimer>,
+    >,
+) {
+    let tick = timeline.tick();
+    for (entity, regen, mut health, last_damaged) in &mut query {
+        if health.is_dead() {
+            trace!("Skipping health regen for dead {:?}", entity);
+            continue;
+        }
+        if let Some(last_damaged) = last_damaged {
+            let since_damaged: i16 = tick - last_damaged.0;
+            if since_damaged < regen.combat_delay_ticks as i16 {
+                trace!("Skipping health regen for {entity:?}, damaged {since_damaged} ticks ago");
+                continue;
+            }
+            commands.entity(entity).remove::<LastDamagedTick>();
+        }
+        if health.current < health.max {
+            health.heal(regen.per_tick);
+        }
+    }
+}
+
+/// Drops every `DamagedThisTick` marker once `update_active_abilities` has
+/// seen it.
+pub fn clear_damaged_this_tick(
+    mut commands: Commands,
+    query: Query<Entity, With<DamagedThisTick>>,
+) {
+    for entity in &query {
+        commands.entity(entity).remove::<DamagedThisTick>();
+    }
+}
+
+/// Removes `Stunned` once its `expires_tick` is reached.
+pub fn expire_stuns(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    query: Query<(Entity, &Stunned)>,
+) {
+    let tick = timeline.tick();
+    for (entity, stunned) in &query {
+        let remaining: i16 = stunned.expires_tick - tick;
+        if remaining <= 0 {
+            commands.entity(entity).remove::<Stunned>();
+        }
+    }
+}
+
+/// Applies each due `ActiveDot` tick through the target's resistances and
+/// shield like a direct hit, then drops expired DoTs. Kills by DoT credit no
+/// ability.
+pub fn tick_damage_over_time(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    mut dot_query: Query<(Entity, &mut ActiveDots)>,
+    mut target_query: Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    mut shield_query: Query<&mut ActiveShield>,
+    mut death_events: MessageWriter<DeathEvent>,
+) {
+    let tick = timeline.tick();
+    for (entity, mut dots) in &mut dot_query {
+        for dot in dots.0.iter_mut() {
+            let due: i16 = tick - dot.next_tick;
+            let remaining: i16 = dot.expires_tick - dot.next_tick;
+            if due >= 0 && remaining >= 0 {
+                deal_damage(
+                    &mut commands,
+                    None,
+                    entity,
+                    dot.amount,
+                    dot.damage_type,
+                    tick,
+                    &mut target_query,
+                    &mut shield_query,
+                    &mut death_events,
+                );
+                dot.next_tick = dot.next_tick + dot.interval_ticks as i16;
+            }
+        }
+        dots.0.retain(|dot| {
+            let remaining: i16 = dot.expires_tick - dot.next_tick;
+            remaining >= 0
+        });
+
+        let dead = target_query
+            .get(entity)
+            .is_ok_and(|(_, health, ..)| health.is_dead());
+        if dead || dots.0.is_empty() {
+            commands.entity(entity).remove::<ActiveDots>();
+        }
+    }
+}
+
 /// Despawn the entity whenever its `ActiveAbility` component is removed.
 ///
 /// Two important paths trigger this:
@@ -72,11 +209,15 @@ pub fn aoe_hitbox_lifetime(
     }
 }
 
+/// Despawns bullets whose flight lifetime ran out. Bullets despawn on impact,
+/// so one still flying at expiry missed and emits [`AbilityMissEvent`].
 pub fn ability_bullet_lifetime(
     mut commands: Commands,
     timeline: Res<LocalTimeline>,
-    query: Query<(Entity, &AbilityBulletOf)>,
+    // Stuck bullets live until they detonate, regardless of flight lifetime.
+    query: Query<(Entity, &AbilityBulletOf), Without<StuckTo>>,
     spawn_query: Query<&AbilityProjectileSpawn>,
+    mut miss_events: MessageWriter<AbilityMissEvent>,
 ) {
     let tick = timeline.tick();
     for (entity, bullet_of) in &query {
@@ -84,7 +225,26 @@ pub fn ability_bullet_lifetime(
             let elapsed = tick - spawn_info.spawn_tick;
             if elapsed >= spawn_info.lifetime_ticks as i16 {
                 commands.entity(entity).try_despawn();
+                miss_events.write(AbilityMissEvent {
+                    caster: spawn_info.shooter,
+                    ability_id: spawn_info.ability_id.clone(),
+                });
             }
         }
     }
 }
+
+/// Despawn minions once their lifetime runs out or they die. Minions never respawn.
+pub fn minion_lifetime(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    query: Query<(Entity, &Minion, &Health)>,
+) {
+    let tick = timeline.tick();
+    for (entity, minion, health) in &query {
+        let remaining: i16 = minion.expires_tick - tick;
+        if remaining <= 0 || health.is_dead() {
+            commands.entity(entity).try_despawn();
+        }
+    }
+}
@@ -1,9 +1,19 @@
-use super::types::{AbilityAsset, AbilityPhases, ConditionalEffects, OnTickEffects, TickEffect};
+use super::types::{
+    AbilityAsset, AbilityCost, AbilityDef, AbilityEffect, AbilityPhases, ConditionalEffects,
+    EffectTrigger, GroundTarget, InputEffect, MaxCharges, MaxRange, OnCastEffects, OnEndEffects,
+    OnHitEffectDefs, OnInputEffects, OnTickEffects, ResetCooldownOnKill, TickEffect,
+    WhileActiveEffects,
+};
+use crate::reflect_loader::{deserialize_versioned_component_map, ReflectLoadError};
 use bevy::asset::io::Reader;
 use bevy::asset::{AssetLoader, LoadContext};
 use bevy::ecs::reflect::ReflectComponent;
 use bevy::prelude::*;
-use bevy::reflect::{PartialReflect, TypeRegistryArc};
+use bevy::reflect::{PartialReflect, TypeRegistry, TypeRegistryArc};
+
+/// Current `.ability.ron` format: a flat component map, optionally tagged
+/// `"version": 2`. Version 1 was a single [`AbilityDef`] struct.
+pub const ABILITY_FORMAT_VERSION: u32 = 2;
 
 /// Extract AbilityPhases from an AbilityAsset's reflected components.
 pub fn extract_phases(asset: &AbilityAsset) -> Option<&AbilityPhases> {
@@ -48,6 +58,76 @@ pub fn extract_on_tick_effects(asset: &AbilityAsset) -> Option<&OnTickEffects> {
     None
 }
 
+/// Extract `GroundTarget` from an `AbilityAsset`'s reflected components.
+pub fn extract_ground_target(asset: &AbilityAsset) -> Option<&GroundTarget> {
+    let target_id = std::any::TypeId::of::<GroundTarget>();
+    for reflected in &asset.components {
+        let info = reflected
+            .get_represented_type_info()
+            .expect("AbilityAsset should have type info");
+        if info.type_id() == target_id {
+            return reflected.try_downcast_ref::<GroundTarget>();
+        }
+    }
+    None
+}
+
+/// Extract `AbilityCost` from an `AbilityAsset`'s reflected components.
+pub fn extract_cost(asset: &AbilityAsset) -> Option<&AbilityCost> {
+    let target_id = std::any::TypeId::of::<AbilityCost>();
+    for reflected in &asset.components {
+        let info = reflected
+            .get_represented_type_info()
+            .expect("AbilityAsset should have type info");
+        if info.type_id() == target_id {
+            return reflected.try_downcast_ref::<AbilityCost>();
+        }
+    }
+    None
+}
+
+/// Extract `MaxCharges` from an `AbilityAsset`'s reflected components.
+pub fn extract_max_charges(asset: &AbilityAsset) -> Option<&MaxCharges> {
+    let target_id = std::any::TypeId::of::<MaxCharges>();
+    for reflected in &asset.components {
+        let info = reflected
+            .get_represented_type_info()
+            .expect("AbilityAsset should have type info");
+        if info.type_id() == target_id {
+            return reflected.try_downcast_ref::<MaxCharges>();
+        }
+    }
+    None
+}
+
+/// Extract `MaxRange` from an `AbilityAsset`'s reflected components.
+pub fn extract_max_range(asset: &AbilityAsset) -> Option<&MaxRange> {
+    let target_id = std::any::TypeId::of::<MaxRange>();
+    for reflected in &asset.components {
+        let info = reflected
+            .get_represented_type_info()
+            .expect("AbilityAsset should have type info");
+        if info.type_id() == target_id {
+            return reflected.try_downcast_ref::<MaxRange>();
+        }
+    }
+    None
+}
+
+/// Extract `ResetCooldownOnKill` from an `AbilityAsset`'s reflected components.
+pub fn extract_reset_cooldown_on_kill(asset: &AbilityAsset) -> Option<&ResetCooldownOnKill> {
+    let target_id = std::any::TypeId::of::<ResetCooldownOnKill>();
+    for reflected in &asset.components {
+        let info = reflected
+            .get_represented_type_info()
+            .expect("AbilityAsset should have type info");
+        if info.type_id() == target_id {
+            return reflected.try_downcast_ref::<ResetCooldownOnKill>();
+        }
+    }
+    None
+}
+
 /// Insert all reflected components from an `AbilityAsset` onto an entity.
 ///
 /// `extra_tick_effects` is appended to the asset's `OnTickEffects` (or a new
@@ -94,6 +174,99 @@ pub(crate) fn apply_ability_archetype(
     }
 }
 
+/// Upgrades a version 1 [`AbilityDef`] into the components of the current
+/// format: its tick fields become [`AbilityPhases`] and each `effects` entry
+/// moves into the archetype component for its trigger.
+pub fn migrate_v1_ability_def(def: AbilityDef) -> AbilityAsset {
+    let mut on_tick = Vec::new();
+    let mut on_cast = Vec::new();
+    let mut while_active = Vec::new();
+    let mut on_hit = Vec::new();
+    let mut on_end = Vec::new();
+    let mut on_input = Vec::new();
+    for trigger in def.effects {
+        match trigger {
+            EffectTrigger::OnTick { tick, effect } => on_tick.push(TickEffect { tick, effect }),
+            EffectTrigger::OnCast(effect) => on_cast.push(effect),
+            EffectTrigger::WhileActive(effect) => while_active.push(effect),
+            EffectTrigger::OnHit(effect) => on_hit.push(effect),
+            EffectTrigger::OnHitIf {
+                below_health_fraction,
+                effect,
+            } => on_hit.push(AbilityEffect::OnHitIf {
+                below_health_fraction,
+                effects: vec![effect],
+            }),
+            EffectTrigger::OnEnd(effect) => on_end.push(effect),
+            EffectTrigger::OnInput { action, effect } => {
+                on_input.push(InputEffect { action, effect })
+            }
+        }
+    }
+
+    let mut components: Vec<Box<dyn PartialReflect>> = vec![Box::new(AbilityPhases {
+        startup: def.startup_ticks,
+        active: def.active_ticks,
+        recovery: def.recovery_ticks,
+        cooldown: def.cooldown_ticks,
+    })
+    .into_partial_reflect()];
+    if def.cost > 0.0 {
+        components.push(Box::new(AbilityCost { cost: def.cost }).into_partial_reflect());
+    }
+    if let Some(charges) = def.max_charges {
+        let max_charges = MaxCharges {
+            charges,
+            refill_ticks: def.charge_refill_ticks,
+        };
+        components.push(Box::new(max_charges).into_partial_reflect());
+    }
+    if !on_tick.is_empty() {
+        components.push(Box::new(OnTickEffects(on_tick)).into_partial_reflect());
+    }
+    if !on_cast.is_empty() {
+        components.push(Box::new(OnCastEffects(on_cast)).into_partial_reflect());
+    }
+    if !while_active.is_empty() {
+        components.push(Box::new(WhileActiveEffects(while_active)).into_partial_reflect());
+    }
+    if !on_hit.is_empty() {
+        components.push(Box::new(OnHitEffectDefs(on_hit)).into_partial_reflect());
+    }
+    if !on_end.is_empty() {
+        components.push(Box::new(OnEndEffects(on_end)).into_partial_reflect());
+    }
+    if !on_input.is_empty() {
+        components.push(Box::new(OnInputEffects(on_input)).into_partial_reflect());
+    }
+    AbilityAsset { components }
+}
+
+/// Parses `.ability.ron` bytes of any supported format version. A file that
+/// isn't a component map is retried as a version 1 [`AbilityDef`] and
+/// migrated; if that fails too, the component map error is returned.
+pub fn parse_ability_asset(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+    path: &str,
+) -> Result<AbilityAsset, ReflectLoadError> {
+    match deserialize_versioned_component_map(bytes, registry) {
+        Ok(map) => match map.version {
+            None | Some(ABILITY_FORMAT_VERSION) => Ok(AbilityAsset {
+                components: map.components,
+            }),
+            Some(version) => Err(ReflectLoadError::UnsupportedVersion(version)),
+        },
+        Err(map_error) => {
+            let Ok(def) = ron::de::from_bytes::<AbilityDef>(bytes) else {
+                return Err(map_error);
+            };
+            info!("Migrated version 1 ability def {path} to version {ABILITY_FORMAT_VERSION}");
+            Ok(migrate_v1_ability_def(def))
+        }
+    }
+}
+
 /// Custom asset loader for `.ability.ron` files using reflect-based deserialization.
 #[derive(TypePath)]
 pub(super) struct AbilityAssetLoader {
@@ -111,7 +284,7 @@ impl FromWorld for AbilityAssetLoader {
 impl AssetLoader for AbilityAssetLoader {
     type Asset = AbilityAsset;
     type Settings = ();
-    type Error = crate::reflect_loader::ReflectLoadError;
+    type Error = ReflectLoadError;
 
     fn extensions(&self) -> &[&str] {
         &["ability.ron"]
@@ -121,13 +294,13 @@ impl AssetLoader for AbilityAssetLoader {
         &self,
         reader: &mut dyn Reader,
         _settings: &Self::Settings,
-        _load_context: &mut LoadContext<'_>,
+        load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
         let mut bytes = Vec::new();
         reader.read_to_end(&mut bytes).await?;
         let registry = self.type_registry.read();
-        let components = crate::reflect_loader::deserialize_component_map(&bytes, &registry)?;
-        Ok(AbilityAsset { components })
+        let path = load_context.path().display().to_string();
+        parse_ability_asset(&bytes, &registry, &path)
     }
 }
 
@@ -135,11 +308,27 @@ impl AssetLoader for AbilityAssetLoader {
 mod tests {
     use super::*;
     use crate::ability::types::{
-        AbilityAsset, AbilityEffect, Condition, ConditionalEffect, ConditionalEffects,
+        AbilityAsset, AbilityEffect, Condition, ConditionalEffect, ConditionalEffects, DamageType,
         EffectTarget, ForceFrame,
     };
+    use crate::PlayerActions;
     use bevy::math::Vec3;
 
+    fn ability_test_registry() -> TypeRegistry {
+        let mut registry = TypeRegistry::default();
+        registry.register::<AbilityPhases>();
+        registry.register::<OnTickEffects>();
+        registry.register::<TickEffect>();
+        registry.register::<OnHitEffectDefs>();
+        registry.register::<OnInputEffects>();
+        registry.register::<InputEffect>();
+        registry.register::<AbilityEffect>();
+        registry.register::<EffectTarget>();
+        registry.register::<ForceFrame>();
+        registry.register::<PlayerActions>();
+        registry
+    }
+
     fn synth_asset(ce: ConditionalEffects) -> AbilityAsset {
         AbilityAsset {
             components: vec![Box::new(ce).into_partial_reflect()],
@@ -194,4 +383,71 @@ mod tests {
         assert_eq!(pick(false).len(), 1);
         assert_ne!(pick(true), pick(false));
     }
+
+    #[test]
+    fn v1_ability_def_migrates_to_components() {
+        let registry = ability_test_registry();
+        let ron = br#"(
+            startup_ticks: 4,
+            active_ticks: 20,
+            recovery_ticks: 0,
+            cooldown_ticks: 16,
+            effects: [
+                OnTick(tick: 0, effect: Melee()),
+                OnHit(Damage(amount: 5.0, target: Victim)),
+            ],
+        )"#;
+        let asset = parse_ability_asset(ron, &registry, "test.ability.ron").unwrap();
+
+        assert_eq!(
+            extract_phases(&asset),
+            Some(&AbilityPhases {
+                startup: 4,
+                active: 20,
+                recovery: 0,
+                cooldown: 16,
+            })
+        );
+        let on_tick = extract_on_tick_effects(&asset).expect("OnTick should migrate");
+        assert_eq!(on_tick.0.len(), 1);
+        assert_eq!(on_tick.0[0].tick, 0);
+        let on_hit = asset
+            .components
+            .iter()
+            .find_map(|c| c.try_downcast_ref::<OnHitEffectDefs>())
+            .expect("OnHit should migrate");
+        assert_eq!(
+            on_hit.0,
+            vec![AbilityEffect::Damage {
+                amount: 5.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }]
+        );
+        assert_eq!(
+            asset.components.len(),
+            3,
+            "empty triggers add no components"
+        );
+    }
+
+    #[test]
+    fn current_version_loads_and_unknown_version_is_rejected() {
+        let registry = ability_test_registry();
+        let current = br#"{
+            "version": 2,
+            "protocol::ability::AbilityPhases": (startup: 4, active: 20, recovery: 0, cooldown: 16),
+        }"#;
+        let asset = parse_ability_asset(current, &registry, "current.ability.ron").unwrap();
+        assert!(extract_phases(&asset).is_some());
+
+        let future = br#"{
+            "version": 3,
+            "protocol::ability::AbilityPhases": (startup: 4, active: 20, recovery: 0, cooldown: 16),
+        }"#;
+        assert!(matches!(
+            parse_ability_asset(future, &registry, "future.ability.ron"),
+            Err(ReflectLoadError::UnsupportedVersion(3))
+        ));
+    }
 }
@@ -2,6 +2,7 @@
 use super::types::AbilityManifest;
 use super::types::{AbilityAsset, AbilityDefs, AbilityId, AbilitySlots};
 use crate::app_state::TrackedAssets;
+use bevy::asset::AssetLoadFailedEvent;
 use bevy::prelude::*;
 use std::collections::HashMap;
 
@@ -99,6 +100,16 @@ pub(super) fn insert_ability_defs(
         trace!("ability folder handle not yet loaded");
         return;
     };
+    if asset_server
+        .get_load_state(folder_handle.0.id())
+        .is_some_and(|state| state.is_failed())
+    {
+        error!("Failed to load abilities folder; continuing with no ability definitions");
+        commands.insert_resource(AbilityDefs {
+            abilities: HashMap::new(),
+        });
+        return;
+    }
     let Some(folder) = loaded_folders.get(&folder_handle.0) else {
         trace!("ability folder not yet available in Assets<LoadedFolder>");
         return;
@@ -282,3 +293,26 @@ pub(super) fn sync_default_ability_slots(
     trace!("Synced default ability slots");
     commands.insert_resource(DefaultAbilitySlots(slots.clone()));
 }
+
+/// Logs when `default.ability_slots.ron` fails to load. `DefaultAbilitySlots` keeps
+/// its empty default, so characters without their own `AbilitySlots` cast nothing.
+pub(super) fn report_default_ability_slots_failure(
+    // Optional because only `load_default_ability_slots` inserts it: an app
+    // that never starts that load has no handle and nothing to report.
+    handle: Option<Res<DefaultAbilitySlotsHandle>>,
+    mut failures: MessageReader<AssetLoadFailedEvent<AbilitySlots>>,
+) {
+    let Some(handle) = handle else {
+        trace!("No default ability slots load to report failures for");
+        failures.clear();
+        return;
+    };
+    for failure in failures.read() {
+        if failure.id == handle.0.id() {
+            error!(
+                "Failed to load {}: {}; falling back to empty default ability slots",
+                failure.path, failure.error
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use super::types::{ActiveShield, DamageType, Minion, Resistances};
+use crate::hit_detection::deal_damage;
+use crate::{CharacterMarker, DeathEvent, Health, Invulnerable, RespawnTimer};
+use avian3d::prelude::*;
+use bevy::ecs::message::MessageWriter;
+use bevy::prelude::*;
+use lightyear::prelude::LocalTimeline;
+
+const MINION_SPEED: f32 = 6.0;
+const MINION_AGGRO_RANGE: f32 = 10.0;
+const MINION_ATTACK_RANGE: f32 = 1.5;
+const MINION_ATTACK_DAMAGE: f32 = 5.0;
+const MINION_ATTACK_INTERVAL_TICKS: u16 = 32;
+const MINION_FOLLOW_DISTANCE: f32 = 3.0;
+
+/// Chases the nearest hostile character within aggro range and hits it on a
+/// fixed cadence once in reach; otherwise walks back toward its owner.
+/// Hostile means anything other than the owner and the owner's other minions.
+/// Hits go through [`deal_damage`], so shields and invulnerability apply.
+pub fn minion_behavior(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    mut minion_query: Query<
+        (Entity, &Minion, &Position, &mut LinearVelocity),
+        Without<RespawnTimer>,
+    >,
+    character_query: Query<
+        (Entity, &Position, Option<&Minion>),
+        (With<CharacterMarker>, With<Health>, Without<RespawnTimer>),
+    >,
+    owner_query: Query<&Position, Without<Minion>>,
+    mut target_query: Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    mut shield_query: Query<&mut ActiveShield>,
+    mut death_events: MessageWriter<DeathEvent>,
+) {
+    let tick = timeline.tick();
+    let attack_this_tick = tick.0 % MINION_ATTACK_INTERVAL_TICKS == 0;
+    for (entity, minion, position, mut velocity) in &mut minion_query {
+        let nearest_hostile = character_query
+            .iter()
+            .filter(|(other, _, other_minion)| {
+                *other != entity
+                    && *other != minion.owner
+                    && other_minion.is_none_or(|m| m.owner != minion.owner)
+            })
+            .map(|(other, p, _)| (other, p.0, p.0.distance(position.0)))
+            .filter(|(_, _, distance)| *distance <= MINION_AGGRO_RANGE)
+            .min_by(|a, b| a.2.total_cmp(&b.2));
+
+        let (goal, stop_distance) = match nearest_hostile {
+            Some((hostile, hostile_pos, distance)) => {
+                if distance <= MINION_ATTACK_RANGE && attack_this_tick {
+                    deal_damage(
+                        &mut commands,
+                        None,
+                        hostile,
+                        MINION_ATTACK_DAMAGE,
+                        DamageType::Physical,
+                        tick,
+                        &mut target_query,
+                        &mut shield_query,
+                        &mut death_events,
+                    );
+                }
+                (hostile_pos, MINION_ATTACK_RANGE)
+            }
+            None => {
+                let Ok(owner_pos) = owner_query.get(minion.owner) else {
+                    trace!("Minion {:?} has no owner position, idling", entity);
+                    velocity.x = 0.0;
+                    velocity.z = 0.0;
+                    continue;
+                };
+                (owner_pos.0, MINION_FOLLOW_DISTANCE)
+            }
+        };
+
+        let offset = Vec3::new(goal.x - position.x, 0.0, goal.z - position.z);
+        let step = if offset.length() > stop_distance {
+            offset.normalize() * MINION_SPEED
+        } else {
+            Vec3::ZERO
+        };
+        velocity.x = step.x;
+        velocity.z = step.z;
+    }
+}
@@ -1,25 +1,50 @@
 mod activation;
+mod aim_assist;
 mod effects;
+mod homing;
 mod lifecycle;
 mod loader;
+mod minion;
 mod spawn;
 mod types;
 
 pub mod loading;
 pub mod plugin;
 
+pub(crate) use effects::{apply_buff, apply_healing_buffs, apply_stun};
 pub(crate) use spawn::spawn_sub_ability;
 
-pub use activation::{ability_action_to_slot, ability_activation, slot_to_ability_action};
-pub use lifecycle::expire_buffs;
+pub use activation::{
+    ability_action_to_slot, ability_activation, active_abilities_of, slot_to_ability_action,
+    swap_loadout,
+};
+pub use aim_assist::{
+    aim_assist_projectiles, steer_toward, AimAssistConfig, GamepadAiming, InputDeviceChannel,
+    InputDeviceReport,
+};
+pub use effects::voxel_wall_cells;
+pub use homing::home_projectiles;
+pub use lifecycle::{expire_buffs, expire_stuns, regenerate_health, tick_damage_over_time};
+pub use loader::{
+    extract_ground_target, extract_max_range, extract_phases, migrate_v1_ability_def,
+    parse_ability_asset, ABILITY_FORMAT_VERSION,
+};
 pub use loading::DefaultAbilitySlots;
 pub use plugin::AbilityPlugin;
 pub use types::{
-    facing_direction, AbilityAsset, AbilityBulletOf, AbilityBullets, AbilityCooldowns, AbilityDef,
-    AbilityDefs, AbilityEffect, AbilityId, AbilityManifest, AbilityPhase, AbilityPhases,
-    AbilityProjectileSpawn, AbilitySlots, ActiveAbility, ActiveAbilityHitboxes, ActiveBuff,
-    ActiveBuffs, ActiveShield, AoEHitbox, Condition, ConditionalEffect, ConditionalEffects,
-    EffectTarget, EffectTrigger, ForceFrame, HitTargets, HitboxOf, InputEffect, MeleeHitbox,
-    OnEndEffects, OnHitEffectDefs, OnHitEffects, OnInputEffects, OnTickEffects,
-    ProjectileSpawnEffect, TickEffect, WhileActiveEffects,
+    by_priority, facing_direction, AbilityAsset, AbilityAvailability, AbilityBulletOf,
+    AbilityBullets, AbilityCastEvent, AbilityCharges, AbilityCooldowns, AbilityCost, AbilityDef,
+    AbilityDefs, AbilityEffect, AbilityHitEvent, AbilityId, AbilityInterpolation, AbilityKill,
+    AbilityLoadouts, AbilityLockouts, AbilityManifest, AbilityMissEvent, AbilityPhase,
+    AbilityPhases, AbilityProjectileSpawn, AbilitySlots, ActiveAbility, ActiveAbilityHitboxes,
+    ActiveBuff, ActiveBuffs, ActiveDot, ActiveDots, ActiveShield, AoEHitbox, BaseMaxHealth,
+    CastState, Channeled, ClashingProjectile, Condition, ConditionalEffect, ConditionalEffects,
+    DamageType, DamagedThisTick, EffectPriority, EffectTarget, EffectTrigger, ForceFrame,
+    GrappleAnchor, GroundTarget, HitTargets, HitboxOf, HomingProjectile, HomingTarget, InputEffect,
+    LoadoutSwapConfig, LockMovement, MaxCharges, MaxRange, MeleeArc, MeleeHitbox, Minion,
+    OnCastEffects, OnEndEffects, OnHitEffectDefs, OnHitEffects, OnInputEffects, OnTickEffects,
+    PracticeMode, ProjectileLimitConfig, ProjectileSpawnEffect, ResetCooldownOnKill, Resistances,
+    ResourcePool, StickyProjectile, StuckTo, Stunned, TargetMask, TargetRelation, TickEffect,
+    VoxelWallEvent, WhileActiveEffects, ACCELERATION_STAT, DEFAULT_PROJECTILE_RADIUS, HEALING_STAT,
+    MAX_HEALTH_STAT, SPEED_STAT,
 };
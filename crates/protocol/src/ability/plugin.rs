@@ -1,25 +1,40 @@
-use super::activation::{ability_activation, update_active_abilities};
+use super::activation::{
+    ability_activation, reset_cooldown_on_kill, swap_loadout, update_active_abilities,
+    update_cast_states,
+};
+use super::aim_assist::{aim_assist_projectiles, AimAssistConfig};
 use super::effects::{
-    apply_on_end_effects, apply_on_input_effects, apply_on_tick_effects, apply_while_active_effects,
+    apply_grapple_effects, apply_on_cast_effects, apply_on_end_effects, apply_on_input_effects,
+    apply_on_tick_effects, apply_while_active_effects,
 };
+use super::homing::home_projectiles;
 use super::lifecycle::{
-    ability_bullet_lifetime, aoe_hitbox_lifetime, despawn_active_ability_on_removal, expire_buffs,
+    ability_bullet_lifetime, aoe_hitbox_lifetime, clear_damaged_this_tick,
+    despawn_active_ability_on_removal, expire_buffs, expire_stuns, minion_lifetime,
+    regenerate_health, regenerate_resource_pools, tick_damage_over_time,
 };
 use super::loader::AbilityAssetLoader;
 use super::loading::{
     insert_ability_defs, load_ability_defs, load_default_ability_slots, reload_ability_defs,
-    sync_default_ability_slots,
+    report_default_ability_slots_failure, sync_default_ability_slots, DefaultAbilitySlots,
 };
+use super::minion::minion_behavior;
 use super::spawn::{
     ability_projectile_spawn, despawn_ability_projectile_spawn, handle_ability_projectile_spawn,
 };
-use super::types::AbilityDefs;
 use super::types::{
-    AbilityAsset, AbilityEffect, AbilityPhases, AbilitySlots, Condition, ConditionalEffect,
-    ConditionalEffects, EffectTarget, ForceFrame, InputEffect, OnEndEffects, OnHitEffectDefs,
-    OnInputEffects, OnTickEffects, TickEffect, WhileActiveEffects,
+    AbilityAsset, AbilityAvailability, AbilityCost, AbilityEffect, AbilityInterpolation,
+    AbilityPhases, AbilitySlots, Channeled, Condition, ConditionalEffect, ConditionalEffects,
+    DamageType, EffectTarget, ForceFrame, GroundTarget, HomingProjectile, InputEffect,
+    LoadoutSwapConfig, LockMovement, MaxCharges, MaxRange, OnCastEffects, OnEndEffects,
+    OnHitEffectDefs, OnInputEffects, OnTickEffects, PracticeMode, ProjectileLimitConfig,
+    ResetCooldownOnKill, Resistances, TargetMask, TickEffect, WhileActiveEffects,
+};
+use super::types::{
+    AbilityCastEvent, AbilityDefs, AbilityHitEvent, AbilityMissEvent, VoxelWallEvent,
 };
 use crate::PlayerActions;
+use avian3d::prelude::SpatialQueryPipeline;
 use bevy::prelude::*;
 
 #[cfg(target_arch = "wasm32")]
@@ -34,6 +49,7 @@ impl Plugin for AbilityPlugin {
         app.register_type::<AbilityPhases>()
             .register_type::<OnTickEffects>()
             .register_type::<TickEffect>()
+            .register_type::<OnCastEffects>()
             .register_type::<WhileActiveEffects>()
             .register_type::<OnHitEffectDefs>()
             .register_type::<OnEndEffects>()
@@ -41,8 +57,20 @@ impl Plugin for AbilityPlugin {
             .register_type::<InputEffect>()
             .register_type::<AbilityEffect>()
             .register_type::<EffectTarget>()
+            .register_type::<DamageType>()
+            .register_type::<Resistances>()
             .register_type::<ForceFrame>()
+            .register_type::<TargetMask>()
+            .register_type::<HomingProjectile>()
             .register_type::<PlayerActions>()
+            .register_type::<GroundTarget>()
+            .register_type::<MaxRange>()
+            .register_type::<MaxCharges>()
+            .register_type::<AbilityCost>()
+            .register_type::<ResetCooldownOnKill>()
+            .register_type::<LockMovement>()
+            .register_type::<Channeled>()
+            .register_type::<AbilityInterpolation>()
             .register_type::<Condition>()
             .register_type::<ConditionalEffect>()
             .register_type::<ConditionalEffects>();
@@ -60,6 +88,14 @@ impl Plugin for AbilityPlugin {
             ]),
         );
 
+        // Empty slots until `default.ability_slots.ron` loads, and permanently if it is
+        // missing, so `ability_activation` never runs without its resource.
+        app.init_resource::<DefaultAbilitySlots>();
+        app.init_resource::<AimAssistConfig>();
+        app.init_resource::<ProjectileLimitConfig>();
+        app.init_resource::<LoadoutSwapConfig>();
+        app.init_resource::<PracticeMode>();
+        app.init_resource::<AbilityAvailability>();
         app.add_systems(Startup, (load_ability_defs, load_default_ability_slots));
 
         #[cfg(target_arch = "wasm32")]
@@ -74,20 +110,32 @@ impl Plugin for AbilityPlugin {
                 insert_ability_defs.run_if(not(resource_exists::<AbilityDefs>)),
                 reload_ability_defs,
                 sync_default_ability_slots,
+                report_default_ability_slots_failure,
             ),
         );
 
         app.add_message::<crate::DeathEvent>();
+        app.add_message::<AbilityCastEvent>();
+        app.add_message::<AbilityHitEvent>();
+        app.add_message::<AbilityMissEvent>();
+        app.add_message::<VoxelWallEvent>();
+        app.add_message::<crate::physics::ApplyImpulse>();
 
         let ready = in_state(crate::app_state::AppState::Ready);
 
         app.add_systems(
             FixedUpdate,
             (
+                swap_loadout,
                 ability_activation,
                 update_active_abilities,
+                clear_damaged_this_tick,
+                update_cast_states,
+                apply_on_cast_effects,
                 apply_on_tick_effects,
                 apply_while_active_effects,
+                // Apps without physics (most ability tests) have no spatial query.
+                apply_grapple_effects.run_if(resource_exists::<SpatialQueryPipeline>),
                 apply_on_end_effects,
                 apply_on_input_effects,
                 ability_projectile_spawn,
@@ -101,7 +149,11 @@ impl Plugin for AbilityPlugin {
             (
                 crate::hit_detection::update_hitbox_positions,
                 crate::hit_detection::process_hitbox_hits,
+                crate::hit_detection::process_projectile_clashes,
                 crate::hit_detection::process_projectile_hits,
+                crate::hit_detection::follow_stuck_projectiles,
+                crate::hit_detection::detonate_stuck_projectiles,
+                crate::hit_detection::emit_ability_misses,
                 crate::hit_detection::cleanup_hitbox_entities,
             )
                 .chain()
@@ -111,7 +163,21 @@ impl Plugin for AbilityPlugin {
 
         app.add_systems(
             FixedUpdate,
-            (expire_buffs, aoe_hitbox_lifetime, ability_bullet_lifetime)
+            (
+                expire_buffs,
+                expire_stuns,
+                regenerate_resource_pools,
+                tick_damage_over_time,
+                regenerate_health.after(tick_damage_over_time),
+                reset_cooldown_on_kill,
+                aoe_hitbox_lifetime,
+                ability_bullet_lifetime,
+                aim_assist_projectiles,
+                home_projectiles.run_if(resource_exists::<SpatialQueryPipeline>),
+                minion_behavior,
+                minion_lifetime,
+                crate::physics::apply_impulses,
+            )
                 .after(crate::hit_detection::process_hitbox_hits)
                 .after(crate::hit_detection::process_projectile_hits)
                 .run_if(ready.clone()),
@@ -1,27 +1,30 @@
 use super::loader::apply_ability_archetype;
 use super::types::facing_direction;
 use super::types::{
-    AbilityAsset, AbilityBulletOf, AbilityBullets, AbilityDefs, AbilityId, AbilityPhase,
-    AbilityProjectileSpawn, ActiveAbility, AoEHitbox, HitTargets, HitboxOf, MeleeHitbox,
-    OnHitEffects, ProjectileSpawnEffect,
+    AbilityAsset, AbilityBulletOf, AbilityBullets, AbilityDefs, AbilityId, AbilityInterpolation,
+    AbilityPhase, AbilityProjectileSpawn, ActiveAbility, AoEHitbox, ClashingProjectile, HitTargets,
+    HitboxOf, HomingProjectile, HomingTarget, MeleeArc, MeleeHitbox, Minion, OnHitEffects,
+    ProjectileLimitConfig, ProjectileSpawnEffect, StickyProjectile, TargetMask,
 };
 use crate::hit_detection::{
-    hitbox_collision_layers, projectile_collision_layers, MELEE_HITBOX_HALF_EXTENTS,
-    MELEE_HITBOX_OFFSET,
+    clashing_projectile_collision_layers, hitbox_collision_layers, projectile_collision_layers,
+    MELEE_HITBOX_HALF_EXTENTS, MELEE_HITBOX_OFFSET,
 };
 use crate::map::MapInstanceId;
-use crate::PlayerId;
+use crate::{CharacterMarker, CharacterPhysicsBundle, Health, PlayerId};
 use avian3d::prelude::*;
+use bevy::ecs::entity::EntityHashMap;
 use bevy::prelude::*;
 use bevy::reflect::TypeRegistryArc;
 use lightyear::prelude::{
     ControlledBy, DisableRollback, LocalTimeline, NetworkTarget, PreSpawned,
-    PredictionDespawnCommandsExt, PredictionTarget, Replicate, Replicated, Tick,
+    PredictionDespawnCommandsExt, PredictionDisable, PredictionTarget, Replicate, Replicated, Tick,
 };
 use std::hash::{DefaultHasher, Hash, Hasher};
 
 const PROJECTILE_SPAWN_OFFSET: f32 = 3.0;
-const BULLET_COLLIDER_RADIUS: f32 = 0.5;
+const MINION_SPAWN_OFFSET: f32 = 2.0;
+const MINION_HEALTH: f32 = 50.0;
 
 fn compute_sub_ability_salt(player_id: PlayerId, slot: u8, depth: u8, id: &str) -> u64 {
     let mut hasher = DefaultHasher::new();
@@ -81,6 +84,7 @@ pub(crate) fn spawn_sub_ability(
                 phase_start_tick: tick,
                 ability_slot: parent_slot,
                 depth,
+                landed_hit: false,
             },
             PreSpawned::default_with_salt(salt),
             Name::new("ActiveAbility"),
@@ -98,11 +102,80 @@ pub(crate) fn spawn_sub_ability(
     }
 }
 
+/// Spawn a `Minion` of `kind` beside `active.caster`, owned by the caster.
+pub(crate) fn spawn_minion(
+    commands: &mut Commands,
+    active: &ActiveAbility,
+    kind: &str,
+    lifetime_ticks: u16,
+    tick: Tick,
+    interpolation: Option<&AbilityInterpolation>,
+    caster_query: &Query<(&mut Position, &Rotation, &MapInstanceId)>,
+    server_query: &Query<&ControlledBy>,
+    player_id_query: &Query<&PlayerId>,
+) {
+    let Ok((caster_pos, caster_rot, caster_map_id)) = caster_query.get(active.caster) else {
+        warn!(
+            "Summon: caster {:?} missing Position/Rotation/MapInstanceId",
+            active.caster
+        );
+        return;
+    };
+    let Ok(&player_id) = player_id_query.get(active.original_caster) else {
+        warn!(
+            "Summon: original_caster {:?} missing PlayerId",
+            active.original_caster
+        );
+        return;
+    };
+    let salt = compute_sub_ability_salt(
+        player_id,
+        active.ability_slot,
+        active.depth,
+        &format!("summon:{kind}"),
+    );
+    let direction = facing_direction(caster_rot);
+
+    let minion = commands
+        .spawn((
+            CharacterMarker,
+            Minion {
+                owner: active.caster,
+                kind: kind.to_string(),
+                expires_tick: tick + lifetime_ticks as i16,
+            },
+            Health::new(MINION_HEALTH),
+            Position(caster_pos.0 + direction * MINION_SPAWN_OFFSET),
+            *caster_rot,
+            LinearVelocity::ZERO,
+            CharacterPhysicsBundle::default(),
+            caster_map_id.clone(),
+            PreSpawned::default_with_salt(salt),
+            Name::new("Minion"),
+        ))
+        .id();
+    if let Some(interpolation) = interpolation {
+        commands.entity(minion).insert(*interpolation);
+    }
+
+    // Replicated without the caster's `ControlledBy`: a minion is not its
+    // player's character and must not match `Controlled` or owner lookups.
+    if server_query.contains(active.caster) {
+        commands.entity(minion).insert((
+            Replicate::to_clients(NetworkTarget::All),
+            PredictionTarget::to_clients(NetworkTarget::All),
+        ));
+    }
+}
+
 pub(crate) fn spawn_melee_hitbox(
     commands: &mut Commands,
     ability_entity: Entity,
     active: &ActiveAbility,
     on_hit_effects: Option<&OnHitEffects>,
+    interpolation: Option<&AbilityInterpolation>,
+    affects: TargetMask,
+    arc: Option<MeleeArc>,
     caster_query: &Query<(&mut Position, &Rotation, &MapInstanceId)>,
 ) {
     let Ok((caster_pos, caster_rot, caster_map_id)) = caster_query.get(active.caster) else {
@@ -112,18 +185,24 @@ pub(crate) fn spawn_melee_hitbox(
         );
         return;
     };
-    let direction = facing_direction(caster_rot);
-    let pos = caster_pos.0 + direction * MELEE_HITBOX_OFFSET;
+    // A fan is a sphere on the caster, narrowed to the arc in process_hitbox_hits.
+    let (pos, collider) = match arc {
+        Some(arc) => (caster_pos.0, Collider::sphere(arc.range)),
+        None => (
+            caster_pos.0 + facing_direction(caster_rot) * MELEE_HITBOX_OFFSET,
+            Collider::cuboid(
+                MELEE_HITBOX_HALF_EXTENTS.x,
+                MELEE_HITBOX_HALF_EXTENTS.y,
+                MELEE_HITBOX_HALF_EXTENTS.z,
+            ),
+        ),
+    };
 
     let mut cmd = commands.spawn((
         Position(pos),
         *caster_rot,
         RigidBody::Kinematic,
-        Collider::cuboid(
-            MELEE_HITBOX_HALF_EXTENTS.x,
-            MELEE_HITBOX_HALF_EXTENTS.y,
-            MELEE_HITBOX_HALF_EXTENTS.z,
-        ),
+        collider,
         Sensor,
         CollisionEventsEnabled,
         CollidingEntities::default(),
@@ -132,11 +211,18 @@ pub(crate) fn spawn_melee_hitbox(
         DisableRollback,
         MeleeHitbox,
         HitTargets::default(),
+        affects,
         Name::new("MeleeHitbox"),
     ));
     if let Some(on_hit) = on_hit_effects {
         cmd.insert(on_hit.clone());
     }
+    if let Some(arc) = arc {
+        cmd.insert(arc);
+    }
+    if let Some(interpolation) = interpolation {
+        cmd.insert(*interpolation);
+    }
     cmd.insert(caster_map_id.clone());
 }
 
@@ -145,12 +231,15 @@ pub(crate) fn spawn_aoe_hitbox(
     ability_entity: Entity,
     active: &ActiveAbility,
     on_hit_effects: Option<&OnHitEffects>,
+    interpolation: Option<&AbilityInterpolation>,
+    affects: TargetMask,
     caster_query: &Query<(&mut Position, &Rotation, &MapInstanceId)>,
-    radius: f32,
-    spawn_tick: Tick,
-    duration_ticks: u16,
+    hitbox: AoEHitbox,
 ) {
-    trace!("Spawning AoE hitbox with {duration_ticks:?} lifetime");
+    trace!(
+        "Spawning AoE hitbox with {:?} lifetime",
+        hitbox.duration_ticks
+    );
     let Ok((caster_pos, caster_rot, caster_map_id)) = caster_query.get(active.caster) else {
         warn!(
             "AoE hitbox spawn: caster {:?} missing Position/Rotation",
@@ -163,7 +252,7 @@ pub(crate) fn spawn_aoe_hitbox(
         Position(caster_pos.0),
         *caster_rot,
         RigidBody::Kinematic,
-        Collider::sphere(radius),
+        Collider::sphere(hitbox.radius),
         Sensor,
         CollisionEventsEnabled,
         CollidingEntities::default(),
@@ -171,15 +260,16 @@ pub(crate) fn spawn_aoe_hitbox(
         HitboxOf(ability_entity),
         DisableRollback,
         HitTargets::default(),
-        AoEHitbox {
-            spawn_tick,
-            duration_ticks,
-        },
+        affects,
+        hitbox,
         Name::new("AoEHitbox"),
     ));
     if let Some(on_hit) = on_hit_effects {
         cmd.insert(on_hit.clone());
     }
+    if let Some(interpolation) = interpolation {
+        cmd.insert(*interpolation);
+    }
     cmd.insert(caster_map_id.clone());
 }
 
@@ -191,13 +281,34 @@ pub fn ability_projectile_spawn(
         &ProjectileSpawnEffect,
         &ActiveAbility,
         Option<&OnHitEffects>,
+        Option<&AbilityInterpolation>,
     )>,
     caster_query: Query<(&Position, &Rotation, &MapInstanceId)>,
     server_query: Query<&ControlledBy>,
+    limit: Res<ProjectileLimitConfig>,
+    live_query: Query<&AbilityProjectileSpawn, Without<PredictionDisable>>,
 ) {
     let tick = timeline.tick();
+    // Each spawn entity lives exactly as long as its bullet, including the
+    // frame before the bullet itself is spawned.
+    let mut live = EntityHashMap::<usize>::default();
+    for spawn_info in &live_query {
+        *live.entry(spawn_info.shooter).or_default() += 1;
+    }
 
-    for (ability_entity, request, active, on_hit_effects) in &query {
+    for (ability_entity, request, active, on_hit_effects, interpolation) in &query {
+        let shooter_live = live.entry(active.caster).or_default();
+        if *shooter_live >= limit.max_per_shooter {
+            trace!(
+                "Projectile refused for {:?}: {} already in flight",
+                active.caster,
+                shooter_live
+            );
+            commands
+                .entity(ability_entity)
+                .remove::<ProjectileSpawnEffect>();
+            continue;
+        }
         let Ok((position, rotation, caster_map_id)) = caster_query.get(active.caster) else {
             warn!(
                 "Projectile spawn: caster {:?} missing Position/Rotation",
@@ -212,10 +323,13 @@ pub fn ability_projectile_spawn(
             direction,
             speed: request.speed,
             lifetime_ticks: request.lifetime_ticks,
+            radius: request.radius,
             ability_id: active.def_id.clone(),
             shooter: active.caster,
         };
 
+        *shooter_live += 1;
+
         let salt = (active.ability_slot as u64) << 8 | (active.depth as u64);
         let mut cmd = commands.spawn((
             spawn_info,
@@ -226,6 +340,18 @@ pub fn ability_projectile_spawn(
         if let Some(on_hit) = on_hit_effects {
             cmd.insert(on_hit.clone());
         }
+        if let Some(sticky) = request.sticky {
+            cmd.insert(sticky);
+        }
+        if request.clash {
+            cmd.insert(ClashingProjectile);
+        }
+        if let Some(homing) = request.homing {
+            cmd.insert(homing);
+        }
+        if let Some(interpolation) = interpolation {
+            cmd.insert(*interpolation);
+        }
         cmd.insert(caster_map_id.clone());
 
         if let Ok(controlled_by) = server_query.get(active.caster) {
@@ -249,23 +375,41 @@ pub fn handle_ability_projectile_spawn(
             Entity,
             &AbilityProjectileSpawn,
             Option<&OnHitEffects>,
+            Option<&StickyProjectile>,
+            Has<ClashingProjectile>,
+            Option<&HomingProjectile>,
+            Option<&AbilityInterpolation>,
             &MapInstanceId,
         ),
         (Without<AbilityBullets>, Without<Replicated>),
     >,
 ) {
-    for (spawn_entity, spawn_info, on_hit_effects, spawn_map_id) in &spawn_query {
+    for (
+        spawn_entity,
+        spawn_info,
+        on_hit_effects,
+        sticky,
+        clash,
+        homing,
+        interpolation,
+        spawn_map_id,
+    ) in &spawn_query
+    {
         trace!("Spawning ability bullet from {:?}", spawn_info.ability_id);
         let mut bullet_cmd = commands.spawn((
             Position(spawn_info.position),
             Rotation::default(),
             LinearVelocity(spawn_info.direction * spawn_info.speed),
             RigidBody::Kinematic,
-            Collider::sphere(BULLET_COLLIDER_RADIUS),
+            Collider::sphere(spawn_info.radius),
             Sensor,
             CollisionEventsEnabled,
             CollidingEntities::default(),
-            projectile_collision_layers(),
+            if clash {
+                clashing_projectile_collision_layers()
+            } else {
+                projectile_collision_layers()
+            },
             AbilityBulletOf(spawn_entity),
             DisableRollback,
             Name::new("AbilityBullet"),
@@ -273,6 +417,18 @@ pub fn handle_ability_projectile_spawn(
         if let Some(on_hit) = on_hit_effects {
             bullet_cmd.insert(on_hit.clone());
         }
+        if let Some(sticky) = sticky {
+            bullet_cmd.insert(*sticky);
+        }
+        if clash {
+            bullet_cmd.insert(ClashingProjectile);
+        }
+        if let Some(homing) = homing {
+            bullet_cmd.insert((*homing, HomingTarget::default()));
+        }
+        if let Some(interpolation) = interpolation {
+            bullet_cmd.insert(*interpolation);
+        }
         bullet_cmd.insert(spawn_map_id.clone());
     }
 }
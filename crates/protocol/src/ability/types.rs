@@ -1,3 +1,4 @@
+use crate::map::MapInstanceId;
 use crate::PlayerActions;
 use avian3d::prelude::Rotation;
 use bevy::ecs::entity::{EntityMapper, MapEntities};
@@ -6,7 +7,7 @@ use bevy::reflect::PartialReflect;
 use lightyear::prelude::Tick;
 use lightyear::utils::collections::EntityHashSet;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 
 /// Returns the normalized facing direction from a rotation.
@@ -27,6 +28,28 @@ pub enum EffectTarget {
     Caster,
     Victim,
     OriginalCaster,
+    /// Every character within `radius` of the caster on its map, each hit as
+    /// the victim of the effect. Excludes the caster unless `include_caster`.
+    /// Supported by `Damage`, `Heal`, `ApplyForce` and `Buff` in OnTick and
+    /// OnEnd effects; inside OnHit effects it means the victim.
+    AllInRadius {
+        radius: f32,
+        #[serde(default)]
+        include_caster: bool,
+    },
+}
+
+/// The element of an [`AbilityEffect::Damage`], scaled by the target's
+/// [`Resistances`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize, Reflect)]
+#[type_path = "protocol::ability"]
+pub enum DamageType {
+    #[default]
+    Physical,
+    Fire,
+    Frost,
+    Lightning,
+    Poison,
 }
 
 /// Coordinate frame used to interpret a force vector in [`AbilityEffect::ApplyForce`].
@@ -41,6 +64,51 @@ pub enum ForceFrame {
     RelativeRotation,
 }
 
+/// Which characters a hitbox may strike, relative to the ability's original
+/// caster. Allies are the caster's side: its owner if it is a [`Minion`], and
+/// every minion of that owner. Everyone else is an enemy. Unset fields are
+/// `false`; an effect that omits `affects` hits enemies only.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Reflect)]
+#[type_path = "protocol::ability"]
+pub struct TargetMask {
+    #[serde(default)]
+    pub enemies: bool,
+    #[serde(default)]
+    pub allies: bool,
+    /// The caster itself. Authored as `self` in RON.
+    #[serde(default, rename = "self")]
+    pub caster: bool,
+}
+
+impl Default for TargetMask {
+    fn default() -> Self {
+        Self {
+            enemies: true,
+            allies: false,
+            caster: false,
+        }
+    }
+}
+
+/// How a hit target relates to the ability's original caster.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TargetRelation {
+    Caster,
+    Ally,
+    Enemy,
+}
+
+impl TargetMask {
+    /// Whether a target with the given relation may be hit.
+    pub fn allows(&self, relation: TargetRelation) -> bool {
+        match relation {
+            TargetRelation::Caster => self.caster,
+            TargetRelation::Ally => self.allies,
+            TargetRelation::Enemy => self.enemies,
+        }
+    }
+}
+
 /// What an ability does when it activates.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
 #[type_path = "protocol::ability"]
@@ -50,12 +118,37 @@ pub enum AbilityEffect {
         id: Option<String>,
         #[serde(default)]
         target: EffectTarget,
+        #[serde(default)]
+        affects: TargetMask,
+        /// Strike a fan this many degrees wide, centered on the caster's
+        /// facing, instead of the default box in front of it.
+        #[serde(default)]
+        arc_degrees: Option<f32>,
+        /// Reach of the fan from the caster's center; without it, the far edge
+        /// of the default box. Ignored unless `arc_degrees` is set.
+        #[serde(default)]
+        range: Option<f32>,
     },
     Projectile {
         #[serde(default)]
         id: Option<String>,
         speed: f32,
         lifetime_ticks: u16,
+        /// Radius of the bullet's sphere collider.
+        #[serde(default = "default_projectile_radius")]
+        radius: f32,
+        /// Stick to the first character hit and apply on-hit effects after `fuse_ticks`.
+        #[serde(default)]
+        stick: bool,
+        #[serde(default)]
+        fuse_ticks: u16,
+        /// Collide with opposing projectiles that also set `clash`; both despawn
+        /// on contact. Other projectiles pass through.
+        #[serde(default)]
+        clash: bool,
+        /// Steer the bullet toward a valid target in flight.
+        #[serde(default)]
+        homing: Option<HomingProjectile>,
     },
     SetVelocity {
         speed: f32,
@@ -64,6 +157,30 @@ pub enum AbilityEffect {
     Damage {
         amount: f32,
         target: EffectTarget,
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// OnHit only: deals `amount` to `target` every `interval_ticks` for
+    /// `duration_ticks`, starting `interval_ticks` after the hit. Each hit adds
+    /// its own [`ActiveDot`], so repeat hits stack with separate timers.
+    DamageOverTime {
+        amount: f32,
+        interval_ticks: u16,
+        duration_ticks: u16,
+        target: EffectTarget,
+        #[serde(default)]
+        damage_type: DamageType,
+    },
+    /// OnHit only: cancels the victim's casts still in Startup or Active and
+    /// keeps their slots from being recast for `lockout_ticks`.
+    Interrupt {
+        lockout_ticks: u16,
+    },
+    /// Restores `amount` health to `target`, scaled by the caster's `healing`
+    /// buffs and capped at `Health::max`. Dead targets are not revived.
+    Heal {
+        amount: f32,
+        target: EffectTarget,
     },
     ApplyForce {
         force: Vec3,
@@ -79,6 +196,18 @@ pub enum AbilityEffect {
         radius: f32,
         #[serde(default)]
         duration_ticks: Option<u16>,
+        #[serde(default)]
+        affects: TargetMask,
+        /// Caps how many targets the hitbox affects over its lifetime, taking
+        /// the nearest first. Ties are broken by position rather than entity
+        /// id, because ids differ between server and client and would pick
+        /// different targets. `None` hits every target in range.
+        #[serde(default)]
+        max_targets: Option<u32>,
+        /// Fraction of damage lost at the edge of `radius`, scaling linearly
+        /// from full damage at the center. `None` deals full damage everywhere.
+        #[serde(default)]
+        falloff: Option<f32>,
     },
     Ability {
         id: String,
@@ -90,12 +219,110 @@ pub enum AbilityEffect {
     Shield {
         absorb: f32,
     },
+    /// OnHit only: after the victim is hit, bounces up to `jumps` times to the
+    /// nearest unhit character within `range` of the previous target, dealing
+    /// the damage the victim took scaled by `damage_falloff` once more per jump.
+    /// Jumps only reach characters the hit's `TargetMask` allows, never the
+    /// caster's own minions; equidistant characters are picked by position.
+    Chain {
+        jumps: u8,
+        range: f32,
+        damage_falloff: f32,
+    },
+    /// Spawns a `Minion` of kind `id` next to the caster that fights for it and
+    /// despawns after `lifetime_ticks`.
+    Summon {
+        id: String,
+        lifetime_ticks: u16,
+    },
+    /// OnHit only: exchanges the caster's and victim's positions.
+    SwapPositions {
+        #[serde(default)]
+        reset_velocity: bool,
+    },
     Buff {
         stat: String,
         multiplier: f32,
         duration_ticks: u16,
         target: EffectTarget,
     },
+    /// Stuns `target` for `duration_ticks`: while [`Stunned`] it ignores
+    /// movement input and cannot activate abilities. A longer stun extends a
+    /// shorter one; a shorter one never cuts it short.
+    Stun {
+        duration_ticks: u16,
+        target: EffectTarget,
+    },
+    /// WhileActive only: on the first Active tick, casts a ray up to
+    /// `max_distance` along the caster's facing, then pulls the caster toward
+    /// the point it struck at `speed` until the caster is up against it.
+    Grapple {
+        speed: f32,
+        max_distance: f32,
+    },
+    /// OnCast only: the server raises a `length`×`height` wall of `Solid(material)`
+    /// voxels across the caster's facing, a few voxels ahead of it, and turns
+    /// them back to air after `duration_ticks`.
+    VoxelWall {
+        length: u32,
+        height: u32,
+        material: u8,
+        duration_ticks: u16,
+    },
+    /// OnHit only: applies `effects` alongside the rest of the hit when the
+    /// victim's health, before this hit, is below `below_health_fraction` of
+    /// its max. For "execute" bonuses.
+    OnHitIf {
+        below_health_fraction: f32,
+        effects: Vec<AbilityEffect>,
+    },
+}
+
+/// Resolution order for effects that fire together on the same tick and
+/// trigger. Dispatch applies lower priorities first; ties keep authoring order.
+/// Defensive effects go first so a same-tick shield is up before any damage.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EffectPriority {
+    Defensive,
+    Buff,
+    Movement,
+    Offensive,
+}
+
+impl AbilityEffect {
+    pub fn priority(&self) -> EffectPriority {
+        match self {
+            AbilityEffect::Shield { .. }
+            | AbilityEffect::VoxelWall { .. }
+            | AbilityEffect::Heal { .. } => EffectPriority::Defensive,
+            AbilityEffect::Buff { .. } => EffectPriority::Buff,
+            AbilityEffect::SetVelocity { .. }
+            | AbilityEffect::ApplyForce { .. }
+            | AbilityEffect::Teleport { .. }
+            | AbilityEffect::SwapPositions { .. }
+            | AbilityEffect::Grapple { .. } => EffectPriority::Movement,
+            AbilityEffect::Melee { .. }
+            | AbilityEffect::Projectile { .. }
+            | AbilityEffect::Damage { .. }
+            | AbilityEffect::DamageOverTime { .. }
+            | AbilityEffect::Interrupt { .. }
+            | AbilityEffect::AreaOfEffect { .. }
+            | AbilityEffect::Ability { .. }
+            | AbilityEffect::Chain { .. }
+            | AbilityEffect::OnHitIf { .. }
+            | AbilityEffect::Stun { .. }
+            | AbilityEffect::Summon { .. } => EffectPriority::Offensive,
+        }
+    }
+}
+
+/// `effects` stably sorted into resolution order by [`AbilityEffect::priority`].
+pub fn by_priority<'a>(
+    effects: impl IntoIterator<Item = &'a AbilityEffect>,
+) -> Vec<&'a AbilityEffect> {
+    let mut sorted: Vec<_> = effects.into_iter().collect();
+    sorted.sort_by_key(|effect| effect.priority());
+    sorted
 }
 
 /// Controls when an effect fires during an ability's lifecycle.
@@ -107,8 +334,15 @@ pub enum EffectTrigger {
         tick: u16,
         effect: AbilityEffect,
     },
+    OnCast(AbilityEffect),
     WhileActive(AbilityEffect),
     OnHit(AbilityEffect),
+    /// An `OnHit` effect applied only to victims below `below_health_fraction`
+    /// of their max health; see [`AbilityEffect::OnHitIf`].
+    OnHitIf {
+        below_health_fraction: f32,
+        effect: AbilityEffect,
+    },
     OnEnd(AbilityEffect),
     OnInput {
         action: PlayerActions,
@@ -116,7 +350,9 @@ pub enum EffectTrigger {
     },
 }
 
-/// Definition of a single ability, loaded from an individual `.ability.ron` file.
+/// Legacy (version 1) single-struct ability definition. `.ability.ron` files
+/// in this shape still load: the loader migrates them into [`AbilityAsset`]
+/// components.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, Reflect, Asset)]
 #[type_path = "protocol::ability"]
 pub struct AbilityDef {
@@ -124,6 +360,12 @@ pub struct AbilityDef {
     pub active_ticks: u16,
     pub recovery_ticks: u16,
     pub cooldown_ticks: u16,
+    #[serde(default)]
+    pub cost: f32,
+    #[serde(default)]
+    pub max_charges: Option<u8>,
+    #[serde(default)]
+    pub charge_refill_ticks: u16,
     pub effects: Vec<EffectTrigger>,
 }
 
@@ -138,6 +380,8 @@ impl AbilityDef {
 }
 
 /// Tick-based phase durations and cooldown. Loaded from RON archetype.
+/// `cooldown` may be authored as integer ticks (`cooldown: 16`) or as float
+/// seconds (`cooldown: 0.25`), which are converted to ticks at load.
 #[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
 #[type_path = "protocol::ability"]
 #[reflect(Component, Serialize, Deserialize)]
@@ -145,10 +389,50 @@ pub struct AbilityPhases {
     pub startup: u16,
     pub active: u16,
     pub recovery: u16,
+    #[serde(deserialize_with = "deserialize_ticks_or_secs")]
     pub cooldown: u16,
 }
 
+/// Accepts an integer tick count or a float number of seconds.
+fn deserialize_ticks_or_secs<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<u16, D::Error> {
+    struct TicksOrSecs;
+
+    impl serde::de::Visitor<'_> for TicksOrSecs {
+        type Value = u16;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("an integer tick count or float seconds")
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, ticks: u64) -> Result<u16, E> {
+            u16::try_from(ticks).map_err(|_| E::custom(format!("{ticks} ticks exceeds u16")))
+        }
+
+        fn visit_i64<E: serde::de::Error>(self, ticks: i64) -> Result<u16, E> {
+            u16::try_from(ticks).map_err(|_| E::custom(format!("{ticks} ticks is out of range")))
+        }
+
+        fn visit_f64<E: serde::de::Error>(self, secs: f64) -> Result<u16, E> {
+            if secs.is_nan() || secs < 0.0 {
+                return Err(E::custom(format!(
+                    "cooldown of {secs}s must be non-negative"
+                )));
+            }
+            Ok(crate::secs_to_ticks(secs as f32))
+        }
+    }
+
+    deserializer.deserialize_any(TicksOrSecs)
+}
+
 impl AbilityPhases {
+    /// Cooldown in seconds, for display.
+    pub fn cooldown_secs(&self) -> f32 {
+        crate::ticks_to_secs(self.cooldown)
+    }
+
     pub fn phase_duration(&self, phase: &AbilityPhase) -> u16 {
         match phase {
             AbilityPhase::Startup => self.startup,
@@ -186,6 +470,71 @@ impl Default for AbilitySlots {
     }
 }
 
+impl AbilitySlots {
+    /// Puts `ability_id` in `slot` if `availability` allows it. Returns whether
+    /// the slot was assigned; denied abilities and out-of-range slots leave the
+    /// loadout unchanged.
+    pub fn try_assign(
+        &mut self,
+        slot: usize,
+        ability_id: AbilityId,
+        availability: &AbilityAvailability,
+    ) -> bool {
+        if !availability.is_available(&ability_id) {
+            trace!("AbilitySlots: {:?} is not available", ability_id);
+            return false;
+        }
+        let Some(entry) = self.0.get_mut(slot) else {
+            trace!("AbilitySlots: slot {slot} out of range");
+            return false;
+        };
+        *entry = Some(ability_id);
+        true
+    }
+}
+
+/// A character's switchable ability sets. `PlayerActions::SwapLoadout` moves
+/// `active` to the next preset and copies it into the character's live
+/// [`AbilitySlots`], which should start out as `presets[active]`. Predicted, so
+/// a swap rolls back with the casts it enabled.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AbilityLoadouts {
+    pub presets: Vec<AbilitySlots>,
+    pub active: usize,
+    /// Tick of the last swap, for [`LoadoutSwapConfig::cooldown_ticks`].
+    pub last_swap: Option<Tick>,
+}
+
+impl AbilityLoadouts {
+    /// Loadouts starting on the first of `presets`.
+    pub fn new(presets: Vec<AbilitySlots>) -> Self {
+        Self {
+            presets,
+            active: 0,
+            last_swap: None,
+        }
+    }
+
+    pub fn is_on_cooldown(&self, current_tick: Tick, cooldown_ticks: u16) -> bool {
+        self.last_swap
+            .map(|last| (current_tick - last).unsigned_abs() <= cooldown_ticks)
+            .unwrap_or(false)
+    }
+}
+
+/// Tuning for `PlayerActions::SwapLoadout`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LoadoutSwapConfig {
+    /// Ticks after a swap before the next one is allowed.
+    pub cooldown_ticks: u16,
+}
+
+impl Default for LoadoutSwapConfig {
+    fn default() -> Self {
+        Self { cooldown_ticks: 32 }
+    }
+}
+
 /// Which phase of an ability is currently executing.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Reflect)]
 #[type_path = "protocol::ability"]
@@ -206,6 +555,8 @@ pub struct ActiveAbility {
     pub phase_start_tick: Tick,
     pub ability_slot: u8,
     pub depth: u8,
+    /// Set by hit detection once one of this ability's hitboxes strikes a target.
+    pub landed_hit: bool,
 }
 
 impl MapEntities for ActiveAbility {
@@ -216,8 +567,77 @@ impl MapEntities for ActiveAbility {
     }
 }
 
+/// On a character while it casts a top-level ability: the ability, its phase
+/// and how far through that phase it is, in `[0, 1]`. Kept in sync with the
+/// character's `ActiveAbility` by `update_cast_states` and removed when the
+/// cast ends, so animation code can drive windup/active/recovery poses
+/// without redoing phase math.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CastState {
+    pub ability_id: AbilityId,
+    pub phase: AbilityPhase,
+    pub phase_progress: f32,
+}
+
+/// Emitted by `ability_activation` when a cast starts, after all gating.
+#[derive(bevy::ecs::message::Message, Clone, Debug, PartialEq)]
+pub struct AbilityCastEvent {
+    pub caster: Entity,
+    pub ability_id: AbilityId,
+    pub slot: u8,
+    pub tick: Tick,
+}
+
+/// Emitted once per victim struck by an ability's hitbox or projectile.
+/// `damage` is the buffed `Damage` dealt to the victim, including any a shield
+/// absorbed; it is zero for hits that only apply non-damage effects.
+#[derive(bevy::ecs::message::Message, Clone, Debug, PartialEq)]
+pub struct AbilityHitEvent {
+    pub caster: Entity,
+    pub victim: Entity,
+    pub ability_id: AbilityId,
+    pub damage: f32,
+}
+
+/// Emitted when an ability whiffs: a melee/AoE ability's Active window ends
+/// without its hitboxes striking anything, or a projectile's lifetime runs out
+/// in flight. Lets clients play miss feedback.
+#[derive(bevy::ecs::message::Message, Clone, Debug, PartialEq)]
+pub struct AbilityMissEvent {
+    pub caster: Entity,
+    pub ability_id: AbilityId,
+}
+
+/// Emitted when a `VoxelWall` effect fires. The server places `cells` that are
+/// currently air and reverts them at `expires_tick`; clients ignore it and see
+/// the wall through the server's voxel broadcasts.
+#[derive(bevy::ecs::message::Message, Clone, Debug, PartialEq)]
+pub struct VoxelWallEvent {
+    pub caster: Entity,
+    pub map_id: MapInstanceId,
+    pub cells: Vec<IVec3>,
+    pub material: u8,
+    pub expires_tick: Tick,
+}
+
+/// A summoned character owned by `owner`. Follows its owner and attacks nearby
+/// characters that aren't on the owner's side until `expires_tick`.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Minion {
+    pub owner: Entity,
+    pub kind: String,
+    pub expires_tick: Tick,
+}
+
+impl MapEntities for Minion {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.owner = entity_mapper.get_mapped(self.owner);
+    }
+}
+
 /// Per-slot cooldown tracking.
 #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[require(AbilityCharges)]
 pub struct AbilityCooldowns {
     pub last_used: [Option<Tick>; 5],
 }
@@ -230,12 +650,120 @@ impl Default for AbilityCooldowns {
     }
 }
 
+/// Per-slot charges spent on [`MaxCharges`] abilities. Slots start full;
+/// while any charge is spent, `refill_start` is when the next one began
+/// refilling.
+#[derive(Component, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AbilityCharges {
+    pub spent: [u8; 5],
+    pub refill_start: [Option<Tick>; 5],
+}
+
+impl AbilityCharges {
+    /// Charges `slot` has left out of `max`.
+    pub fn remaining(&self, slot: usize, max: u8) -> u8 {
+        max.saturating_sub(self.spent[slot])
+    }
+
+    /// Restores one spent charge of `slot` per `refill_ticks` elapsed since
+    /// its refill started.
+    pub fn refill(&mut self, slot: usize, current_tick: Tick, refill_ticks: u16) {
+        let Some(start) = self.refill_start[slot] else {
+            return;
+        };
+        let elapsed = (current_tick - start).max(0) as u16;
+        let refilled = (elapsed / refill_ticks.max(1)).min(self.spent[slot] as u16);
+        self.spent[slot] -= refilled as u8;
+        self.refill_start[slot] =
+            (self.spent[slot] > 0).then(|| start + (refilled * refill_ticks) as i16);
+    }
+
+    /// Spends one charge of `slot`, starting its refill if none is running.
+    pub fn spend(&mut self, slot: usize, current_tick: Tick) {
+        self.spent[slot] += 1;
+        self.refill_start[slot].get_or_insert(current_tick);
+    }
+}
+
+/// Per-slot recast lockouts from [`AbilityEffect::Interrupt`]. A slot can't be
+/// activated until its tick is reached.
+#[derive(Component, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct AbilityLockouts {
+    pub until: [Option<Tick>; 5],
+}
+
+impl AbilityLockouts {
+    pub fn is_locked(&self, slot: usize, current_tick: Tick) -> bool {
+        self.until[slot].is_some_and(|until| (until - current_tick) > 0)
+    }
+
+    /// Locks `slot` until `until`, never shortening an existing lockout.
+    pub fn lock(&mut self, slot: usize, until: Tick) {
+        let extends = self.until[slot].is_none_or(|current| (until - current) > 0);
+        if extends {
+            self.until[slot] = Some(until);
+        }
+    }
+}
+
 impl AbilityCooldowns {
     pub fn is_on_cooldown(&self, slot: usize, current_tick: Tick, cooldown_ticks: u16) -> bool {
         self.last_used[slot]
             .map(|last| (current_tick - last).unsigned_abs() <= cooldown_ticks)
             .unwrap_or(false)
     }
+
+    /// Ticks until `slot` can be used again; zero once [`Self::is_on_cooldown`]
+    /// is false.
+    pub fn remaining_ticks(&self, slot: usize, current_tick: Tick, cooldown_ticks: u16) -> u16 {
+        self.last_used[slot]
+            .map(|last| {
+                cooldown_ticks
+                    .saturating_add(1)
+                    .saturating_sub((current_tick - last).unsigned_abs())
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// Testing toggle for practising combos: abilities ignore their cooldowns and
+/// resource costs, and the server respawns dead characters at once, in place. Off by default; the
+/// server enables it with `--practice`, and clients should pass the same flag
+/// so their predicted casts match.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PracticeMode(pub bool);
+
+/// Operator filter on which abilities may be cast, so individual abilities can
+/// be disabled without removing their RON files. Enforced by
+/// `ability_activation`; the server sets it, and clients that don't match it
+/// briefly mispredict refused casts.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub enum AbilityAvailability {
+    /// Every loaded ability may be cast.
+    #[default]
+    All,
+    /// Only these abilities may be cast.
+    Allow(HashSet<AbilityId>),
+    /// Every ability except these may be cast.
+    Deny(HashSet<AbilityId>),
+}
+
+impl AbilityAvailability {
+    /// Whether `ability_id` passes the filter.
+    pub fn is_available(&self, ability_id: &AbilityId) -> bool {
+        match self {
+            Self::All => true,
+            Self::Allow(allowed) => allowed.contains(ability_id),
+            Self::Deny(denied) => !denied.contains(ability_id),
+        }
+    }
+}
+
+/// Bullet collider radius for a `Projectile` effect that doesn't set one.
+pub const DEFAULT_PROJECTILE_RADIUS: f32 = 0.5;
+
+fn default_projectile_radius() -> f32 {
+    DEFAULT_PROJECTILE_RADIUS
 }
 
 /// One-shot: inserted by apply_on_tick_effects when processing Projectile.
@@ -243,8 +771,63 @@ impl AbilityCooldowns {
 pub struct ProjectileSpawnEffect {
     pub speed: f32,
     pub lifetime_ticks: u16,
+    pub radius: f32,
+    pub sticky: Option<StickyProjectile>,
+    pub clash: bool,
+    pub homing: Option<HomingProjectile>,
+}
+
+/// On a projectile spawn and its bullet: instead of applying `OnHitEffects` on
+/// impact, the bullet attaches to the character it hits and applies them to that
+/// character `fuse_ticks` later.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StickyProjectile {
+    pub fuse_ticks: u16,
 }
 
+/// On a projectile spawn and its bullet: the bullet collides with opposing
+/// clashing bullets, and both despawn on contact.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ClashingProjectile;
+
+/// On a projectile spawn and its bullet: the bullet turns toward the nearest
+/// live character within `range` that `affects` allows (enemies only by
+/// default) and, if `line_of_sight` is set, that no terrain hides. It keeps its
+/// target until that target stops qualifying, then re-acquires.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+#[type_path = "protocol::ability"]
+pub struct HomingProjectile {
+    /// Maximum turn rate toward the target, in radians per second.
+    pub turn_rate: f32,
+    pub range: f32,
+    #[serde(default)]
+    pub affects: TargetMask,
+    #[serde(default = "default_line_of_sight")]
+    pub line_of_sight: bool,
+}
+
+fn default_line_of_sight() -> bool {
+    true
+}
+
+/// On a homing bullet: the character it is currently steering toward.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq)]
+pub struct HomingTarget(pub Option<Entity>);
+
+/// On a sticky bullet that has attached: it follows `victim` at `offset` and
+/// detonates at `detonate_tick`.
+#[derive(Component, Clone, Debug, PartialEq)]
+pub struct StuckTo {
+    pub victim: Entity,
+    pub offset: Vec3,
+    pub detonate_tick: Tick,
+}
+
+/// On an ActiveAbility entity with a `Grapple` effect: the point its ray
+/// latched onto, or `None` once the ray missed or the caster arrived.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GrappleAnchor(pub Option<Vec3>);
+
 /// Relationship: hitbox entity belongs to an ActiveAbility entity.
 #[derive(Component, Debug)]
 #[relationship(relationship_target = ActiveAbilityHitboxes)]
@@ -259,11 +842,57 @@ pub struct ActiveAbilityHitboxes(Vec<Entity>);
 #[derive(Component, Clone, Debug)]
 pub struct MeleeHitbox;
 
+/// On a fan-shaped melee hitbox: a sphere of `range` around the caster that
+/// only counts targets within `half_angle` radians of the caster's facing.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct MeleeArc {
+    pub half_angle: f32,
+    pub range: f32,
+}
+
+impl MeleeArc {
+    /// A fan `arc_degrees` wide in total.
+    pub fn new(arc_degrees: f32, range: f32) -> Self {
+        Self {
+            half_angle: (arc_degrees * 0.5).to_radians(),
+            range,
+        }
+    }
+
+    /// Whether a target at `offset` from the caster is inside the fan around
+    /// `facing`. Height is ignored; a target directly above or below counts.
+    pub fn contains(&self, facing: Vec3, offset: Vec3) -> bool {
+        let flat_offset = Vec3::new(offset.x, 0.0, offset.z);
+        let flat_facing = Vec3::new(facing.x, 0.0, facing.z);
+        if flat_offset.length_squared() < f32::EPSILON
+            || flat_facing.length_squared() < f32::EPSILON
+        {
+            return true;
+        }
+        flat_facing.angle_between(flat_offset) <= self.half_angle
+    }
+}
+
 /// Tracks spawn tick and duration for AoE hitbox lifetime management.
 #[derive(Component, Clone, Debug)]
 pub struct AoEHitbox {
     pub spawn_tick: Tick,
     pub duration_ticks: u16,
+    /// Most targets this hitbox may hit; see `AbilityEffect::AreaOfEffect`.
+    pub max_targets: Option<u32>,
+    pub radius: f32,
+    /// Damage lost at the edge of `radius`; see `AbilityEffect::AreaOfEffect`.
+    pub falloff: Option<f32>,
+}
+
+impl AoEHitbox {
+    /// Damage multiplier for a target `distance` from the hitbox center.
+    pub fn damage_scale(&self, distance: f32) -> f32 {
+        let Some(falloff) = self.falloff else {
+            return 1.0;
+        };
+        (1.0 - falloff * (distance / self.radius).min(1.0)).max(0.0)
+    }
 }
 
 /// Tracks entities already hit by this hitbox to prevent duplicate effects.
@@ -273,12 +902,62 @@ pub struct HitTargets(pub EntityHashSet);
 /// Carried on ActiveAbility entities (for melee) and bullet entities (for projectiles).
 #[derive(Component, Clone, Debug)]
 pub struct OnHitEffects {
+    pub ability_id: AbilityId,
     pub effects: Vec<AbilityEffect>,
     pub caster: Entity,
     pub original_caster: Entity,
+    /// Slot of `original_caster` the ability was cast from.
+    pub ability_slot: u8,
     pub depth: u8,
 }
 
+impl OnHitEffects {
+    /// `effects` landing as hits from `active`.
+    pub fn from_ability(active: &ActiveAbility, effects: Vec<AbilityEffect>) -> Self {
+        Self {
+            ability_id: active.def_id.clone(),
+            effects,
+            caster: active.caster,
+            original_caster: active.original_caster,
+            ability_slot: active.ability_slot,
+            depth: active.depth,
+        }
+    }
+
+    /// Effects from a non-ability `source` such as a hazard. The source counts
+    /// as both caster and original caster, and any kill is credited to
+    /// `source_id`.
+    pub fn from_source(source: Entity, source_id: AbilityId, effects: Vec<AbilityEffect>) -> Self {
+        Self {
+            ability_id: source_id,
+            effects,
+            caster: source,
+            original_caster: source,
+            ability_slot: 0,
+            depth: 0,
+        }
+    }
+}
+
+/// The ability hit behind a [`DeathEvent`](crate::DeathEvent).
+#[derive(Clone, Debug, PartialEq)]
+pub struct AbilityKill {
+    pub ability_id: AbilityId,
+    /// The character whose slot cast the ability.
+    pub original_caster: Entity,
+    pub ability_slot: u8,
+}
+
+impl From<&OnHitEffects> for AbilityKill {
+    fn from(on_hit: &OnHitEffects) -> Self {
+        Self {
+            ability_id: on_hit.ability_id.clone(),
+            original_caster: on_hit.original_caster,
+            ability_slot: on_hit.ability_slot,
+        }
+    }
+}
+
 /// Active-phase tick effect with offset metadata.
 #[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 #[type_path = "protocol::ability"]
@@ -294,6 +973,12 @@ pub struct TickEffect {
 #[reflect(Component, Serialize, Deserialize)]
 pub struct OnTickEffects(pub Vec<TickEffect>);
 
+/// Archetype component: effects that fire exactly once, when Startup → Active.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct OnCastEffects(pub Vec<AbilityEffect>);
+
 /// Archetype component: effects that fire every tick during Active phase.
 #[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
 #[type_path = "protocol::ability"]
@@ -326,6 +1011,84 @@ pub struct OnInputEffects(pub Vec<InputEffect>);
 #[reflect(Component, Serialize, Deserialize)]
 pub struct OnHitEffectDefs(pub Vec<AbilityEffect>);
 
+/// Archetype component: the ability is aimed at a point on the ground no farther
+/// than `range` from the caster. Clients draw a targeting reticle while it is held.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct GroundTarget {
+    pub range: f32,
+}
+
+/// Archetype component: activation is refused, without consuming the cooldown,
+/// unless a damageable target on the caster's map is within `range`.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct MaxRange {
+    pub range: f32,
+}
+
+/// Archetype component: the ability stores up to `charges` casts. Each cast
+/// spends a charge instead of waiting out `AbilityPhases::cooldown`, and one
+/// charge refills every `refill_ticks`; with none left the slot is blocked.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct MaxCharges {
+    pub charges: u8,
+    pub refill_ticks: u16,
+}
+
+/// Archetype component: activation spends `cost` from the caster's
+/// [`ResourcePool`] and is refused, without consuming the cooldown, when the
+/// pool holds less. Casters without a pool cast for free.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct AbilityCost {
+    pub cost: f32,
+}
+
+/// Archetype component: when a hit from this ability kills its target, the
+/// caster's cooldown for the slot it was cast from is cleared ("execute").
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct ResetCooldownOnKill;
+
+/// Archetype component: the caster's movement input is ignored from the
+/// ability's Startup through its Active phase, so a `WhileActive`
+/// `SetVelocity` dash isn't steered off course. Casters with other active
+/// abilities still move normally.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct LockMovement;
+
+/// Archetype component: the ability is channeled. Through its Startup and
+/// Active phases it ends early once the caster releases the slot's action or
+/// takes a hit ([`DamagedThisTick`]).
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize, Default)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Channeled;
+
+/// Archetype component: how clients visually smooth the entities this ability
+/// spawns. Copied onto its minions, hitboxes and projectiles; `Snap` entities
+/// skip frame interpolation so rapidly respawned effects never slide in from a
+/// stale position.
+#[derive(
+    Component, Clone, Copy, Debug, PartialEq, Eq, Reflect, Serialize, Deserialize, Default,
+)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub enum AbilityInterpolation {
+    #[default]
+    Smoothed,
+    Snap,
+}
+
 /// Caster-state condition evaluated at ability activation.
 #[derive(Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 #[type_path = "protocol::ability"]
@@ -393,6 +1156,48 @@ pub struct ActiveShield {
     pub remaining: f32,
 }
 
+/// Crowd control from [`AbilityEffect::Stun`]: the character ignores movement
+/// input and cannot activate abilities until `expires_tick`.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Stunned {
+    pub expires_tick: Tick,
+}
+
+/// Marks a character that took on-hit damage since `update_active_abilities`
+/// last ran, which then breaks its [`Channeled`] abilities and clears it.
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DamagedThisTick;
+
+/// Mana-like resource spent by [`AbilityCost`] abilities. Refills by
+/// `regen_per_tick` every tick, up to `max`.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ResourcePool {
+    pub current: f32,
+    pub max: f32,
+    pub regen_per_tick: f32,
+}
+
+impl ResourcePool {
+    /// A full pool of `max`.
+    pub fn new(max: f32, regen_per_tick: f32) -> Self {
+        Self {
+            current: max,
+            max,
+            regen_per_tick,
+        }
+    }
+
+    /// Subtracts `cost` if the pool holds at least that much. Returns whether
+    /// it was spent.
+    pub fn try_spend(&mut self, cost: f32) -> bool {
+        if cost > self.current {
+            return false;
+        }
+        self.current -= cost;
+        true
+    }
+}
+
 /// Temporary stat modifiers on a character. Tick-based expiry.
 #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct ActiveBuffs(pub Vec<ActiveBuff>);
@@ -402,8 +1207,85 @@ pub struct ActiveBuff {
     pub stat: String,
     pub multiplier: f32,
     pub expires_tick: Tick,
+    /// Ability that applied the buff. Recasting it refreshes the buff rather
+    /// than stacking another.
+    pub source: AbilityId,
+}
+
+/// Damage-over-time effects ticking on a character, each on its own timer.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActiveDots(pub Vec<ActiveDot>);
+
+/// One damage-over-time application: deals `amount` on `next_tick` and every
+/// `interval_ticks` after it, up to and including `expires_tick`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActiveDot {
+    pub amount: f32,
+    pub interval_ticks: u16,
+    pub next_tick: Tick,
+    pub expires_tick: Tick,
+    pub damage_type: DamageType,
+}
+
+/// Buff stat that scales `Health.max`.
+pub const MAX_HEALTH_STAT: &str = "max_health";
+
+/// Buff stat that scales the amount a caster's `Heal` effects restore.
+pub const HEALING_STAT: &str = "healing";
+
+/// Buff stat that scales a character's top movement speed.
+pub const SPEED_STAT: &str = "speed";
+
+/// Buff stat that scales how quickly a character reaches its movement speed.
+pub const ACCELERATION_STAT: &str = "acceleration";
+
+impl ActiveBuffs {
+    /// Combined multiplier of every active buff on `stat`.
+    pub fn multiplier(&self, stat: &str) -> f32 {
+        self.0
+            .iter()
+            .filter(|b| b.stat == stat)
+            .map(|b| b.multiplier)
+            .product()
+    }
+
+    pub fn has(&self, stat: &str) -> bool {
+        self.0.iter().any(|b| b.stat == stat)
+    }
+
+    /// Adds `buff`, replacing any buff of the same stat and source so repeat
+    /// casts refresh its duration instead of stacking.
+    pub fn add(&mut self, buff: ActiveBuff) {
+        match self
+            .0
+            .iter_mut()
+            .find(|b| b.stat == buff.stat && b.source == buff.source)
+        {
+            Some(existing) => *existing = buff,
+            None => self.0.push(buff),
+        }
+    }
+}
+
+/// Incoming damage multipliers by [`DamageType`]: 0.5 halves fire damage, 2.0
+/// doubles it. Types not listed take full damage. Loadable from RON.
+#[derive(Component, Clone, Debug, Default, PartialEq, Serialize, Deserialize, Reflect)]
+#[type_path = "protocol::ability"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct Resistances(pub HashMap<DamageType, f32>);
+
+impl Resistances {
+    /// The multiplier applied to incoming `damage_type` damage.
+    pub fn multiplier(&self, damage_type: DamageType) -> f32 {
+        self.0.get(&damage_type).copied().unwrap_or(1.0)
+    }
 }
 
+/// Unbuffed `Health.max`, recorded while any `max_health` buff is active so
+/// expiry can restore it.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BaseMaxHealth(pub f32);
+
 /// Marker on a ProjectileSpawn entity -- stores spawn parameters.
 #[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
 #[type_path = "protocol::ability"]
@@ -413,10 +1295,28 @@ pub struct AbilityProjectileSpawn {
     pub direction: Vec3,
     pub speed: f32,
     pub lifetime_ticks: u16,
+    /// Radius of the bullet's sphere collider.
+    pub radius: f32,
     pub ability_id: AbilityId,
     pub shooter: Entity,
 }
 
+/// Caps how many projectiles one character may have in flight. A cast that
+/// would exceed the cap fires nothing; the projectiles already out are kept.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ProjectileLimitConfig {
+    /// Live projectiles allowed per shooter, counting ones spawned this tick.
+    pub max_per_shooter: usize,
+}
+
+impl Default for ProjectileLimitConfig {
+    fn default() -> Self {
+        Self {
+            max_per_shooter: 16,
+        }
+    }
+}
+
 /// Relationship: projectile belongs to a character.
 #[derive(Component, Debug)]
 #[relationship(relationship_target = AbilityBullets)]
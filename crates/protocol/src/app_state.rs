@@ -36,10 +36,14 @@ fn check_assets_loaded(
     tracked: Res<TrackedAssets>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
-    let all_loaded = tracked
-        .0
-        .iter()
-        .all(|handle| asset_server.is_loaded_with_dependencies(handle));
+    // Failed loads are reported by their owning plugin and fall back to defaults;
+    // waiting on them would leave the app in `Loading` forever.
+    let all_loaded = tracked.0.iter().all(|handle| {
+        asset_server.is_loaded_with_dependencies(handle)
+            || asset_server
+                .get_load_state(handle.id())
+                .is_some_and(|state| state.is_failed())
+    });
 
     if all_loaded {
         info!("All tracked assets loaded, transitioning to AppState::Ready");
@@ -1,9 +1,21 @@
 pub mod movement;
+pub mod name;
 pub mod types;
 
-pub use movement::{apply_movement, detect_grounded, update_facing};
+pub use movement::{
+    apply_dodge, apply_ground_drag, apply_move_deadzone, apply_movement, detect_grounded,
+    detect_wall_contact, expire_invulnerability, step_up_obstacles, update_facing,
+    CharacterContacts, MovementLocks,
+};
+pub use name::{
+    default_player_name, sanitize_player_name, PlayerNameChannel, PlayerNameLimits,
+    PlayerNameRequest,
+};
 pub use types::{
-    CharacterMarker, CharacterPhysicsBundle, CharacterType, ColorComponent, DeathEvent,
-    DummyTarget, Health, Invulnerable, IsGrounded, PlayerId, RespawnPoint, RespawnTimer,
-    RespawnTimerConfig, CHARACTER_CAPSULE_HEIGHT, CHARACTER_CAPSULE_RADIUS, DEFAULT_RESPAWN_TICKS,
+    CharacterCollisionResponse, CharacterDimensions, CharacterMarker, CharacterPhysicsBundle,
+    CharacterType, ColorComponent, DeathEvent, DodgeConfig, DodgeCooldown, DummyTarget, Health,
+    HealthRegen, Invulnerable, IsGrounded, LastDamagedTick, MovementConfig, PlayerId, RespawnPoint,
+    RespawnTimer, RespawnTimerConfig, TouchingWall, CHARACTER_CAPSULE_HEIGHT,
+    CHARACTER_CAPSULE_RADIUS, CHARACTER_COLORS, DEFAULT_RESPAWN_INVULNERABLE_TICKS,
+    DEFAULT_RESPAWN_TICKS,
 };
@@ -1,43 +1,209 @@
-use super::types::{CharacterMarker, IsGrounded};
+use super::types::{
+    CharacterCollisionResponse, CharacterDimensions, CharacterMarker, DodgeConfig, DodgeCooldown,
+    Invulnerable, IsGrounded, MovementConfig, RespawnTimer, TouchingWall,
+};
+use crate::ability::{
+    facing_direction, AbilityPhase, ActiveAbility, ActiveBuffs, LockMovement, Stunned,
+    ACCELERATION_STAT, SPEED_STAT,
+};
 use crate::map::MapInstanceId;
 use crate::PlayerActions;
 use avian3d::prelude::{forces::ForcesItem, *};
+use bevy::ecs::system::SystemParam;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::{LocalTimeline, PredictionDisable};
+
+/// How far below the capsule's feet the ground ray still finds the floor.
+const GROUND_PROBE_MARGIN: f32 = 1.0;
+/// How far past the capsule surface a wall still counts as touching.
+const WALL_CONTACT_MARGIN: f32 = 0.3;
+const WALL_JUMP_PUSH_SPEED: f32 = 12.0;
+const WALL_JUMP_UP_SPEED: f32 = 14.0;
+/// Fastest fall speed while clinging, i.e. airborne and steering into a wall.
+const WALL_SLIDE_SPEED: f32 = 3.0;
+/// Tallest ledge `step_up_obstacles` climbs: one voxel plus a little slack.
+const MAX_STEP_HEIGHT: f32 = 1.1;
+/// Height above the feet of the ray that detects a blocking ledge.
+const STEP_PROBE_HEIGHT: f32 = 0.1;
+/// How far past the capsule surface a ledge still counts as blocking.
+const STEP_PROBE_MARGIN: f32 = 0.3;
+
+/// Applies a radial deadzone to a stick axis, clamped to length 1. Inputs no
+/// longer than `deadzone` become zero; the rest of the range is rescaled so the
+/// output length runs from 0 at the deadzone edge to 1 at full deflection.
+pub fn apply_move_deadzone(axis: Vec2, deadzone: f32) -> Vec2 {
+    debug_assert!(
+        (0.0..1.0).contains(&deadzone),
+        "move deadzone must be in [0, 1), got {deadzone}"
+    );
+    let axis = axis.clamp_length_max(1.0);
+    let length = axis.length();
+    if length <= deadzone {
+        return Vec2::ZERO;
+    }
+    axis * ((length - deadzone) / (1.0 - deadzone) / length)
+}
+
+/// Camera-relative horizontal movement direction from input, length at most 1,
+/// after [`MovementConfig::move_deadzone`].
+fn movement_direction(action_state: &ActionState<PlayerActions>, deadzone: f32) -> Vec3 {
+    let move_dir = apply_move_deadzone(action_state.axis_pair(&PlayerActions::Move), deadzone);
+    let yaw = action_state.value(&PlayerActions::CameraYaw);
+    Quat::from_rotation_y(yaw) * Vec3::new(-move_dir.x, 0.0, move_dir.y)
+}
+
 /// Apply horizontal acceleration based on movement input.
-/// Jump is now handled by the data-defined `jump` ability.
+/// Jump from the ground is handled by the data-defined `jump` ability; pressing
+/// Jump while airborne next to a wall wall-jumps, and steering into a wall
+/// while airborne slows the fall to a slide. `speed` and `acceleration` buffs
+/// scale the top speed and acceleration. The force never drives the character
+/// along any of the `blocked` directions, from [`CharacterContacts`].
+#[allow(clippy::too_many_arguments)]
 pub fn apply_movement(
     mass: &ComputedMass,
     delta_secs: f32,
     action_state: &ActionState<PlayerActions>,
+    move_deadzone: f32,
+    buffs: Option<&ActiveBuffs>,
+    grounded: bool,
+    wall: Option<&TouchingWall>,
+    blocked: &[Dir3],
     forces: &mut ForcesItem,
 ) {
     const MAX_SPEED: f32 = 15.0;
     const MAX_ACCELERATION: f32 = 500.0;
 
-    let max_velocity_delta_per_tick = MAX_ACCELERATION * delta_secs;
+    let buff = |stat| buffs.map_or(1.0, |buffs| buffs.multiplier(stat));
+    let max_speed = MAX_SPEED * buff(SPEED_STAT);
+    let max_velocity_delta_per_tick = MAX_ACCELERATION * buff(ACCELERATION_STAT) * delta_secs;
 
-    // Horizontal movement (camera-relative)
-    let move_dir = action_state
-        .axis_pair(&PlayerActions::Move)
-        .clamp_length_max(1.0);
-    let yaw = action_state.value(&PlayerActions::CameraYaw);
-    let move_dir = Quat::from_rotation_y(yaw) * Vec3::new(-move_dir.x, 0.0, move_dir.y);
+    let move_dir = movement_direction(action_state, move_deadzone);
 
     let linear_velocity = forces.linear_velocity();
     let ground_linear_velocity = Vec3::new(linear_velocity.x, 0.0, linear_velocity.z);
+    let vertical_speed = linear_velocity.y;
 
-    let desired_ground_linear_velocity = move_dir * MAX_SPEED;
-    let new_ground_linear_velocity = ground_linear_velocity
+    let desired_ground_linear_velocity = move_dir * max_speed;
+    let mut new_ground_linear_velocity = ground_linear_velocity
         .move_towards(desired_ground_linear_velocity, max_velocity_delta_per_tick);
+    for toward in blocked {
+        let closing = new_ground_linear_velocity.dot(**toward);
+        if closing > 0.0 {
+            new_ground_linear_velocity -= **toward * closing;
+        }
+    }
 
     let required_acceleration = (new_ground_linear_velocity - ground_linear_velocity) / delta_secs;
 
     forces.apply_force(required_acceleration * mass.value());
+
+    let (false, Some(wall)) = (grounded, wall) else {
+        return;
+    };
+    if action_state.just_pressed(&PlayerActions::Jump) {
+        let target = wall.normal * WALL_JUMP_PUSH_SPEED + Vec3::Y * WALL_JUMP_UP_SPEED;
+        let current = Vec3::new(0.0, vertical_speed, 0.0);
+        forces.apply_linear_impulse((target - current) * mass.value());
+    } else if move_dir.dot(-wall.normal) > 0.5 && vertical_speed < -WALL_SLIDE_SPEED {
+        let slowdown = Vec3::Y * (-WALL_SLIDE_SPEED - vertical_speed);
+        forces.apply_linear_impulse(slowdown * mass.value());
+    }
+}
+
+/// Which casters `handle_character_movement` must leave alone: [`Stunned`]
+/// ones, and those running a [`LockMovement`] ability that hasn't reached
+/// Recovery. Startup counts so the tick an ability turns Active, before
+/// movement sees the new phase, is covered too.
+#[derive(SystemParam)]
+pub struct MovementLocks<'w, 's> {
+    abilities:
+        Query<'w, 's, &'static ActiveAbility, (With<LockMovement>, Without<PredictionDisable>)>,
+    stunned: Query<'w, 's, (), With<Stunned>>,
+}
+
+impl MovementLocks<'_, '_> {
+    /// Whether `caster`'s movement input should be ignored this tick.
+    pub fn is_locked(&self, caster: Entity) -> bool {
+        self.stunned.contains(caster)
+            || self
+                .abilities
+                .iter()
+                .any(|active| active.caster == caster && active.phase != AbilityPhase::Recovery)
+    }
+}
+
+/// On a `Dodge` press off cooldown, sets the character's horizontal velocity to
+/// `DodgeConfig::speed` along the movement input (or its facing, with no input)
+/// and grants `Invulnerable` i-frames. Vertical velocity is kept. [`Stunned`]
+/// characters can't dodge. Everything it touches is predicted, so a rollback
+/// replays the dodge identically.
+pub fn apply_dodge(
+    mut commands: Commands,
+    config: Res<DodgeConfig>,
+    movement_config: Res<MovementConfig>,
+    timeline: Res<LocalTimeline>,
+    mut query: Query<
+        (
+            Entity,
+            &ActionState<PlayerActions>,
+            &ComputedMass,
+            &Rotation,
+            &mut DodgeCooldown,
+            Option<&Invulnerable>,
+            Has<Stunned>,
+            Forces,
+        ),
+        (With<CharacterMarker>, Without<RespawnTimer>),
+    >,
+) {
+    let tick = timeline.tick();
+    for (entity, action_state, mass, rotation, mut cooldown, invulnerable, stunned, mut forces) in
+        &mut query
+    {
+        if !action_state.just_pressed(&PlayerActions::Dodge) {
+            continue;
+        }
+        if stunned {
+            trace!("Dodge refused for {entity:?}: stunned");
+            continue;
+        }
+        if cooldown.is_on_cooldown(tick, config.cooldown_ticks) {
+            trace!("Dodge refused for {entity:?}: on cooldown");
+            continue;
+        }
+        cooldown.last_used = Some(tick);
+
+        let direction = movement_direction(action_state, movement_config.move_deadzone)
+            .try_normalize()
+            .unwrap_or_else(|| facing_direction(rotation).with_y(0.0).normalize_or_zero());
+        let velocity = forces.linear_velocity();
+        let ground_velocity = Vec3::new(velocity.x, 0.0, velocity.z);
+        forces.apply_linear_impulse((direction * config.speed - ground_velocity) * mass.value());
+
+        let expires_at = tick + config.invulnerable_ticks as i16;
+        if invulnerable.is_none_or(|existing| existing.expires_at - expires_at < 0) {
+            commands.entity(entity).insert(Invulnerable { expires_at });
+        }
+    }
+}
+
+/// Removes `Invulnerable` once its `expires_at` tick is reached.
+pub fn expire_invulnerability(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    query: Query<(Entity, &Invulnerable)>,
+) {
+    let tick = timeline.tick();
+    for (entity, invuln) in &query {
+        if tick >= invuln.expires_at {
+            commands.entity(entity).remove::<Invulnerable>();
+        }
+    }
 }
 
 /// Maintains the `IsGrounded` marker on character entities by ray casting
-/// downward from the capsule center each tick. Must run before
+/// downward from the center of each capsule's lower cap each tick. Must run before
 /// `handle_character_movement` and `ability_activation` so consumers see a
 /// fresh marker.
 pub fn detect_grounded(
@@ -45,17 +211,23 @@ pub fn detect_grounded(
     spatial_query: SpatialQuery,
     map_ids: Query<&MapInstanceId>,
     characters: Query<
-        (Entity, &Position, Option<&MapInstanceId>, Has<IsGrounded>),
+        (
+            Entity,
+            &Position,
+            &CharacterDimensions,
+            Option<&MapInstanceId>,
+            Has<IsGrounded>,
+        ),
         With<CharacterMarker>,
     >,
 ) {
-    for (entity, position, player_map_id, has_grounded) in &characters {
+    for (entity, position, dimensions, player_map_id, has_grounded) in &characters {
         let filter = SpatialQueryFilter::from_excluded_entities([entity]);
         let hit = spatial_query
             .cast_ray_predicate(
-                position.0,
+                dimensions.ground_ray_origin(position.0),
                 Dir3::NEG_Y,
-                4.0,
+                dimensions.radius + GROUND_PROBE_MARGIN,
                 false,
                 &filter,
                 &|hit_entity| match (player_map_id, map_ids.get(hit_entity).ok()) {
@@ -76,6 +248,229 @@ pub fn detect_grounded(
     }
 }
 
+/// Bleeds off horizontal velocity of grounded characters that have no movement
+/// input, at `MovementConfig::ground_drag` per second.
+pub fn apply_ground_drag(
+    config: Res<MovementConfig>,
+    time: Res<Time>,
+    mut characters: Query<
+        (&ActionState<PlayerActions>, &mut LinearVelocity),
+        (
+            With<CharacterMarker>,
+            With<IsGrounded>,
+            Without<RespawnTimer>,
+        ),
+    >,
+) {
+    if config.ground_drag <= 0.0 {
+        return;
+    }
+    let retained = (-config.ground_drag * time.delta_secs()).exp();
+    for (action_state, mut velocity) in &mut characters {
+        if movement_direction(action_state, config.move_deadzone) != Vec3::ZERO {
+            continue;
+        }
+        velocity.x *= retained;
+        velocity.z *= retained;
+    }
+}
+
+/// Extra gap at which two characters count as touching for [`CharacterContacts`].
+/// Slightly more than a tick of travel at top speed, so a character is blocked
+/// before it can step into contact.
+const CHARACTER_CONTACT_MARGIN: f32 = 0.3;
+
+/// Living characters' positions and shapes, for blocking movement into one
+/// another under `CharacterCollisionResponse::Solid`.
+#[derive(SystemParam)]
+pub struct CharacterContacts<'w, 's> {
+    config: Res<'w, MovementConfig>,
+    characters: Query<
+        'w,
+        's,
+        (
+            Entity,
+            &'static Position,
+            &'static CharacterDimensions,
+            Option<&'static MapInstanceId>,
+        ),
+        (With<CharacterMarker>, Without<RespawnTimer>),
+    >,
+}
+
+impl CharacterContacts<'_, '_> {
+    /// Horizontal directions from `entity` toward every character it touches
+    /// on the same map. Empty unless characters are `Solid`, so movement can
+    /// push through as usual. Feeding these to [`apply_movement`] stops the
+    /// movement force itself, so the solver never sees a closing contact to
+    /// shove the other character with.
+    pub fn blocked_directions(&self, entity: Entity) -> Vec<Dir3> {
+        if self.config.character_collision != CharacterCollisionResponse::Solid {
+            return Vec::new();
+        }
+        let Ok((_, position, dimensions, map_id)) = self.characters.get(entity) else {
+            trace!("{entity:?} is not a living character, nothing blocks it");
+            return Vec::new();
+        };
+        self.characters
+            .iter()
+            .filter(|(other, .., other_map)| *other != entity && *other_map == map_id)
+            .filter_map(|(_, other_pos, other_dimensions, _)| {
+                let contact_distance =
+                    dimensions.radius + other_dimensions.radius + CHARACTER_CONTACT_MARGIN;
+                let vertical_reach = dimensions.feet_offset() + other_dimensions.feet_offset();
+                let offset = other_pos.0 - position.0;
+                let horizontal = Vec3::new(offset.x, 0.0, offset.z);
+                if horizontal.length() > contact_distance || offset.y.abs() >= vertical_reach {
+                    return None;
+                }
+                Dir3::new(horizontal).ok()
+            })
+            .collect()
+    }
+}
+
+/// Lifts grounded characters onto ledges up to `MAX_STEP_HEIGHT` tall that
+/// block their input direction. A low ray finds the ledge face, a ray just above
+/// `MAX_STEP_HEIGHT` confirms there is clearance, and a downward ray past the
+/// face finds the ledge top to snap the feet to. Only `Position`, input and
+/// colliders feed in, so replays produce the same result. Must run after
+/// `detect_grounded`.
+pub fn step_up_obstacles(
+    config: Res<MovementConfig>,
+    spatial_query: SpatialQuery,
+    map_ids: Query<&MapInstanceId>,
+    mut characters: Query<
+        (
+            Entity,
+            &ActionState<PlayerActions>,
+            &CharacterDimensions,
+            &mut Position,
+            &mut LinearVelocity,
+            Option<&MapInstanceId>,
+        ),
+        (
+            With<CharacterMarker>,
+            With<IsGrounded>,
+            Without<RespawnTimer>,
+        ),
+    >,
+) {
+    for (entity, action_state, dimensions, mut position, mut velocity, player_map_id) in
+        &mut characters
+    {
+        let probe_distance = dimensions.radius + STEP_PROBE_MARGIN;
+        let move_dir = movement_direction(action_state, config.move_deadzone);
+        let Ok(direction) = Dir3::new(move_dir) else {
+            continue;
+        };
+        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+        let same_map = |hit_entity: Entity| match (player_map_id, map_ids.get(hit_entity).ok()) {
+            (Some(a), Some(b)) => a == b,
+            _ => true,
+        };
+        let feet = position.0 - Vec3::Y * dimensions.feet_offset();
+        let low_origin = feet + Vec3::Y * STEP_PROBE_HEIGHT;
+        let Some(face) = spatial_query.cast_ray_predicate(
+            low_origin,
+            direction,
+            probe_distance,
+            false,
+            &filter,
+            &same_map,
+        ) else {
+            continue;
+        };
+        let high_origin = feet + Vec3::Y * (MAX_STEP_HEIGHT + STEP_PROBE_HEIGHT);
+        if spatial_query
+            .cast_ray_predicate(
+                high_origin,
+                direction,
+                probe_distance,
+                false,
+                &filter,
+                &same_map,
+            )
+            .is_some()
+        {
+            trace!("Step-up for {entity:?} blocked: obstacle taller than {MAX_STEP_HEIGHT}");
+            continue;
+        }
+        let top_origin = high_origin + *direction * (face.distance + STEP_PROBE_MARGIN);
+        let Some(top) = spatial_query.cast_ray_predicate(
+            top_origin,
+            Dir3::NEG_Y,
+            MAX_STEP_HEIGHT + STEP_PROBE_HEIGHT,
+            false,
+            &filter,
+            &same_map,
+        ) else {
+            continue;
+        };
+        let rise = top_origin.y - top.distance - feet.y;
+        if rise <= 0.0 || rise > MAX_STEP_HEIGHT {
+            continue;
+        }
+        position.0.y += rise;
+        velocity.y = velocity.y.max(0.0);
+    }
+}
+
+/// Maintains `TouchingWall` on character entities by ray casting along the four
+/// horizontal world axes from the capsule center each tick, keeping the closest
+/// hit. Axes are fixed rather than facing-relative so the result depends only on
+/// `Position`, which keeps it deterministic across rollback. Must run before
+/// `handle_character_movement`.
+pub fn detect_wall_contact(
+    mut commands: Commands,
+    spatial_query: SpatialQuery,
+    map_ids: Query<&MapInstanceId>,
+    characters: Query<
+        (
+            Entity,
+            &Position,
+            &CharacterDimensions,
+            Option<&MapInstanceId>,
+            Option<&TouchingWall>,
+        ),
+        With<CharacterMarker>,
+    >,
+) {
+    const DIRECTIONS: [Dir3; 4] = [Dir3::X, Dir3::NEG_X, Dir3::Z, Dir3::NEG_Z];
+    for (entity, position, dimensions, player_map_id, touching) in &characters {
+        let filter = SpatialQueryFilter::from_excluded_entities([entity]);
+        let nearest = DIRECTIONS
+            .iter()
+            .filter_map(|dir| {
+                spatial_query.cast_ray_predicate(
+                    position.0,
+                    *dir,
+                    dimensions.radius + WALL_CONTACT_MARGIN,
+                    false,
+                    &filter,
+                    &|hit_entity| match (player_map_id, map_ids.get(hit_entity).ok()) {
+                        (Some(a), Some(b)) => a == b,
+                        _ => true,
+                    },
+                )
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance));
+        let contact = nearest.map(|hit| TouchingWall {
+            normal: Vec3::new(hit.normal.x, 0.0, hit.normal.z).normalize_or_zero(),
+        });
+        match (contact, touching) {
+            (Some(contact), Some(existing)) if contact == *existing => {}
+            (Some(contact), _) => {
+                commands.entity(entity).insert(contact);
+            }
+            (None, Some(_)) => {
+                commands.entity(entity).remove::<TouchingWall>();
+            }
+            (None, None) => {}
+        }
+    }
+}
+
 /// Update character facing direction based on movement input.
 /// Separate from `apply_movement` because `Forces` already accesses `Rotation`.
 pub fn update_facing(
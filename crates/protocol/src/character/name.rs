@@ -0,0 +1,94 @@
+use bevy::prelude::*;
+use lightyear::prelude::PeerId;
+use serde::{Deserialize, Serialize};
+
+/// Channel for client display-name requests.
+pub struct PlayerNameChannel;
+
+/// Sent by a client to ask for a display name on its character. The server
+/// sanitizes it with [`sanitize_player_name`] before setting the replicated `Name`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct PlayerNameRequest {
+    pub name: String,
+}
+
+/// Server-side limits applied to requested player names.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct PlayerNameLimits {
+    /// Longest accepted name, in characters. Longer names are truncated.
+    pub max_chars: usize,
+}
+
+impl Default for PlayerNameLimits {
+    fn default() -> Self {
+        Self { max_chars: 24 }
+    }
+}
+
+/// Name shown for a player whose requested name sanitizes to nothing.
+pub fn default_player_name(peer_id: PeerId) -> String {
+    format!("Player {peer_id}")
+}
+
+/// `raw` with control characters stripped, surrounding whitespace trimmed and
+/// capped at `limits.max_chars`. Falls back to [`default_player_name`] if
+/// nothing is left.
+pub fn sanitize_player_name(raw: &str, peer_id: PeerId, limits: &PlayerNameLimits) -> String {
+    let stripped: String = raw.chars().filter(|c| !c.is_control()).collect();
+    let truncated: String = stripped.trim().chars().take(limits.max_chars).collect();
+    let name = truncated.trim_end();
+    if name.is_empty() {
+        return default_player_name(peer_id);
+    }
+    name.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PEER: PeerId = PeerId::Netcode(7);
+
+    #[test]
+    fn overlong_name_is_truncated() {
+        let limits = PlayerNameLimits { max_chars: 5 };
+        assert_eq!(
+            sanitize_player_name("  Bartholomew  ", PEER, &limits),
+            "Barth"
+        );
+        assert_eq!(
+            sanitize_player_name("ab cdef", PEER, &PlayerNameLimits { max_chars: 3 }),
+            "ab"
+        );
+    }
+
+    #[test]
+    fn control_characters_are_stripped() {
+        let limits = PlayerNameLimits::default();
+        assert_eq!(
+            sanitize_player_name("Al\u{7}i\tce\n", PEER, &limits),
+            "Alice"
+        );
+        assert_eq!(
+            sanitize_player_name("\u{1b}[31mRed", PEER, &limits),
+            "[31mRed"
+        );
+    }
+
+    #[test]
+    fn empty_name_falls_back_to_player_id() {
+        let limits = PlayerNameLimits::default();
+        assert_eq!(
+            sanitize_player_name("", PEER, &limits),
+            default_player_name(PEER)
+        );
+        assert_eq!(
+            sanitize_player_name(" \t\r\n ", PEER, &limits),
+            default_player_name(PEER)
+        );
+        assert_eq!(
+            sanitize_player_name("\u{0}", PEER, &limits),
+            default_player_name(PEER)
+        );
+    }
+}
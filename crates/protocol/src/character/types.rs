@@ -1,3 +1,4 @@
+use crate::ability::AbilityKill;
 use crate::hit_detection::character_collision_layers;
 use crate::map::MapSaveTarget;
 use crate::world_object::ReflectPersist;
@@ -6,6 +7,7 @@ use bevy::prelude::*;
 use lightyear::prelude::{PeerId, Tick};
 use serde::{Deserialize, Serialize};
 
+/// Default capsule size; per-character sizes live in [`CharacterDimensions`].
 pub const CHARACTER_CAPSULE_RADIUS: f32 = 2.0;
 pub const CHARACTER_CAPSULE_HEIGHT: f32 = 2.0;
 
@@ -17,8 +19,46 @@ pub const CHARACTER_CAPSULE_HEIGHT: f32 = 2.0;
 pub struct PlayerId(pub PeerId);
 
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[require(CharacterDimensions, DodgeCooldown)]
 pub struct CharacterMarker;
 
+/// Capsule size of a character. Drives its collider and every probe that needs
+/// the capsule's extent: the ground, wall and step-up rays and character blocking.
+/// Replicated so clients build the same collider as the server.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Reflect)]
+#[type_path = "protocol"]
+pub struct CharacterDimensions {
+    pub radius: f32,
+    /// Length of the cylindrical section, excluding the hemispherical caps.
+    pub height: f32,
+}
+
+impl Default for CharacterDimensions {
+    fn default() -> Self {
+        Self {
+            radius: CHARACTER_CAPSULE_RADIUS,
+            height: CHARACTER_CAPSULE_HEIGHT,
+        }
+    }
+}
+
+impl CharacterDimensions {
+    pub fn collider(&self) -> Collider {
+        Collider::capsule(self.radius, self.height)
+    }
+
+    /// Distance from the capsule center down to the bottom of its lower cap.
+    pub fn feet_offset(&self) -> f32 {
+        self.height / 2.0 + self.radius
+    }
+
+    /// Start of the downward ground ray: the center of the capsule's lower cap,
+    /// so the ray only needs to reach `radius` plus a margin to find the floor.
+    pub fn ground_ray_origin(&self, position: Vec3) -> Vec3 {
+        position - Vec3::Y * (self.height / 2.0)
+    }
+}
+
 /// Marker to distinguish dummy targets from player characters.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct DummyTarget;
@@ -33,6 +73,15 @@ pub enum CharacterType {
     Humanoid,
 }
 
+impl CharacterType {
+    /// Capsule size characters of this type spawn with.
+    pub fn dimensions(self) -> CharacterDimensions {
+        match self {
+            CharacterType::Humanoid => CharacterDimensions::default(),
+        }
+    }
+}
+
 /// Marks a respawn location. Server-only, not replicated.
 #[derive(Component, Clone, Debug)]
 #[require(MapSaveTarget)]
@@ -58,6 +107,21 @@ impl Health {
         was_alive && self.current <= 0.0
     }
 
+    /// Restores health, clamping to `max`. The dead stay dead. Returns the
+    /// amount actually restored.
+    pub fn heal(&mut self, amount: f32) -> f32 {
+        debug_assert!(
+            amount >= 0.0,
+            "heal amount must be non-negative, got {amount}"
+        );
+        if self.is_dead() {
+            return 0.0;
+        }
+        let before = self.current;
+        self.current = (self.current + amount).min(self.max);
+        self.current - before
+    }
+
     pub fn is_dead(&self) -> bool {
         self.current <= 0.0
     }
@@ -65,35 +129,85 @@ impl Health {
     pub fn restore_full(&mut self) {
         self.current = self.max;
     }
+
+    /// Changes `max`, scaling `current` so the health fraction is unchanged.
+    pub fn set_max_preserving_fraction(&mut self, max: f32) {
+        debug_assert!(max > 0.0, "max health must be positive, got {max}");
+        let fraction = if self.max > 0.0 {
+            (self.current / self.max).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        self.max = max;
+        self.current = fraction * max;
+    }
 }
 
 /// Emitted when an entity's health transitions from alive to dead.
 #[derive(bevy::ecs::message::Message)]
 pub struct DeathEvent {
     pub entity: Entity,
+    /// The ability hit that dealt the killing blow, if an ability did.
+    pub killed_by: Option<AbilityKill>,
 }
 
-/// Post-respawn invulnerability. Prevents damage while present.
+/// Post-respawn and dodge invulnerability. Prevents damage while present.
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Invulnerable {
     pub expires_at: Tick,
 }
 
+/// Passive regeneration: restores `per_tick` health each tick once the entity
+/// has gone `combat_delay_ticks` without taking damage. Loadable from RON.
+#[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
+#[type_path = "protocol"]
+#[reflect(Component, Serialize, Deserialize)]
+pub struct HealthRegen {
+    pub per_tick: f32,
+    pub combat_delay_ticks: u16,
+}
+
+impl Default for HealthRegen {
+    /// About 3 health per second, starting 5 seconds after the last hit.
+    fn default() -> Self {
+        Self {
+            per_tick: 0.05,
+            combat_delay_ticks: 320,
+        }
+    }
+}
+
+/// The tick an entity last took hit damage. Pauses its [`HealthRegen`] and is
+/// removed once the combat delay has passed. Not networked.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct LastDamagedTick(pub Tick);
+
 /// Default respawn delay when no `RespawnTimerConfig` is present.
 pub const DEFAULT_RESPAWN_TICKS: u16 = 256;
 
-/// Per-entity configuration for respawn delay. Loadable from RON.
+/// Default post-respawn invulnerability when no `RespawnTimerConfig` is present.
+pub const DEFAULT_RESPAWN_INVULNERABLE_TICKS: u16 = 128;
+
+fn default_respawn_invulnerable_ticks() -> u16 {
+    DEFAULT_RESPAWN_INVULNERABLE_TICKS
+}
+
+/// Per-entity configuration for respawn delay and the `Invulnerable` window
+/// granted on respawn. Loadable from RON.
 #[derive(Component, Clone, Debug, PartialEq, Reflect, Serialize, Deserialize)]
 #[type_path = "protocol"]
 #[reflect(Component, Serialize, Deserialize)]
 pub struct RespawnTimerConfig {
     pub duration_ticks: u16,
+    #[serde(default = "default_respawn_invulnerable_ticks")]
+    pub invulnerable_ticks: u16,
 }
 
 impl Default for RespawnTimerConfig {
     fn default() -> Self {
         Self {
             duration_ticks: DEFAULT_RESPAWN_TICKS,
+            invulnerable_ticks: DEFAULT_RESPAWN_INVULNERABLE_TICKS,
         }
     }
 }
@@ -108,6 +222,16 @@ pub struct RespawnTimer {
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct ColorComponent(pub Color);
 
+/// Colors handed out to joining characters in order, wrapping around.
+/// Clients remap these by index for colorblind-friendly palettes.
+pub const CHARACTER_COLORS: [Color; 5] = [
+    Color::Srgba(bevy::color::palettes::css::LIMEGREEN),
+    Color::Srgba(bevy::color::palettes::css::PINK),
+    Color::Srgba(bevy::color::palettes::css::YELLOW),
+    Color::Srgba(bevy::color::palettes::css::AQUA),
+    Color::Srgba(bevy::color::palettes::css::CRIMSON),
+];
+
 #[derive(Bundle)]
 pub struct CharacterPhysicsBundle {
     pub collider: Collider,
@@ -115,20 +239,106 @@ pub struct CharacterPhysicsBundle {
     pub locked_axes: LockedAxes,
     pub friction: Friction,
     pub collision_layers: CollisionLayers,
+    /// Keeps fast or shoved characters from tunneling through thin chunk colliders.
+    pub ccd: SweptCcd,
 }
 
 impl Default for CharacterPhysicsBundle {
     fn default() -> Self {
+        Self::new(&MovementConfig::default(), &CharacterDimensions::default())
+    }
+}
+
+impl CharacterPhysicsBundle {
+    pub fn new(config: &MovementConfig, dimensions: &CharacterDimensions) -> Self {
         Self {
-            collider: Collider::capsule(CHARACTER_CAPSULE_RADIUS, CHARACTER_CAPSULE_HEIGHT),
+            collider: dimensions.collider(),
             rigid_body: RigidBody::Dynamic,
             locked_axes: LockedAxes::ROTATION_LOCKED,
-            friction: Friction::ZERO.with_combine_rule(CoefficientCombine::Min),
+            friction: Friction::new(config.friction).with_combine_rule(config.friction_combine),
             collision_layers: character_collision_layers(),
+            ccd: SweptCcd::default(),
+        }
+    }
+}
+
+/// Tuning for how characters move and stop. The defaults reproduce the original
+/// frictionless feel; movement input still decelerates characters on its own.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct MovementConfig {
+    /// Coefficient on the character collider. Applied when the physics bundle is inserted.
+    pub friction: f32,
+    pub friction_combine: CoefficientCombine,
+    /// Exponential decay rate (per second) of horizontal velocity while grounded
+    /// with no movement input. Zero disables `apply_ground_drag`.
+    pub ground_drag: f32,
+    pub character_collision: CharacterCollisionResponse,
+    /// Radial deadzone on the `Move` stick, as a fraction of full deflection.
+    /// Shorter inputs read as no input; longer ones are rescaled so movement
+    /// still ramps smoothly from zero at the deadzone edge.
+    pub move_deadzone: f32,
+}
+
+/// How characters react when they walk into each other.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CharacterCollisionResponse {
+    /// The physics solver resolves contacts, so a moving character shoves a
+    /// standing one along.
+    #[default]
+    PushBack,
+    /// Characters block each other like walls: `CharacterContacts` keeps the
+    /// movement force from driving any character into one it is touching.
+    Solid,
+}
+
+impl Default for MovementConfig {
+    fn default() -> Self {
+        Self {
+            friction: 0.0,
+            friction_combine: CoefficientCombine::Min,
+            ground_drag: 0.0,
+            character_collision: CharacterCollisionResponse::default(),
+            move_deadzone: 0.1,
+        }
+    }
+}
+
+/// Tuning for `PlayerActions::Dodge`.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct DodgeConfig {
+    /// Horizontal speed the dodge sets, in units per second.
+    pub speed: f32,
+    /// How long the dodge's `Invulnerable` i-frames last.
+    pub invulnerable_ticks: u16,
+    /// Ticks after a dodge before the next one is allowed.
+    pub cooldown_ticks: u16,
+}
+
+impl Default for DodgeConfig {
+    fn default() -> Self {
+        Self {
+            speed: 30.0,
+            invulnerable_ticks: 16,
+            cooldown_ticks: 64,
         }
     }
 }
 
+/// Tick of the character's last dodge. Predicted, so rollback restores it along
+/// with the velocity and i-frames the dodge produced.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct DodgeCooldown {
+    pub last_used: Option<Tick>,
+}
+
+impl DodgeCooldown {
+    pub fn is_on_cooldown(&self, current_tick: Tick, cooldown_ticks: u16) -> bool {
+        self.last_used
+            .map(|last| (current_tick - last).unsigned_abs() <= cooldown_ticks)
+            .unwrap_or(false)
+    }
+}
+
 /// Local-only marker present when the character's ground ray cast hits.
 /// Toggled each FixedUpdate tick by `detect_grounded`. SparseSet storage avoids
 /// archetype churn during jumps. Not registered for replication or prediction —
@@ -136,3 +346,13 @@ impl Default for CharacterPhysicsBundle {
 #[derive(Component, Debug)]
 #[component(storage = "SparseSet")]
 pub struct IsGrounded;
+
+/// Local-only marker present while a horizontal ray cast finds a wall right
+/// next to the character. `normal` points from the wall toward the character.
+/// Maintained by `detect_wall_contact`; like `IsGrounded`, it is derived from
+/// replicated state and never replicated itself.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+#[component(storage = "SparseSet")]
+pub struct TouchingWall {
+    pub normal: Vec3,
+}
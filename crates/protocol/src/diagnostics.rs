@@ -1,13 +1,40 @@
-//! Tracy diagnostics for gameplay systems.
+//! Tracy diagnostics for gameplay systems, plus the state checksum used for
+//! desync detection.
 //!
 //! All `plot!` calls are no-ops when `tracy-client/enable` is not active.
 
+use std::collections::VecDeque;
+
+use avian3d::prelude::Position;
 use bevy::prelude::*;
 use bevy::time::Real;
 use leafwing_input_manager::prelude::ActionState;
+use serde::{Deserialize, Serialize};
 use tracy_client::plot;
 
-use crate::PlayerActions;
+use crate::map::MapInstanceId;
+use crate::{CharacterMarker, Health, PlayerActions};
+
+/// How often the server sends its [`StateChecksum`] to clients.
+pub const STATE_CHECKSUM_INTERVAL_TICKS: u16 = 64;
+
+/// Ticks of local checksums kept for comparison against late server checksums.
+const STATE_CHECKSUM_HISTORY_TICKS: usize = 256;
+
+/// Position resolution of the checksum. Matches the rollback threshold, so
+/// differences too small to trigger a rollback don't register as desyncs.
+const CHECKSUM_POSITION_QUANTUM: f32 = 0.01;
+
+/// Channel for development diagnostics messages.
+pub struct DiagnosticsChannel;
+
+/// Server checksum of [`map_state_checksum`] at `tick` for the map the
+/// receiving client is on, for clients to compare against their own.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateChecksum {
+    pub tick: u16,
+    pub checksum: u64,
+}
 
 /// Shared tracy diagnostics registered by both client and server.
 pub struct SharedDiagnosticsPlugin;
@@ -15,6 +42,7 @@ pub struct SharedDiagnosticsPlugin;
 impl Plugin for SharedDiagnosticsPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<FixedStepCounter>()
+            .init_resource::<StateChecksumHistory>()
             .add_systems(FixedUpdate, count_fixed_steps)
             .add_systems(Last, plot_frame_diagnostics);
     }
@@ -40,6 +68,109 @@ fn plot_frame_diagnostics(time: Res<Time<Real>>, mut counter: ResMut<FixedStepCo
     counter.steps = 0;
 }
 
+/// Recent per-tick checksums a client recorded for its own map, oldest first.
+#[derive(Resource, Default, Debug)]
+pub struct StateChecksumHistory {
+    entries: VecDeque<StateChecksum>,
+}
+
+impl StateChecksumHistory {
+    /// Records `checksum` for `tick`, replacing an earlier entry for the same tick
+    /// (a rollback re-simulating it).
+    pub fn record(&mut self, tick: u16, checksum: u64) {
+        if let Some(entry) = self.entries.iter_mut().find(|e| e.tick == tick) {
+            entry.checksum = checksum;
+            return;
+        }
+        self.entries.push_back(StateChecksum { tick, checksum });
+        while self.entries.len() > STATE_CHECKSUM_HISTORY_TICKS {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Checksum recorded for `tick`, if it's still in the history.
+    pub fn get(&self, tick: u16) -> Option<u64> {
+        self.entries
+            .iter()
+            .find(|e| e.tick == tick)
+            .map(|e| e.checksum)
+    }
+
+    /// Most recently recorded checksum.
+    pub fn latest(&self) -> Option<StateChecksum> {
+        self.entries.back().copied()
+    }
+}
+
+/// FNV-1a with its standard offset basis. Unlike `DefaultHasher`, its output is
+/// fixed across processes, platforms and Rust versions, so native and wasm
+/// peers agree.
+struct ChecksumHasher(u64);
+
+impl ChecksumHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        for byte in value.to_le_bytes() {
+            self.0 ^= byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// Deterministic checksum over the position and health of the `characters` on
+/// `map`. Independent of entity ids and iteration order, so a server and a
+/// client holding the same state produce the same value. Both sides pass every
+/// `CharacterMarker` entity they simulate; clients restrict that to
+/// `Predicted`.
+pub fn map_state_checksum<'a>(
+    map: &MapInstanceId,
+    characters: impl IntoIterator<Item = (&'a Position, &'a Health, &'a MapInstanceId)>,
+) -> u64 {
+    let mut character_hashes: Vec<u64> = characters
+        .into_iter()
+        .filter(|(_, _, character_map)| *character_map == map)
+        .map(|(position, health, _)| character_state_hash(position, health))
+        .collect();
+    character_hashes.sort_unstable();
+
+    let mut hasher = ChecksumHasher::new();
+    for hash in character_hashes {
+        hasher.write_u64(hash);
+    }
+    hasher.finish()
+}
+
+/// [`map_state_checksum`] over every character in `world`.
+pub fn world_state_checksum(world: &mut World, map: &MapInstanceId) -> u64 {
+    let mut query =
+        world.query_filtered::<(&Position, &Health, &MapInstanceId), With<CharacterMarker>>();
+    map_state_checksum(map, query.iter(world))
+}
+
+fn character_state_hash(position: &Position, health: &Health) -> u64 {
+    let mut hasher = ChecksumHasher::new();
+    for axis in position.0.to_array() {
+        hasher.write_u64(quantize(axis) as u64);
+    }
+    hasher.write_u64(quantize(health.current) as u64);
+    hasher.write_u64(quantize(health.max) as u64);
+    hasher.finish()
+}
+
+fn quantize(value: f32) -> i64 {
+    (value / CHECKSUM_POSITION_QUANTUM).round() as i64
+}
+
 /// Plots per-tick input state from an `ActionState`.
 ///
 /// Called by client and server diagnostics plugins with appropriate entity
@@ -63,3 +194,104 @@ pub fn plot_action_state(action_state: &ActionState<PlayerActions>) {
         }
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn spawn_characters(world: &mut World, positions: &[Vec3]) -> Vec<Entity> {
+        positions
+            .iter()
+            .map(|position| {
+                world
+                    .spawn((
+                        CharacterMarker,
+                        Position(*position),
+                        Health::new(100.0),
+                        MapInstanceId::Overworld,
+                    ))
+                    .id()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_worlds_produce_equal_checksums() {
+        let positions = [Vec3::new(1.0, 2.0, 3.0), Vec3::new(-4.0, 0.5, 8.0)];
+        let mut a = World::new();
+        spawn_characters(&mut a, &positions);
+        let mut b = World::new();
+        // Unrelated entity shifts ids; spawning in reverse changes iteration order.
+        b.spawn(Position(Vec3::ONE));
+        spawn_characters(&mut b, &[positions[1], positions[0]]);
+
+        assert_eq!(
+            world_state_checksum(&mut a, &MapInstanceId::Overworld),
+            world_state_checksum(&mut b, &MapInstanceId::Overworld)
+        );
+    }
+
+    #[test]
+    fn perturbed_position_changes_checksum() {
+        let mut world = World::new();
+        let characters = spawn_characters(&mut world, &[Vec3::ZERO, Vec3::X * 5.0]);
+        let before = world_state_checksum(&mut world, &MapInstanceId::Overworld);
+
+        world.get_mut::<Position>(characters[1]).unwrap().0.y += 0.5;
+
+        assert_ne!(
+            before,
+            world_state_checksum(&mut world, &MapInstanceId::Overworld)
+        );
+    }
+
+    #[test]
+    fn characters_on_other_maps_do_not_affect_checksum() {
+        let mut world = World::new();
+        spawn_characters(&mut world, &[Vec3::ZERO]);
+        let before = world_state_checksum(&mut world, &MapInstanceId::Overworld);
+
+        world.spawn((
+            CharacterMarker,
+            Position(Vec3::X),
+            Health::new(100.0),
+            MapInstanceId::Homebase { owner: 1 },
+        ));
+
+        assert_eq!(
+            before,
+            world_state_checksum(&mut world, &MapInstanceId::Overworld)
+        );
+    }
+
+    #[test]
+    fn checksum_is_fixed_across_runs() {
+        // FNV-1a over one character at (1, 2, 3) with full health.
+        const GOLDEN: u64 = 0x976b_2b2f_4e1c_392b;
+        let mut world = World::new();
+        spawn_characters(&mut world, &[Vec3::new(1.0, 2.0, 3.0)]);
+
+        assert_eq!(
+            world_state_checksum(&mut world, &MapInstanceId::Overworld),
+            GOLDEN
+        );
+    }
+
+    #[test]
+    fn history_replaces_resimulated_ticks() {
+        let mut history = StateChecksumHistory::default();
+        history.record(10, 1);
+        history.record(11, 2);
+        history.record(10, 3);
+
+        assert_eq!(history.get(10), Some(3));
+        assert_eq!(
+            history.latest(),
+            Some(StateChecksum {
+                tick: 11,
+                checksum: 2
+            })
+        );
+        assert_eq!(history.get(9), None);
+    }
+}
@@ -1,20 +1,29 @@
 use avian3d::prelude::*;
 use bevy::ecs::message::MessageWriter;
+use bevy::ecs::system::{SystemParam, SystemState};
 use bevy::prelude::*;
 use bevy::reflect::TypeRegistryArc;
-use lightyear::prelude::{ControlledBy, Tick};
+use lightyear::prelude::{
+    ControlledBy, LocalTimeline, PredictionDespawnCommandsExt, PredictionDisable, Tick,
+};
 
+use super::systems::{nearest_first, target_relation};
 use crate::ability::{
-    spawn_sub_ability, AbilityAsset, AbilityDefs, AbilityEffect, ActiveBuffs, ActiveShield,
-    EffectTarget, ForceFrame, OnHitEffects,
+    apply_buff, apply_healing_buffs, apply_stun, by_priority, spawn_sub_ability, AbilityAsset,
+    AbilityDefs, AbilityEffect, AbilityHitEvent, AbilityKill, AbilityLockouts, AbilityPhase,
+    ActiveAbility, ActiveBuffs, ActiveDot, ActiveDots, ActiveShield, DamageType, DamagedThisTick,
+    EffectTarget, ForceFrame, Minion, OnHitEffects, Resistances, TargetMask,
 };
-use crate::{DeathEvent, Health, Invulnerable, PlayerId};
+use crate::map::MapInstanceId;
+use crate::{CharacterMarker, DeathEvent, Health, Invulnerable, LastDamagedTick, PlayerId};
 
 fn resolve_on_hit_target(target: &EffectTarget, victim: Entity, on_hit: &OnHitEffects) -> Entity {
     match target {
         EffectTarget::Victim => victim,
         EffectTarget::Caster => on_hit.caster,
         EffectTarget::OriginalCaster => on_hit.original_caster,
+        // `queue_effects_in_radius` hits each character in range as the victim.
+        EffectTarget::AllInRadius { .. } => victim,
     }
 }
 
@@ -59,6 +68,296 @@ fn resolve_force_frame(
     }
 }
 
+/// Messages written while applying on-hit effects.
+#[derive(SystemParam)]
+pub struct HitMessages<'w> {
+    death: MessageWriter<'w, DeathEvent>,
+    hit: MessageWriter<'w, AbilityHitEvent>,
+}
+
+/// Damage `entity` with a hit from `on_hit`, scaled by its `Resistances` to
+/// `damage_type` and draining its `ActiveShield` first. Invulnerable targets
+/// still drain their shield but take no health damage. Damage with no
+/// `on_hit`, such as a damage-over-time tick, credits a kill to no ability.
+/// Any damage stamps `LastDamagedTick` at `tick`, pausing `HealthRegen`.
+/// Returns the damage after resistances.
+pub(crate) fn deal_damage(
+    commands: &mut Commands,
+    on_hit: Option<&OnHitEffects>,
+    entity: Entity,
+    amount: f32,
+    damage_type: DamageType,
+    tick: Tick,
+    target_query: &mut Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    shield_query: &mut Query<&mut ActiveShield>,
+    death_events: &mut MessageWriter<DeathEvent>,
+) -> f32 {
+    let amount = amount * resistance_multiplier(entity, damage_type, target_query);
+    if amount > 0.0 {
+        commands.entity(entity).try_insert(LastDamagedTick(tick));
+    }
+    let mut remaining_damage = amount;
+    if let Ok(mut shield) = shield_query.get_mut(entity) {
+        if shield.remaining >= remaining_damage {
+            shield.remaining -= remaining_damage;
+            return amount;
+        }
+        remaining_damage -= shield.remaining;
+        shield.remaining = 0.0;
+        commands.entity(entity).remove::<ActiveShield>();
+    }
+
+    if let Ok((_, mut health, invulnerable, _)) = target_query.get_mut(entity) {
+        if invulnerable.is_none() && health.apply_damage(remaining_damage) {
+            death_events.write(DeathEvent {
+                entity,
+                killed_by: on_hit.map(AbilityKill::from),
+            });
+        }
+    } else {
+        warn!("Damage target {:?} not found", entity);
+    }
+    amount
+}
+
+/// How much of `damage_type` damage `entity` takes, per its `Resistances`.
+/// Entities without `Resistances` take full damage.
+fn resistance_multiplier(
+    entity: Entity,
+    damage_type: DamageType,
+    target_query: &Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+) -> f32 {
+    target_query
+        .get(entity)
+        .ok()
+        .and_then(|(.., resistances)| resistances)
+        .map_or(1.0, |resistances| resistances.multiplier(damage_type))
+}
+
+/// Adds `dot` to `entity`'s `ActiveDots`, alongside any already ticking.
+fn queue_add_dot(commands: &mut Commands, entity: Entity, dot: ActiveDot) {
+    commands
+        .entity(entity)
+        .queue(
+            move |mut entity: EntityWorldMut| match entity.get_mut::<ActiveDots>() {
+                Some(mut dots) => dots.0.push(dot),
+                None => {
+                    entity.insert(ActiveDots(vec![dot]));
+                }
+            },
+        );
+}
+
+/// Cancels `victim`'s casts in Startup or Active and locks each one's slot for
+/// `lockout_ticks`. Deferred because the hit queries don't see ability entities.
+fn queue_interrupt(commands: &mut Commands, victim: Entity, lockout_ticks: u16, tick: Tick) {
+    commands.queue(move |world: &mut World| {
+        let mut casts =
+            world.query_filtered::<(Entity, &ActiveAbility), Without<PredictionDisable>>();
+        let interrupted: Vec<(Entity, usize)> = casts
+            .iter(world)
+            .filter(|(_, active)| {
+                active.caster == victim
+                    && matches!(active.phase, AbilityPhase::Startup | AbilityPhase::Active)
+            })
+            .map(|(entity, active)| (entity, active.ability_slot as usize))
+            .collect();
+        if interrupted.is_empty() {
+            trace!("Interrupt: {:?} had no cast to cancel", victim);
+            return;
+        }
+        let Ok(mut victim_entity) = world.get_entity_mut(victim) else {
+            trace!("Interrupt: victim {:?} is gone", victim);
+            return;
+        };
+        let until = tick + lockout_ticks as i16;
+        let mut lockouts = victim_entity
+            .get::<AbilityLockouts>()
+            .cloned()
+            .unwrap_or_default();
+        for &(_, slot) in &interrupted {
+            lockouts.lock(slot, until);
+        }
+        victim_entity.insert(lockouts);
+        for (entity, _) in interrupted {
+            world.commands().entity(entity).prediction_despawn();
+        }
+        world.flush();
+    });
+}
+
+/// Applies `on_hit` to every character within `radius` of its caster on the
+/// caster's map, as if each were hit from the caster's position. The caster is
+/// skipped unless `include_caster`. Deferred to run with a fresh
+/// [`EffectApplier`], whose queries conflict with the ability effect systems'.
+pub(crate) fn queue_effects_in_radius(
+    commands: &mut Commands,
+    on_hit: OnHitEffects,
+    radius: f32,
+    include_caster: bool,
+) {
+    commands.queue(move |world: &mut World| {
+        let mut state: SystemState<(
+            EffectApplier,
+            Query<(
+                Entity,
+                &Position,
+                Option<&MapInstanceId>,
+                Has<CharacterMarker>,
+            )>,
+        )> = SystemState::new(world);
+        let (mut applier, positions) = state.get_mut(world);
+        let Ok((_, center, caster_map, _)) = positions.get(on_hit.caster) else {
+            warn!("AllInRadius: caster {:?} has no Position", on_hit.caster);
+            return;
+        };
+        let center = center.0;
+        let targets: Vec<Entity> = positions
+            .iter()
+            .filter(|(entity, position, map, is_character)| {
+                *is_character
+                    && (include_caster || *entity != on_hit.caster)
+                    && *map == caster_map
+                    && position.0.distance_squared(center) <= radius * radius
+            })
+            .map(|(entity, ..)| entity)
+            .collect();
+        if targets.is_empty() {
+            trace!("AllInRadius: no character within {radius} of {center}");
+        }
+        for target in targets {
+            applier.apply_effects_to(target, &on_hit, center);
+        }
+        state.apply(world);
+    });
+}
+
+/// Exchange the `Position`s of `a` and `b`, optionally zeroing the velocity of
+/// both. Deferred because the hit queries only hold positions read-only.
+fn queue_swap_positions(commands: &mut Commands, a: Entity, b: Entity, reset_velocity: bool) {
+    if a == b {
+        trace!("SwapPositions: caster {:?} hit itself, nothing to swap", a);
+        return;
+    }
+    commands.queue(move |world: &mut World| {
+        let positions = (
+            world.get::<Position>(a).copied(),
+            world.get::<Position>(b).copied(),
+        );
+        let (Some(pos_a), Some(pos_b)) = positions else {
+            warn!("SwapPositions: {:?} or {:?} missing Position", a, b);
+            return;
+        };
+        for (entity, pos) in [(a, pos_b), (b, pos_a)] {
+            let mut entity = world.entity_mut(entity);
+            entity.insert(pos);
+            if reset_velocity {
+                if let Some(mut velocity) = entity.get_mut::<LinearVelocity>() {
+                    *velocity = LinearVelocity::ZERO;
+                }
+            }
+        }
+    });
+}
+
+/// Applies on-hit effects from outside the ability hit systems, e.g. lava voxels,
+/// kill planes or fall damage. Builds on the same pipeline as ability hits, so
+/// shields, invulnerability, buffs and death events all behave identically.
+/// Like the hit systems, it needs `AbilityDefs` to be loaded.
+#[derive(SystemParam)]
+pub struct EffectApplier<'w, 's> {
+    commands: Commands<'w, 's>,
+    ability_defs: Res<'w, AbilityDefs>,
+    ability_assets: Res<'w, Assets<AbilityAsset>>,
+    registry: Res<'w, AppTypeRegistry>,
+    timeline: Res<'w, LocalTimeline>,
+    server_query: Query<'w, 's, &'static ControlledBy>,
+    player_id_query: Query<'w, 's, &'static PlayerId>,
+    target_query: Query<
+        'w,
+        's,
+        (
+            &'static Position,
+            &'static mut Health,
+            Option<&'static Invulnerable>,
+            Option<&'static Resistances>,
+        ),
+    >,
+    character_query: Query<'w, 's, (Entity, &'static Position), With<CharacterMarker>>,
+    minion_query: Query<'w, 's, &'static Minion>,
+    forces_query: Query<'w, 's, Forces>,
+    shield_query: Query<'w, 's, &'static mut ActiveShield>,
+    buff_query: Query<'w, 's, &'static ActiveBuffs>,
+    rotation_query: Query<'w, 's, &'static Rotation>,
+    messages: HitMessages<'w>,
+}
+
+impl EffectApplier<'_, '_> {
+    /// Applies `effects` to `entity` as if hit by them at `source_pos`.
+    /// Non-ability sources build `effects` with [`OnHitEffects::from_source`].
+    pub fn apply_effects_to(&mut self, entity: Entity, effects: &OnHitEffects, source_pos: Vec3) {
+        if !self.target_query.contains(entity) {
+            trace!("apply_effects_to: {entity:?} has no Health");
+            return;
+        }
+        apply_on_hit_effects(
+            &mut self.commands,
+            &self.ability_defs,
+            &self.ability_assets,
+            &self.registry.0,
+            self.timeline.tick(),
+            &self.server_query,
+            &self.player_id_query,
+            effects,
+            entity,
+            source_pos,
+            1.0,
+            TargetMask::default(),
+            &mut self.target_query,
+            &self.character_query,
+            &self.minion_query,
+            &mut self.forces_query,
+            &mut self.shield_query,
+            &self.buff_query,
+            &self.rotation_query,
+            &mut self.messages,
+        );
+    }
+}
+
+/// Collects `effects` into `out`, replacing each `OnHitIf` with its inner
+/// effects when `victim_health_fraction` is below its threshold and dropping
+/// it otherwise. Victims without health never meet a threshold.
+fn expand_on_hit_conditions<'a>(
+    effects: &'a [AbilityEffect],
+    victim_health_fraction: Option<f32>,
+    out: &mut Vec<&'a AbilityEffect>,
+) {
+    for effect in effects {
+        let AbilityEffect::OnHitIf {
+            below_health_fraction,
+            effects: conditional,
+        } = effect
+        else {
+            out.push(effect);
+            continue;
+        };
+        if victim_health_fraction.is_some_and(|fraction| fraction < *below_health_fraction) {
+            expand_on_hit_conditions(conditional, victim_health_fraction, out);
+        }
+    }
+}
+
 pub(crate) fn apply_on_hit_effects(
     commands: &mut Commands,
     ability_defs: &AbilityDefs,
@@ -70,44 +369,130 @@ pub(crate) fn apply_on_hit_effects(
     on_hit: &OnHitEffects,
     victim: Entity,
     source_pos: Vec3,
-    target_query: &mut Query<(&Position, &mut Health, Option<&Invulnerable>)>,
+    damage_scale: f32,
+    affects: TargetMask,
+    target_query: &mut Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    character_query: &Query<(Entity, &Position), With<CharacterMarker>>,
+    minion_query: &Query<&Minion>,
     forces_query: &mut Query<Forces>,
     shield_query: &mut Query<&mut ActiveShield>,
     buff_query: &Query<&ActiveBuffs>,
     rotation_query: &Query<&Rotation>,
-    death_events: &mut MessageWriter<DeathEvent>,
+    messages: &mut HitMessages,
 ) {
-    for effect in &on_hit.effects {
+    let mut victim_damage = 0.0;
+    let mut victim_damage_type = None;
+    let mut chains = Vec::new();
+    let victim_health_fraction = target_query
+        .get(victim)
+        .ok()
+        .filter(|(_, health, ..)| health.max > 0.0)
+        .map(|(_, health, ..)| health.current / health.max);
+    let mut effects = Vec::new();
+    expand_on_hit_conditions(&on_hit.effects, victim_health_fraction, &mut effects);
+    for effect in by_priority(effects) {
         match effect {
-            AbilityEffect::Damage { amount, target } => {
+            AbilityEffect::Damage {
+                amount,
+                target,
+                damage_type,
+            } => {
                 let entity = resolve_on_hit_target(target, victim, on_hit);
-                let mut remaining_damage = apply_damage_buffs(*amount, on_hit.caster, buff_query);
-
-                if let Ok(mut shield) = shield_query.get_mut(entity) {
-                    if shield.remaining >= remaining_damage {
-                        shield.remaining -= remaining_damage;
-                        continue;
-                    }
-                    remaining_damage -= shield.remaining;
-                    shield.remaining = 0.0;
-                    commands.entity(entity).remove::<ActiveShield>();
+                let amount = apply_damage_buffs(*amount, on_hit.caster, buff_query) * damage_scale;
+                let dealt = deal_damage(
+                    commands,
+                    Some(on_hit),
+                    entity,
+                    amount,
+                    *damage_type,
+                    tick,
+                    target_query,
+                    shield_query,
+                    &mut messages.death,
+                );
+                if entity == victim {
+                    victim_damage += dealt;
+                    victim_damage_type.get_or_insert(*damage_type);
                 }
-
-                if let Ok((_, mut health, invulnerable)) = target_query.get_mut(entity) {
-                    if invulnerable.is_none() && health.apply_damage(remaining_damage) {
-                        death_events.write(DeathEvent { entity });
-                    }
+            }
+            AbilityEffect::DamageOverTime {
+                amount,
+                interval_ticks,
+                duration_ticks,
+                target,
+                damage_type,
+            } => {
+                if *interval_ticks == 0 {
+                    warn!("DamageOverTime with interval_ticks 0 ignored");
+                    continue;
+                }
+                let entity = resolve_on_hit_target(target, victim, on_hit);
+                let dot = ActiveDot {
+                    amount: apply_damage_buffs(*amount, on_hit.caster, buff_query),
+                    interval_ticks: *interval_ticks,
+                    next_tick: tick + *interval_ticks as i16,
+                    expires_tick: tick + *duration_ticks as i16,
+                    damage_type: *damage_type,
+                };
+                queue_add_dot(commands, entity, dot);
+            }
+            AbilityEffect::Stun {
+                duration_ticks,
+                target,
+            } => {
+                let entity = resolve_on_hit_target(target, victim, on_hit);
+                apply_stun(commands, entity, *duration_ticks, tick);
+            }
+            AbilityEffect::Interrupt { lockout_ticks } => {
+                queue_interrupt(commands, victim, *lockout_ticks, tick);
+            }
+            AbilityEffect::Buff {
+                stat,
+                multiplier,
+                duration_ticks,
+                target,
+            } => {
+                let entity = resolve_on_hit_target(target, victim, on_hit);
+                apply_buff(
+                    commands,
+                    entity,
+                    stat,
+                    *multiplier,
+                    *duration_ticks,
+                    tick,
+                    on_hit.ability_id.clone(),
+                );
+            }
+            AbilityEffect::Heal { amount, target } => {
+                let entity = resolve_on_hit_target(target, victim, on_hit);
+                let amount = apply_healing_buffs(*amount, on_hit.caster, buff_query);
+                if let Ok((_, mut health, ..)) = target_query.get_mut(entity) {
+                    health.heal(amount);
                 } else {
-                    warn!("Damage target {:?} not found", entity);
+                    warn!("Heal target {:?} not found", entity);
                 }
             }
+            AbilityEffect::Chain {
+                jumps,
+                range,
+                damage_falloff,
+            } => {
+                // Jumps start from the damage the victim actually took, so they
+                // resolve once every other effect has landed.
+                chains.push((*jumps, *range, *damage_falloff));
+            }
             AbilityEffect::ApplyForce {
                 force,
                 frame,
                 target,
             } => {
                 let entity = resolve_on_hit_target(target, victim, on_hit);
-                let Ok(target_pos) = target_query.get(entity).map(|(p, _, _)| p.0) else {
+                let Ok(target_pos) = target_query.get(entity).map(|(p, ..)| p.0) else {
                     warn!("ApplyForce target {:?} not found", entity);
                     continue;
                 };
@@ -144,9 +529,76 @@ pub(crate) fn apply_on_hit_effects(
                     player_id_query,
                 );
             }
+            AbilityEffect::SwapPositions { reset_velocity } => {
+                queue_swap_positions(commands, on_hit.caster, victim, *reset_velocity);
+            }
             _ => {
                 warn!("Unhandled OnHit effect: {:?}", effect);
             }
         }
     }
+    for (jumps, range, damage_falloff) in chains {
+        if victim_damage <= 0.0 {
+            trace!(
+                "Chain: victim {:?} took no damage, nothing to chain",
+                victim
+            );
+            break;
+        }
+        let Ok(start) = character_query.get(victim).map(|(_, p)| p.0) else {
+            warn!("Chain: victim {:?} is not a character", victim);
+            break;
+        };
+        // Jumps deal the type of the first `Damage` the victim took.
+        let damage_type = victim_damage_type.unwrap_or(DamageType::Physical);
+        let mut damage = victim_damage;
+        let mut from = start;
+        let mut chained = vec![victim];
+        for _ in 0..jumps {
+            let Some((_, next_pos, next)) = character_query
+                .iter()
+                .filter(|(e, p)| !chained.contains(e) && p.0.distance(from) <= range)
+                .filter(|(e, _)| affects.allows(target_relation(*e, on_hit, minion_query)))
+                .filter(|(e, _)| {
+                    minion_query.get(*e).map_or(true, |minion| {
+                        minion.owner != on_hit.caster && minion.owner != on_hit.original_caster
+                    })
+                })
+                .map(|(e, p)| (p.0.distance(from), p.0, e))
+                .min_by(|a, b| nearest_first((a.0, a.1), (b.0, b.1)))
+            else {
+                trace!("Chain: no character within {} of {:?}", range, from);
+                break;
+            };
+            damage *= damage_falloff;
+            let dealt = deal_damage(
+                commands,
+                Some(on_hit),
+                next,
+                damage,
+                damage_type,
+                tick,
+                target_query,
+                shield_query,
+                &mut messages.death,
+            );
+            messages.hit.write(AbilityHitEvent {
+                caster: on_hit.caster,
+                victim: next,
+                ability_id: on_hit.ability_id.clone(),
+                damage: dealt,
+            });
+            chained.push(next);
+            from = next_pos;
+        }
+    }
+    if victim_damage > 0.0 {
+        commands.entity(victim).try_insert(DamagedThisTick);
+    }
+    messages.hit.write(AbilityHitEvent {
+        caster: on_hit.caster,
+        victim,
+        ability_id: on_hit.ability_id.clone(),
+        damage: victim_damage,
+    });
 }
@@ -1,8 +1,10 @@
 use avian3d::prelude::*;
-use bevy::prelude::Vec3;
+use bevy::prelude::*;
 
 pub const MELEE_HITBOX_OFFSET: f32 = 3.0;
 pub const MELEE_HITBOX_HALF_EXTENTS: Vec3 = Vec3::new(1.5, 2.0, 1.0);
+/// Reach of an arc melee that sets no `range`: the far edge of the default box.
+pub const MELEE_ARC_DEFAULT_RANGE: f32 = MELEE_HITBOX_OFFSET + MELEE_HITBOX_HALF_EXTENTS.z;
 
 #[derive(PhysicsLayer, Default)]
 pub enum GameLayer {
@@ -15,6 +17,126 @@ pub enum GameLayer {
     Damageable,
 }
 
+/// First bit free for layers defined outside [`GameLayer`].
+const CUSTOM_LAYER_START: u32 = 6;
+
+/// Mask for the `index`th downstream-defined layer (e.g. `Destructible`,
+/// `NoClip`), numbered from zero after the [`GameLayer`] bits.
+pub fn custom_layer(index: u32) -> LayerMask {
+    debug_assert!(
+        CUSTOM_LAYER_START + index < 32,
+        "custom layer {index} does not fit in a LayerMask"
+    );
+    LayerMask(1 << (CUSTOM_LAYER_START + index))
+}
+
+/// Collision layers for each kind of body the game spawns. Defaults match the
+/// `*_collision_layers()` functions, which spawn sites keep using; whenever a
+/// body gets one of those defaults, [`apply_collision_layer_config`] swaps in
+/// the configured layers. Insert a customized config before the plugins to
+/// change interactions without editing [`GameLayer`].
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct CollisionLayerConfig {
+    pub character: CollisionLayers,
+    pub terrain: CollisionLayers,
+    pub projectile: CollisionLayers,
+    pub clashing_projectile: CollisionLayers,
+    pub hitbox: CollisionLayers,
+    pub damageable: CollisionLayers,
+}
+
+impl Default for CollisionLayerConfig {
+    fn default() -> Self {
+        Self {
+            character: character_collision_layers(),
+            terrain: terrain_collision_layers(),
+            projectile: projectile_collision_layers(),
+            clashing_projectile: clashing_projectile_collision_layers(),
+            hitbox: hitbox_collision_layers(),
+            damageable: damageable_collision_layers(),
+        }
+    }
+}
+
+impl CollisionLayerConfig {
+    fn roles(&self) -> [CollisionLayers; 6] {
+        [
+            self.character,
+            self.terrain,
+            self.projectile,
+            self.clashing_projectile,
+            self.hitbox,
+            self.damageable,
+        ]
+    }
+
+    fn roles_mut(&mut self) -> [&mut CollisionLayers; 6] {
+        [
+            &mut self.character,
+            &mut self.terrain,
+            &mut self.projectile,
+            &mut self.clashing_projectile,
+            &mut self.hitbox,
+            &mut self.damageable,
+        ]
+    }
+
+    /// Makes bodies on layer `a` collide (or stop colliding) with layer `b`, in
+    /// both directions. Only the configured roles change; bodies on custom
+    /// layers set their own filters.
+    pub fn with_interaction(
+        mut self,
+        a: impl Into<LayerMask>,
+        b: impl Into<LayerMask>,
+        collide: bool,
+    ) -> Self {
+        let (a, b) = (a.into(), b.into());
+        for layers in self.roles_mut() {
+            for (own, other) in [(a, b), (b, a)] {
+                if layers.memberships & own == LayerMask::NONE {
+                    continue;
+                }
+                if collide {
+                    layers.filters.add(other);
+                } else {
+                    layers.filters.remove(other);
+                }
+            }
+        }
+        self
+    }
+
+    /// Configured layers for a body spawned with one of the default role
+    /// layers, or `None` for any other layers.
+    pub fn configured(&self, layers: CollisionLayers) -> Option<CollisionLayers> {
+        Self::default()
+            .roles()
+            .into_iter()
+            .zip(self.roles())
+            .find_map(|(default, configured)| (default == layers).then_some(configured))
+    }
+}
+
+/// Rewrites default role layers to their [`CollisionLayerConfig`] value
+/// whenever `CollisionLayers` is inserted.
+pub fn apply_collision_layer_config(
+    trigger: On<Insert, CollisionLayers>,
+    config: Res<CollisionLayerConfig>,
+    mut layers_query: Query<&mut CollisionLayers>,
+) {
+    let entity = trigger.entity;
+    let mut layers = layers_query
+        .get_mut(entity)
+        .expect("inserted CollisionLayers should be queryable");
+    let Some(configured) = config.configured(*layers) else {
+        trace!("{entity:?} has custom collision layers; leaving them");
+        return;
+    };
+    if *layers != configured {
+        *layers = configured;
+    }
+}
+
 /// Collision layer config for characters.
 pub fn character_collision_layers() -> CollisionLayers {
     CollisionLayers::new(
@@ -42,6 +164,19 @@ pub fn projectile_collision_layers() -> CollisionLayers {
     )
 }
 
+/// Collision layer config for projectiles that clash with other projectiles.
+/// A pair only collides when both use these layers.
+pub fn clashing_projectile_collision_layers() -> CollisionLayers {
+    CollisionLayers::new(
+        GameLayer::Projectile,
+        [
+            GameLayer::Character,
+            GameLayer::Damageable,
+            GameLayer::Projectile,
+        ],
+    )
+}
+
 /// Collision layer config for hitbox entities (melee/AoE).
 pub fn hitbox_collision_layers() -> CollisionLayers {
     CollisionLayers::new(
@@ -61,3 +196,86 @@ pub fn damageable_collision_layers() -> CollisionLayers {
         ],
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn customized_config_yields_expected_masks() {
+        let destructible = custom_layer(0);
+        let mut config = CollisionLayerConfig::default()
+            .with_interaction(GameLayer::Character, GameLayer::Character, false)
+            .with_interaction(GameLayer::Projectile, destructible, true);
+        config.terrain = CollisionLayers::new(
+            GameLayer::Terrain,
+            [GameLayer::Character, GameLayer::Projectile],
+        );
+
+        assert_eq!(
+            config.character,
+            CollisionLayers::new(
+                GameLayer::Character,
+                [
+                    GameLayer::Terrain,
+                    GameLayer::Hitbox,
+                    GameLayer::Projectile,
+                    GameLayer::Damageable,
+                ],
+            )
+        );
+        assert_eq!(
+            config.projectile.filters,
+            LayerMask::from([GameLayer::Character, GameLayer::Damageable]) | destructible
+        );
+        assert!(config.clashing_projectile.filters.has_all(destructible));
+        assert_eq!(config.hitbox, hitbox_collision_layers(), "untouched role");
+
+        assert_eq!(
+            config.configured(character_collision_layers()),
+            Some(config.character)
+        );
+        assert_eq!(
+            config.configured(terrain_collision_layers()),
+            Some(config.terrain)
+        );
+        let custom = CollisionLayers::new(destructible, [GameLayer::Projectile]);
+        assert_eq!(config.configured(custom), None);
+    }
+
+    #[test]
+    fn spawned_bodies_get_their_configured_layers() {
+        let config = CollisionLayerConfig::default().with_interaction(
+            GameLayer::Character,
+            GameLayer::Character,
+            false,
+        );
+        let mut app = App::new();
+        app.insert_resource(config);
+        app.add_observer(apply_collision_layer_config);
+
+        let world = app.world_mut();
+        let character = world.spawn(character_collision_layers()).id();
+        let custom_layers = CollisionLayers::new(custom_layer(0), [GameLayer::Character]);
+        let custom = world.spawn(custom_layers).id();
+        let reinserted = world.spawn_empty().id();
+        world
+            .entity_mut(reinserted)
+            .insert(character_collision_layers());
+
+        assert_eq!(
+            world.get::<CollisionLayers>(character),
+            Some(&config.character)
+        );
+        assert_eq!(
+            world.get::<CollisionLayers>(reinserted),
+            Some(&config.character),
+            "inserting default layers later is rewritten too"
+        );
+        assert_eq!(
+            world.get::<CollisionLayers>(custom),
+            Some(&custom_layers),
+            "custom layers are left alone"
+        );
+    }
+}
@@ -2,11 +2,17 @@ mod effects;
 mod layers;
 mod systems;
 
+pub use effects::EffectApplier;
+pub(crate) use effects::{deal_damage, queue_effects_in_radius};
 pub use layers::{
-    character_collision_layers, damageable_collision_layers, hitbox_collision_layers,
-    projectile_collision_layers, terrain_collision_layers, GameLayer, MELEE_HITBOX_HALF_EXTENTS,
-    MELEE_HITBOX_OFFSET,
+    apply_collision_layer_config, character_collision_layers, clashing_projectile_collision_layers,
+    custom_layer, damageable_collision_layers, hitbox_collision_layers,
+    projectile_collision_layers, terrain_collision_layers, CollisionLayerConfig, GameLayer,
+    MELEE_ARC_DEFAULT_RANGE, MELEE_HITBOX_HALF_EXTENTS, MELEE_HITBOX_OFFSET,
 };
+pub(crate) use systems::relation_to;
 pub use systems::{
-    cleanup_hitbox_entities, process_hitbox_hits, process_projectile_hits, update_hitbox_positions,
+    cleanup_hitbox_entities, detonate_stuck_projectiles, emit_ability_misses,
+    follow_stuck_projectiles, process_hitbox_hits, process_projectile_clashes,
+    process_projectile_hits, update_hitbox_positions,
 };
@@ -2,35 +2,84 @@ use avian3d::prelude::*;
 use bevy::ecs::message::MessageWriter;
 use bevy::prelude::*;
 use lightyear::prelude::{ControlledBy, LocalTimeline};
+use std::cmp::Ordering;
 
-use super::effects::apply_on_hit_effects;
+use super::effects::{apply_on_hit_effects, HitMessages};
 use super::layers::MELEE_HITBOX_OFFSET;
 use crate::ability::{
-    facing_direction, AbilityAsset, AbilityBulletOf, AbilityDefs, AbilityPhase, ActiveAbility,
-    ActiveBuffs, ActiveShield, AoEHitbox, HitTargets, HitboxOf, MeleeHitbox, OnHitEffects,
+    facing_direction, AbilityAsset, AbilityBulletOf, AbilityDefs, AbilityMissEvent, AbilityPhase,
+    AbilityProjectileSpawn, ActiveAbility, ActiveAbilityHitboxes, ActiveBuffs, ActiveShield,
+    AoEHitbox, ClashingProjectile, HitTargets, HitboxOf, MeleeArc, MeleeHitbox, Minion,
+    OnHitEffects, Resistances, StickyProjectile, StuckTo, TargetMask, TargetRelation,
 };
-use crate::{DeathEvent, Health, Invulnerable, PlayerId};
+use crate::{CharacterMarker, Health, Invulnerable, PlayerId};
 
 /// Update melee hitbox positions to follow caster's position + facing offset.
+/// A [`MeleeArc`] hitbox is centered on the caster.
 pub fn update_hitbox_positions(
-    mut hitbox_query: Query<(&HitboxOf, &mut Position, &mut Rotation), With<MeleeHitbox>>,
+    mut hitbox_query: Query<
+        (&HitboxOf, &mut Position, &mut Rotation, Has<MeleeArc>),
+        With<MeleeHitbox>,
+    >,
     ability_query: Query<&ActiveAbility>,
     caster_query: Query<(&Position, &Rotation), Without<MeleeHitbox>>,
 ) {
-    for (hitbox_of, mut hitbox_pos, mut hitbox_rot) in &mut hitbox_query {
+    for (hitbox_of, mut hitbox_pos, mut hitbox_rot, is_arc) in &mut hitbox_query {
         let Ok(active) = ability_query.get(hitbox_of.0) else {
             continue;
         };
         let Ok((caster_pos, caster_rot)) = caster_query.get(active.caster) else {
             continue;
         };
-        let direction = facing_direction(caster_rot);
-        hitbox_pos.0 = caster_pos.0 + direction * MELEE_HITBOX_OFFSET;
+        let offset = if is_arc { 0.0 } else { MELEE_HITBOX_OFFSET };
+        hitbox_pos.0 = caster_pos.0 + facing_direction(caster_rot) * offset;
         *hitbox_rot = *caster_rot;
     }
 }
 
+/// How `target` relates to the caster of `on_hit`.
+pub(crate) fn target_relation(
+    target: Entity,
+    on_hit: &OnHitEffects,
+    minion_query: &Query<&Minion>,
+) -> TargetRelation {
+    if target == on_hit.caster {
+        return TargetRelation::Caster;
+    }
+    relation_to(target, on_hit.original_caster, minion_query)
+}
+
+/// Orders `(distance, position)` pairs nearest first. Equidistant entries are
+/// ordered by position, not `Entity`, whose ids differ between server and
+/// client, so both pick the same target.
+pub(crate) fn nearest_first(a: (f32, Vec3), b: (f32, Vec3)) -> Ordering {
+    a.0.total_cmp(&b.0)
+        .then(a.1.x.total_cmp(&b.1.x))
+        .then(a.1.y.total_cmp(&b.1.y))
+        .then(a.1.z.total_cmp(&b.1.z))
+}
+
+/// How `target` relates to `caster`. Each side is led by a non-minion
+/// character; a minion belongs to its owner's side.
+pub(crate) fn relation_to(
+    target: Entity,
+    caster: Entity,
+    minion_query: &Query<&Minion>,
+) -> TargetRelation {
+    if target == caster {
+        return TargetRelation::Caster;
+    }
+    let side = |entity: Entity| minion_query.get(entity).map_or(entity, |m| m.owner);
+    if side(target) == side(caster) {
+        TargetRelation::Ally
+    } else {
+        TargetRelation::Enemy
+    }
+}
+
 /// Detect hits from hitbox entities (melee and AoE) using `CollidingEntities`.
+/// Targets the hitbox's [`TargetMask`] excludes, or outside its [`MeleeArc`],
+/// are skipped.
 pub fn process_hitbox_hits(
     mut commands: Commands,
     ability_defs: Res<AbilityDefs>,
@@ -44,26 +93,52 @@ pub fn process_hitbox_hits(
         &OnHitEffects,
         &mut HitTargets,
         &Position,
+        &TargetMask,
+        Option<&HitboxOf>,
+        Option<&AoEHitbox>,
+        Option<(&MeleeArc, &Rotation)>,
     )>,
-    mut target_query: Query<(&Position, &mut Health, Option<&Invulnerable>)>,
+    mut ability_query: Query<&mut ActiveAbility>,
+    minion_query: Query<&Minion>,
+    mut target_query: Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    character_query: Query<(Entity, &Position), With<CharacterMarker>>,
     mut forces_query: Query<Forces>,
     mut shield_query: Query<&mut ActiveShield>,
     buff_query: Query<&ActiveBuffs>,
     rotation_query: Query<&Rotation>,
-    mut death_events: MessageWriter<DeathEvent>,
+    mut messages: HitMessages,
 ) {
     let tick = timeline.tick();
-    for (colliding, on_hit, mut hit_targets, hitbox_pos) in &mut hitbox_query {
-        for &target in colliding.iter() {
-            if target == on_hit.caster || target == on_hit.original_caster {
-                continue;
-            }
-            if !hit_targets.0.insert(target) {
-                continue;
-            }
-            if target_query.get(target).is_err() {
-                continue;
-            }
+    for (colliding, on_hit, mut hit_targets, hitbox_pos, affects, hitbox_of, aoe, arc) in
+        &mut hitbox_query
+    {
+        let mut targets: Vec<(f32, Vec3, Entity)> = colliding
+            .iter()
+            .copied()
+            .filter(|&target| affects.allows(target_relation(target, on_hit, &minion_query)))
+            .filter(|target| !hit_targets.0.contains(target))
+            .filter_map(|target| {
+                let (target_pos, ..) = target_query.get(target).ok()?;
+                let offset = target_pos.0 - hitbox_pos.0;
+                if arc.is_some_and(|(arc, rot)| !arc.contains(facing_direction(rot), offset)) {
+                    return None;
+                }
+                Some((offset.length_squared(), target_pos.0, target))
+            })
+            .collect();
+        if let Some(max_targets) = aoe.and_then(|aoe| aoe.max_targets) {
+            let remaining = (max_targets as usize).saturating_sub(hit_targets.0.len());
+            targets.sort_by(|a, b| nearest_first((a.0, a.1), (b.0, b.1)));
+            targets.truncate(remaining);
+        }
+        for (distance_squared, _, target) in targets {
+            hit_targets.0.insert(target);
+            let damage_scale = aoe.map_or(1.0, |aoe| aoe.damage_scale(distance_squared.sqrt()));
             apply_on_hit_effects(
                 &mut commands,
                 ability_defs.as_ref(),
@@ -75,17 +150,48 @@ pub fn process_hitbox_hits(
                 on_hit,
                 target,
                 hitbox_pos.0,
+                damage_scale,
+                *affects,
                 &mut target_query,
+                &character_query,
+                &minion_query,
                 &mut forces_query,
                 &mut shield_query,
                 &buff_query,
                 &rotation_query,
-                &mut death_events,
+                &mut messages,
             );
+            if let Some(mut active) = hitbox_of.and_then(|h| ability_query.get_mut(h.0).ok()) {
+                active.landed_hit = true;
+            }
         }
     }
 }
 
+/// Emit [`AbilityMissEvent`] for abilities whose Active window ended this tick
+/// without any of their hitboxes landing a hit. Runs after this tick's hits are
+/// processed and before the hitboxes are cleaned up. Projectile abilities have
+/// no hitboxes; `ability_bullet_lifetime` reports their misses.
+pub fn emit_ability_misses(
+    timeline: Res<LocalTimeline>,
+    ability_query: Query<(&ActiveAbility, &ActiveAbilityHitboxes)>,
+    mut miss_events: MessageWriter<AbilityMissEvent>,
+) {
+    let tick = timeline.tick();
+    for (active, _) in &ability_query {
+        if active.phase != AbilityPhase::Recovery
+            || active.phase_start_tick != tick
+            || active.landed_hit
+        {
+            continue;
+        }
+        miss_events.write(AbilityMissEvent {
+            caster: active.caster,
+            ability_id: active.def_id.clone(),
+        });
+    }
+}
+
 /// Despawn hitbox entities when their parent ability leaves Active phase.
 /// AoE hitboxes are excluded — their lifetime is governed by `duration_ticks`
 /// via `aoe_hitbox_lifetime`, which can outlive the parent's Active phase.
@@ -105,6 +211,38 @@ pub fn cleanup_hitbox_entities(
     }
 }
 
+/// Despawn clashing bullets touching a clashing bullet from the other side.
+/// Both bullets of a pair see the contact, so both despawn. Sides are led by
+/// non-minion characters, as in `target_relation`.
+pub fn process_projectile_clashes(
+    mut commands: Commands,
+    bullet_query: Query<
+        (Entity, &CollidingEntities, &AbilityBulletOf),
+        (With<ClashingProjectile>, Without<StuckTo>),
+    >,
+    spawn_query: Query<&AbilityProjectileSpawn>,
+    minion_query: Query<&Minion>,
+) {
+    let side = |bullet_of: &AbilityBulletOf| {
+        let shooter = spawn_query.get(bullet_of.0).ok()?.shooter;
+        Some(minion_query.get(shooter).map_or(shooter, |m| m.owner))
+    };
+    for (bullet, colliding, bullet_of) in &bullet_query {
+        let Some(own_side) = side(bullet_of) else {
+            continue;
+        };
+        let clashed = colliding.iter().any(|&other| {
+            bullet_query
+                .get(other)
+                .is_ok_and(|(_, _, other_of)| side(other_of).is_some_and(|s| s != own_side))
+        });
+        if clashed {
+            trace!("Bullet {bullet:?} clashed with an opposing projectile");
+            commands.entity(bullet).try_despawn();
+        }
+    }
+}
+
 /// Detect projectile hits via CollidingEntities and apply on-hit effects.
 pub fn process_projectile_hits(
     mut commands: Commands,
@@ -115,24 +253,49 @@ pub fn process_projectile_hits(
     server_query: Query<&ControlledBy>,
     player_id_query: Query<&PlayerId>,
     bullet_query: Query<
-        (Entity, &CollidingEntities, &OnHitEffects, &Position),
-        With<AbilityBulletOf>,
+        (
+            Entity,
+            &CollidingEntities,
+            &OnHitEffects,
+            &Position,
+            Option<&StickyProjectile>,
+        ),
+        (With<AbilityBulletOf>, Without<StuckTo>),
     >,
-    mut target_query: Query<(&Position, &mut Health, Option<&Invulnerable>)>,
+    mut target_query: Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    character_query: Query<(Entity, &Position), With<CharacterMarker>>,
+    minion_query: Query<&Minion>,
     mut forces_query: Query<Forces>,
     mut shield_query: Query<&mut ActiveShield>,
     buff_query: Query<&ActiveBuffs>,
     rotation_query: Query<&Rotation>,
-    mut death_events: MessageWriter<DeathEvent>,
+    mut messages: HitMessages,
 ) {
     let tick = timeline.tick();
-    for (bullet, colliding, on_hit, bullet_pos) in &bullet_query {
+    for (bullet, colliding, on_hit, bullet_pos, sticky) in &bullet_query {
         for &target in colliding.iter() {
             if target == on_hit.original_caster {
                 continue;
             }
-            if target_query.get(target).is_err() {
+            let Ok((target_pos, ..)) = target_query.get(target) else {
                 continue;
+            };
+            if let Some(sticky) = sticky {
+                trace!("Sticky bullet {bullet:?} attached to {target:?}");
+                commands.entity(bullet).insert((
+                    StuckTo {
+                        victim: target,
+                        offset: bullet_pos.0 - target_pos.0,
+                        detonate_tick: tick + sticky.fuse_ticks as i16,
+                    },
+                    LinearVelocity::ZERO,
+                ));
+                break;
             }
             apply_on_hit_effects(
                 &mut commands,
@@ -145,15 +308,93 @@ pub fn process_projectile_hits(
                 on_hit,
                 target,
                 bullet_pos.0,
+                1.0,
+                TargetMask::default(),
                 &mut target_query,
+                &character_query,
+                &minion_query,
                 &mut forces_query,
                 &mut shield_query,
                 &buff_query,
                 &rotation_query,
-                &mut death_events,
+                &mut messages,
             );
             commands.entity(bullet).try_despawn();
             break;
         }
     }
 }
+
+/// Keep attached sticky bullets on their victim.
+pub fn follow_stuck_projectiles(
+    mut bullet_query: Query<(&StuckTo, &mut Position)>,
+    victim_query: Query<&Position, Without<StuckTo>>,
+) {
+    for (stuck, mut bullet_pos) in &mut bullet_query {
+        if let Ok(victim_pos) = victim_query.get(stuck.victim) {
+            bullet_pos.0 = victim_pos.0 + stuck.offset;
+        }
+    }
+}
+
+/// Apply an attached sticky bullet's on-hit effects to its victim once its fuse
+/// runs out, then despawn it. Bullets whose victim is gone just despawn.
+pub fn detonate_stuck_projectiles(
+    mut commands: Commands,
+    ability_defs: Res<AbilityDefs>,
+    ability_assets: Res<Assets<AbilityAsset>>,
+    registry: Res<AppTypeRegistry>,
+    timeline: Res<LocalTimeline>,
+    server_query: Query<&ControlledBy>,
+    player_id_query: Query<&PlayerId>,
+    bullet_query: Query<(Entity, &StuckTo, &OnHitEffects, &Position)>,
+    mut target_query: Query<(
+        &Position,
+        &mut Health,
+        Option<&Invulnerable>,
+        Option<&Resistances>,
+    )>,
+    character_query: Query<(Entity, &Position), With<CharacterMarker>>,
+    minion_query: Query<&Minion>,
+    mut forces_query: Query<Forces>,
+    mut shield_query: Query<&mut ActiveShield>,
+    buff_query: Query<&ActiveBuffs>,
+    rotation_query: Query<&Rotation>,
+    mut messages: HitMessages,
+) {
+    let tick = timeline.tick();
+    for (bullet, stuck, on_hit, bullet_pos) in &bullet_query {
+        let victim_alive = target_query.contains(stuck.victim);
+        let remaining: i16 = stuck.detonate_tick - tick;
+        if victim_alive && remaining > 0 {
+            continue;
+        }
+        if victim_alive {
+            apply_on_hit_effects(
+                &mut commands,
+                ability_defs.as_ref(),
+                ability_assets.as_ref(),
+                &registry.0,
+                tick,
+                &server_query,
+                &player_id_query,
+                on_hit,
+                stuck.victim,
+                bullet_pos.0,
+                1.0,
+                TargetMask::default(),
+                &mut target_query,
+                &character_query,
+                &minion_query,
+                &mut forces_query,
+                &mut shield_query,
+                &buff_query,
+                &rotation_query,
+                &mut messages,
+            );
+        } else {
+            trace!("Sticky bullet {bullet:?} lost its victim, despawning");
+        }
+        commands.entity(bullet).try_despawn();
+    }
+}
@@ -16,46 +16,83 @@ pub mod hit_detection;
 pub mod map;
 pub mod physics;
 pub mod reflect_loader;
+pub mod scheduled_events;
+pub mod settings;
 pub mod terrain;
 pub mod transition;
 pub mod vox_model;
 pub mod world_object;
+pub mod world_time;
 
 pub use ability::{
-    ability_action_to_slot, AbilityAsset, AbilityBulletOf, AbilityBullets, AbilityCooldowns,
-    AbilityDef, AbilityDefs, AbilityEffect, AbilityId, AbilityManifest, AbilityPhase,
-    AbilityPhases, AbilityPlugin, AbilityProjectileSpawn, AbilitySlots, ActiveAbility, ActiveBuff,
-    ActiveBuffs, ActiveShield, DefaultAbilitySlots, EffectTarget, EffectTrigger, ForceFrame,
-    InputEffect, OnEndEffects, OnHitEffectDefs, OnHitEffects, OnInputEffects, OnTickEffects,
-    ProjectileSpawnEffect, TickEffect, WhileActiveEffects,
+    ability_action_to_slot, active_abilities_of, AbilityAsset, AbilityAvailability,
+    AbilityBulletOf, AbilityBullets, AbilityCastEvent, AbilityCharges, AbilityCooldowns,
+    AbilityCost, AbilityDef, AbilityDefs, AbilityEffect, AbilityHitEvent, AbilityId,
+    AbilityInterpolation, AbilityLoadouts, AbilityLockouts, AbilityManifest, AbilityMissEvent,
+    AbilityPhase, AbilityPhases, AbilityPlugin, AbilityProjectileSpawn, AbilitySlots,
+    ActiveAbility, ActiveBuff, ActiveBuffs, ActiveDot, ActiveDots, ActiveShield, BaseMaxHealth,
+    CastState, DamageType, DefaultAbilitySlots, EffectPriority, EffectTarget, EffectTrigger,
+    ForceFrame, InputEffect, LoadoutSwapConfig, Minion, OnCastEffects, OnEndEffects,
+    OnHitEffectDefs, OnHitEffects, OnInputEffects, OnTickEffects, PracticeMode,
+    ProjectileSpawnEffect, Resistances, ResourcePool, Stunned, TickEffect, VoxelWallEvent,
+    WhileActiveEffects,
 };
 pub use app_state::{AppState, AppStatePlugin, TrackedAssets};
-pub use character::{apply_movement, detect_grounded, update_facing};
 pub use character::{
-    CharacterMarker, CharacterPhysicsBundle, CharacterType, ColorComponent, DeathEvent,
-    DummyTarget, Health, Invulnerable, IsGrounded, PlayerId, RespawnPoint, RespawnTimer,
-    RespawnTimerConfig, CHARACTER_CAPSULE_HEIGHT, CHARACTER_CAPSULE_RADIUS, DEFAULT_RESPAWN_TICKS,
+    apply_dodge, apply_ground_drag, apply_move_deadzone, apply_movement, detect_grounded,
+    detect_wall_contact, expire_invulnerability, step_up_obstacles, update_facing,
+    CharacterContacts, MovementLocks,
+};
+pub use character::{
+    default_player_name, sanitize_player_name, PlayerNameChannel, PlayerNameLimits,
+    PlayerNameRequest,
+};
+pub use character::{
+    CharacterCollisionResponse, CharacterDimensions, CharacterMarker, CharacterPhysicsBundle,
+    CharacterType, ColorComponent, DeathEvent, DodgeConfig, DodgeCooldown, DummyTarget, Health,
+    HealthRegen, Invulnerable, IsGrounded, LastDamagedTick, MovementConfig, PlayerId, RespawnPoint,
+    RespawnTimer, RespawnTimerConfig, TouchingWall, CHARACTER_CAPSULE_HEIGHT,
+    CHARACTER_CAPSULE_RADIUS, CHARACTER_COLORS, DEFAULT_RESPAWN_INVULNERABLE_TICKS,
+    DEFAULT_RESPAWN_TICKS,
 };
 pub use hit_detection::{
-    character_collision_layers, damageable_collision_layers, hitbox_collision_layers,
-    projectile_collision_layers, terrain_collision_layers, GameLayer,
+    character_collision_layers, custom_layer, damageable_collision_layers, hitbox_collision_layers,
+    projectile_collision_layers, terrain_collision_layers, CollisionLayerConfig, EffectApplier,
+    GameLayer,
 };
 pub use map::{
-    attach_chunk_colliders, ChunkChannel, ChunkDataSync, MapChannel, MapInstanceId, MapRegistry,
-    MapSaveTarget, MapSwitchTarget, MapTransitionEnd, MapTransitionReady, MapTransitionStart,
-    PendingTransition, PlayerMapSwitchRequest, SavedEntity, SavedEntityKind, SectionBlocksUpdate,
+    attach_chunk_colliders, spawn_map_props, ChunkChannel, ChunkDataSync, MapChannel,
+    MapInstanceId, MapProp, MapProps, MapRegistry, MapSaveTarget, MapSwitchTarget,
+    MapTransitionEnd, MapTransitionReady, MapTransitionStart, PendingTransition,
+    PlayerMapSwitchRequest, PropShape, SavedEntity, SavedEntityKind, SectionBlocksUpdate,
     TransitionReadySent, UnloadColumn, VoxelChannel, VoxelChunk, VoxelEditAck, VoxelEditBroadcast,
-    VoxelEditReject, VoxelEditRequest, VoxelType,
+    VoxelEditReject, VoxelEditRequest, VoxelHazard, VoxelMaterialDef, VoxelMaterialRegistry,
+    VoxelType, DEFAULT_VOXEL_COLOR, LAVA_MATERIAL,
 };
+pub use scheduled_events::{ScheduledEvents, ScheduledEventsPlugin};
 pub use terrain::{TerrainDefRegistry, TerrainPlugin};
 pub use transition::{MapTransitionEntity, TransitionPlugin};
 pub use vox_model::{VoxModelAsset, VoxModelPlugin, VoxModelRegistry};
 pub use world_object::{WorldObjectDefRegistry, WorldObjectId, WorldObjectPlugin};
+pub use world_time::{WorldTime, WorldTimeConfig};
 
 pub const PROTOCOL_ID: u64 = 0;
 pub const PRIVATE_KEY: [u8; 32] = [0; 32];
 pub const FIXED_TIMESTEP_HZ: f64 = 64.0;
 
+/// Converts a fixed-timestep tick count to seconds.
+pub fn ticks_to_secs(ticks: u16) -> f32 {
+    (ticks as f64 / FIXED_TIMESTEP_HZ) as f32
+}
+
+/// Converts seconds to the nearest fixed-timestep tick count, saturating at `u16::MAX`.
+pub fn secs_to_ticks(secs: f32) -> u16 {
+    debug_assert!(secs >= 0.0, "negative duration {secs}s");
+    (secs as f64 * FIXED_TIMESTEP_HZ)
+        .round()
+        .clamp(0.0, u16::MAX as f64) as u16
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy, Hash, Reflect)]
 pub enum PlayerActions {
     Move,
@@ -67,6 +104,9 @@ pub enum PlayerActions {
     Ability2,
     Ability3,
     Ability4,
+    Dodge,
+    /// Cycle to the character's next `AbilityLoadouts` preset.
+    SwapLoadout,
 }
 
 impl Actionlike for PlayerActions {
@@ -79,25 +119,140 @@ impl Actionlike for PlayerActions {
     }
 }
 
+/// Channel for server lifecycle notices.
+pub struct ServerControlChannel;
+
+/// Sent to every connected client when the server begins shutting down, ahead of
+/// the disconnect, so clients can say why the connection ended.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct ServerClosing {
+    pub reason: String,
+}
+
+/// Channel for character death and respawn notices.
+pub struct CharacterLifecycleChannel;
+
+/// Sent to every connected client when a player's character dies or respawns,
+/// so clients can show a death screen or countdown without diffing components.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Copy)]
+pub enum RespawnNotice {
+    /// `player`'s character died and respawns in `respawn_ticks` ticks.
+    Died { player: PeerId, respawn_ticks: u16 },
+    /// `player`'s character respawned at `position` with full health.
+    Respawned { player: PeerId, position: Vec3 },
+}
+
 #[cfg(feature = "test_utils")]
 #[derive(Serialize, Deserialize, Debug, PartialEq, Clone, Reflect, Event)]
 pub struct TestTrigger {
     pub data: String,
 }
 
+/// How clients keep a replicated physics component in sync with the server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SyncMode {
+    /// Simulated ahead on predicted entities and rolled back on mismatch, with
+    /// visual correction smoothing the fix-up.
+    pub prediction: bool,
+    /// Interpolated between confirmed server states on interpolated entities.
+    pub interpolation: bool,
+}
+
+impl SyncMode {
+    pub const PREDICTED: Self = Self {
+        prediction: true,
+        interpolation: false,
+    };
+    pub const INTERPOLATED: Self = Self {
+        prediction: false,
+        interpolation: true,
+    };
+    pub const PREDICTED_AND_INTERPOLATED: Self = Self {
+        prediction: true,
+        interpolation: true,
+    };
+}
+
+/// Chooses prediction and interpolation for the physics components `ProtocolPlugin`
+/// registers. Insert it before adding `ProtocolPlugin` to override the defaults,
+/// which predict everything and also interpolate `Position`/`Rotation`.
+/// Velocities can't be interpolated; only their `prediction` flag is used.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentSyncConfig {
+    pub position: SyncMode,
+    pub rotation: SyncMode,
+    pub velocity: SyncMode,
+}
+
+impl Default for ComponentSyncConfig {
+    fn default() -> Self {
+        Self {
+            position: SyncMode::PREDICTED_AND_INTERPOLATED,
+            rotation: SyncMode::PREDICTED_AND_INTERPOLATED,
+            velocity: SyncMode::PREDICTED,
+        }
+    }
+}
+
+/// Whether the server relays every client's inputs to all other clients, so
+/// they can predict remote characters from their inputs. Insert it before adding
+/// `ProtocolPlugin` to override the default (on). With it off, remote characters
+/// are interpolated from replicated state instead, which saves bandwidth and
+/// keeps each player's inputs private; a client's own inputs still reach the
+/// server.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputRebroadcastConfig {
+    pub rebroadcast_inputs: bool,
+}
+
+impl Default for InputRebroadcastConfig {
+    fn default() -> Self {
+        Self {
+            rebroadcast_inputs: true,
+        }
+    }
+}
+
+impl InputRebroadcastConfig {
+    /// The clients that predict, and the clients that interpolate, a character
+    /// owned by `owner`. Predicting a remote character needs its inputs, so with
+    /// rebroadcast off only the owner predicts it and everyone else interpolates.
+    pub fn character_sync_targets(&self, owner: PeerId) -> (NetworkTarget, NetworkTarget) {
+        if self.rebroadcast_inputs {
+            (NetworkTarget::All, NetworkTarget::None)
+        } else {
+            (
+                NetworkTarget::Single(owner),
+                NetworkTarget::AllExceptSingle(owner),
+            )
+        }
+    }
+}
+
+/// Lightyear input settings for `PlayerActions`, as `ProtocolPlugin` installs them.
+pub fn player_input_config(rebroadcast: &InputRebroadcastConfig) -> InputConfig<PlayerActions> {
+    InputConfig::<PlayerActions> {
+        rebroadcast_inputs: rebroadcast.rebroadcast_inputs,
+        // At 5, srv_tick_past_buffer_end occasionally hits +1,
+        // causing axis values to persist (stuck movement) and discrete
+        // transitions (JustPressed) to be missed.
+        packet_redundancy: 20,
+        ..default()
+    }
+}
+
 pub struct ProtocolPlugin;
 
 impl Plugin for ProtocolPlugin {
     fn build(&self, app: &mut App) {
+        let sync = *app
+            .world_mut()
+            .get_resource_or_init::<ComponentSyncConfig>();
+        let rebroadcast = *app
+            .world_mut()
+            .get_resource_or_init::<InputRebroadcastConfig>();
         app.add_plugins(InputPlugin::<PlayerActions> {
-            config: InputConfig::<PlayerActions> {
-                rebroadcast_inputs: true,
-                // At 5, srv_tick_past_buffer_end occasionally hits +1,
-                // causing axis values to persist (stuck movement) and discrete
-                // transitions (JustPressed) to be missed.
-                packet_redundancy: 20,
-                ..default()
-            },
+            config: player_input_config(&rebroadcast),
         });
 
         // Voxel channel
@@ -149,6 +304,51 @@ impl Plugin for ProtocolPlugin {
         app.register_message::<MapTransitionEnd>()
             .add_direction(NetworkDirection::ServerToClient);
 
+        // Server lifecycle notices
+        app.add_channel::<ServerControlChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        })
+        .add_direction(NetworkDirection::ServerToClient);
+        app.register_message::<ServerClosing>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        // Character death and respawn notices
+        app.add_channel::<CharacterLifecycleChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        })
+        .add_direction(NetworkDirection::ServerToClient);
+        app.register_message::<RespawnNotice>()
+            .add_direction(NetworkDirection::ServerToClient);
+
+        // Input device reports for aim assist
+        app.add_channel::<ability::InputDeviceChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        })
+        .add_direction(NetworkDirection::ClientToServer);
+        app.register_message::<ability::InputDeviceReport>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // Player display names
+        app.add_channel::<PlayerNameChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        })
+        .add_direction(NetworkDirection::ClientToServer);
+        app.register_message::<PlayerNameRequest>()
+            .add_direction(NetworkDirection::ClientToServer);
+
+        // Desync detection
+        app.add_channel::<diagnostics::DiagnosticsChannel>(ChannelSettings {
+            mode: ChannelMode::UnorderedUnreliable,
+            ..default()
+        })
+        .add_direction(NetworkDirection::ServerToClient);
+        app.register_message::<diagnostics::StateChecksum>()
+            .add_direction(NetworkDirection::ServerToClient);
+
         #[cfg(feature = "test_utils")]
         app.register_event::<TestTrigger>()
             .add_direction(NetworkDirection::Bidirectional);
@@ -160,6 +360,10 @@ impl Plugin for ProtocolPlugin {
         app.register_component::<world_object::WorldObjectId>();
         app.register_component::<world_object::VisualKind>();
         app.register_component::<world_object::ActiveTransformation>();
+        app.register_component::<MapProp>();
+
+        // Cosmetic time of day, carried by the server's world clock entity
+        app.register_component::<WorldTime>();
 
         // Marker components
         app.register_component::<PlayerId>();
@@ -168,43 +372,115 @@ impl Plugin for ProtocolPlugin {
         app.register_component::<CharacterMarker>().add_prediction();
         app.register_component::<DummyTarget>().add_prediction();
         app.register_component::<CharacterType>().add_prediction();
+        app.register_component::<CharacterDimensions>()
+            .add_prediction();
         app.register_component::<Health>().add_prediction();
         app.register_component::<Invulnerable>().add_prediction();
+        app.register_component::<DodgeCooldown>().add_prediction();
         app.register_component::<RespawnTimerConfig>();
+        app.register_component::<HealthRegen>();
         app.register_component::<RespawnTimer>().add_prediction();
 
-        // Velocity prediction without visual correction
-        app.register_component::<LinearVelocity>()
-            .add_prediction()
-            .add_should_rollback(linear_velocity_should_rollback);
-
-        app.register_component::<AngularVelocity>()
-            .add_prediction()
-            .add_should_rollback(angular_velocity_should_rollback);
-
         // Ability components
-        app.register_component::<AbilitySlots>();
+        app.register_component::<AbilitySlots>().add_prediction();
+        app.register_component::<AbilityLoadouts>().add_prediction();
+        app.register_component::<AbilityLockouts>().add_prediction();
+        app.register_component::<AbilityCharges>().add_prediction();
         app.register_component::<ActiveAbility>()
             .add_prediction()
             .add_map_entities();
         app.register_component::<AbilityCooldowns>()
             .add_prediction();
         app.register_component::<ActiveShield>().add_prediction();
+        app.register_component::<CastState>().add_prediction();
         app.register_component::<ActiveBuffs>().add_prediction();
+        app.register_component::<ActiveDots>().add_prediction();
+        app.register_component::<Stunned>().add_prediction();
+        app.register_component::<ResourcePool>().add_prediction();
+        app.register_component::<Resistances>().add_prediction();
+        app.register_component::<BaseMaxHealth>().add_prediction();
+        app.register_component::<ability::GrappleAnchor>()
+            .add_prediction();
+        app.register_component::<ability::DamagedThisTick>()
+            .add_prediction();
         app.register_component::<AbilityProjectileSpawn>();
-
-        // Position/Rotation with prediction + visual correction + interpolation
-        app.register_component::<Position>()
+        app.register_component::<ability::StickyProjectile>();
+        app.register_component::<ability::ClashingProjectile>();
+        app.register_component::<ability::HomingProjectile>();
+        app.register_component::<ability::GamepadAiming>()
+            .add_prediction();
+        app.register_component::<Minion>()
             .add_prediction()
-            .add_should_rollback(position_should_rollback)
-            .add_linear_correction_fn()
-            .add_linear_interpolation();
+            .add_map_entities();
+        app.register_component::<AbilityInterpolation>();
+
+        register_physics_sync(app, sync);
+    }
+}
 
-        app.register_component::<Rotation>()
+/// Registers `Position`, `Rotation` and the velocities per `sync`.
+fn register_physics_sync(app: &mut App, sync: ComponentSyncConfig) {
+    debug_assert!(
+        !sync.velocity.interpolation,
+        "velocities have no interpolation function"
+    );
+
+    if sync.velocity.prediction {
+        app.register_component::<LinearVelocity>()
+            .add_prediction()
+            .add_should_rollback(linear_velocity_should_rollback);
+        app.register_component::<AngularVelocity>()
             .add_prediction()
-            .add_should_rollback(rotation_should_rollback)
-            .add_linear_correction_fn()
-            .add_linear_interpolation();
+            .add_should_rollback(angular_velocity_should_rollback);
+    } else {
+        app.register_component::<LinearVelocity>();
+        app.register_component::<AngularVelocity>();
+    }
+
+    match (sync.position.prediction, sync.position.interpolation) {
+        (true, true) => {
+            app.register_component::<Position>()
+                .add_prediction()
+                .add_should_rollback(position_should_rollback)
+                .add_linear_correction_fn()
+                .add_linear_interpolation();
+        }
+        (true, false) => {
+            app.register_component::<Position>()
+                .add_prediction()
+                .add_should_rollback(position_should_rollback)
+                .add_linear_correction_fn();
+        }
+        (false, true) => {
+            app.register_component::<Position>()
+                .add_linear_interpolation();
+        }
+        (false, false) => {
+            app.register_component::<Position>();
+        }
+    }
+
+    match (sync.rotation.prediction, sync.rotation.interpolation) {
+        (true, true) => {
+            app.register_component::<Rotation>()
+                .add_prediction()
+                .add_should_rollback(rotation_should_rollback)
+                .add_linear_correction_fn()
+                .add_linear_interpolation();
+        }
+        (true, false) => {
+            app.register_component::<Rotation>()
+                .add_prediction()
+                .add_should_rollback(rotation_should_rollback)
+                .add_linear_correction_fn();
+        }
+        (false, true) => {
+            app.register_component::<Rotation>()
+                .add_linear_interpolation();
+        }
+        (false, false) => {
+            app.register_component::<Rotation>();
+        }
     }
 }
 
@@ -235,6 +511,14 @@ impl Plugin for SharedGameplayPlugin {
         app.add_plugins(world_object::WorldObjectPlugin);
         app.add_plugins(vox_model::VoxModelPlugin);
         app.add_plugins(TransitionPlugin);
+        app.init_asset::<MapProps>();
+        app.init_asset_loader::<map::MapPropsLoader>();
+        app.init_resource::<MovementConfig>();
+        app.init_resource::<DodgeConfig>();
+        app.init_resource::<WorldTime>();
+        app.init_resource::<WorldTimeConfig>();
+        app.init_resource::<CollisionLayerConfig>();
+        app.add_observer(hit_detection::apply_collision_layer_config);
 
         app.add_plugins(lightyear::avian3d::plugin::LightyearAvianPlugin {
             replication_mode: lightyear::avian3d::plugin::AvianReplicationMode::Position,
@@ -0,0 +1,84 @@
+use bevy::prelude::*;
+use std::collections::HashMap;
+use voxel_map_engine::prelude::WorldVoxel;
+
+/// Material id of lava voxels, a hazard in the default registry.
+pub const LAVA_MATERIAL: u8 = 6;
+
+/// Render color of materials without a `color` in the registry.
+pub const DEFAULT_VOXEL_COLOR: Color = Color::srgb(0.5, 0.7, 0.3);
+
+/// Damage dealt to characters standing in or on a hazard voxel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VoxelHazard {
+    /// Damage per application.
+    pub damage: f32,
+    /// Damage is applied on ticks that are a multiple of this.
+    pub interval_ticks: u16,
+}
+
+/// Gameplay and render properties of one voxel material.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VoxelMaterialDef {
+    pub hazard: Option<VoxelHazard>,
+    /// Color the client tints this material's faces with.
+    pub color: Option<Color>,
+}
+
+/// Properties of voxel materials, keyed by their `WorldVoxel::Solid` id.
+/// Materials without an entry are plain terrain in [`DEFAULT_VOXEL_COLOR`].
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct VoxelMaterialRegistry {
+    pub materials: HashMap<u8, VoxelMaterialDef>,
+}
+
+impl Default for VoxelMaterialRegistry {
+    fn default() -> Self {
+        Self {
+            materials: HashMap::from([
+                (1, VoxelMaterialDef::colored(Color::srgb(0.36, 0.62, 0.25))),
+                (2, VoxelMaterialDef::colored(Color::srgb(0.5, 0.48, 0.45))),
+                (3, VoxelMaterialDef::colored(Color::srgb(0.86, 0.78, 0.52))),
+                (
+                    LAVA_MATERIAL,
+                    VoxelMaterialDef {
+                        hazard: Some(VoxelHazard {
+                            damage: 5.0,
+                            interval_ticks: 16,
+                        }),
+                        color: Some(Color::srgb(1.0, 0.35, 0.05)),
+                    },
+                ),
+            ]),
+        }
+    }
+}
+
+impl VoxelMaterialDef {
+    /// A plain, harmless material rendered in `color`.
+    pub fn colored(color: Color) -> Self {
+        Self {
+            hazard: None,
+            color: Some(color),
+        }
+    }
+}
+
+impl VoxelMaterialRegistry {
+    /// The hazard of `voxel`'s material, if any. Air is never a hazard.
+    pub fn hazard(&self, voxel: WorldVoxel) -> Option<&VoxelHazard> {
+        let WorldVoxel::Solid(material) = voxel else {
+            return None;
+        };
+        self.materials.get(&material)?.hazard.as_ref()
+    }
+
+    /// Render color of solid `material`, or [`DEFAULT_VOXEL_COLOR`] if it
+    /// has none.
+    pub fn color(&self, material: u8) -> Color {
+        self.materials
+            .get(&material)
+            .and_then(|def| def.color)
+            .unwrap_or(DEFAULT_VOXEL_COLOR)
+    }
+}
@@ -1,6 +1,8 @@
 mod chunk;
 mod colliders;
+mod materials;
 mod persistence;
+mod props;
 mod transition;
 mod types;
 mod voxel;
@@ -11,7 +13,13 @@ pub use voxel_map_engine::prelude::{VoxelChunk, VoxelType};
 
 pub use chunk::{ChunkChannel, ChunkDataSync, UnloadColumn};
 pub use colliders::attach_chunk_colliders;
+pub use materials::{
+    VoxelHazard, VoxelMaterialDef, VoxelMaterialRegistry, DEFAULT_VOXEL_COLOR, LAVA_MATERIAL,
+};
 pub use persistence::{MapSaveTarget, SavedEntity, SavedEntityKind};
+pub use props::{
+    prop_physics, spawn_map_props, MapProp, MapProps, MapPropsLoader, PropPlacement, PropShape,
+};
 pub use transition::{
     MapChannel, MapTransitionEnd, MapTransitionReady, MapTransitionStart, PendingTransition,
     PlayerMapSwitchRequest, TransitionReadySent,
@@ -0,0 +1,179 @@
+use avian3d::prelude::*;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::types::MapInstanceId;
+use crate::reflect_loader::ReflectLoadError;
+use crate::terrain_collision_layers;
+
+/// Primitive shape of a static prop, used for both its collider and its mesh.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PropShape {
+    Cuboid { size: Vec3 },
+    Cylinder { radius: f32, height: f32 },
+    Sphere { radius: f32 },
+}
+
+impl PropShape {
+    pub fn collider(&self) -> Collider {
+        match *self {
+            PropShape::Cuboid { size } => Collider::cuboid(size.x, size.y, size.z),
+            PropShape::Cylinder { radius, height } => Collider::cylinder(radius, height),
+            PropShape::Sphere { radius } => Collider::sphere(radius),
+        }
+    }
+
+    pub fn mesh(&self) -> Mesh {
+        match *self {
+            PropShape::Cuboid { size } => Cuboid::from_size(size).into(),
+            PropShape::Cylinder { radius, height } => Cylinder::new(radius, height).into(),
+            PropShape::Sphere { radius } => Sphere::new(radius).into(),
+        }
+    }
+}
+
+/// Replicated component marking a static prop placed from a map's props file.
+/// Server and clients each derive the collider (and clients the mesh) from `shape`.
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MapProp {
+    pub shape: PropShape,
+    pub color: Color,
+}
+
+/// One prop entry in a `.props.ron` file.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+pub struct PropPlacement {
+    pub shape: PropShape,
+    pub position: Vec3,
+    #[serde(default)]
+    pub rotation: Quat,
+    #[serde(default = "default_prop_color")]
+    pub color: Color,
+}
+
+fn default_prop_color() -> Color {
+    Color::srgb(0.6, 0.6, 0.6)
+}
+
+/// Static props placed in a map at server start. Loaded from `props/<map>.props.ron`:
+/// ```ron
+/// ([
+///     (shape: Cuboid(size: (4.0, 2.0, 4.0)), position: (10.0, 1.0, 0.0)),
+///     (shape: Cylinder(radius: 0.5, height: 6.0), position: (-8.0, 3.0, 4.0)),
+/// ])
+/// ```
+#[derive(Asset, TypePath, Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct MapProps(pub Vec<PropPlacement>);
+
+impl MapProps {
+    pub fn from_ron(bytes: &[u8]) -> Result<Self, ReflectLoadError> {
+        Ok(ron::de::from_bytes(bytes)?)
+    }
+}
+
+/// Loader for `.props.ron` files.
+#[derive(Default, TypePath)]
+pub struct MapPropsLoader;
+
+impl AssetLoader for MapPropsLoader {
+    type Asset = MapProps;
+    type Settings = ();
+    type Error = ReflectLoadError;
+
+    fn extensions(&self) -> &[&str] {
+        &["props.ron"]
+    }
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        MapProps::from_ron(&bytes)
+    }
+}
+
+/// Static body, collider and terrain layers for a prop. Inserted by the server at
+/// spawn and by clients when the prop replicates.
+pub fn prop_physics(shape: &PropShape) -> impl Bundle {
+    (
+        RigidBody::Static,
+        shape.collider(),
+        terrain_collision_layers(),
+    )
+}
+
+/// Spawns every prop in `props` on `map_id` as a static collider. Returns the
+/// spawned entities so the caller can add replication.
+pub fn spawn_map_props(
+    commands: &mut Commands,
+    props: &MapProps,
+    map_id: &MapInstanceId,
+) -> Vec<Entity> {
+    props
+        .0
+        .iter()
+        .map(|placement| {
+            commands
+                .spawn((
+                    MapProp {
+                        shape: placement.shape,
+                        color: placement.color,
+                    },
+                    Position(placement.position),
+                    Rotation(placement.rotation),
+                    map_id.clone(),
+                    prop_physics(&placement.shape),
+                ))
+                .id()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overworld_props_file_parses() {
+        let bytes = include_bytes!("../../../../assets/props/overworld.props.ron");
+        let props = MapProps::from_ron(bytes).unwrap();
+        assert!(!props.0.is_empty());
+    }
+
+    #[test]
+    fn loaded_props_spawn_static_colliders() {
+        let ron = br#"([
+            (shape: Cuboid(size: (4.0, 2.0, 4.0)), position: (10.0, 1.0, 0.0)),
+            (shape: Sphere(radius: 1.0), position: (-3.0, 5.0, 2.0)),
+        ])"#;
+        let props = MapProps::from_ron(ron).unwrap();
+
+        let mut world = World::new();
+        let entities = spawn_map_props(&mut world.commands(), &props, &MapInstanceId::Overworld);
+        world.flush();
+
+        assert_eq!(entities.len(), 2);
+        for (entity, placement) in entities.iter().zip(&props.0) {
+            let entity = world.entity(*entity);
+            assert_eq!(entity.get::<RigidBody>(), Some(&RigidBody::Static));
+            assert!(entity.contains::<Collider>());
+            assert_eq!(entity.get::<Position>().unwrap().0, placement.position);
+            assert_eq!(
+                entity.get::<MapInstanceId>(),
+                Some(&MapInstanceId::Overworld)
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_props_file_errors() {
+        assert!(MapProps::from_ron(b"([(shape: Pyramid())])").is_err());
+    }
+}
@@ -21,3 +21,26 @@ impl CollisionHooks for MapCollisionHooks<'_, '_> {
         }
     }
 }
+
+/// Request to knock `entity` back by `impulse`. Applied through avian's `Forces`,
+/// so the velocity change is `impulse / mass` and heavier bodies resist more,
+/// the same way on-hit `ApplyForce` knockback behaves. Entities that aren't
+/// rigid bodies ignore it.
+#[derive(bevy::ecs::message::Message, Clone, Copy, Debug, PartialEq)]
+pub struct ApplyImpulse {
+    pub entity: Entity,
+    pub impulse: Vec3,
+}
+
+pub fn apply_impulses(
+    mut requests: bevy::ecs::message::MessageReader<ApplyImpulse>,
+    mut forces_query: Query<Forces>,
+) {
+    for request in requests.read() {
+        let Ok(mut forces) = forces_query.get_mut(request.entity) else {
+            trace!("ApplyImpulse: {:?} is not a rigid body", request.entity);
+            continue;
+        };
+        forces.apply_linear_impulse(request.impulse);
+    }
+}
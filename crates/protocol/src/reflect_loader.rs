@@ -1,17 +1,27 @@
 use bevy::prelude::*;
-use bevy::reflect::serde::{TypeRegistrationDeserializer, TypedReflectDeserializer};
+use bevy::reflect::serde::TypedReflectDeserializer;
 use bevy::reflect::{PartialReflect, ReflectFromReflect, TypeRegistry};
-use serde::de::{DeserializeSeed, Deserializer, MapAccess, Visitor};
+use serde::de::{DeserializeSeed, Deserializer, Error as _, MapAccess, Visitor};
 use std::fmt;
 
+/// Reserved top-level key holding a file's format version rather than a
+/// component, e.g. `"version": 2`.
+pub const VERSION_KEY: &str = "version";
+
+/// Components read from a component map, plus its `"version"` entry if any.
+pub struct VersionedComponents {
+    pub version: Option<u32>,
+    pub components: Vec<Box<dyn PartialReflect>>,
+}
+
 /// `DeserializeSeed` that reads a flat `{ "type::Path": (data) }` RON map
-/// into a `Vec<Box<dyn PartialReflect>>`.
+/// into [`VersionedComponents`].
 pub struct ComponentMapDeserializer<'a> {
     pub registry: &'a TypeRegistry,
 }
 
 impl<'a, 'de> DeserializeSeed<'de> for ComponentMapDeserializer<'a> {
-    type Value = Vec<Box<dyn PartialReflect>>;
+    type Value = VersionedComponents;
 
     fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
         deserializer.deserialize_map(ComponentMapVisitor {
@@ -25,17 +35,25 @@ struct ComponentMapVisitor<'a> {
 }
 
 impl<'a, 'de> Visitor<'de> for ComponentMapVisitor<'a> {
-    type Value = Vec<Box<dyn PartialReflect>>;
+    type Value = VersionedComponents;
 
     fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "a map of component type paths to component data")
     }
 
     fn visit_map<M: MapAccess<'de>>(self, mut map: M) -> Result<Self::Value, M::Error> {
+        let mut version = None;
         let mut components = Vec::new();
-        while let Some(registration) =
-            map.next_key_seed(TypeRegistrationDeserializer::new(self.registry))?
-        {
+        while let Some(key) = map.next_key::<String>()? {
+            if key == VERSION_KEY {
+                if version.replace(map.next_value()?).is_some() {
+                    return Err(M::Error::duplicate_field(VERSION_KEY));
+                }
+                continue;
+            }
+            let registration = self.registry.get_with_type_path(&key).ok_or_else(|| {
+                M::Error::custom(format_args!("no registration found for type `{key}`"))
+            })?;
             let value =
                 map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?;
             let value = self
@@ -47,20 +65,32 @@ impl<'a, 'de> Visitor<'de> for ComponentMapVisitor<'a> {
                 .unwrap_or(value);
             components.push(value);
         }
-        Ok(components)
+        Ok(VersionedComponents {
+            version,
+            components,
+        })
     }
 }
 
 /// Deserialize a `Vec<Box<dyn PartialReflect>>` from RON bytes using a flat
-/// `{ "type::Path": (data) }` map format.
+/// `{ "type::Path": (data) }` map format. A `"version"` entry is ignored; use
+/// [`deserialize_versioned_component_map`] to read it.
 pub fn deserialize_component_map(
     bytes: &[u8],
     registry: &TypeRegistry,
 ) -> Result<Vec<Box<dyn PartialReflect>>, ReflectLoadError> {
+    Ok(deserialize_versioned_component_map(bytes, registry)?.components)
+}
+
+/// [`deserialize_component_map`], also returning the map's `"version"` entry.
+pub fn deserialize_versioned_component_map(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+) -> Result<VersionedComponents, ReflectLoadError> {
     let mut deserializer = ron::de::Deserializer::from_bytes(bytes)?;
-    let components = ComponentMapDeserializer { registry }.deserialize(&mut deserializer)?;
+    let map = ComponentMapDeserializer { registry }.deserialize(&mut deserializer)?;
     deserializer.end()?;
-    Ok(components)
+    Ok(map)
 }
 
 /// Error type for reflect-based asset loading failures.
@@ -68,6 +98,8 @@ pub fn deserialize_component_map(
 pub enum ReflectLoadError {
     Io(std::io::Error),
     Ron(ron::error::SpannedError),
+    /// The file's `"version"` is newer than, or otherwise unknown to, this build.
+    UnsupportedVersion(u32),
 }
 
 impl fmt::Display for ReflectLoadError {
@@ -75,6 +107,7 @@ impl fmt::Display for ReflectLoadError {
         match self {
             Self::Io(e) => write!(f, "IO error: {e}"),
             Self::Ron(e) => write!(f, "RON error: {e}"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported format version {v}"),
         }
     }
 }
@@ -84,6 +117,7 @@ impl std::error::Error for ReflectLoadError {
         match self {
             Self::Io(e) => Some(e),
             Self::Ron(e) => Some(e),
+            Self::UnsupportedVersion(_) => None,
         }
     }
 }
@@ -156,6 +190,21 @@ mod tests {
         assert_eq!(phases.cooldown, 16);
     }
 
+    #[test]
+    fn deserialize_cooldown_in_seconds() {
+        let registry = ability_test_registry();
+        let ron = br#"{
+            "protocol::ability::AbilityPhases": (startup: 4, active: 20, recovery: 0, cooldown: 1.5),
+        }"#;
+        let components = deserialize_component_map(ron, &registry).unwrap();
+
+        let phases = components[0]
+            .try_downcast_ref::<AbilityPhases>()
+            .expect("should downcast to AbilityPhases");
+        assert_eq!(phases.cooldown, 96);
+        assert_eq!(phases.cooldown_secs(), 1.5);
+    }
+
     #[test]
     fn deserialize_ability_with_multiple_components() {
         let registry = ability_test_registry();
@@ -0,0 +1,194 @@
+use bevy::ecs::message::{Message, MessageWriter};
+use bevy::prelude::*;
+use lightyear::prelude::{LocalTimeline, Tick};
+use std::marker::PhantomData;
+
+/// How many ticks fired events are kept, so a rollback up to this far can
+/// replay them.
+pub const SCHEDULED_EVENT_HISTORY_TICKS: i16 = 64;
+
+/// An event waiting in [`ScheduledEvents`].
+#[derive(Clone, Debug, PartialEq)]
+struct Scheduled<E> {
+    fire_at: Tick,
+    /// Tick the event was scheduled on; rolling back past it drops the event,
+    /// since resimulation schedules it again.
+    scheduled_at: Tick,
+    event: E,
+}
+
+/// Messages of type `E` to write on a future tick, for delayed detonations,
+/// timed spawns and the like. [`fire_scheduled_events`] writes each one on
+/// exactly its tick, in tick order.
+///
+/// Rollback-aware: when the timeline goes back to a tick already processed,
+/// events scheduled on or after it are dropped (resimulation schedules them
+/// again) and events that fired on or after it become pending again. For that
+/// to hold, schedule from systems ordered after [`fire_scheduled_events::<E>`].
+#[derive(Resource, Debug)]
+pub struct ScheduledEvents<E: Message + Clone> {
+    pending: Vec<Scheduled<E>>,
+    fired: Vec<Scheduled<E>>,
+    last_tick: Option<Tick>,
+}
+
+impl<E: Message + Clone> Default for ScheduledEvents<E> {
+    fn default() -> Self {
+        Self {
+            pending: Vec::new(),
+            fired: Vec::new(),
+            last_tick: None,
+        }
+    }
+}
+
+impl<E: Message + Clone> ScheduledEvents<E> {
+    /// Schedules `event` to fire `delay_ticks` after `now`. The delay must be at
+    /// least one tick, since this tick's events have already fired.
+    pub fn schedule(&mut self, now: Tick, delay_ticks: u16, event: E) {
+        debug_assert!(
+            (1..=i16::MAX as u16).contains(&delay_ticks),
+            "delay of {delay_ticks} ticks is out of range"
+        );
+        self.pending.push(Scheduled {
+            fire_at: now + delay_ticks as i16,
+            scheduled_at: now,
+            event,
+        });
+    }
+
+    /// Number of events that haven't fired yet.
+    pub fn pending_len(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Removes and returns the events due on or before `tick`, oldest first.
+    /// Called once per tick; a call for a tick already processed is a rollback
+    /// and rewinds first.
+    pub fn take_due(&mut self, tick: Tick) -> Vec<E> {
+        self.rewind_to(tick);
+        self.last_tick = Some(tick);
+
+        let mut due = Vec::new();
+        let mut index = 0;
+        while index < self.pending.len() {
+            if self.pending[index].fire_at - tick <= 0 {
+                due.push(self.pending.swap_remove(index));
+            } else {
+                index += 1;
+            }
+        }
+        due.sort_by_key(|s| s.fire_at - tick);
+        self.fired
+            .retain(|s| tick - s.fire_at < SCHEDULED_EVENT_HISTORY_TICKS);
+        self.fired.extend(due.iter().cloned());
+        due.into_iter().map(|s| s.event).collect()
+    }
+
+    fn rewind_to(&mut self, tick: Tick) {
+        let Some(last) = self.last_tick else {
+            return;
+        };
+        if tick - last > 0 {
+            return;
+        }
+        self.pending.retain(|s| s.scheduled_at - tick < 0);
+        let fired = std::mem::take(&mut self.fired);
+        for scheduled in fired {
+            if scheduled.scheduled_at - tick >= 0 {
+                continue;
+            }
+            if scheduled.fire_at - tick >= 0 {
+                self.pending.push(scheduled);
+            } else {
+                self.fired.push(scheduled);
+            }
+        }
+    }
+}
+
+/// Writes the [`ScheduledEvents<E>`] due this tick as `E` messages.
+pub fn fire_scheduled_events<E: Message + Clone>(
+    timeline: Res<LocalTimeline>,
+    mut scheduled: ResMut<ScheduledEvents<E>>,
+    mut writer: MessageWriter<E>,
+) {
+    for event in scheduled.take_due(timeline.tick()) {
+        writer.write(event);
+    }
+}
+
+/// Adds `E` as a message with a [`ScheduledEvents<E>`] queue, fired in
+/// `FixedUpdate`. Systems that schedule or read `E` should run after
+/// [`fire_scheduled_events::<E>`].
+pub struct ScheduledEventsPlugin<E>(PhantomData<E>);
+
+impl<E> Default for ScheduledEventsPlugin<E> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<E: Message + Clone> Plugin for ScheduledEventsPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.add_message::<E>();
+        app.init_resource::<ScheduledEvents<E>>();
+        app.add_systems(FixedUpdate, fire_scheduled_events::<E>);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Message, Clone, Debug, PartialEq)]
+    struct Detonate(u32);
+
+    #[test]
+    fn event_fires_exactly_on_its_tick() {
+        let mut scheduled = ScheduledEvents::default();
+        scheduled.schedule(Tick(10), 5, Detonate(1));
+
+        for tick in 10..15 {
+            assert!(
+                scheduled.take_due(Tick(tick)).is_empty(),
+                "fired early on tick {tick}"
+            );
+        }
+        assert_eq!(scheduled.take_due(Tick(15)), vec![Detonate(1)]);
+        assert!(scheduled.take_due(Tick(16)).is_empty(), "fires only once");
+    }
+
+    #[test]
+    fn due_events_fire_in_tick_order() {
+        let mut scheduled = ScheduledEvents::default();
+        scheduled.schedule(Tick(0), 3, Detonate(3));
+        scheduled.schedule(Tick(0), 1, Detonate(1));
+        scheduled.schedule(Tick(0), 2, Detonate(2));
+
+        assert_eq!(
+            scheduled.take_due(Tick(5)),
+            vec![Detonate(1), Detonate(2), Detonate(3)]
+        );
+    }
+
+    #[test]
+    fn rollback_replays_fired_and_drops_resimulated_events() {
+        let mut scheduled = ScheduledEvents::default();
+        scheduled.schedule(Tick(0), 4, Detonate(1));
+        for tick in 0..=4 {
+            scheduled.take_due(Tick(tick));
+        }
+        // Scheduled during ticks that are about to be resimulated.
+        scheduled.schedule(Tick(4), 10, Detonate(2));
+
+        // Roll back to tick 3 and resimulate.
+        assert!(scheduled.take_due(Tick(3)).is_empty());
+        assert_eq!(
+            scheduled.pending_len(),
+            1,
+            "the event scheduled on tick 4 is dropped"
+        );
+        assert_eq!(scheduled.take_due(Tick(4)), vec![Detonate(1)]);
+    }
+}
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+/// Player-facing client settings, changed at runtime and persisted between
+/// sessions by [`SettingsPlugin`]. Nothing here touches the fixed timestep or
+/// networked state. Fields missing from a saved file keep their defaults.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// Press F6 to toggle.
+    pub visual_smoothing: VisualSmoothingSettings,
+    /// Press F7 to toggle.
+    pub hit_stop: HitStopSettings,
+    /// Press F8 to toggle.
+    pub camera_shake: CameraShakeSettings,
+    /// The most recently attempted server, offered by the Reconnect button.
+    pub last_server: Option<SocketAddr>,
+}
+
+/// Whether and how quickly characters' rendered transforms ease toward their
+/// physics state. Off by default.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VisualSmoothingSettings {
+    pub enabled: bool,
+    /// Exponential approach rate per second: each second the rendered pose
+    /// closes all but `exp(-rate)` of its gap to the physics pose.
+    pub rate: f32,
+}
+
+impl Default for VisualSmoothingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            rate: 20.0,
+        }
+    }
+}
+
+/// When heavy hits freeze the action for a moment. On by default.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HitStopSettings {
+    pub enabled: bool,
+    /// Smallest hit damage that counts as heavy.
+    pub heavy_hit_damage: f32,
+    /// How long the hit stop lasts, in real seconds.
+    pub duration_secs: f32,
+    /// Animation playback speed during the hit stop.
+    pub time_scale: f32,
+}
+
+impl Default for HitStopSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            heavy_hit_damage: 25.0,
+            duration_secs: 0.08,
+            time_scale: 0.05,
+        }
+    }
+}
+
+/// Whether damage and nearby explosions shake the camera. On by default; turn
+/// it off for motion sensitivity.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CameraShakeSettings {
+    pub enabled: bool,
+}
+
+impl Default for CameraShakeSettings {
+    fn default() -> Self {
+        Self { enabled: true }
+    }
+}
+
+impl Settings {
+    /// Loads the settings saved at `path`, falling back to defaults if the
+    /// file is missing or unparseable.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => ron::from_str(&contents)
+                .map_err(|e| warn!("Ignoring invalid settings in {path:?}: {e}"))
+                .unwrap_or_default(),
+            Err(e) => {
+                trace!("No settings at {path:?}: {e}");
+                Self::default()
+            }
+        }
+    }
+
+    /// The per-user settings file: under `$XDG_CONFIG_HOME` (or `~/.config`)
+    /// on Unix and `%APPDATA%` on Windows. `None` when neither is set, as on
+    /// the web.
+    pub fn default_path() -> Option<PathBuf> {
+        let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(
+            config_dir
+                .join("bevy-lightyear-template")
+                .join("settings.ron"),
+        )
+    }
+
+    /// Writes these settings to `path`, creating its directory if needed.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let contents = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(std::io::Error::other)?;
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let tmp_path = path.with_extension("ron.tmp");
+        std::fs::write(&tmp_path, contents)?;
+        std::fs::rename(&tmp_path, path)
+    }
+}
+
+/// File [`Settings`] are persisted to; `None` keeps them in memory only.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SettingsPath(pub Option<PathBuf>);
+
+/// Inserts default [`Settings`] unless already present, and saves them to
+/// [`SettingsPath`] whenever they change.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        app.init_resource::<SettingsPath>();
+        app.add_systems(Last, persist_settings.run_if(resource_changed::<Settings>));
+    }
+}
+
+fn persist_settings(settings: Res<Settings>, path: Res<SettingsPath>) {
+    let Some(path) = &path.0 else {
+        trace!("Settings not persisted: no settings path");
+        return;
+    };
+    if let Err(e) = settings.save(path) {
+        warn!("Failed to persist settings to {path:?}: {e}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changed_settings_are_saved_and_reloaded() {
+        let dir = std::env::temp_dir().join(format!("settings-test-{}", std::process::id()));
+        let path = dir.join("nested").join("settings.ron");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(SettingsPath(Some(path.clone())));
+        app.add_plugins(SettingsPlugin);
+        app.update();
+
+        let addr: SocketAddr = "10.0.0.1:6000".parse().unwrap();
+        {
+            let mut settings = app.world_mut().resource_mut::<Settings>();
+            settings.last_server = Some(addr);
+            settings.hit_stop.enabled = false;
+        }
+        app.update();
+
+        let loaded = Settings::load(&path);
+        assert_eq!(loaded.last_server, Some(addr));
+        assert!(!loaded.hit_stop.enabled);
+        assert_eq!(loaded.visual_smoothing, VisualSmoothingSettings::default());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_or_invalid_settings_fall_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!("settings-invalid-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("settings.ron");
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        std::fs::write(&path, "not ron at all").unwrap();
+        assert_eq!(Settings::load(&path), Settings::default());
+
+        std::fs::write(&path, "(hit_stop: (enabled: false))").unwrap();
+        let partial = Settings::load(&path);
+        assert!(!partial.hit_stop.enabled);
+        assert_eq!(
+            partial.hit_stop.duration_secs,
+            HitStopSettings::default().duration_secs,
+            "fields missing from the file keep their defaults"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
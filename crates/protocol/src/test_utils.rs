@@ -2,6 +2,10 @@
 //!
 //! Enable with the `test_utils` feature flag.
 
+mod harness;
+
+pub use harness::{TestClient, TestHarness};
+
 use bevy::prelude::App;
 use lightyear::prelude::{AppMessageExt, Message};
 
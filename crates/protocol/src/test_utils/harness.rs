@@ -0,0 +1,277 @@
+//! Headless client/server harness connected over in-memory crossbeam links.
+//!
+//! Every app runs `MinimalPlugins` plus the full [`ProtocolPlugin`](crate::ProtocolPlugin)
+//! and shares a manually advanced clock, so every [`TestHarness::tick`] steps the
+//! server and then each client by exactly one fixed timestep.
+
+use std::time::{Duration, Instant};
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use lightyear::crossbeam::CrossbeamIo;
+use lightyear::prelude::client::{ClientPlugins, Connect, RawClient};
+use lightyear::prelude::server::{RawServer, ServerPlugins, Start};
+use lightyear::prelude::*;
+
+use crate::FIXED_TIMESTEP_HZ;
+
+/// Upper bound on ticks `add_client` waits for the link to come up.
+const MAX_CONNECT_TICKS: usize = 50;
+
+/// One client app connected to the harness server.
+pub struct TestClient {
+    pub app: App,
+    /// The `Client` entity in `app`.
+    pub entity: Entity,
+    /// The server's `ClientOf` entity representing this client.
+    pub client_of: Entity,
+}
+
+impl TestClient {
+    /// Whether the client entity currently has `Connected`.
+    pub fn is_connected(&self) -> bool {
+        self.app.world().get::<Connected>(self.entity).is_some()
+    }
+}
+
+/// A server app and its connected client apps, advanced in lockstep.
+pub struct TestHarness {
+    pub server: App,
+    /// The `Server` entity in the server app.
+    pub server_entity: Entity,
+    pub clients: Vec<TestClient>,
+    tick_duration: Duration,
+    now: Instant,
+}
+
+impl Default for TestHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TestHarness {
+    /// Builds a harness with one connected client and only the protocol registered.
+    pub fn new() -> Self {
+        Self::with_setup(|_| {}, |_| {})
+    }
+
+    /// Builds a harness with one connected client, letting callers add plugins to
+    /// each app before the link is started.
+    pub fn with_setup(
+        server_setup: impl FnOnce(&mut App),
+        client_setup: impl FnOnce(&mut App),
+    ) -> Self {
+        let mut harness = Self::server_only(server_setup);
+        harness.add_client(client_setup);
+        harness
+    }
+
+    /// Builds and starts a server with no clients; call [`add_client`](Self::add_client)
+    /// to connect them.
+    pub fn server_only(server_setup: impl FnOnce(&mut App)) -> Self {
+        Self::start_server(|_| {}, server_setup)
+    }
+
+    /// [`server_only`](Self::server_only) for a server whose `ProtocolPlugin` reads
+    /// `config`, such as an [`InputRebroadcastConfig`](crate::InputRebroadcastConfig),
+    /// instead of the default.
+    pub fn server_only_with_config(
+        config: impl Resource,
+        server_setup: impl FnOnce(&mut App),
+    ) -> Self {
+        Self::start_server(
+            |app| {
+                app.insert_resource(config);
+            },
+            server_setup,
+        )
+    }
+
+    fn start_server(
+        before_protocol: impl FnOnce(&mut App),
+        server_setup: impl FnOnce(&mut App),
+    ) -> Self {
+        let tick_duration = Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ);
+        let now = Instant::now();
+
+        let mut server = App::new();
+        server.add_plugins(MinimalPlugins);
+        server.add_plugins(ServerPlugins { tick_duration });
+        before_protocol(&mut server);
+        server.add_plugins(crate::ProtocolPlugin);
+        server.insert_resource(TimeUpdateStrategy::ManualInstant(now));
+        server_setup(&mut server);
+        server.finish();
+        server.cleanup();
+
+        let server_entity = server
+            .world_mut()
+            .spawn((Name::new("Test Server"), Server::default(), RawServer))
+            .id();
+        server.world_mut().trigger(Start {
+            entity: server_entity,
+        });
+
+        Self {
+            server,
+            server_entity,
+            clients: Vec::new(),
+            tick_duration,
+            now,
+        }
+    }
+
+    /// Connects a new client, stepping until it reports `Connected`.
+    /// Returns its index in `clients`. Panics if the link never comes up.
+    pub fn add_client(&mut self, client_setup: impl FnOnce(&mut App)) -> usize {
+        self.connect_client(|_| {}, ReplicationSender::default(), client_setup)
+    }
+
+    /// [`add_client`](Self::add_client) for a client whose `ProtocolPlugin` reads
+    /// `config`, such as a [`ComponentSyncConfig`](crate::ComponentSyncConfig),
+    /// instead of the default.
+    pub fn add_client_with_config(
+        &mut self,
+        config: impl Resource,
+        client_setup: impl FnOnce(&mut App),
+    ) -> usize {
+        self.connect_client(
+            |app| {
+                app.insert_resource(config);
+            },
+            ReplicationSender::default(),
+            client_setup,
+        )
+    }
+
+    /// [`add_client`](Self::add_client) whose server-side `ClientOf` replicates
+    /// through `sender`, e.g. one with a non-default send interval.
+    pub fn add_client_with_sender(
+        &mut self,
+        sender: ReplicationSender,
+        client_setup: impl FnOnce(&mut App),
+    ) -> usize {
+        self.connect_client(|_| {}, sender, client_setup)
+    }
+
+    fn connect_client(
+        &mut self,
+        before_protocol: impl FnOnce(&mut App),
+        server_sender: ReplicationSender,
+        client_setup: impl FnOnce(&mut App),
+    ) -> usize {
+        let (client_io, server_io) = CrossbeamIo::new_pair();
+        let index = self.clients.len();
+
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(ClientPlugins {
+            tick_duration: self.tick_duration,
+        });
+        before_protocol(&mut app);
+        app.add_plugins(crate::ProtocolPlugin);
+        app.insert_resource(TimeUpdateStrategy::ManualInstant(self.now));
+        client_setup(&mut app);
+        app.finish();
+        app.cleanup();
+
+        let client_of = self
+            .server
+            .world_mut()
+            .spawn((
+                Name::new(format!("Test ClientOf {index}")),
+                LinkOf {
+                    server: self.server_entity,
+                },
+                Link::new(None),
+                server_sender,
+                ReplicationReceiver::default(),
+                server_io,
+            ))
+            .id();
+        let entity = app
+            .world_mut()
+            .spawn((
+                Name::new(format!("Test Client {index}")),
+                Client::default(),
+                Link::new(None),
+                ReplicationSender::default(),
+                ReplicationReceiver::default(),
+                PredictionManager::default(),
+                RawClient,
+                client_io,
+            ))
+            .id();
+        app.world_mut().trigger(Connect { entity });
+
+        self.clients.push(TestClient {
+            app,
+            entity,
+            client_of,
+        });
+        for _ in 0..MAX_CONNECT_TICKS {
+            self.tick();
+            if self.clients[index].is_connected() {
+                return index;
+            }
+        }
+        panic!("TestHarness client {index} did not connect within {MAX_CONNECT_TICKS} ticks");
+    }
+
+    /// The first client's app.
+    pub fn client(&mut self) -> &mut App {
+        &mut self
+            .clients
+            .first_mut()
+            .expect("TestHarness has no clients")
+            .app
+    }
+
+    /// Advances the shared clock by one fixed timestep and updates the server, then each client.
+    pub fn tick(&mut self) {
+        self.now += self.tick_duration;
+        self.server
+            .insert_resource(TimeUpdateStrategy::ManualInstant(self.now));
+        self.server.update();
+        for client in &mut self.clients {
+            client
+                .app
+                .insert_resource(TimeUpdateStrategy::ManualInstant(self.now));
+            client.app.update();
+        }
+    }
+
+    /// Calls [`tick`](Self::tick) `n` times.
+    pub fn tick_n(&mut self, n: usize) {
+        for _ in 0..n {
+            self.tick();
+        }
+    }
+
+    /// Ticks until `predicate` holds on the world of client `index`, up to `max_ticks`.
+    /// Returns whether the predicate was satisfied.
+    pub fn tick_until_on(
+        &mut self,
+        index: usize,
+        max_ticks: usize,
+        mut predicate: impl FnMut(&mut World) -> bool,
+    ) -> bool {
+        for _ in 0..max_ticks {
+            self.tick();
+            if predicate(self.clients[index].app.world_mut()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// [`tick_until_on`](Self::tick_until_on) for the first client.
+    pub fn tick_until(
+        &mut self,
+        max_ticks: usize,
+        predicate: impl FnMut(&mut World) -> bool,
+    ) -> bool {
+        self.tick_until_on(0, max_ticks, predicate)
+    }
+}
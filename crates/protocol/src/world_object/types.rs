@@ -185,6 +185,9 @@ impl From<crate::reflect_loader::ReflectLoadError> for WorldObjectLoadError {
         match e {
             crate::reflect_loader::ReflectLoadError::Io(io) => Self::Io(io),
             crate::reflect_loader::ReflectLoadError::Ron(ron) => Self::Ron(ron),
+            e @ crate::reflect_loader::ReflectLoadError::UnsupportedVersion(_) => {
+                ron::error::Error::Message(e.to_string()).into()
+            }
         }
     }
 }
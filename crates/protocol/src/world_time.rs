@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use lightyear::prelude::{NetworkTarget, Replicate, Replicated};
+use serde::{Deserialize, Serialize};
+
+use crate::FIXED_TIMESTEP_HZ;
+
+/// Cosmetic time of day, in server ticks since the world started. The
+/// resource is what systems read; the server also mirrors it onto a
+/// replicated [`WorldClock`] entity so clients can follow along. Nothing in
+/// the simulation reads it, so clients trailing the server by a few ticks
+/// never affects determinism.
+#[derive(
+    Resource, Component, Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize,
+)]
+pub struct WorldTime {
+    pub ticks: u64,
+}
+
+/// How long a day-night cycle lasts.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WorldTimeConfig {
+    pub day_length_ticks: u64,
+}
+
+impl Default for WorldTimeConfig {
+    fn default() -> Self {
+        Self {
+            // Ten minutes.
+            day_length_ticks: FIXED_TIMESTEP_HZ as u64 * 600,
+        }
+    }
+}
+
+impl WorldTimeConfig {
+    /// How far through the current day `time` is, in `[0, 1)`: 0 is sunrise,
+    /// 0.25 noon, 0.5 sunset and 0.75 midnight.
+    pub fn day_fraction(&self, time: WorldTime) -> f32 {
+        debug_assert!(self.day_length_ticks > 0, "day length must be positive");
+        (time.ticks % self.day_length_ticks) as f32 / self.day_length_ticks as f32
+    }
+}
+
+/// Marks the server's singleton entity carrying the replicated [`WorldTime`].
+#[derive(Component, Debug)]
+pub struct WorldClock;
+
+/// Server: spawns the replicated [`WorldClock`] entity.
+pub fn spawn_world_clock(mut commands: Commands, time: Res<WorldTime>) {
+    commands.spawn((
+        WorldClock,
+        *time,
+        Replicate::to_clients(NetworkTarget::All),
+        Name::new("WorldClock"),
+    ));
+}
+
+/// Server: advances [`WorldTime`] one tick and mirrors it onto the
+/// [`WorldClock`] entity for replication.
+pub fn advance_world_time(
+    mut time: ResMut<WorldTime>,
+    mut clock_query: Query<&mut WorldTime, With<WorldClock>>,
+) {
+    time.ticks += 1;
+    for mut clock in &mut clock_query {
+        *clock = *time;
+    }
+}
+
+/// Client: copies the replicated clock's [`WorldTime`] into the resource.
+pub fn sync_world_time(
+    mut time: ResMut<WorldTime>,
+    clock_query: Query<&WorldTime, (With<Replicated>, Changed<WorldTime>)>,
+) {
+    let Ok(clock) = clock_query.single() else {
+        trace!("sync_world_time: no world clock update this frame");
+        return;
+    };
+    *time = *clock;
+}
@@ -1,14 +1,19 @@
 use avian3d::prelude::CollidingEntities;
 use bevy::ecs::reflect::ReflectComponent;
+use bevy::ecs::system::RunSystemOnce;
 use bevy::prelude::*;
 use bevy::reflect::PartialReflect;
 use leafwing_input_manager::prelude::ActionState;
-use lightyear::prelude::{ComponentRegistry, LocalTimeline, PeerId, Server, Tick};
+use lightyear::prelude::{
+    ComponentRegistry, LocalTimeline, PeerId, PredictionDisable, Server, Tick,
+};
 use lightyear_replication::prespawn::PreSpawnedReceiver;
 use protocol::ability::{
-    AbilityAsset, AbilityPhases, ActiveBuff, ActiveBuffs, ActiveShield, HitTargets, HitboxOf,
-    InputEffect, MeleeHitbox, OnEndEffects, OnHitEffectDefs, OnHitEffects, OnInputEffects,
-    OnTickEffects, TickEffect, WhileActiveEffects,
+    AbilityAsset, AbilityCharges, AbilityCost, AbilityInterpolation, AbilityPhases, ActiveBuff,
+    ActiveBuffs, ActiveDots, ActiveShield, Channeled, ClashingProjectile, HitTargets, HitboxOf,
+    InputEffect, MaxCharges, MaxRange, MeleeHitbox, OnEndEffects, OnHitEffectDefs, OnHitEffects,
+    OnInputEffects, OnTickEffects, ProjectileLimitConfig, ResetCooldownOnKill, StickyProjectile,
+    StuckTo, TargetMask, TickEffect, WhileActiveEffects, DEFAULT_PROJECTILE_RADIUS,
 };
 use protocol::*;
 use std::collections::HashMap;
@@ -62,6 +67,7 @@ fn spawn_test_active_ability(app: &mut App, active: ActiveAbility) -> Entity {
     let phase = active.phase.clone();
     let caster = active.caster;
     let original_caster = active.original_caster;
+    let ability_slot = active.ability_slot;
     let depth = active.depth;
     let entity = app.world_mut().spawn(active).id();
     apply_test_archetype(app, entity, &def_id);
@@ -73,9 +79,11 @@ fn spawn_test_active_ability(app: &mut App, active: ActiveAbility) -> Entity {
             if !defs.0.is_empty() {
                 let effects = defs.0.clone();
                 app.world_mut().entity_mut(entity).insert(OnHitEffects {
+                    ability_id: def_id.clone(),
                     effects,
                     caster,
                     original_caster,
+                    ability_slot,
                     depth,
                 });
             }
@@ -133,6 +141,9 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     effect: AbilityEffect::Melee {
                         id: None,
                         target: EffectTarget::Caster,
+                        affects: TargetMask::default(),
+                        arc_degrees: None,
+                        range: None,
                     },
                 }],
                 vec![],
@@ -140,6 +151,7 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     AbilityEffect::Damage {
                         amount: 5.0,
                         target: EffectTarget::Victim,
+                        damage_type: DamageType::Physical,
                     },
                     AbilityEffect::ApplyForce {
                         force: Vec3::new(0.0, 0.9, 2.85),
@@ -171,6 +183,9 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     effect: AbilityEffect::Melee {
                         id: None,
                         target: EffectTarget::Caster,
+                        affects: TargetMask::default(),
+                        arc_degrees: None,
+                        range: None,
                     },
                 }],
                 vec![],
@@ -178,6 +193,7 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     AbilityEffect::Damage {
                         amount: 6.0,
                         target: EffectTarget::Victim,
+                        damage_type: DamageType::Physical,
                     },
                     AbilityEffect::ApplyForce {
                         force: Vec3::new(0.0, 1.05, 3.32),
@@ -209,6 +225,9 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     effect: AbilityEffect::Melee {
                         id: None,
                         target: EffectTarget::Caster,
+                        affects: TargetMask::default(),
+                        arc_degrees: None,
+                        range: None,
                     },
                 }],
                 vec![],
@@ -216,6 +235,7 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     AbilityEffect::Damage {
                         amount: 10.0,
                         target: EffectTarget::Victim,
+                        damage_type: DamageType::Physical,
                     },
                     AbilityEffect::ApplyForce {
                         force: Vec3::new(0.0, 2.4, 7.65),
@@ -261,6 +281,11 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                         id: None,
                         speed: 20.0,
                         lifetime_ticks: 192,
+                        radius: DEFAULT_PROJECTILE_RADIUS,
+                        stick: false,
+                        fuse_ticks: 0,
+                        clash: false,
+                        homing: None,
                     },
                 }],
                 vec![],
@@ -268,6 +293,7 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
                     AbilityEffect::Damage {
                         amount: 25.0,
                         target: EffectTarget::Victim,
+                        damage_type: DamageType::Physical,
                     },
                     AbilityEffect::ApplyForce {
                         force: Vec3::new(0.0, 2.4, 7.65),
@@ -283,12 +309,18 @@ fn test_defs() -> Vec<(String, AbilityAsset)> {
 }
 
 fn test_app() -> App {
+    test_app_with(|_| {})
+}
+
+/// `test_app`, letting callers add plugins before the app first updates.
+fn test_app_with(setup: impl FnOnce(&mut App)) -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
     app.add_plugins(AssetPlugin::default());
     app.add_plugins(bevy::state::app::StatesPlugin);
     app.add_plugins(protocol::AppStatePlugin);
     app.add_plugins(AbilityPlugin);
+    setup(&mut app);
     app.init_resource::<ComponentRegistry>();
     app.world_mut().register_component::<Server>();
     app.world_mut().register_component::<PreSpawnedReceiver>();
@@ -374,6 +406,37 @@ fn find_active_ability_for_def(world: &mut World, def_id: &str) -> Option<(Entit
         .map(|(e, a)| (e, a.clone()))
 }
 
+/// All messages of type `M` still buffered in the world.
+fn read_messages<M: bevy::ecs::message::Message + Clone>(app: &App) -> Vec<M> {
+    let messages = app.world().resource::<bevy::ecs::message::Messages<M>>();
+    messages.get_cursor().read(messages).cloned().collect()
+}
+
+#[test]
+fn activation_emits_cast_event() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let char_entity = spawn_character(app.world_mut());
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(char_entity)
+        .unwrap()
+        .press(&PlayerActions::Ability2);
+
+    app.update();
+
+    let tick = app.world().resource::<LocalTimeline>().tick();
+    assert_eq!(
+        read_messages::<AbilityCastEvent>(&app),
+        vec![AbilityCastEvent {
+            caster: char_entity,
+            ability_id: AbilityId("dash".into()),
+            slot: 1,
+            tick,
+        }]
+    );
+}
+
 #[test]
 fn activation_on_press() {
     let mut app = test_app();
@@ -420,6 +483,175 @@ fn activation_blocked_by_cooldown() {
     );
 }
 
+#[test]
+fn stunned_caster_cannot_activate_punch() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let char_entity = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(char_entity).insert(Stunned {
+        expires_tick: Tick(120),
+    });
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(char_entity)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+
+    app.update();
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "punch").is_none(),
+        "Stunned caster should not activate"
+    );
+    assert_eq!(
+        app.world()
+            .get::<AbilityCooldowns>(char_entity)
+            .unwrap()
+            .last_used[0],
+        None,
+        "A stunned cast consumes no cooldown"
+    );
+}
+
+#[test]
+fn swap_loadout_changes_slot_ability_and_respects_cooldown() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let char_entity = spawn_character(app.world_mut());
+    let dash_slots = AbilitySlots([Some(AbilityId("dash".into())), None, None, None, None]);
+    app.world_mut()
+        .entity_mut(char_entity)
+        .insert(AbilityLoadouts::new(vec![
+            punch_slots(),
+            dash_slots.clone(),
+        ]));
+
+    let press = |app: &mut App, action: PlayerActions| {
+        let mut action_state = app
+            .world_mut()
+            .get_mut::<ActionState<PlayerActions>>(char_entity)
+            .unwrap();
+        action_state.release(&action);
+        action_state.press(&action);
+    };
+
+    press(&mut app, PlayerActions::SwapLoadout);
+    app.update();
+    assert_eq!(
+        app.world().get::<AbilitySlots>(char_entity),
+        Some(&dash_slots)
+    );
+
+    press(&mut app, PlayerActions::Ability1);
+    app.update();
+    assert!(find_active_ability_for_def(app.world_mut(), "dash").is_some());
+    assert!(find_active_ability_for_def(app.world_mut(), "punch").is_none());
+
+    // Still within the swap cooldown: the loadout stays put.
+    press(&mut app, PlayerActions::SwapLoadout);
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<AbilityLoadouts>(char_entity)
+            .unwrap()
+            .active,
+        1
+    );
+
+    let cooldown = LoadoutSwapConfig::default().cooldown_ticks;
+    advance_timeline(app.world_mut(), cooldown as i16 + 1);
+    press(&mut app, PlayerActions::SwapLoadout);
+    app.update();
+    assert_eq!(
+        app.world()
+            .get::<AbilityLoadouts>(char_entity)
+            .unwrap()
+            .active,
+        0
+    );
+    assert_eq!(
+        app.world().get::<AbilitySlots>(char_entity),
+        Some(&punch_slots())
+    );
+}
+
+#[test]
+fn practice_mode_recasts_despite_cooldown() {
+    let mut app = test_app();
+    app.insert_resource(PracticeMode(true));
+    insert_timeline(app.world_mut(), 100);
+    let char_entity = spawn_character(app.world_mut());
+
+    app.world_mut()
+        .get_mut::<AbilityCooldowns>(char_entity)
+        .unwrap()
+        .last_used[0] = Some(Tick(90));
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(char_entity)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+
+    app.update();
+
+    assert!(
+        find_active_ability(app.world_mut()).is_some(),
+        "Practice mode should ignore the cooldown"
+    );
+}
+
+#[test]
+fn denied_ability_does_not_activate() {
+    let mut app = test_app();
+    app.insert_resource(AbilityAvailability::Deny(
+        [AbilityId("punch".into())].into_iter().collect(),
+    ));
+    insert_timeline(app.world_mut(), 100);
+    let char_entity = spawn_character(app.world_mut());
+
+    {
+        let mut action_state = app
+            .world_mut()
+            .get_mut::<ActionState<PlayerActions>>(char_entity)
+            .unwrap();
+        action_state.press(&PlayerActions::Ability1);
+        action_state.press(&PlayerActions::Ability2);
+    }
+
+    app.update();
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "punch").is_none(),
+        "Denied ability should not activate"
+    );
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "dash").is_some(),
+        "Other abilities should still activate"
+    );
+    assert_eq!(
+        app.world()
+            .get::<AbilityCooldowns>(char_entity)
+            .unwrap()
+            .last_used[0],
+        None,
+        "A refused cast consumes no cooldown"
+    );
+}
+
+#[test]
+fn loadout_cannot_assign_unavailable_ability() {
+    let denied = AbilityAvailability::Deny([AbilityId("punch".into())].into_iter().collect());
+    let mut slots = AbilitySlots::default();
+    assert!(!slots.try_assign(0, AbilityId("punch".into()), &denied));
+    assert!(slots.try_assign(1, AbilityId("dash".into()), &denied));
+    assert_eq!(slots.0[0], None);
+    assert_eq!(slots.0[1], Some(AbilityId("dash".into())));
+
+    let allowed = AbilityAvailability::Allow([AbilityId("dash".into())].into_iter().collect());
+    assert!(!slots.try_assign(2, AbilityId("fireball".into()), &allowed));
+    assert_eq!(slots.0[2], None);
+}
+
 #[test]
 fn activation_empty_slot() {
     let mut app = test_app();
@@ -474,6 +706,7 @@ fn phase_startup_to_active() {
             phase_start_tick: Tick(100),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -502,6 +735,7 @@ fn phase_active_to_recovery() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -530,6 +764,7 @@ fn phase_recovery_completes() {
             phase_start_tick: Tick(300),
             ability_slot: 1,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -555,6 +790,7 @@ fn bullet_lifetime_despawn() {
             direction: Vec3::NEG_Z,
             speed: 20.0,
             lifetime_ticks: 192,
+            radius: DEFAULT_PROJECTILE_RADIUS,
             ability_id: AbilityId("fireball".into()),
             shooter: Entity::PLACEHOLDER,
         })
@@ -568,6 +804,14 @@ fn bullet_lifetime_despawn() {
         app.world().get_entity(bullet_entity).is_err(),
         "Bullet should be despawned after lifetime expires"
     );
+    assert_eq!(
+        read_messages::<AbilityMissEvent>(&app),
+        vec![AbilityMissEvent {
+            caster: Entity::PLACEHOLDER,
+            ability_id: AbilityId("fireball".into()),
+        }],
+        "an expired bullet missed"
+    );
 }
 
 #[test]
@@ -583,6 +827,7 @@ fn bullet_lifetime_alive() {
             direction: Vec3::NEG_Z,
             speed: 20.0,
             lifetime_ticks: 192,
+            radius: DEFAULT_PROJECTILE_RADIUS,
             ability_id: AbilityId("fireball".into()),
             shooter: Entity::PLACEHOLDER,
         })
@@ -615,6 +860,7 @@ fn on_hit_effects_dispatched_on_first_active_tick() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -651,6 +897,7 @@ fn on_hit_effects_removed_on_recovery() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -691,6 +938,7 @@ fn melee_hitbox_entity_spawned() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -724,6 +972,7 @@ fn hitbox_entity_has_correct_on_hit_effects() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -785,6 +1034,7 @@ fn on_end_effects_dispatched_on_active_to_recovery() {
             phase_start_tick: Tick(200),
             ability_slot: 1,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -857,6 +1107,7 @@ fn sub_ability_spawned_on_cast() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -915,6 +1166,7 @@ fn sub_ability_depth_limited() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 4,
+            landed_hit: false,
         },
     );
 
@@ -947,6 +1199,7 @@ fn sub_ability_phase_management() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 1,
+            landed_hit: false,
         },
     );
 
@@ -991,6 +1244,7 @@ fn on_input_effects_dispatched_during_active() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -1029,6 +1283,7 @@ fn on_input_effects_removed_on_despawn() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
@@ -1101,192 +1356,480 @@ fn spawn_target(world: &mut World, pos: Vec3) -> Entity {
 }
 
 #[test]
-fn aoe_hitbox_damages_target() {
-    let mut app = test_app_with_hit_detection();
+fn impulse_changes_heavier_character_velocity_less() {
+    let mut app = test_app();
     insert_timeline(app.world_mut(), 200);
-    let caster = spawn_character(app.world_mut());
-    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    let light = spawn_target(app.world_mut(), Vec3::ZERO);
+    let heavy = spawn_target(app.world_mut(), Vec3::new(10.0, 0.0, 0.0));
+    *app.world_mut()
+        .get_mut::<avian3d::prelude::ComputedMass>(heavy)
+        .unwrap() = avian3d::prelude::ComputedMass::new(4.0);
+
+    let impulse = Vec3::new(0.0, 0.0, 8.0);
+    for entity in [light, heavy] {
+        app.world_mut()
+            .write_message(protocol::physics::ApplyImpulse { entity, impulse });
+    }
+    app.update();
 
-    insert_test_ability(
-        &mut app,
-        "aoe_test",
-        build_ability_asset(
-            AbilityPhases {
-                startup: 0,
-                active: 1,
-                recovery: 4,
-                cooldown: 0,
-            },
-            vec![TickEffect {
-                tick: 0,
-                effect: AbilityEffect::AreaOfEffect {
-                    id: None,
-                    target: EffectTarget::Caster,
-                    radius: 5.0,
-                    duration_ticks: None,
-                },
-            }],
-            vec![],
-            vec![AbilityEffect::Damage {
-                amount: 25.0,
-                target: EffectTarget::Victim,
-            }],
-            vec![],
-            vec![],
-        ),
+    let velocity = |entity| {
+        app.world()
+            .get::<avian3d::prelude::LinearVelocity>(entity)
+            .unwrap()
+            .0
+    };
+    assert_eq!(velocity(light), Vec3::new(0.0, 0.0, 8.0));
+    assert_eq!(
+        velocity(heavy),
+        Vec3::new(0.0, 0.0, 2.0),
+        "4x the mass should take a quarter of the velocity change"
     );
+}
 
-    spawn_test_active_ability(
-        &mut app,
-        ActiveAbility {
-            def_id: AbilityId("aoe_test".into()),
-            caster,
-            original_caster: caster,
-            target: caster,
-            phase: AbilityPhase::Active,
-            phase_start_tick: Tick(200),
-            ability_slot: 0,
-            depth: 0,
+/// Caster at the origin with slot 0 bound to a `MaxRange { range: 3.0 }` ability,
+/// plus a damageable target at `target_pos`. Presses slot 0 and runs one tick.
+fn cast_ranged_ability_at(target_pos: Vec3) -> (App, Entity) {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 0,
+            active: 4,
+            recovery: 2,
+            cooldown: 30,
         },
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
     );
+    asset
+        .components
+        .push(Box::new(MaxRange { range: 3.0 }).into_partial_reflect());
+    insert_test_ability(&mut app, "ranged_punch", asset);
 
-    // Update 1: spawn AoE hitbox entity
-    app.update();
-
-    // Simulate physics: populate CollidingEntities on the hitbox with the target
-    let hitbox_entity = app
-        .world_mut()
-        .query_filtered::<Entity, With<HitboxOf>>()
-        .iter(app.world())
-        .next()
-        .expect("AoE hitbox entity should exist");
+    let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert(AbilitySlots([
+        Some(AbilityId("ranged_punch".into())),
+        None,
+        None,
+        None,
+        None,
+    ]));
+    spawn_target(app.world_mut(), target_pos);
 
     app.world_mut()
-        .get_mut::<CollidingEntities>(hitbox_entity)
+        .get_mut::<ActionState<PlayerActions>>(caster)
         .unwrap()
-        .insert(target);
-
-    // Advance timeline: ability will transition Active -> Recovery
-    advance_timeline(app.world_mut(), 1);
-
-    // Update 2: hit detection should process the collision BEFORE cleanup despawns the hitbox
+        .press(&PlayerActions::Ability1);
     app.update();
+    (app, caster)
+}
 
-    let health = app.world().get::<Health>(target).unwrap();
-    assert_eq!(
-        health.current, 75.0,
-        "Target should take 25 damage from AoE hit (got {} HP remaining)",
-        health.current
+#[test]
+fn max_range_allows_cast_with_target_in_range() {
+    let (mut app, caster) = cast_ranged_ability_at(Vec3::new(2.0, 0.0, 0.0));
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "ranged_punch").is_some(),
+        "Target within range should allow the cast"
     );
+    let cooldowns = app.world().get::<AbilityCooldowns>(caster).unwrap();
+    assert!(cooldowns.last_used[0].is_some());
 }
 
 #[test]
-fn teleport_moves_caster() {
-    let mut app = test_app();
-    insert_timeline(app.world_mut(), 200);
-    let char_entity = spawn_character(app.world_mut());
+fn max_range_refuses_cast_and_keeps_cooldown_when_target_too_far() {
+    let (mut app, caster) = cast_ranged_ability_at(Vec3::new(10.0, 0.0, 0.0));
 
-    insert_test_ability(
-        &mut app,
-        "teleport_test",
-        build_ability_asset(
-            AbilityPhases {
-                startup: 0,
-                active: 4,
-                recovery: 2,
-                cooldown: 0,
-            },
-            vec![TickEffect {
-                tick: 0,
-                effect: AbilityEffect::Teleport { distance: 10.0 },
-            }],
-            vec![],
-            vec![],
-            vec![],
-            vec![],
-        ),
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "ranged_punch").is_none(),
+        "Target out of range should refuse the cast"
+    );
+    let cooldowns = app.world().get::<AbilityCooldowns>(caster).unwrap();
+    assert!(
+        cooldowns.last_used[0].is_none(),
+        "Refused cast must not consume the cooldown"
     );
+}
 
-    spawn_test_active_ability(
-        &mut app,
-        ActiveAbility {
-            def_id: AbilityId("teleport_test".into()),
-            caster: char_entity,
-            original_caster: char_entity,
-            target: char_entity,
-            phase: AbilityPhase::Active,
-            phase_start_tick: Tick(200),
-            ability_slot: 0,
-            depth: 0,
+/// Caster with slot 0 bound to an ability costing 20 and the given `pool`.
+/// Presses slot 0 and runs one tick under `practice`.
+fn cast_costly_ability_with(pool: ResourcePool, practice: PracticeMode) -> (App, Entity) {
+    let mut app = test_app();
+    app.insert_resource(practice);
+    insert_timeline(app.world_mut(), 200);
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 0,
+            active: 4,
+            recovery: 2,
+            cooldown: 30,
         },
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
     );
+    asset
+        .components
+        .push(Box::new(AbilityCost { cost: 20.0 }).into_partial_reflect());
+    insert_test_ability(&mut app, "costly_punch", asset);
 
+    let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert((
+        AbilitySlots([
+            Some(AbilityId("costly_punch".into())),
+            None,
+            None,
+            None,
+            None,
+        ]),
+        pool,
+    ));
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
     app.update();
-
-    let pos = app
-        .world()
-        .get::<avian3d::prelude::Position>(char_entity)
-        .unwrap();
-    // Default Rotation faces NEG_Z, so teleport should move to ~(0, 0, -10)
-    assert!((pos.0.x).abs() < 0.01, "X should be ~0, got {}", pos.0.x);
-    assert!(
-        (pos.0.z - (-10.0)).abs() < 0.01,
-        "Z should be ~-10, got {}",
-        pos.0.z
-    );
+    (app, caster)
 }
 
 #[test]
-fn shield_absorbs_damage() {
-    let mut app = test_app_with_hit_detection();
+fn ability_cost_refuses_cast_without_enough_resource() {
+    let (mut app, caster) = cast_costly_ability_with(
+        ResourcePool {
+            current: 10.0,
+            max: 50.0,
+            regen_per_tick: 0.0,
+        },
+        PracticeMode(false),
+    );
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "costly_punch").is_none(),
+        "Not enough resource should refuse the cast"
+    );
+    let pool = app.world().get::<ResourcePool>(caster).unwrap();
+    assert_eq!(pool.current, 10.0, "Refused cast must not spend resource");
+    let cooldowns = app.world().get::<AbilityCooldowns>(caster).unwrap();
+    assert!(
+        cooldowns.last_used[0].is_none(),
+        "Refused cast must not consume the cooldown"
+    );
+}
+
+#[test]
+fn ability_cost_is_deducted_on_cast() {
+    let (mut app, caster) = cast_costly_ability_with(
+        ResourcePool {
+            current: 50.0,
+            max: 50.0,
+            regen_per_tick: 0.0,
+        },
+        PracticeMode(false),
+    );
+
+    assert!(find_active_ability_for_def(app.world_mut(), "costly_punch").is_some());
+    let pool = app.world().get::<ResourcePool>(caster).unwrap();
+    assert_eq!(pool.current, 30.0);
+}
+
+#[test]
+fn practice_mode_casts_costly_ability_without_spending() {
+    let (mut app, caster) = cast_costly_ability_with(
+        ResourcePool {
+            current: 10.0,
+            max: 50.0,
+            regen_per_tick: 0.0,
+        },
+        PracticeMode(true),
+    );
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "costly_punch").is_some(),
+        "practice mode has infinite resources"
+    );
+    let pool = app.world().get::<ResourcePool>(caster).unwrap();
+    assert_eq!(pool.current, 10.0, "practice casts spend nothing");
+}
+
+#[test]
+fn resource_pool_regenerates_up_to_max() {
+    let mut app = test_app();
     insert_timeline(app.world_mut(), 200);
+    let pooled = app
+        .world_mut()
+        .spawn(ResourcePool {
+            current: 10.0,
+            max: 12.5,
+            regen_per_tick: 1.0,
+        })
+        .id();
+
+    let current = |app: &App| app.world().get::<ResourcePool>(pooled).unwrap().current;
+    app.update();
+    assert_eq!(current(&app), 11.0);
+    app.update();
+    app.update();
+    assert_eq!(current(&app), 12.5, "regen should clamp to max");
+}
+
+/// Caster with slot 0 bound to a one-tick-per-phase ability holding
+/// `MaxCharges { charges: 2, refill_ticks: 60 }` and a 200-tick cooldown.
+fn spawn_charged_caster(app: &mut App) -> Entity {
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 1,
+            active: 1,
+            recovery: 1,
+            cooldown: 200,
+        },
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    asset.components.push(
+        Box::new(MaxCharges {
+            charges: 2,
+            refill_ticks: 60,
+        })
+        .into_partial_reflect(),
+    );
+    insert_test_ability(app, "charged_punch", asset);
+
     let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert(AbilitySlots([
+        Some(AbilityId("charged_punch".into())),
+        None,
+        None,
+        None,
+        None,
+    ]));
+    caster
+}
 
-    let target = app
+/// Presses slot 0 on the next tick and reports whether that cast started,
+/// then runs ticks until it finishes so the next cast isn't refused as a
+/// re-cast.
+fn cast_charged_punch(app: &mut App, caster: Entity) -> bool {
+    advance_timeline(app.world_mut(), 1);
+    let mut action_state = app
         .world_mut()
-        .spawn((
-            CharacterMarker,
-            Health::new(100.0),
-            ActiveShield { remaining: 50.0 },
-            avian3d::prelude::Position(Vec3::new(1.0, 0.0, 0.0)),
-            avian3d::prelude::Rotation::default(),
-            avian3d::prelude::LinearVelocity(Vec3::ZERO),
-            forces_components(),
-            protocol::map::MapInstanceId::Overworld,
-        ))
-        .id();
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap();
+    action_state.release(&PlayerActions::Ability1);
+    action_state.press(&PlayerActions::Ability1);
+    app.update();
+    let cast = find_active_ability_for_def(app.world_mut(), "charged_punch").is_some();
+    for _ in 0..5 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+    cast
+}
+
+#[test]
+fn charged_ability_casts_once_per_charge_despite_cooldown() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_charged_caster(&mut app);
+
+    assert!(cast_charged_punch(&mut app, caster), "first charge");
+    assert!(
+        cast_charged_punch(&mut app, caster),
+        "second charge casts inside the first cast's cooldown"
+    );
+    assert!(
+        !cast_charged_punch(&mut app, caster),
+        "no charges left should block the cast"
+    );
+    let charges = app.world().get::<AbilityCharges>(caster).unwrap();
+    assert_eq!(charges.remaining(0, 2), 0);
+}
+
+#[test]
+fn charged_ability_refills_one_charge_per_refill_interval() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_charged_caster(&mut app);
+    // Both charges are spent by tick 107; the first refill is due at tick
+    // 161, 60 ticks after the first cast.
+    assert!(cast_charged_punch(&mut app, caster));
+    assert!(cast_charged_punch(&mut app, caster));
+
+    advance_timeline(app.world_mut(), 40);
+    assert!(
+        !cast_charged_punch(&mut app, caster),
+        "tick 153 is before the first refill"
+    );
+    advance_timeline(app.world_mut(), 2);
+    assert!(
+        cast_charged_punch(&mut app, caster),
+        "tick 161 refills one charge"
+    );
+    assert!(
+        !cast_charged_punch(&mut app, caster),
+        "only one charge was refilled"
+    );
+}
+
+/// Caster holding slot 0, bound to a `Channeled` ability with a long Active
+/// phase, after enough ticks for the cast to reach Active.
+fn spawn_channeling_caster(app: &mut App) -> Entity {
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 1,
+            active: 50,
+            recovery: 0,
+            cooldown: 100,
+        },
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    asset
+        .components
+        .push(Box::new(Channeled).into_partial_reflect());
+    insert_test_ability(app, "beam", asset);
+
+    let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert((
+        AbilitySlots([Some(AbilityId("beam".into())), None, None, None, None]),
+        Health::new(100.0),
+    ));
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+    for _ in 0..5 {
+        app.update();
+        advance_timeline(app.world_mut(), 1);
+    }
+    caster
+}
+
+#[test]
+fn channeled_ability_stays_active_while_held_and_breaks_on_damage() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_channeling_caster(&mut app);
+
+    let (_, active) = find_active_ability_for_def(app.world_mut(), "beam")
+        .expect("holding the button keeps the channel going");
+    assert_eq!(active.phase, AbilityPhase::Active);
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("jab".into()),
+                vec![AbilityEffect::Damage {
+                    amount: 5.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+            );
+            applier.apply_effects_to(caster, &effects, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+    app.update();
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "beam").is_none(),
+        "taking a hit should break the channel"
+    );
+}
+
+#[test]
+fn channeled_ability_breaks_when_released() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_channeling_caster(&mut app);
+    assert!(find_active_ability_for_def(app.world_mut(), "beam").is_some());
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap()
+        .release(&PlayerActions::Ability1);
+    app.update();
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "beam").is_none(),
+        "releasing the button should end the channel"
+    );
+}
+
+#[test]
+fn channel_from_a_slot_without_an_action_ignores_release() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_channeling_caster(&mut app);
+    let (entity, _) = find_active_ability_for_def(app.world_mut(), "beam")
+        .expect("holding the button keeps the channel going");
+    app.world_mut()
+        .get_mut::<ActiveAbility>(entity)
+        .unwrap()
+        .ability_slot = u8::MAX;
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap()
+        .release(&PlayerActions::Ability1);
+    app.update();
+
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "beam").is_some(),
+        "a slot with no action has nothing to release"
+    );
+}
+
+#[test]
+fn all_in_radius_damage_hits_only_nearby_characters() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_target(app.world_mut(), Vec3::ZERO);
+    let near = spawn_target(app.world_mut(), Vec3::new(1.0, 0.0, 0.0));
+    let also_near = spawn_target(app.world_mut(), Vec3::new(0.0, 0.0, -2.5));
+    let far = spawn_target(app.world_mut(), Vec3::new(10.0, 0.0, 0.0));
 
     insert_test_ability(
         &mut app,
-        "shield_test",
+        "nova",
         build_ability_asset(
             AbilityPhases {
                 startup: 0,
-                active: 4,
-                recovery: 2,
+                active: 2,
+                recovery: 0,
                 cooldown: 0,
             },
             vec![TickEffect {
                 tick: 0,
-                effect: AbilityEffect::Melee {
-                    id: None,
-                    target: EffectTarget::Caster,
+                effect: AbilityEffect::Damage {
+                    amount: 15.0,
+                    target: EffectTarget::AllInRadius {
+                        radius: 3.0,
+                        include_caster: false,
+                    },
+                    damage_type: DamageType::Physical,
                 },
             }],
             vec![],
-            vec![AbilityEffect::Damage {
-                amount: 30.0,
-                target: EffectTarget::Victim,
-            }],
+            vec![],
             vec![],
             vec![],
         ),
     );
-
     spawn_test_active_ability(
         &mut app,
         ActiveAbility {
-            def_id: AbilityId("shield_test".into()),
+            def_id: AbilityId("nova".into()),
             caster,
             original_caster: caster,
             target: caster,
@@ -1294,81 +1837,134 @@ fn shield_absorbs_damage() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
-
-    // First update: spawn hitbox
     app.update();
 
-    let hitbox_entity = app
-        .world_mut()
-        .query_filtered::<Entity, With<HitboxOf>>()
-        .iter(app.world())
-        .next()
-        .expect("hitbox should exist");
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(near), 85.0);
+    assert_eq!(health(also_near), 85.0);
+    assert_eq!(health(far), 100.0, "targets outside the radius are spared");
+    assert_eq!(health(caster), 100.0, "the caster is excluded by default");
+}
+
+#[test]
+fn sticky_bullet_attaches_to_victim_and_detonates_after_fuse() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let victim = spawn_target(app.world_mut(), Vec3::new(0.0, 0.0, -2.0));
 
+    let spawn_entity = app
+        .world_mut()
+        .spawn(AbilityProjectileSpawn {
+            spawn_tick: Tick(200),
+            position: Vec3::ZERO,
+            direction: Vec3::NEG_Z,
+            speed: 20.0,
+            lifetime_ticks: 192,
+            radius: DEFAULT_PROJECTILE_RADIUS,
+            ability_id: AbilityId("sticky".into()),
+            shooter: caster,
+        })
+        .id();
+    let bullet = app
+        .world_mut()
+        .spawn((
+            AbilityBulletOf(spawn_entity),
+            StickyProjectile { fuse_ticks: 5 },
+            OnHitEffects {
+                ability_id: AbilityId("sticky".into()),
+                effects: vec![AbilityEffect::Damage {
+                    amount: 25.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+                caster,
+                original_caster: caster,
+                ability_slot: 0,
+                depth: 0,
+            },
+            avian3d::prelude::Position(Vec3::new(0.0, 0.0, -1.5)),
+            avian3d::prelude::LinearVelocity(Vec3::NEG_Z * 20.0),
+            CollidingEntities::default(),
+        ))
+        .id();
     app.world_mut()
-        .get_mut::<CollidingEntities>(hitbox_entity)
+        .get_mut::<CollidingEntities>(bullet)
         .unwrap()
-        .insert(target);
+        .insert(victim);
 
-    // Second update: process hits
     app.update();
 
-    let shield = app
+    let stuck = app
         .world()
-        .get::<ActiveShield>(target)
-        .expect("Shield should still exist");
-    assert_eq!(shield.remaining, 20.0);
+        .get::<StuckTo>(bullet)
+        .expect("Sticky bullet should attach on hit");
+    assert_eq!(stuck.victim, victim);
+    assert_eq!(app.world().get::<Health>(victim).unwrap().current, 100.0);
+
+    // Victim moves; the bullet rides along
+    app.world_mut()
+        .get_mut::<avian3d::prelude::Position>(victim)
+        .unwrap()
+        .0 = Vec3::new(4.0, 0.0, -2.0);
+    advance_timeline(app.world_mut(), 2);
+    app.update();
+    let bullet_pos = app
+        .world()
+        .get::<avian3d::prelude::Position>(bullet)
+        .unwrap()
+        .0;
+    assert!(bullet_pos.distance(Vec3::new(4.0, 0.0, -1.5)) < 1e-4);
+    assert_eq!(app.world().get::<Health>(victim).unwrap().current, 100.0);
+
+    advance_timeline(app.world_mut(), 3);
+    app.update();
 
-    let health = app.world().get::<Health>(target).unwrap();
     assert_eq!(
-        health.current, 100.0,
-        "Health should be untouched when shield absorbs all damage"
+        app.world().get::<Health>(victim).unwrap().current,
+        75.0,
+        "Sticky bullet should detonate once the fuse runs out"
     );
+    assert!(app.world().get_entity(bullet).is_err());
 }
 
 #[test]
-fn shield_overflow_damages_health() {
+fn hitbox_hit_emits_hit_event() {
     let mut app = test_app_with_hit_detection();
     insert_timeline(app.world_mut(), 200);
     let caster = spawn_character(app.world_mut());
-
-    let target = app
-        .world_mut()
-        .spawn((
-            CharacterMarker,
-            Health::new(100.0),
-            ActiveShield { remaining: 20.0 },
-            avian3d::prelude::Position(Vec3::new(1.0, 0.0, 0.0)),
-            avian3d::prelude::Rotation::default(),
-            avian3d::prelude::LinearVelocity(Vec3::ZERO),
-            forces_components(),
-            protocol::map::MapInstanceId::Overworld,
-        ))
-        .id();
+    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
 
     insert_test_ability(
         &mut app,
-        "shield_overflow_test",
+        "hit_event_test",
         build_ability_asset(
             AbilityPhases {
                 startup: 0,
-                active: 4,
-                recovery: 2,
+                active: 1,
+                recovery: 4,
                 cooldown: 0,
             },
             vec![TickEffect {
                 tick: 0,
-                effect: AbilityEffect::Melee {
+                effect: AbilityEffect::AreaOfEffect {
                     id: None,
                     target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
                 },
             }],
             vec![],
             vec![AbilityEffect::Damage {
-                amount: 50.0,
+                amount: 25.0,
                 target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
             }],
             vec![],
             vec![],
@@ -1378,7 +1974,7 @@ fn shield_overflow_damages_health() {
     spawn_test_active_ability(
         &mut app,
         ActiveAbility {
-            def_id: AbilityId("shield_overflow_test".into()),
+            def_id: AbilityId("hit_event_test".into()),
             caster,
             original_caster: caster,
             target: caster,
@@ -1386,178 +1982,184 @@ fn shield_overflow_damages_health() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
 
     app.update();
+    assert!(read_messages::<AbilityHitEvent>(&app).is_empty());
 
     let hitbox_entity = app
         .world_mut()
         .query_filtered::<Entity, With<HitboxOf>>()
         .iter(app.world())
         .next()
-        .expect("hitbox should exist");
-
+        .expect("AoE hitbox entity should exist");
     app.world_mut()
         .get_mut::<CollidingEntities>(hitbox_entity)
         .unwrap()
         .insert(target);
 
+    advance_timeline(app.world_mut(), 1);
     app.update();
 
-    // Shield depleted -> removed
-    assert!(
-        app.world().get::<ActiveShield>(target).is_none(),
-        "Shield should be removed after being fully depleted"
-    );
-
-    // Health takes overflow: 100 - (50 - 20) = 70
-    let health = app.world().get::<Health>(target).unwrap();
     assert_eq!(
-        health.current, 70.0,
-        "Health should take overflow damage past shield"
+        read_messages::<AbilityHitEvent>(&app),
+        vec![AbilityHitEvent {
+            caster,
+            victim: target,
+            ability_id: AbilityId("hit_event_test".into()),
+            damage: 25.0,
+        }]
     );
 }
 
-#[test]
-fn buff_inserted_on_target() {
-    let mut app = test_app();
+/// Runs an AoE ability through its two-tick Active window, optionally with a
+/// target overlapping the hitbox, and returns the miss events emitted as it
+/// enters Recovery.
+fn aoe_ability_misses(land_hit: bool) -> Vec<AbilityMissEvent> {
+    let mut app = test_app_with_hit_detection();
     insert_timeline(app.world_mut(), 200);
-    let char_entity = spawn_character(app.world_mut());
+    let caster = spawn_character(app.world_mut());
+    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
 
     insert_test_ability(
         &mut app,
-        "buff_test",
+        "miss_test",
         build_ability_asset(
             AbilityPhases {
                 startup: 0,
-                active: 4,
-                recovery: 2,
+                active: 2,
+                recovery: 4,
                 cooldown: 0,
             },
             vec![TickEffect {
                 tick: 0,
-                effect: AbilityEffect::Buff {
-                    stat: "speed".into(),
-                    multiplier: 1.5,
-                    duration_ticks: 100,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
                     target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
                 },
             }],
             vec![],
-            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 25.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
             vec![],
             vec![],
         ),
     );
-
     spawn_test_active_ability(
         &mut app,
         ActiveAbility {
-            def_id: AbilityId("buff_test".into()),
-            caster: char_entity,
-            original_caster: char_entity,
-            target: char_entity,
+            def_id: AbilityId("miss_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
             phase: AbilityPhase::Active,
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
-
     app.update();
 
-    let buffs = app
-        .world()
-        .get::<ActiveBuffs>(char_entity)
-        .expect("ActiveBuffs should be present on caster");
-    assert_eq!(buffs.0.len(), 1);
-    assert_eq!(buffs.0[0].stat, "speed");
-    assert_eq!(buffs.0[0].multiplier, 1.5);
-    // expires_tick = 200 + 100 = Tick(300)
-    assert_eq!(buffs.0[0].expires_tick, Tick(200) + 100i16);
-}
-
-#[test]
-fn buff_expires_after_duration() {
-    let mut app = test_app();
-    app.add_systems(Update, ability::expire_buffs);
-    insert_timeline(app.world_mut(), 200);
-    let char_entity = spawn_character(app.world_mut());
-
-    app.world_mut()
-        .entity_mut(char_entity)
-        .insert(ActiveBuffs(vec![ActiveBuff {
-            stat: "speed".into(),
-            multiplier: 1.5,
-            expires_tick: Tick(210),
-        }]));
-
-    // At tick 200: buff should still exist (expires at 210)
+    if land_hit {
+        let hitbox_entity = app
+            .world_mut()
+            .query_filtered::<Entity, With<HitboxOf>>()
+            .iter(app.world())
+            .next()
+            .expect("AoE hitbox entity should exist");
+        app.world_mut()
+            .get_mut::<CollidingEntities>(hitbox_entity)
+            .unwrap()
+            .insert(target);
+    }
+    advance_timeline(app.world_mut(), 1);
     app.update();
     assert!(
-        app.world().get::<ActiveBuffs>(char_entity).is_some(),
-        "Buff should still exist before expiry tick"
+        read_messages::<AbilityMissEvent>(&app).is_empty(),
+        "no miss while the Active window is open"
     );
 
-    // Advance to tick 211 (past expiry)
-    advance_timeline(app.world_mut(), 11);
+    advance_timeline(app.world_mut(), 1);
     app.update();
+    read_messages::<AbilityMissEvent>(&app)
+}
 
-    assert!(
-        app.world().get::<ActiveBuffs>(char_entity).is_none(),
-        "ActiveBuffs should be removed after expiry"
-    );
+#[test]
+fn ability_that_hits_nothing_emits_miss_event() {
+    let misses = aoe_ability_misses(false);
+    assert_eq!(misses.len(), 1);
+    assert_eq!(misses[0].ability_id, AbilityId("miss_test".into()));
 }
 
 #[test]
-fn buff_increases_damage() {
+fn ability_that_lands_a_hit_emits_no_miss_event() {
+    assert!(aoe_ability_misses(true).is_empty());
+}
+
+/// Overlaps a 25-damage AoE with `affects` on its caster, one of the caster's
+/// minions and an unrelated character, returning which of the three were hit.
+fn aoe_hits_with_mask(affects: TargetMask) -> [bool; 3] {
     let mut app = test_app_with_hit_detection();
     insert_timeline(app.world_mut(), 200);
     let caster = spawn_character(app.world_mut());
-
-    // Give caster a 2x damage buff
     app.world_mut()
         .entity_mut(caster)
-        .insert(ActiveBuffs(vec![ActiveBuff {
-            stat: "damage".into(),
-            multiplier: 2.0,
-            expires_tick: Tick(999),
-        }]));
-
-    let target = spawn_target(app.world_mut(), Vec3::new(1.0, 0.0, 0.0));
+        .insert((Health::new(100.0), forces_components()));
+    let ally = spawn_target(app.world_mut(), Vec3::new(2.0, 0.0, 0.0));
+    app.world_mut().entity_mut(ally).insert(Minion {
+        owner: caster,
+        kind: "wolf".into(),
+        expires_tick: Tick(1000),
+    });
+    let enemy = spawn_target(app.world_mut(), Vec3::new(-2.0, 0.0, 0.0));
 
     insert_test_ability(
         &mut app,
-        "buff_dmg_test",
+        "mask_test",
         build_ability_asset(
             AbilityPhases {
                 startup: 0,
-                active: 4,
-                recovery: 2,
+                active: 2,
+                recovery: 0,
                 cooldown: 0,
             },
             vec![TickEffect {
                 tick: 0,
-                effect: AbilityEffect::Melee {
+                effect: AbilityEffect::AreaOfEffect {
                     id: None,
                     target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects,
+                    max_targets: None,
+                    falloff: None,
                 },
             }],
             vec![],
             vec![AbilityEffect::Damage {
-                amount: 10.0,
+                amount: 25.0,
                 target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
             }],
             vec![],
             vec![],
         ),
     );
-
     spawn_test_active_ability(
         &mut app,
         ActiveAbility {
-            def_id: AbilityId("buff_dmg_test".into()),
+            def_id: AbilityId("mask_test".into()),
             caster,
             original_caster: caster,
             target: caster,
@@ -1565,10 +2167,9 @@ fn buff_increases_damage() {
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
-
-    // First update: spawn hitbox
     app.update();
 
     let hitbox_entity = app
@@ -1576,61 +2177,61 @@ fn buff_increases_damage() {
         .query_filtered::<Entity, With<HitboxOf>>()
         .iter(app.world())
         .next()
-        .expect("hitbox should exist");
-
-    app.world_mut()
-        .get_mut::<CollidingEntities>(hitbox_entity)
-        .unwrap()
-        .insert(target);
-
-    // Second update: process hits with buff active
+        .expect("AoE hitbox entity should exist");
+    let targets = [caster, ally, enemy];
+    for target in targets {
+        app.world_mut()
+            .get_mut::<CollidingEntities>(hitbox_entity)
+            .unwrap()
+            .insert(target);
+    }
+    advance_timeline(app.world_mut(), 1);
     app.update();
 
-    let health = app.world().get::<Health>(target).unwrap();
+    targets.map(|entity| app.world().get::<Health>(entity).unwrap().current < 100.0)
+}
+
+#[test]
+fn default_target_mask_hits_enemies_only() {
     assert_eq!(
-        health.current, 80.0,
-        "10 base damage * 2.0 buff = 20 effective damage; 100 - 20 = 80 HP"
+        aoe_hits_with_mask(TargetMask::default()),
+        [false, false, true]
     );
 }
 
-fn assert_vec3_approx(actual: Vec3, expected: Vec3, msg: &str) {
-    let diff = (actual - expected).length();
-    assert!(
-        diff < 1e-4,
-        "{msg}: expected {expected:?}, got {actual:?} (diff {diff})"
-    );
+#[test]
+fn every_target_mask_combination_hits_exactly_its_targets() {
+    for bits in 0..8u8 {
+        let mask = TargetMask {
+            caster: bits & 1 != 0,
+            allies: bits & 2 != 0,
+            enemies: bits & 4 != 0,
+        };
+        assert_eq!(
+            aoe_hits_with_mask(mask),
+            [mask.caster, mask.allies, mask.enemies],
+            "{mask:?} hit the wrong targets"
+        );
+    }
 }
 
-/// Apply an `ApplyForce` effect via an AoE hitbox and return the resulting velocity of the target.
-fn run_force_test(
-    force: Vec3,
-    frame: ForceFrame,
-    caster_rotation: Quat,
-    target_pos: Vec3,
-    target_rotation: Quat,
-) -> Vec3 {
+#[test]
+fn aoe_max_targets_hits_only_the_nearest_targets() {
     let mut app = test_app_with_hit_detection();
     insert_timeline(app.world_mut(), 200);
     let caster = spawn_character(app.world_mut());
-    let target = spawn_target(app.world_mut(), target_pos);
-
-    app.world_mut()
-        .get_mut::<avian3d::prelude::Rotation>(caster)
-        .unwrap()
-        .0 = caster_rotation;
-    app.world_mut()
-        .get_mut::<avian3d::prelude::Rotation>(target)
-        .unwrap()
-        .0 = target_rotation;
+    // Spawned first and overlapped first, so only distance can favor `near`.
+    let far = spawn_target(app.world_mut(), Vec3::new(4.0, 0.0, 0.0));
+    let near = spawn_target(app.world_mut(), Vec3::new(2.0, 0.0, 0.0));
 
     insert_test_ability(
         &mut app,
-        "force_test",
+        "max_targets_test",
         build_ability_asset(
             AbilityPhases {
                 startup: 0,
-                active: 1,
-                recovery: 4,
+                active: 3,
+                recovery: 0,
                 cooldown: 0,
             },
             vec![TickEffect {
@@ -1638,25 +2239,27 @@ fn run_force_test(
                 effect: AbilityEffect::AreaOfEffect {
                     id: None,
                     target: EffectTarget::Caster,
-                    radius: 10.0,
-                    duration_ticks: None,
+                    radius: 5.0,
+                    duration_ticks: Some(3),
+                    affects: TargetMask::default(),
+                    max_targets: Some(1),
+                    falloff: None,
                 },
             }],
             vec![],
-            vec![AbilityEffect::ApplyForce {
-                force,
-                frame,
+            vec![AbilityEffect::Damage {
+                amount: 25.0,
                 target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
             }],
             vec![],
             vec![],
         ),
     );
-
     spawn_test_active_ability(
         &mut app,
         ActiveAbility {
-            def_id: AbilityId("force_test".into()),
+            def_id: AbilityId("max_targets_test".into()),
             caster,
             original_caster: caster,
             target: caster,
@@ -1664,10 +2267,9 @@ fn run_force_test(
             phase_start_tick: Tick(200),
             ability_slot: 0,
             depth: 0,
+            landed_hit: false,
         },
     );
-
-    // Update 1: spawn AoE hitbox
     app.update();
 
     let hitbox_entity = app
@@ -1675,217 +2277,3415 @@ fn run_force_test(
         .query_filtered::<Entity, With<HitboxOf>>()
         .iter(app.world())
         .next()
-        .expect("AoE hitbox should exist");
-
-    app.world_mut()
-        .get_mut::<CollidingEntities>(hitbox_entity)
-        .unwrap()
-        .insert(target);
-
-    advance_timeline(app.world_mut(), 1);
-
-    // Update 2: process hit -> apply force
-    app.update();
+        .expect("AoE hitbox entity should exist");
+    for target in [far, near] {
+        app.world_mut()
+            .get_mut::<CollidingEntities>(hitbox_entity)
+            .unwrap()
+            .insert(target);
+    }
+    for _ in 0..2 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
 
-    app.world()
-        .get::<avian3d::prelude::LinearVelocity>(target)
-        .unwrap()
-        .0
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(near), 75.0, "nearest target should be hit once");
+    assert_eq!(health(far), 100.0, "cap should spare the farther target");
 }
 
 #[test]
-fn force_frame_world() {
-    // Caster and victim have arbitrary rotations -- World frame ignores both.
-    let vel = run_force_test(
-        Vec3::new(1.0, 0.0, 0.0),
-        ForceFrame::World,
-        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-        Vec3::new(3.0, 0.0, 0.0),
-        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-    );
-    assert_vec3_approx(
-        vel,
-        Vec3::new(1.0, 0.0, 0.0),
-        "World frame: force applied verbatim",
-    );
-}
+fn aoe_falloff_scales_damage_by_distance_among_capped_targets() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let ring: Vec<Entity> = [1.0, 2.0, 3.0, 4.0]
+        .into_iter()
+        .map(|x| spawn_target(app.world_mut(), Vec3::new(x, 0.0, 0.0)))
+        .collect();
 
-#[test]
-fn force_frame_caster() {
-    // Caster rotated 90 deg around Y: local +X maps to world -Z.
-    let vel = run_force_test(
-        Vec3::new(1.0, 0.0, 0.0),
-        ForceFrame::Caster,
-        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-        Vec3::new(3.0, 0.0, 0.0),
-        Quat::IDENTITY,
+    insert_test_ability(
+        &mut app,
+        "falloff_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 3,
+                recovery: 0,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: Some(3),
+                    affects: TargetMask::default(),
+                    max_targets: Some(3),
+                    falloff: Some(0.5),
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 20.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
     );
-    assert_vec3_approx(
-        vel,
-        Vec3::new(0.0, 0.0, -1.0),
-        "Caster frame: rotated by caster rotation",
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("falloff_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
     );
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    for &target in ring.iter().rev() {
+        app.world_mut()
+            .get_mut::<CollidingEntities>(hitbox_entity)
+            .unwrap()
+            .insert(target);
+    }
+    for _ in 0..2 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+
+    let damage_taken: Vec<f32> = ring
+        .iter()
+        .map(|&entity| 100.0 - app.world().get::<Health>(entity).unwrap().current)
+        .collect();
+    // Half the damage is lost at the 5.0 radius edge: 20 * (1 - 0.5 * d / 5).
+    let expected = [18.0, 16.0, 14.0, 0.0];
+    for (taken, expected) in damage_taken.iter().zip(expected) {
+        assert!(
+            (taken - expected).abs() < 1e-4,
+            "expected damage {expected:?}, got {damage_taken:?}"
+        );
+    }
 }
 
 #[test]
-fn force_frame_victim() {
-    // Victim rotated 90 deg around Y: local +X maps to world -Z.
-    let vel = run_force_test(
-        Vec3::new(1.0, 0.0, 0.0),
-        ForceFrame::Victim,
-        Quat::IDENTITY,
-        Vec3::new(3.0, 0.0, 0.0),
-        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
-    );
-    assert_vec3_approx(
+fn aoe_max_targets_breaks_distance_ties_by_position() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    // Spawned first, so it has the lower `Entity` id.
+    let east = spawn_target(app.world_mut(), Vec3::new(2.0, 0.0, 0.0));
+    let west = spawn_target(app.world_mut(), Vec3::new(-2.0, 0.0, 0.0));
+
+    insert_test_ability(
+        &mut app,
+        "tie_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 3,
+                recovery: 0,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: Some(3),
+                    affects: TargetMask::default(),
+                    max_targets: Some(1),
+                    falloff: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 20.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("tie_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    for target in [east, west] {
+        app.world_mut()
+            .get_mut::<CollidingEntities>(hitbox_entity)
+            .unwrap()
+            .insert(target);
+    }
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(west), 80.0, "the lower x wins the tie");
+    assert_eq!(health(east), 100.0);
+}
+
+#[test]
+fn target_mask_parses_self_field_from_ron() {
+    let mask: TargetMask = ron::from_str("(allies: true, self: true)").unwrap();
+    assert_eq!(
+        mask,
+        TargetMask {
+            enemies: false,
+            allies: true,
+            caster: true,
+        }
+    );
+}
+
+/// Lands one 25-damage hit from a `ResetCooldownOnKill` ability cast from slot 2
+/// on a target with `target_health`, returning the caster's slot 2 cooldown.
+fn execute_cooldown_after_hit(target_health: f32) -> Option<Tick> {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    app.world_mut()
+        .get_mut::<AbilityCooldowns>(caster)
+        .unwrap()
+        .last_used[2] = Some(Tick(200));
+    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    app.world_mut().get_mut::<Health>(target).unwrap().current = target_health;
+
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 0,
+            active: 2,
+            recovery: 4,
+            cooldown: 100,
+        },
+        vec![TickEffect {
+            tick: 0,
+            effect: AbilityEffect::AreaOfEffect {
+                id: None,
+                target: EffectTarget::Caster,
+                radius: 5.0,
+                duration_ticks: None,
+                affects: TargetMask::default(),
+                max_targets: None,
+                falloff: None,
+            },
+        }],
+        vec![],
+        vec![AbilityEffect::Damage {
+            amount: 25.0,
+            target: EffectTarget::Victim,
+            damage_type: DamageType::Physical,
+        }],
+        vec![],
+        vec![],
+    );
+    asset
+        .components
+        .push(Box::new(ResetCooldownOnKill).into_partial_reflect());
+    insert_test_ability(&mut app, "execute", asset);
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("execute".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 2,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+
+    app.world()
+        .get::<AbilityCooldowns>(caster)
+        .unwrap()
+        .last_used[2]
+}
+
+#[test]
+fn lethal_hit_resets_cooldown_on_kill_slot() {
+    assert_eq!(execute_cooldown_after_hit(20.0), None);
+}
+
+#[test]
+fn non_lethal_hit_keeps_cooldown_on_kill_slot() {
+    assert_eq!(execute_cooldown_after_hit(100.0), Some(Tick(200)));
+}
+
+#[test]
+fn aoe_hitbox_damages_target() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+
+    insert_test_ability(
+        &mut app,
+        "aoe_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 4,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 25.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("aoe_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    // Update 1: spawn AoE hitbox entity
+    app.update();
+
+    // Simulate physics: populate CollidingEntities on the hitbox with the target
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    // Advance timeline: ability will transition Active -> Recovery
+    advance_timeline(app.world_mut(), 1);
+
+    // Update 2: hit detection should process the collision BEFORE cleanup despawns the hitbox
+    app.update();
+
+    let health = app.world().get::<Health>(target).unwrap();
+    assert_eq!(
+        health.current, 75.0,
+        "Target should take 25 damage from AoE hit (got {} HP remaining)",
+        health.current
+    );
+}
+
+#[test]
+fn swap_positions_exchanges_caster_and_victim() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let target = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    app.world_mut()
+        .get_mut::<avian3d::prelude::LinearVelocity>(target)
+        .unwrap()
+        .0 = Vec3::new(0.0, 2.0, 0.0);
+
+    insert_test_ability(
+        &mut app,
+        "swap_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 4,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::SwapPositions {
+                reset_velocity: true,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("swap_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+
+    let position = |entity| {
+        app.world()
+            .get::<avian3d::prelude::Position>(entity)
+            .unwrap()
+            .0
+    };
+    assert_eq!(position(caster), Vec3::new(3.0, 0.0, 0.0));
+    assert_eq!(position(target), Vec3::ZERO);
+    assert_eq!(
+        app.world()
+            .get::<avian3d::prelude::LinearVelocity>(target)
+            .unwrap()
+            .0,
+        Vec3::ZERO,
+        "reset_velocity should zero the victim's velocity"
+    );
+}
+
+/// Casts an AoE whose hitbox strikes `first` with `on_hit`, so any `Chain`
+/// among them jumps on from `first`.
+fn chain_from(app: &mut App, caster: Entity, first: Entity, on_hit: Vec<AbilityEffect>) {
+    insert_test_ability(
+        app,
+        "chain_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 4,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
+                },
+            }],
+            vec![],
+            on_hit,
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        app,
+        ActiveAbility {
+            def_id: AbilityId("chain_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(first);
+
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+}
+
+fn chain_damage(amount: f32, jumps: u8) -> Vec<AbilityEffect> {
+    vec![
+        AbilityEffect::Damage {
+            amount,
+            target: EffectTarget::Victim,
+            damage_type: DamageType::Physical,
+        },
+        AbilityEffect::Chain {
+            jumps,
+            range: 3.0,
+            damage_falloff: 0.5,
+        },
+    ]
+}
+
+#[test]
+fn chain_jumps_between_clustered_targets_with_falloff() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let first = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    let second = spawn_target(app.world_mut(), Vec3::new(5.0, 0.0, 0.0));
+    // Out of range of `first`, so only reachable by a second jump via `second`.
+    let third = spawn_target(app.world_mut(), Vec3::new(7.5, 0.0, 0.0));
+
+    chain_from(&mut app, caster, first, chain_damage(20.0, 3));
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(first), 80.0, "First victim takes full damage");
+    assert_eq!(health(second), 90.0, "First jump deals half damage");
+    assert_eq!(health(third), 95.0, "Second jump halves damage again");
+}
+
+#[test]
+fn chain_starts_from_the_damage_the_victim_took() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let first = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    app.world_mut().get_mut::<Health>(first).unwrap().current = 40.0;
+    let second = spawn_target(app.world_mut(), Vec3::new(5.0, 0.0, 0.0));
+
+    // The chain is authored first and its damage is all behind `OnHitIf`.
+    chain_from(
+        &mut app,
+        caster,
+        first,
+        vec![
+            AbilityEffect::Chain {
+                jumps: 1,
+                range: 3.0,
+                damage_falloff: 0.5,
+            },
+            AbilityEffect::OnHitIf {
+                below_health_fraction: 0.5,
+                effects: vec![AbilityEffect::Damage {
+                    amount: 30.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+            },
+        ],
+    );
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(first), 10.0);
+    assert_eq!(
+        health(second),
+        85.0,
+        "the jump halves the 30 the victim took"
+    );
+}
+
+#[test]
+fn chain_never_jumps_to_the_casters_minions() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let first = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+    let minion = spawn_target(app.world_mut(), Vec3::new(4.0, 0.0, 0.0));
+    app.world_mut().entity_mut(minion).insert(Minion {
+        owner: caster,
+        kind: "wolf".into(),
+        expires_tick: Tick(400),
+    });
+    let enemy = spawn_target(app.world_mut(), Vec3::new(5.5, 0.0, 0.0));
+
+    chain_from(&mut app, caster, first, chain_damage(20.0, 1));
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(
+        health(minion),
+        100.0,
+        "the nearer minion is the caster's own"
+    );
+    assert_eq!(health(enemy), 90.0);
+}
+
+#[test]
+fn chain_breaks_distance_ties_by_position_not_spawn_order() {
+    for flip in [false, true] {
+        let mut app = test_app_with_hit_detection();
+        insert_timeline(app.world_mut(), 200);
+        let caster = spawn_character(app.world_mut());
+        let first = spawn_target(app.world_mut(), Vec3::new(3.0, 0.0, 0.0));
+        let mut positions = [Vec3::new(3.0, 0.0, 2.0), Vec3::new(3.0, 0.0, -2.0)];
+        if flip {
+            positions.reverse();
+        }
+        let [a, b] = positions.map(|pos| spawn_target(app.world_mut(), pos));
+
+        chain_from(&mut app, caster, first, chain_damage(20.0, 1));
+
+        let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+        let (lower_z, higher_z) = if flip { (a, b) } else { (b, a) };
+        assert_eq!(
+            health(lower_z),
+            90.0,
+            "the lower z wins the tie, flip {flip}"
+        );
+        assert_eq!(health(higher_z), 100.0, "flip {flip}");
+    }
+}
+
+/// Activates an ability that summons a minion and spawns an AoE hitbox on its
+/// first Active tick, returning the `AbilityInterpolation` found on the ability,
+/// the minion and the hitbox.
+fn summoned_interpolation_tags(
+    mode: Option<AbilityInterpolation>,
+) -> [Option<AbilityInterpolation>; 3] {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 0,
+            active: 1,
+            recovery: 0,
+            cooldown: 0,
+        },
+        vec![
+            TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Summon {
+                    id: "wolf".into(),
+                    lifetime_ticks: 5,
+                },
+            },
+            TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 5.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
+                },
+            },
+        ],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    if let Some(mode) = mode {
+        asset.components.push(Box::new(mode).into_partial_reflect());
+    }
+    insert_test_ability(&mut app, "snap_test", asset);
+    let ability = spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("snap_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+
+    let minion = app
+        .world_mut()
+        .query_filtered::<Entity, With<Minion>>()
+        .iter(app.world())
+        .next()
+        .expect("Summon should spawn a minion");
+    let hitbox = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox entity should exist");
+    [ability, minion, hitbox].map(|entity| app.world().get::<AbilityInterpolation>(entity).copied())
+}
+
+#[test]
+fn snap_ability_tags_its_spawned_entities() {
+    assert_eq!(
+        summoned_interpolation_tags(Some(AbilityInterpolation::Snap)),
+        [Some(AbilityInterpolation::Snap); 3],
+        "ability, minion and hitbox should all snap"
+    );
+}
+
+#[test]
+fn untagged_ability_spawns_smoothed_entities() {
+    assert_eq!(
+        summoned_interpolation_tags(None),
+        [None; 3],
+        "no marker means the default smoothing applies"
+    );
+}
+
+#[test]
+fn summon_spawns_minion_that_despawns_after_lifetime() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    insert_test_ability(
+        &mut app,
+        "summon_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 0,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Summon {
+                    id: "wolf".into(),
+                    lifetime_ticks: 5,
+                },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("summon_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let (minion_entity, minion) = app
+        .world_mut()
+        .query::<(Entity, &Minion)>()
+        .iter(app.world())
+        .next()
+        .map(|(e, m)| (e, m.clone()))
+        .expect("Summon should spawn a minion");
+    assert_eq!(minion.owner, caster);
+    assert_eq!(minion.kind, "wolf");
+    assert!(app.world().get::<CharacterMarker>(minion_entity).is_some());
+
+    advance_timeline(app.world_mut(), 4);
+    app.update();
+    assert!(
+        app.world().get_entity(minion_entity).is_ok(),
+        "Minion should survive until its lifetime runs out"
+    );
+
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+    assert!(
+        app.world().get_entity(minion_entity).is_err(),
+        "Minion should despawn after its lifetime"
+    );
+}
+
+#[test]
+fn minion_attack_drains_shield_before_health() {
+    let mut app = test_app();
+    // Minions attack on ticks divisible by their attack interval.
+    insert_timeline(app.world_mut(), 224);
+    let owner = spawn_character(app.world_mut());
+    app.world_mut().spawn((
+        CharacterMarker,
+        Minion {
+            owner,
+            kind: "wolf".into(),
+            expires_tick: Tick(300),
+        },
+        Health::new(50.0),
+        avian3d::prelude::Position(Vec3::new(5.0, 0.0, 0.0)),
+        avian3d::prelude::LinearVelocity(Vec3::ZERO),
+    ));
+    let hostile = spawn_target(app.world_mut(), Vec3::new(6.0, 0.0, 0.0));
+    app.world_mut()
+        .entity_mut(hostile)
+        .insert(ActiveShield { remaining: 3.0 });
+
+    app.update();
+
+    let health = app.world().get::<Health>(hostile).unwrap().current;
+    assert_eq!(
+        health, 98.0,
+        "the shield absorbs the first 3 of the 5 damage"
+    );
+    assert!(app.world().get::<ActiveShield>(hostile).is_none());
+}
+
+#[test]
+fn teleport_moves_caster() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+
+    insert_test_ability(
+        &mut app,
+        "teleport_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Teleport { distance: 10.0 },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("teleport_test".into()),
+            caster: char_entity,
+            original_caster: char_entity,
+            target: char_entity,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let pos = app
+        .world()
+        .get::<avian3d::prelude::Position>(char_entity)
+        .unwrap();
+    // Default Rotation faces NEG_Z, so teleport should move to ~(0, 0, -10)
+    assert!((pos.0.x).abs() < 0.01, "X should be ~0, got {}", pos.0.x);
+    assert!(
+        (pos.0.z - (-10.0)).abs() < 0.01,
+        "Z should be ~-10, got {}",
+        pos.0.z
+    );
+}
+
+#[test]
+fn arc_melee_excludes_targets_outside_the_arc() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    // The caster faces -Z; one target is 30° off its facing, the other 80°.
+    let off_center = |degrees: f32| {
+        let radians = degrees.to_radians();
+        Vec3::new(radians.sin(), 0.0, -radians.cos()) * 3.0
+    };
+    let inside = spawn_target(app.world_mut(), off_center(30.0));
+    let outside = spawn_target(app.world_mut(), off_center(80.0));
+
+    insert_test_ability(
+        &mut app,
+        "arc_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Melee {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    affects: TargetMask::default(),
+                    arc_degrees: Some(90.0),
+                    range: Some(4.0),
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 30.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("arc_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("hitbox should exist");
+    let mut colliding = app
+        .world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap();
+    colliding.insert(inside);
+    colliding.insert(outside);
+    app.update();
+
+    assert_eq!(app.world().get::<Health>(inside).unwrap().current, 70.0);
+    assert_eq!(
+        app.world().get::<Health>(outside).unwrap().current,
+        100.0,
+        "a target 80° off-center is outside a 90° arc"
+    );
+}
+
+#[test]
+fn shield_absorbs_damage() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    let target = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            ActiveShield { remaining: 50.0 },
+            avian3d::prelude::Position(Vec3::new(1.0, 0.0, 0.0)),
+            avian3d::prelude::Rotation::default(),
+            avian3d::prelude::LinearVelocity(Vec3::ZERO),
+            forces_components(),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+
+    insert_test_ability(
+        &mut app,
+        "shield_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Melee {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    affects: TargetMask::default(),
+                    arc_degrees: None,
+                    range: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 30.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("shield_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    // First update: spawn hitbox
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("hitbox should exist");
+
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    // Second update: process hits
+    app.update();
+
+    let shield = app
+        .world()
+        .get::<ActiveShield>(target)
+        .expect("Shield should still exist");
+    assert_eq!(shield.remaining, 20.0);
+
+    let health = app.world().get::<Health>(target).unwrap();
+    assert_eq!(
+        health.current, 100.0,
+        "Health should be untouched when shield absorbs all damage"
+    );
+}
+
+#[test]
+fn shield_overflow_damages_health() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    let target = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            ActiveShield { remaining: 20.0 },
+            avian3d::prelude::Position(Vec3::new(1.0, 0.0, 0.0)),
+            avian3d::prelude::Rotation::default(),
+            avian3d::prelude::LinearVelocity(Vec3::ZERO),
+            forces_components(),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+
+    insert_test_ability(
+        &mut app,
+        "shield_overflow_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Melee {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    affects: TargetMask::default(),
+                    arc_degrees: None,
+                    range: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 50.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("shield_overflow_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("hitbox should exist");
+
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    app.update();
+
+    // Shield depleted -> removed
+    assert!(
+        app.world().get::<ActiveShield>(target).is_none(),
+        "Shield should be removed after being fully depleted"
+    );
+
+    // Health takes overflow: 100 - (50 - 20) = 70
+    let health = app.world().get::<Health>(target).unwrap();
+    assert_eq!(
+        health.current, 70.0,
+        "Health should take overflow damage past shield"
+    );
+}
+
+/// Applies `damage` to each of `targets` through the public [`EffectApplier`],
+/// as a hazard would.
+fn apply_hazard_damage(app: &mut App, targets: Vec<Entity>, damage: f32) {
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("lava".into()),
+                vec![AbilityEffect::Damage {
+                    amount: damage,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+            );
+            for &target in &targets {
+                applier.apply_effects_to(target, &effects, Vec3::ZERO);
+            }
+        })
+        .expect("EffectApplier params are available");
+}
+
+/// Poisons `target` with a `DamageOverTime` of `amount` every `interval_ticks`
+/// for `duration_ticks`, starting from the current tick.
+fn apply_poison(
+    app: &mut App,
+    target: Entity,
+    amount: f32,
+    interval_ticks: u16,
+    duration_ticks: u16,
+) {
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("poison".into()),
+                vec![AbilityEffect::DamageOverTime {
+                    amount,
+                    interval_ticks,
+                    duration_ticks,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Poison,
+                }],
+            );
+            applier.apply_effects_to(target, &effects, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+}
+
+/// Steps the timeline and app `ticks` times.
+fn step_ticks(app: &mut App, ticks: usize) {
+    for _ in 0..ticks {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+}
+
+#[test]
+fn damage_over_time_ticks_every_interval_until_expiry() {
+    let mut app = test_app();
+    app.add_systems(Update, ability::tick_damage_over_time);
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+
+    apply_poison(&mut app, target, 5.0, 3, 12);
+
+    // Ticks at 203, 206, 209 and 212; stepping past expiry adds nothing more.
+    step_ticks(&mut app, 16);
+
+    assert_eq!(app.world().get::<Health>(target).unwrap().current, 80.0);
+    assert!(
+        app.world().get::<ActiveDots>(target).is_none(),
+        "expired DoT should be removed"
+    );
+}
+
+#[test]
+fn overlapping_damage_over_time_stacks_on_separate_timers() {
+    let mut app = test_app();
+    app.add_systems(Update, ability::tick_damage_over_time);
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+    let health = |app: &App| app.world().get::<Health>(target).unwrap().current;
+
+    // 5 at 203, 206, 209 and 212.
+    apply_poison(&mut app, target, 5.0, 3, 12);
+    step_ticks(&mut app, 4);
+    // The same poison again from tick 204: 2 at 208 and 212.
+    apply_poison(&mut app, target, 2.0, 4, 8);
+    assert_eq!(
+        app.world().get::<ActiveDots>(target).unwrap().0.len(),
+        2,
+        "a second application runs alongside the first"
+    );
+
+    step_ticks(&mut app, 2);
+    assert_eq!(health(&app), 90.0, "only the first DoT has ticked by 206");
+    step_ticks(&mut app, 2);
+    assert_eq!(
+        health(&app),
+        88.0,
+        "the second ticks on its own timer at 208"
+    );
+    step_ticks(&mut app, 8);
+    assert_eq!(health(&app), 76.0, "both run to expiry: 20 + 4 damage");
+    assert!(app.world().get::<ActiveDots>(target).is_none());
+}
+
+#[test]
+fn damage_over_time_is_scaled_by_resistance_to_its_type() {
+    let mut app = test_app();
+    app.add_systems(Update, ability::tick_damage_over_time);
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+    app.world_mut()
+        .entity_mut(target)
+        .insert(Resistances(HashMap::from([(DamageType::Poison, 0.5)])));
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("poison".into()),
+                vec![AbilityEffect::DamageOverTime {
+                    amount: 10.0,
+                    interval_ticks: 2,
+                    duration_ticks: 4,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Poison,
+                }],
+            );
+            applier.apply_effects_to(target, &effects, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+
+    for _ in 0..6 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+
+    assert_eq!(
+        app.world().get::<Health>(target).unwrap().current,
+        90.0,
+        "two poison ticks at half damage"
+    );
+}
+
+#[test]
+fn interrupt_cancels_cast_and_locks_out_its_slot() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 100);
+    let victim = spawn_character(app.world_mut());
+    let press = |app: &mut App| {
+        let mut action_state = app
+            .world_mut()
+            .get_mut::<ActionState<PlayerActions>>(victim)
+            .unwrap();
+        action_state.release(&PlayerActions::Ability1);
+        action_state.press(&PlayerActions::Ability1);
+    };
+    let clear_cooldown = |app: &mut App| {
+        app.world_mut()
+            .get_mut::<AbilityCooldowns>(victim)
+            .unwrap()
+            .last_used[0] = None;
+    };
+
+    press(&mut app);
+    app.update();
+    assert!(find_active_ability_for_def(app.world_mut(), "punch").is_some());
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("kick".into()),
+                vec![AbilityEffect::Interrupt { lockout_ticks: 30 }],
+            );
+            applier.apply_effects_to(victim, &effects, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+    app.update();
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "punch").is_none(),
+        "the interrupted cast should be cancelled"
+    );
+
+    // The slot stays locked even once its cooldown is cleared.
+    clear_cooldown(&mut app);
+    advance_timeline(app.world_mut(), 1);
+    press(&mut app);
+    app.update();
+    assert!(
+        find_active_ability_for_def(app.world_mut(), "punch").is_none(),
+        "an interrupted slot cannot be recast during the lockout"
+    );
+
+    advance_timeline(app.world_mut(), 30);
+    clear_cooldown(&mut app);
+    press(&mut app);
+    app.update();
+    assert!(find_active_ability_for_def(app.world_mut(), "punch").is_some());
+}
+
+#[test]
+fn on_hit_if_bonus_damage_applies_only_below_health_threshold() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let healthy = spawn_target(app.world_mut(), Vec3::ZERO);
+    let wounded = spawn_target(app.world_mut(), Vec3::X);
+    app.world_mut().get_mut::<Health>(wounded).unwrap().current = 45.0;
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("execute".into()),
+                vec![
+                    AbilityEffect::Damage {
+                        amount: 10.0,
+                        target: EffectTarget::Victim,
+                        damage_type: DamageType::Physical,
+                    },
+                    AbilityEffect::OnHitIf {
+                        below_health_fraction: 0.5,
+                        effects: vec![AbilityEffect::Damage {
+                            amount: 30.0,
+                            target: EffectTarget::Victim,
+                            damage_type: DamageType::Physical,
+                        }],
+                    },
+                ],
+            );
+            for target in [healthy, wounded] {
+                applier.apply_effects_to(target, &effects, Vec3::ZERO);
+            }
+        })
+        .expect("EffectApplier params are available");
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(healthy), 90.0, "a healthy target takes no bonus");
+    assert_eq!(health(wounded), 5.0, "wounded target also takes the bonus");
+}
+
+#[test]
+fn damaging_hit_records_last_damaged_tick() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let damaged = spawn_target(app.world_mut(), Vec3::ZERO);
+    let healed = spawn_target(app.world_mut(), Vec3::X);
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let damage = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("punch".into()),
+                vec![AbilityEffect::Damage {
+                    amount: 10.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+            );
+            let heal = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("mend".into()),
+                vec![AbilityEffect::Heal {
+                    amount: 10.0,
+                    target: EffectTarget::Victim,
+                }],
+            );
+            applier.apply_effects_to(damaged, &damage, Vec3::ZERO);
+            applier.apply_effects_to(healed, &heal, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+
+    assert_eq!(
+        app.world().get::<LastDamagedTick>(damaged),
+        Some(&LastDamagedTick(Tick(200)))
+    );
+    assert!(
+        app.world().get::<LastDamagedTick>(healed).is_none(),
+        "healing is not combat damage"
+    );
+}
+
+#[test]
+fn health_regen_pauses_after_damage_and_resumes_after_delay() {
+    const COMBAT_DELAY_TICKS: u16 = 10;
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+    app.world_mut().entity_mut(target).insert((
+        HealthRegen {
+            per_tick: 2.0,
+            combat_delay_ticks: COMBAT_DELAY_TICKS,
+        },
+        LastDamagedTick(Tick(200)),
+    ));
+    app.world_mut()
+        .get_mut::<Health>(target)
+        .unwrap()
+        .apply_damage(50.0);
+    let current = |app: &App| app.world().get::<Health>(target).unwrap().current;
+
+    app.update();
+    assert_eq!(current(&app), 50.0, "no regen right after a hit");
+
+    advance_timeline(app.world_mut(), COMBAT_DELAY_TICKS as i16 - 1);
+    app.update();
+    assert_eq!(
+        current(&app),
+        50.0,
+        "still paused one tick before the delay ends"
+    );
+
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+    assert_eq!(current(&app), 52.0, "regen resumes once the delay elapses");
+    assert!(app.world().get::<LastDamagedTick>(target).is_none());
+
+    for _ in 0..30 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+    assert_eq!(current(&app), 100.0, "regen stops at max health");
+}
+
+#[test]
+fn damage_over_time_pauses_health_regen() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+    app.world_mut().entity_mut(target).insert(HealthRegen {
+        per_tick: 1.0,
+        combat_delay_ticks: 100,
+    });
+
+    app.world_mut()
+        .run_system_once(move |mut applier: EffectApplier| {
+            let effects = OnHitEffects::from_source(
+                Entity::PLACEHOLDER,
+                AbilityId("poison".into()),
+                vec![AbilityEffect::DamageOverTime {
+                    amount: 5.0,
+                    interval_ticks: 2,
+                    duration_ticks: 4,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Poison,
+                }],
+            );
+            applier.apply_effects_to(target, &effects, Vec3::ZERO);
+        })
+        .expect("EffectApplier params are available");
+
+    for _ in 0..6 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+
+    assert_eq!(
+        app.world().get::<LastDamagedTick>(target),
+        Some(&LastDamagedTick(Tick(204))),
+        "each DoT tick restarts the combat delay"
+    );
+    assert_eq!(
+        app.world().get::<Health>(target).unwrap().current,
+        90.0,
+        "regen stays paused while the DoT ticks"
+    );
+}
+
+#[test]
+fn health_regen_skips_dead_and_respawning_characters() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let regen = HealthRegen {
+        per_tick: 5.0,
+        combat_delay_ticks: 0,
+    };
+    let dead = spawn_target(app.world_mut(), Vec3::ZERO);
+    let respawning = spawn_target(app.world_mut(), Vec3::X * 5.0);
+    app.world_mut().entity_mut(dead).insert(regen.clone());
+    app.world_mut().entity_mut(respawning).insert((
+        regen,
+        RespawnTimer {
+            expires_at: Tick(300),
+        },
+    ));
+    for entity in [dead, respawning] {
+        app.world_mut()
+            .get_mut::<Health>(entity)
+            .unwrap()
+            .apply_damage(100.0);
+    }
+
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(dead), 0.0, "a dead character does not regenerate");
+    assert_eq!(
+        health(respawning),
+        0.0,
+        "respawn restores health, not regen"
+    );
+}
+
+#[test]
+fn resistances_scale_damage_by_type() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let target = spawn_target(app.world_mut(), Vec3::ZERO);
+    app.world_mut()
+        .entity_mut(target)
+        .insert(Resistances(HashMap::from([(DamageType::Fire, 0.5)])));
+
+    let hit = |app: &mut App, damage_type| {
+        app.world_mut()
+            .run_system_once(move |mut applier: EffectApplier| {
+                let effects = OnHitEffects::from_source(
+                    Entity::PLACEHOLDER,
+                    AbilityId("strike".into()),
+                    vec![AbilityEffect::Damage {
+                        amount: 20.0,
+                        target: EffectTarget::Victim,
+                        damage_type,
+                    }],
+                );
+                applier.apply_effects_to(target, &effects, Vec3::ZERO);
+            })
+            .expect("EffectApplier params are available");
+        app.world().get::<Health>(target).unwrap().current
+    };
+
+    assert_eq!(hit(&mut app, DamageType::Fire), 90.0, "half fire damage");
+    assert_eq!(
+        hit(&mut app, DamageType::Physical),
+        70.0,
+        "unlisted types deal full damage"
+    );
+}
+
+#[test]
+fn apply_effects_to_damages_respecting_shields_and_invulnerability() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let exposed = spawn_target(app.world_mut(), Vec3::ZERO);
+    let shielded = spawn_target(app.world_mut(), Vec3::X);
+    app.world_mut()
+        .entity_mut(shielded)
+        .insert(ActiveShield { remaining: 25.0 });
+    let invulnerable = spawn_target(app.world_mut(), Vec3::Z);
+    app.world_mut()
+        .entity_mut(invulnerable)
+        .insert(Invulnerable {
+            expires_at: Tick(300),
+        });
+
+    apply_hazard_damage(&mut app, vec![exposed, shielded, invulnerable], 40.0);
+
+    let health = |entity| app.world().get::<Health>(entity).unwrap().current;
+    assert_eq!(health(exposed), 60.0);
+    assert_eq!(health(shielded), 85.0, "shield absorbs the first 25");
+    assert!(app.world().get::<ActiveShield>(shielded).is_none());
+    assert_eq!(
+        health(invulnerable),
+        100.0,
+        "invulnerable targets take no damage"
+    );
+}
+
+#[test]
+fn buff_inserted_on_target() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+
+    insert_test_ability(
+        &mut app,
+        "buff_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Buff {
+                    stat: "speed".into(),
+                    multiplier: 1.5,
+                    duration_ticks: 100,
+                    target: EffectTarget::Caster,
+                },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("buff_test".into()),
+            caster: char_entity,
+            original_caster: char_entity,
+            target: char_entity,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+
+    let buffs = app
+        .world()
+        .get::<ActiveBuffs>(char_entity)
+        .expect("ActiveBuffs should be present on caster");
+    assert_eq!(buffs.0.len(), 1);
+    assert_eq!(buffs.0[0].stat, "speed");
+    assert_eq!(buffs.0[0].multiplier, 1.5);
+    // expires_tick = 200 + 100 = Tick(300)
+    assert_eq!(buffs.0[0].expires_tick, Tick(200) + 100i16);
+}
+
+#[test]
+fn buff_expires_after_duration() {
+    let mut app = test_app();
+    app.add_systems(Update, ability::expire_buffs);
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+
+    app.world_mut()
+        .entity_mut(char_entity)
+        .insert(ActiveBuffs(vec![ActiveBuff {
+            stat: "speed".into(),
+            multiplier: 1.5,
+            expires_tick: Tick(210),
+            source: AbilityId("test_buff".into()),
+        }]));
+
+    // At tick 200: buff should still exist (expires at 210)
+    app.update();
+    assert!(
+        app.world().get::<ActiveBuffs>(char_entity).is_some(),
+        "Buff should still exist before expiry tick"
+    );
+
+    // Advance to tick 211 (past expiry)
+    advance_timeline(app.world_mut(), 11);
+    app.update();
+
+    assert!(
+        app.world().get::<ActiveBuffs>(char_entity).is_none(),
+        "ActiveBuffs should be removed after expiry"
+    );
+}
+
+#[test]
+fn max_health_buff_scales_max_and_expiry_restores_it() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(char_entity).insert(Health {
+        current: 50.0,
+        max: 100.0,
+    });
+
+    insert_test_ability(
+        &mut app,
+        "max_hp_buff",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Buff {
+                    stat: "max_health".into(),
+                    multiplier: 1.5,
+                    duration_ticks: 10,
+                    target: EffectTarget::Caster,
+                },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("max_hp_buff".into()),
+            caster: char_entity,
+            original_caster: char_entity,
+            target: char_entity,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+
+    let health = app.world().get::<Health>(char_entity).unwrap();
+    assert_eq!(health.max, 150.0, "Buff should scale max health");
+    assert_eq!(
+        health.current, 75.0,
+        "Buff should preserve the health fraction"
+    );
+
+    advance_timeline(app.world_mut(), 11);
+    app.update();
+
+    let health = app.world().get::<Health>(char_entity).unwrap();
+    assert_eq!(health.max, 100.0, "Expiry should restore the original max");
+    assert_eq!(health.current, 50.0, "Expiry must not leave overheal");
+    assert!(app
+        .world()
+        .get::<ability::BaseMaxHealth>(char_entity)
+        .is_none());
+}
+
+#[test]
+fn recasting_a_buff_refreshes_it_instead_of_stacking() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(char_entity).insert(Health {
+        current: 100.0,
+        max: 100.0,
+    });
+
+    insert_test_ability(
+        &mut app,
+        "max_hp_buff",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Buff {
+                    stat: "max_health".into(),
+                    multiplier: 1.5,
+                    duration_ticks: 10,
+                    target: EffectTarget::Caster,
+                },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+    let cast_at = |app: &mut App, tick: u16| {
+        spawn_test_active_ability(
+            app,
+            ActiveAbility {
+                def_id: AbilityId("max_hp_buff".into()),
+                caster: char_entity,
+                original_caster: char_entity,
+                target: char_entity,
+                phase: AbilityPhase::Active,
+                phase_start_tick: Tick(tick),
+                ability_slot: 0,
+                depth: 0,
+                landed_hit: false,
+            },
+        );
+        app.update();
+    };
+
+    cast_at(&mut app, 200);
+    advance_timeline(app.world_mut(), 5);
+    cast_at(&mut app, 205);
+
+    let buffs = app.world().get::<ActiveBuffs>(char_entity).unwrap();
+    assert_eq!(buffs.0.len(), 1, "the recast replaces the first buff");
+    assert_eq!(buffs.0[0].expires_tick, Tick(215));
+    assert_eq!(
+        app.world().get::<Health>(char_entity).unwrap().max,
+        150.0,
+        "a refreshed buff scales max health once"
+    );
+
+    advance_timeline(app.world_mut(), 6);
+    app.update();
+    assert_eq!(
+        app.world().get::<Health>(char_entity).unwrap().max,
+        150.0,
+        "the buff outlasts the first cast's expiry"
+    );
+
+    advance_timeline(app.world_mut(), 5);
+    app.update();
+    assert_eq!(app.world().get::<Health>(char_entity).unwrap().max, 100.0);
+}
+
+/// Casts an OnTick `Heal { amount, target: Caster }` from `caster` at tick 200.
+fn cast_self_heal(app: &mut App, caster: Entity, amount: f32) {
+    insert_test_ability(
+        app,
+        "self_heal",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Heal {
+                    amount,
+                    target: EffectTarget::Caster,
+                },
+            }],
+            vec![],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+    spawn_test_active_ability(
+        app,
+        ActiveAbility {
+            def_id: AbilityId("self_heal".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+}
+
+#[test]
+fn heal_raises_caster_health_and_clamps_at_max() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert(Health {
+        current: 90.0,
+        max: 100.0,
+    });
+
+    cast_self_heal(&mut app, caster, 20.0);
+
+    let health = app.world().get::<Health>(caster).unwrap();
+    assert_eq!(health.current, 100.0, "heal should clamp at max health");
+    assert_eq!(health.max, 100.0, "heal must not raise max health");
+}
+
+#[test]
+fn healing_buff_scales_heal_amount() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    app.world_mut().entity_mut(caster).insert((
+        Health {
+            current: 20.0,
+            max: 100.0,
+        },
+        ActiveBuffs(vec![ActiveBuff {
+            stat: "healing".into(),
+            multiplier: 1.5,
+            expires_tick: Tick(999),
+            source: AbilityId("test_buff".into()),
+        }]),
+    ));
+
+    cast_self_heal(&mut app, caster, 20.0);
+
+    assert_eq!(app.world().get::<Health>(caster).unwrap().current, 50.0);
+}
+
+#[test]
+fn buff_increases_damage() {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    // Give caster a 2x damage buff
+    app.world_mut()
+        .entity_mut(caster)
+        .insert(ActiveBuffs(vec![ActiveBuff {
+            stat: "damage".into(),
+            multiplier: 2.0,
+            expires_tick: Tick(999),
+            source: AbilityId("test_buff".into()),
+        }]));
+
+    let target = spawn_target(app.world_mut(), Vec3::new(1.0, 0.0, 0.0));
+
+    insert_test_ability(
+        &mut app,
+        "buff_dmg_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 4,
+                recovery: 2,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::Melee {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    affects: TargetMask::default(),
+                    arc_degrees: None,
+                    range: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 10.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("buff_dmg_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    // First update: spawn hitbox
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("hitbox should exist");
+
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    // Second update: process hits with buff active
+    app.update();
+
+    let health = app.world().get::<Health>(target).unwrap();
+    assert_eq!(
+        health.current, 80.0,
+        "10 base damage * 2.0 buff = 20 effective damage; 100 - 20 = 80 HP"
+    );
+}
+
+fn assert_vec3_approx(actual: Vec3, expected: Vec3, msg: &str) {
+    let diff = (actual - expected).length();
+    assert!(
+        diff < 1e-4,
+        "{msg}: expected {expected:?}, got {actual:?} (diff {diff})"
+    );
+}
+
+/// Apply an `ApplyForce` effect via an AoE hitbox and return the resulting velocity of the target.
+fn run_force_test(
+    force: Vec3,
+    frame: ForceFrame,
+    caster_rotation: Quat,
+    target_pos: Vec3,
+    target_rotation: Quat,
+) -> Vec3 {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let target = spawn_target(app.world_mut(), target_pos);
+
+    app.world_mut()
+        .get_mut::<avian3d::prelude::Rotation>(caster)
+        .unwrap()
+        .0 = caster_rotation;
+    app.world_mut()
+        .get_mut::<avian3d::prelude::Rotation>(target)
+        .unwrap()
+        .0 = target_rotation;
+
+    insert_test_ability(
+        &mut app,
+        "force_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 4,
+                cooldown: 0,
+            },
+            vec![TickEffect {
+                tick: 0,
+                effect: AbilityEffect::AreaOfEffect {
+                    id: None,
+                    target: EffectTarget::Caster,
+                    radius: 10.0,
+                    duration_ticks: None,
+                    affects: TargetMask::default(),
+                    max_targets: None,
+                    falloff: None,
+                },
+            }],
+            vec![],
+            vec![AbilityEffect::ApplyForce {
+                force,
+                frame,
+                target: EffectTarget::Victim,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("force_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    // Update 1: spawn AoE hitbox
+    app.update();
+
+    let hitbox_entity = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("AoE hitbox should exist");
+
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox_entity)
+        .unwrap()
+        .insert(target);
+
+    advance_timeline(app.world_mut(), 1);
+
+    // Update 2: process hit -> apply force
+    app.update();
+
+    app.world()
+        .get::<avian3d::prelude::LinearVelocity>(target)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn force_frame_world() {
+    // Caster and victim have arbitrary rotations -- World frame ignores both.
+    let vel = run_force_test(
+        Vec3::new(1.0, 0.0, 0.0),
+        ForceFrame::World,
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+        Vec3::new(3.0, 0.0, 0.0),
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+    );
+    assert_vec3_approx(
+        vel,
+        Vec3::new(1.0, 0.0, 0.0),
+        "World frame: force applied verbatim",
+    );
+}
+
+#[test]
+fn force_frame_caster() {
+    // Caster rotated 90 deg around Y: local +X maps to world -Z.
+    let vel = run_force_test(
+        Vec3::new(1.0, 0.0, 0.0),
+        ForceFrame::Caster,
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+        Vec3::new(3.0, 0.0, 0.0),
+        Quat::IDENTITY,
+    );
+    assert_vec3_approx(
+        vel,
+        Vec3::new(0.0, 0.0, -1.0),
+        "Caster frame: rotated by caster rotation",
+    );
+}
+
+#[test]
+fn force_frame_victim() {
+    // Victim rotated 90 deg around Y: local +X maps to world -Z.
+    let vel = run_force_test(
+        Vec3::new(1.0, 0.0, 0.0),
+        ForceFrame::Victim,
+        Quat::IDENTITY,
+        Vec3::new(3.0, 0.0, 0.0),
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+    );
+    assert_vec3_approx(
         vel,
         Vec3::new(0.0, 0.0, -1.0),
         "Victim frame: rotated by victim rotation",
     );
 }
 
-#[test]
-fn force_frame_relative_position() {
-    // Victim directly in +X from source. The RelativePosition frame maps +Z -> world +X.
-    // Force (0, 0, 1) = "push toward victim" -> world +X.
-    let vel = run_force_test(
-        Vec3::new(0.0, 0.0, 1.0),
-        ForceFrame::RelativePosition,
-        Quat::IDENTITY,
-        Vec3::new(5.0, 0.0, 0.0),
-        Quat::IDENTITY,
+#[test]
+fn force_frame_relative_position() {
+    // Victim directly in +X from source. The RelativePosition frame maps +Z -> world +X.
+    // Force (0, 0, 1) = "push toward victim" -> world +X.
+    let vel = run_force_test(
+        Vec3::new(0.0, 0.0, 1.0),
+        ForceFrame::RelativePosition,
+        Quat::IDENTITY,
+        Vec3::new(5.0, 0.0, 0.0),
+        Quat::IDENTITY,
+    );
+    assert_vec3_approx(
+        vel,
+        Vec3::new(1.0, 0.0, 0.0),
+        "RelativePosition: +Z maps to caster->victim direction",
+    );
+}
+
+#[test]
+fn force_frame_relative_rotation() {
+    // Caster identity, victim 90 deg Y: relative = victim * caster.inverse() = 90 deg Y.
+    // 90 deg Y * Vec3::X = Vec3::NEG_Z.
+    let vel = run_force_test(
+        Vec3::new(1.0, 0.0, 0.0),
+        ForceFrame::RelativeRotation,
+        Quat::IDENTITY,
+        Vec3::new(3.0, 0.0, 0.0),
+        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+    );
+    assert_vec3_approx(
+        vel,
+        Vec3::new(0.0, 0.0, -1.0),
+        "RelativeRotation: victim_rot * caster_rot.inverse() * force",
+    );
+}
+
+#[test]
+fn archetype_loads_from_ron_bytes() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.register_type::<AbilityPhases>()
+        .register_type::<OnTickEffects>()
+        .register_type::<TickEffect>()
+        .register_type::<WhileActiveEffects>()
+        .register_type::<OnHitEffectDefs>()
+        .register_type::<OnEndEffects>()
+        .register_type::<OnInputEffects>()
+        .register_type::<InputEffect>()
+        .register_type::<AbilityEffect>()
+        .register_type::<EffectTarget>()
+        .register_type::<ForceFrame>();
+
+    let registry = app.world().resource::<AppTypeRegistry>().0.clone();
+    let reg = registry.read();
+
+    let ron_bytes = br#"
+    {
+        "protocol::ability::AbilityPhases": (startup: 4, active: 20, recovery: 0, cooldown: 16),
+        "protocol::ability::OnTickEffects": ([(tick: 0, effect: Melee())]),
+        "protocol::ability::OnHitEffectDefs": ([
+            Damage(amount: 5.0, target: Victim),
+        ]),
+        "protocol::ability::WhileActiveEffects": ([
+            SetVelocity(speed: 15.0, target: Caster),
+        ]),
+    }
+    "#;
+
+    let components = protocol::reflect_loader::deserialize_component_map(ron_bytes, &reg).unwrap();
+    drop(reg);
+
+    assert_eq!(components.len(), 4);
+
+    // Apply to entity and verify
+    let entity = app.world_mut().spawn_empty().id();
+    let reg = registry.read();
+    let mut entity_mut = app.world_mut().entity_mut(entity);
+    for component in &components {
+        let type_path = component.reflect_type_path();
+        let registration = reg.get_with_type_path(type_path).unwrap();
+        let reflect_component = registration.data::<ReflectComponent>().unwrap();
+        reflect_component.insert(&mut entity_mut, component.as_ref(), &reg);
+    }
+    drop(reg);
+
+    let phases = app.world().get::<AbilityPhases>(entity).unwrap();
+    assert_eq!(phases.startup, 4);
+    assert_eq!(phases.active, 20);
+    assert_eq!(phases.recovery, 0);
+    assert_eq!(phases.cooldown, 16);
+
+    let on_tick = app.world().get::<OnTickEffects>(entity).unwrap();
+    assert_eq!(on_tick.0.len(), 1);
+    assert_eq!(on_tick.0[0].tick, 0);
+
+    let while_active = app.world().get::<WhileActiveEffects>(entity).unwrap();
+    assert_eq!(while_active.0.len(), 1);
+
+    let on_hit = app.world().get::<OnHitEffectDefs>(entity).unwrap();
+    assert_eq!(on_hit.0.len(), 1);
+}
+
+#[test]
+fn archetype_ability_full_lifecycle() {
+    let mut app = test_app();
+    let world = app.world_mut();
+    insert_timeline(world, 0);
+    let character = spawn_character(world);
+
+    world
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+    app.update();
+
+    let (ability_entity, active) = find_active_ability(app.world_mut()).unwrap();
+    assert_eq!(active.phase, AbilityPhase::Startup);
+
+    // Verify archetype components are present from spawn
+    assert!(app.world().get::<AbilityPhases>(ability_entity).is_some());
+    assert!(app.world().get::<OnTickEffects>(ability_entity).is_some());
+    assert!(app.world().get::<OnHitEffectDefs>(ability_entity).is_some());
+    assert!(app.world().get::<OnInputEffects>(ability_entity).is_some());
+
+    // Advance through startup to active phase (punch has 4 startup ticks)
+    for _ in 0..4 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+
+    let (_, active) = find_active_ability(app.world_mut()).unwrap();
+    assert_eq!(active.phase, AbilityPhase::Active);
+
+    // MeleeHitbox should have spawned on first Active tick
+    let hitbox_count = app
+        .world_mut()
+        .query::<&MeleeHitbox>()
+        .iter(app.world())
+        .count();
+    assert_eq!(
+        hitbox_count, 1,
+        "melee hitbox should spawn on first Active tick"
+    );
+}
+
+/// Upper bound on frames to wait for the asset server to finish or fail a load.
+const MAX_ASSET_WAIT_FRAMES: usize = 500;
+
+#[test]
+fn unreadable_default_slots_asset_falls_back_to_empty_slots() {
+    // An asset root whose abilities folder loads but whose default slots fail to parse.
+    let root = std::env::temp_dir().join(format!("protocol-bad-slots-{}", std::process::id()));
+    std::fs::create_dir_all(root.join("abilities")).unwrap();
+    std::fs::write(root.join("default.ability_slots.ron"), "not ability slots").unwrap();
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin {
+        file_path: root.to_string_lossy().into_owned(),
+        ..default()
+    });
+    app.add_plugins(bevy::state::app::StatesPlugin);
+    app.add_plugins(protocol::AppStatePlugin);
+    app.add_plugins(AbilityPlugin);
+
+    let mut ready = false;
+    for _ in 0..MAX_ASSET_WAIT_FRAMES {
+        app.update();
+        if *app.world().resource::<State<AppState>>().get() == AppState::Ready {
+            ready = true;
+            break;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+    std::fs::remove_dir_all(&root).unwrap();
+    assert!(
+        ready,
+        "a failed slots load must not hold the app in Loading"
+    );
+
+    let asset_server = app.world().resource::<AssetServer>();
+    let slots_handle = asset_server.load::<AbilitySlots>("default.ability_slots.ron");
+    assert!(
+        asset_server
+            .get_load_state(slots_handle.id())
+            .is_some_and(|state| state.is_failed()),
+        "the fixture's default slots must fail to load"
+    );
+    let slots = app.world().resource::<DefaultAbilitySlots>();
+    assert!(slots.0 .0.iter().all(Option::is_none));
+    assert!(
+        app.world().resource::<AbilityDefs>().abilities.is_empty(),
+        "the empty abilities folder yields no definitions"
+    );
+}
+
+#[test]
+fn character_without_slots_does_not_activate_with_empty_defaults() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let char_entity = spawn_character(app.world_mut());
+    app.world_mut()
+        .entity_mut(char_entity)
+        .remove::<AbilitySlots>();
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(char_entity)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+    app.update();
+
+    assert!(
+        find_active_ability(app.world_mut()).is_none(),
+        "empty default slots should cast nothing"
+    );
+}
+
+#[test]
+fn on_cast_effect_fires_once_on_entering_active() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let character = spawn_character(app.world_mut());
+
+    let mut asset = build_ability_asset(
+        AbilityPhases {
+            startup: 2,
+            active: 4,
+            recovery: 2,
+            cooldown: 0,
+        },
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+        vec![],
+    );
+    asset.components.push(
+        Box::new(OnCastEffects(vec![AbilityEffect::Teleport {
+            distance: 1.0,
+        }]))
+        .into_partial_reflect(),
+    );
+    insert_test_ability(&mut app, "punch", asset);
+
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap()
+        .press(&PlayerActions::Ability1);
+    app.update();
+
+    let distance_moved = |app: &App| {
+        app.world()
+            .get::<avian3d::prelude::Position>(character)
+            .unwrap()
+            .0
+            .length()
+    };
+
+    // Startup: nothing fires yet.
+    advance_timeline(app.world_mut(), 1);
+    app.update();
+    assert_eq!(distance_moved(&app), 0.0);
+
+    // Entering Active fires OnCast; staying Active never repeats it.
+    for _ in 0..5 {
+        advance_timeline(app.world_mut(), 1);
+        app.update();
+    }
+    let (_, active) = find_active_ability(app.world_mut()).unwrap();
+    assert_ne!(active.phase, AbilityPhase::Startup);
+    assert!(
+        (distance_moved(&app) - 1.0).abs() < 1e-5,
+        "OnCast teleport should fire exactly once"
+    );
+}
+
+#[test]
+fn tick_second_conversions_round_trip() {
+    assert_eq!(ticks_to_secs(64), 1.0);
+    assert_eq!(ticks_to_secs(16), 0.25);
+    assert_eq!(secs_to_ticks(1.5), 96);
+    assert_eq!(secs_to_ticks(0.01), 1, "rounds to the nearest tick");
+    assert_eq!(secs_to_ticks(ticks_to_secs(37)), 37);
+    assert_eq!(secs_to_ticks(10_000.0), u16::MAX);
+}
+
+/// A 30-damage strike lands on a target on the same tick the target's own
+/// ability fires `target_on_tick` at offset 0. Returns the target's health and
+/// remaining shield.
+fn strike_during_target_cast(target_on_tick: Vec<AbilityEffect>) -> (f32, Option<f32>) {
+    let mut app = test_app_with_hit_detection();
+    insert_timeline(app.world_mut(), 200);
+    let striker = spawn_character(app.world_mut());
+    let target = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            avian3d::prelude::Position(Vec3::new(1.0, 0.0, 0.0)),
+            avian3d::prelude::Rotation::default(),
+            avian3d::prelude::LinearVelocity(Vec3::ZERO),
+            forces_components(),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+    let phases = AbilityPhases {
+        startup: 0,
+        active: 4,
+        recovery: 2,
+        cooldown: 0,
+    };
+    let melee = AbilityEffect::Melee {
+        id: None,
+        target: EffectTarget::Caster,
+        affects: TargetMask::default(),
+        arc_degrees: None,
+        range: None,
+    };
+    insert_test_ability(
+        &mut app,
+        "strike",
+        build_ability_asset(
+            phases.clone(),
+            vec![TickEffect {
+                tick: 0,
+                effect: melee,
+            }],
+            vec![],
+            vec![AbilityEffect::Damage {
+                amount: 30.0,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+            vec![],
+            vec![],
+        ),
+    );
+    let target_on_tick = target_on_tick
+        .into_iter()
+        .map(|effect| TickEffect { tick: 0, effect })
+        .collect();
+    insert_test_ability(
+        &mut app,
+        "guard",
+        build_ability_asset(phases, target_on_tick, vec![], vec![], vec![], vec![]),
+    );
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("strike".into()),
+            caster: striker,
+            original_caster: striker,
+            target: striker,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    // Tick 200 spawns the strike's hitbox.
+    app.update();
+    let hitbox = app
+        .world_mut()
+        .query_filtered::<Entity, With<HitboxOf>>()
+        .iter(app.world())
+        .next()
+        .expect("strike hitbox should exist");
+
+    // Tick 201: the target's ability enters Active as the strike connects.
+    advance_timeline(app.world_mut(), 1);
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("guard".into()),
+            caster: target,
+            original_caster: target,
+            target,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(201),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.world_mut()
+        .get_mut::<CollidingEntities>(hitbox)
+        .unwrap()
+        .insert(target);
+    app.update();
+
+    let health = app.world().get::<Health>(target).unwrap().current;
+    let shield = app.world().get::<ActiveShield>(target).map(|s| s.remaining);
+    (health, shield)
+}
+
+#[test]
+fn same_tick_shield_resolves_before_damage_regardless_of_authoring_order() {
+    let shield = AbilityEffect::Shield { absorb: 50.0 };
+    let swing = AbilityEffect::Melee {
+        id: None,
+        target: EffectTarget::Caster,
+        affects: TargetMask::default(),
+        arc_degrees: None,
+        range: None,
+    };
+
+    for on_tick in [
+        vec![shield.clone(), swing.clone()],
+        vec![swing.clone(), shield.clone()],
+    ] {
+        let (health, remaining) = strike_during_target_cast(on_tick.clone());
+        assert_eq!(
+            health, 100.0,
+            "shield should absorb the hit for {on_tick:?}"
+        );
+        assert_eq!(remaining, Some(20.0), "for {on_tick:?}");
+    }
+}
+
+/// Runs one ability whose `effects` are applied to its own caster and returns
+/// the caster's `(health, shield remaining)`. In `AbilityPhase::Active` they
+/// are tick-0 `on_tick` effects; in `AbilityPhase::Recovery`, `on_end` effects.
+fn same_ability_shield_and_damage(
+    phase: AbilityPhase,
+    effects: Vec<AbilityEffect>,
+) -> (f32, Option<f32>) {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_target(app.world_mut(), Vec3::ZERO);
+    let phases = AbilityPhases {
+        startup: 0,
+        active: 4,
+        recovery: 2,
+        cooldown: 0,
+    };
+    let asset = if phase == AbilityPhase::Recovery {
+        build_ability_asset(phases, vec![], vec![], vec![], effects, vec![])
+    } else {
+        let on_tick = effects
+            .into_iter()
+            .map(|effect| TickEffect { tick: 0, effect })
+            .collect();
+        build_ability_asset(phases, on_tick, vec![], vec![], vec![], vec![])
+    };
+    insert_test_ability(&mut app, "bulwark", asset);
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("bulwark".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+    app.update();
+
+    let health = app.world().get::<Health>(caster).unwrap().current;
+    let shield = app.world().get::<ActiveShield>(caster).map(|s| s.remaining);
+    (health, shield)
+}
+
+#[test]
+fn same_ability_shield_is_up_before_its_own_damage_lands() {
+    let shield = AbilityEffect::Shield { absorb: 50.0 };
+    let backlash = AbilityEffect::Damage {
+        amount: 30.0,
+        target: EffectTarget::AllInRadius {
+            radius: 2.0,
+            include_caster: true,
+        },
+        damage_type: DamageType::Physical,
+    };
+
+    for on_tick in [
+        vec![shield.clone(), backlash.clone()],
+        vec![backlash.clone(), shield.clone()],
+    ] {
+        let (health, remaining) =
+            same_ability_shield_and_damage(AbilityPhase::Active, on_tick.clone());
+        assert_eq!(
+            health, 100.0,
+            "the shield's insert is queued ahead of the damage for {on_tick:?}"
+        );
+        assert_eq!(remaining, Some(20.0), "for {on_tick:?}");
+    }
+}
+
+#[test]
+fn on_end_shield_is_up_before_its_own_damage_lands() {
+    let shield = AbilityEffect::Shield { absorb: 50.0 };
+    let backlash = AbilityEffect::Damage {
+        amount: 30.0,
+        target: EffectTarget::AllInRadius {
+            radius: 2.0,
+            include_caster: true,
+        },
+        damage_type: DamageType::Physical,
+    };
+
+    for on_end in [
+        vec![shield.clone(), backlash.clone()],
+        vec![backlash.clone(), shield.clone()],
+    ] {
+        let (health, remaining) =
+            same_ability_shield_and_damage(AbilityPhase::Recovery, on_end.clone());
+        assert_eq!(health, 100.0, "shield absorbs the backlash for {on_end:?}");
+        assert_eq!(remaining, Some(20.0), "for {on_end:?}");
+    }
+}
+
+#[test]
+fn effects_resolve_defensive_then_movement_then_offensive() {
+    let damage = AbilityEffect::Damage {
+        amount: 1.0,
+        target: EffectTarget::Victim,
+        damage_type: DamageType::Physical,
+    };
+    let teleport = AbilityEffect::Teleport { distance: 1.0 };
+    let shield = AbilityEffect::Shield { absorb: 1.0 };
+    let authored = [damage.clone(), teleport.clone(), shield.clone()];
+
+    assert_eq!(
+        protocol::ability::by_priority(&authored),
+        vec![&shield, &teleport, &damage]
+    );
+}
+
+/// `test_app` with avian physics, for effects that need spatial queries.
+fn physics_test_app() -> App {
+    test_app_with(|app| {
+        app.add_plugins(bevy::diagnostic::DiagnosticsPlugin);
+        app.add_plugins(bevy::transform::TransformPlugin);
+        app.add_plugins(bevy::mesh::MeshPlugin);
+        app.add_plugins(avian3d::prelude::PhysicsPlugins::default());
+        app.insert_resource(avian3d::prelude::Gravity(Vec3::ZERO));
+        app.finish();
+    })
+}
+
+#[test]
+fn grapple_pulls_caster_toward_target_and_stops_near_it() {
+    use avian3d::prelude::{LinearVelocity, Position, RigidBody, Rotation};
+
+    let mut app = physics_test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Position(Vec3::ZERO),
+            Rotation::default(),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+    let target_pos = Vec3::new(0.0, 0.0, -20.0);
+    app.world_mut().spawn((
+        CharacterMarker,
+        CharacterDimensions::default().collider(),
+        RigidBody::Static,
+        character_collision_layers(),
+        Position(target_pos),
+        Rotation::default(),
+        protocol::map::MapInstanceId::Overworld,
+    ));
+    // Let physics build the spatial query pipeline before the ray is cast.
+    app.update();
+
+    insert_test_ability(
+        &mut app,
+        "grapple_test",
+        build_ability_asset(
+            AbilityPhases {
+                startup: 0,
+                active: 200,
+                recovery: 0,
+                cooldown: 0,
+            },
+            vec![],
+            vec![AbilityEffect::Grapple {
+                speed: 20.0,
+                max_distance: 30.0,
+            }],
+            vec![],
+            vec![],
+            vec![],
+        ),
+    );
+    spawn_test_active_ability(
+        &mut app,
+        ActiveAbility {
+            def_id: AbilityId("grapple_test".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: Tick(200),
+            ability_slot: 0,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+
+    app.update();
+    app.update();
+    let pulling = app.world().get::<LinearVelocity>(caster).unwrap().0;
+    assert!(
+        pulling.z < -10.0,
+        "caster should be pulled toward the target, velocity {pulling:?}"
+    );
+
+    for _ in 0..80 {
+        app.update();
+    }
+    let gap = app
+        .world()
+        .get::<Position>(caster)
+        .unwrap()
+        .0
+        .distance(target_pos);
+    let contact = 2.0 * CHARACTER_CAPSULE_RADIUS;
+    assert!(
+        gap > contact && gap < contact + 1.0,
+        "caster should stop just short of the target, centers {gap} apart"
+    );
+    assert_eq!(
+        app.world().get::<LinearVelocity>(caster).unwrap().0,
+        Vec3::ZERO,
+        "pull ends on arrival"
+    );
+}
+
+/// Fires a bullet from the origin along -Z from a gamepad-aiming shooter, with a
+/// target slightly off its line, and returns the bullet's velocity after 10 ticks.
+fn aim_assisted_bullet_velocity(config: protocol::ability::AimAssistConfig) -> Vec3 {
+    use avian3d::prelude::{LinearVelocity, Position};
+    use protocol::ability::{aim_assist_projectiles, GamepadAiming};
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(bevy::time::TimeUpdateStrategy::FixedTimesteps(1));
+    app.insert_resource(config);
+    app.add_systems(FixedUpdate, aim_assist_projectiles);
+
+    let shooter = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            Position(Vec3::ZERO),
+            GamepadAiming,
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+    app.world_mut().spawn((
+        CharacterMarker,
+        Health::new(100.0),
+        Position(Vec3::new(3.0, 0.0, -20.0)),
+        protocol::map::MapInstanceId::Overworld,
+    ));
+    let spawn = app
+        .world_mut()
+        .spawn(AbilityProjectileSpawn {
+            spawn_tick: Tick(0),
+            position: Vec3::ZERO,
+            direction: Vec3::NEG_Z,
+            speed: 20.0,
+            lifetime_ticks: 192,
+            radius: DEFAULT_PROJECTILE_RADIUS,
+            ability_id: AbilityId("fireball".into()),
+            shooter,
+        })
+        .id();
+    let bullet = app
+        .world_mut()
+        .spawn((
+            Position(Vec3::ZERO),
+            LinearVelocity(Vec3::NEG_Z * 20.0),
+            AbilityBulletOf(spawn),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+
+    for _ in 0..10 {
+        app.update();
+    }
+    app.world().get::<LinearVelocity>(bullet).unwrap().0
+}
+
+#[test]
+fn aim_assist_nudges_near_miss_toward_target() {
+    let velocity = aim_assisted_bullet_velocity(protocol::ability::AimAssistConfig::default());
+    assert!(
+        velocity.x > 1.0,
+        "aim assist should steer toward the target at +X, velocity {velocity:?}"
+    );
+    assert!(
+        (velocity.length() - 20.0).abs() < 0.01,
+        "steering keeps the bullet's speed, got {}",
+        velocity.length()
+    );
+}
+
+#[test]
+fn disabled_aim_assist_leaves_bullet_straight() {
+    let velocity = aim_assisted_bullet_velocity(protocol::ability::AimAssistConfig {
+        strength: 0.0,
+        ..default()
+    });
+    assert_eq!(velocity, Vec3::NEG_Z * 20.0);
+}
+
+#[test]
+fn homing_bullet_ignores_allies_and_retargets_when_enemy_becomes_invalid() {
+    use avian3d::prelude::{Collider, LinearVelocity, Position, RigidBody, Rotation};
+    use protocol::ability::{HomingProjectile, HomingTarget};
+    use protocol::map::MapInstanceId;
+
+    let mut app = physics_test_app();
+    insert_timeline(app.world_mut(), 100);
+    let shooter = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            Position(Vec3::ZERO),
+            MapInstanceId::Overworld,
+        ))
+        .id();
+    // The shooter's own minion sits closest, straight ahead.
+    app.world_mut().spawn((
+        CharacterMarker,
+        Health::new(100.0),
+        Position(Vec3::new(0.0, 0.0, -5.0)),
+        Minion {
+            owner: shooter,
+            kind: "test".into(),
+            expires_tick: Tick(10_000),
+        },
+        MapInstanceId::Overworld,
+    ));
+    let near_enemy = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            Position(Vec3::new(2.0, 0.0, -10.0)),
+            MapInstanceId::Overworld,
+        ))
+        .id();
+    let far_enemy = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            Position(Vec3::new(-3.0, 0.0, -15.0)),
+            MapInstanceId::Overworld,
+        ))
+        .id();
+    let spawn = app
+        .world_mut()
+        .spawn(AbilityProjectileSpawn {
+            spawn_tick: Tick(100),
+            position: Vec3::ZERO,
+            direction: Vec3::NEG_Z,
+            speed: 20.0,
+            lifetime_ticks: 192,
+            radius: DEFAULT_PROJECTILE_RADIUS,
+            ability_id: AbilityId("fireball".into()),
+            shooter,
+        })
+        .id();
+    let bullet = app
+        .world_mut()
+        .spawn((
+            Position(Vec3::ZERO),
+            LinearVelocity(Vec3::NEG_Z * 20.0),
+            HomingProjectile {
+                turn_rate: 3.0,
+                range: 30.0,
+                affects: TargetMask::default(),
+                line_of_sight: true,
+            },
+            HomingTarget::default(),
+            AbilityBulletOf(spawn),
+            MapInstanceId::Overworld,
+        ))
+        .id();
+
+    app.update();
+    assert_eq!(
+        app.world().get::<HomingTarget>(bullet).unwrap().0,
+        Some(near_enemy),
+        "homing should skip the shooter's minion for the nearest enemy"
     );
-    assert_vec3_approx(
-        vel,
-        Vec3::new(1.0, 0.0, 0.0),
-        "RelativePosition: +Z maps to caster->victim direction",
+    let velocity = app.world().get::<LinearVelocity>(bullet).unwrap().0;
+    assert!(
+        velocity.x > 0.0,
+        "bullet should turn toward the enemy at +X, velocity {velocity:?}"
     );
-}
 
-#[test]
-fn force_frame_relative_rotation() {
-    // Caster identity, victim 90 deg Y: relative = victim * caster.inverse() = 90 deg Y.
-    // 90 deg Y * Vec3::X = Vec3::NEG_Z.
-    let vel = run_force_test(
-        Vec3::new(1.0, 0.0, 0.0),
-        ForceFrame::RelativeRotation,
-        Quat::IDENTITY,
-        Vec3::new(3.0, 0.0, 0.0),
-        Quat::from_rotation_y(std::f32::consts::FRAC_PI_2),
+    app.world_mut().entity_mut(near_enemy).insert(RespawnTimer {
+        expires_at: Tick(1_000),
+    });
+    app.update();
+    assert_eq!(
+        app.world().get::<HomingTarget>(bullet).unwrap().0,
+        Some(far_enemy),
+        "a dead target should be dropped for the next valid enemy"
     );
-    assert_vec3_approx(
-        vel,
-        Vec3::new(0.0, 0.0, -1.0),
-        "RelativeRotation: victim_rot * caster_rot.inverse() * force",
+
+    app.world_mut().spawn((
+        Collider::cuboid(20.0, 5.0, 0.5),
+        RigidBody::Static,
+        terrain_collision_layers(),
+        Position(Vec3::new(0.0, 0.0, -8.0)),
+        Rotation::default(),
+        MapInstanceId::Overworld,
+    ));
+    // Let physics add the wall to the spatial query pipeline.
+    app.update();
+    app.update();
+    assert_eq!(
+        app.world().get::<HomingTarget>(bullet).unwrap().0,
+        None,
+        "an enemy behind terrain is not a valid target"
     );
 }
 
-#[test]
-fn archetype_loads_from_ron_bytes() {
-    let mut app = App::new();
-    app.add_plugins(MinimalPlugins);
-    app.add_plugins(AssetPlugin::default());
-    app.register_type::<AbilityPhases>()
-        .register_type::<OnTickEffects>()
-        .register_type::<TickEffect>()
-        .register_type::<WhileActiveEffects>()
-        .register_type::<OnHitEffectDefs>()
-        .register_type::<OnEndEffects>()
-        .register_type::<OnInputEffects>()
-        .register_type::<InputEffect>()
-        .register_type::<AbilityEffect>()
-        .register_type::<EffectTarget>()
-        .register_type::<ForceFrame>();
-
-    let registry = app.world().resource::<AppTypeRegistry>().0.clone();
-    let reg = registry.read();
-
-    let ron_bytes = br#"
-    {
-        "protocol::ability::AbilityPhases": (startup: 4, active: 20, recovery: 0, cooldown: 16),
-        "protocol::ability::OnTickEffects": ([(tick: 0, effect: Melee())]),
-        "protocol::ability::OnHitEffectDefs": ([
-            Damage(amount: 5.0, target: Victim),
-        ]),
-        "protocol::ability::WhileActiveEffects": ([
-            SetVelocity(speed: 15.0, target: Caster),
-        ]),
-    }
-    "#;
+/// Spawns a stationary bullet of `radius` at the origin, beside a character
+/// whose capsule surface is 0.5 from the bullet's center. Returns the spawned
+/// bullet's collider radius and the character's health a few ticks later.
+fn stationary_bullet_beside_character(radius: f32) -> (f32, f32) {
+    use avian3d::prelude::{Collider, Position, Rotation};
 
-    let components = protocol::reflect_loader::deserialize_component_map(ron_bytes, &reg).unwrap();
-    drop(reg);
+    let mut app = physics_test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    let target = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Health::new(100.0),
+            Position(Vec3::new(CHARACTER_CAPSULE_RADIUS + 0.5, 0.0, 0.0)),
+            Rotation::default(),
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
+    let spawn = app
+        .world_mut()
+        .spawn((
+            AbilityProjectileSpawn {
+                spawn_tick: Tick(200),
+                position: Vec3::ZERO,
+                direction: Vec3::NEG_Z,
+                speed: 0.0,
+                lifetime_ticks: 192,
+                radius,
+                ability_id: AbilityId("fireball".into()),
+                shooter: caster,
+            },
+            OnHitEffects {
+                ability_id: AbilityId("fireball".into()),
+                effects: vec![AbilityEffect::Damage {
+                    amount: 25.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+                caster,
+                original_caster: caster,
+                ability_slot: 0,
+                depth: 0,
+            },
+            protocol::map::MapInstanceId::Overworld,
+        ))
+        .id();
 
-    assert_eq!(components.len(), 4);
+    app.update();
+    let world = app.world_mut();
+    let collider_radius = world
+        .query::<(&Collider, &AbilityBulletOf)>()
+        .iter(world)
+        .find(|(_, bullet_of)| bullet_of.0 == spawn)
+        .map(|(collider, _)| {
+            collider
+                .shape()
+                .as_ball()
+                .expect("bullet collider is a sphere")
+                .radius
+        })
+        .expect("bullet should spawn");
 
-    // Apply to entity and verify
-    let entity = app.world_mut().spawn_empty().id();
-    let reg = registry.read();
-    let mut entity_mut = app.world_mut().entity_mut(entity);
-    for component in &components {
-        let type_path = component.reflect_type_path();
-        let registration = reg.get_with_type_path(type_path).unwrap();
-        let reflect_component = registration.data::<ReflectComponent>().unwrap();
-        reflect_component.insert(&mut entity_mut, component.as_ref(), &reg);
+    for _ in 0..5 {
+        app.update();
     }
-    drop(reg);
+    let health = app.world().get::<Health>(target).unwrap().current;
+    (collider_radius, health)
+}
 
-    let phases = app.world().get::<AbilityPhases>(entity).unwrap();
-    assert_eq!(phases.startup, 4);
-    assert_eq!(phases.active, 20);
-    assert_eq!(phases.recovery, 0);
-    assert_eq!(phases.cooldown, 16);
+#[test]
+fn projectile_radius_sets_bullet_collider_and_hit_range() {
+    let (small_radius, small_health) = stationary_bullet_beside_character(0.2);
+    assert_eq!(small_radius, 0.2);
+    assert_eq!(small_health, 100.0, "a small bullet misses the character");
+
+    let (big_radius, big_health) = stationary_bullet_beside_character(1.0);
+    assert_eq!(big_radius, 1.0);
+    assert_eq!(big_health, 75.0, "a big bullet reaches the same character");
+}
 
-    let on_tick = app.world().get::<OnTickEffects>(entity).unwrap();
-    assert_eq!(on_tick.0.len(), 1);
-    assert_eq!(on_tick.0[0].tick, 0);
+/// Two overlapping stationary bullets from different casters, both spawned
+/// with or without `ClashingProjectile`. Returns how many remain after a few
+/// ticks.
+fn overlapping_opposing_bullets(clash: bool) -> usize {
+    let mut app = physics_test_app();
+    insert_timeline(app.world_mut(), 200);
+    for x in [-0.25, 0.25] {
+        let shooter = app.world_mut().spawn_empty().id();
+        let mut spawn = app.world_mut().spawn((
+            AbilityProjectileSpawn {
+                spawn_tick: Tick(200),
+                position: Vec3::new(x, 0.0, 0.0),
+                direction: Vec3::NEG_Z,
+                speed: 0.0,
+                lifetime_ticks: 192,
+                radius: DEFAULT_PROJECTILE_RADIUS,
+                ability_id: AbilityId("fireball".into()),
+                shooter,
+            },
+            protocol::map::MapInstanceId::Overworld,
+        ));
+        if clash {
+            spawn.insert(ClashingProjectile);
+        }
+    }
 
-    let while_active = app.world().get::<WhileActiveEffects>(entity).unwrap();
-    assert_eq!(while_active.0.len(), 1);
+    app.update();
+    let world = app.world_mut();
+    assert_eq!(
+        world.query::<&AbilityBulletOf>().iter(world).count(),
+        2,
+        "both bullets should spawn"
+    );
+    for _ in 0..5 {
+        app.update();
+    }
+    let world = app.world_mut();
+    world.query::<&AbilityBulletOf>().iter(world).count()
+}
 
-    let on_hit = app.world().get::<OnHitEffectDefs>(entity).unwrap();
-    assert_eq!(on_hit.0.len(), 1);
+#[test]
+fn clashing_projectiles_cancel_each_other() {
+    assert_eq!(
+        overlapping_opposing_bullets(true),
+        0,
+        "clashing bullets despawn on contact"
+    );
+    assert_eq!(
+        overlapping_opposing_bullets(false),
+        2,
+        "other bullets pass through each other"
+    );
 }
 
 #[test]
-fn archetype_ability_full_lifecycle() {
+fn cast_state_tracks_phase_and_progress() {
     let mut app = test_app();
-    let world = app.world_mut();
-    insert_timeline(world, 0);
-    let character = spawn_character(world);
+    insert_timeline(app.world_mut(), 100);
+    let caster = spawn_character(app.world_mut());
+    let cast_state = |app: &App| app.world().get::<CastState>(caster).cloned();
 
-    world
-        .get_mut::<ActionState<PlayerActions>>(character)
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
         .unwrap()
         .press(&PlayerActions::Ability1);
     app.update();
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(caster)
+        .unwrap()
+        .release(&PlayerActions::Ability1);
+    // punch: 4 startup ticks, then 20 active ticks, then no recovery.
+    let punch = AbilityId("punch".into());
+    assert_eq!(
+        cast_state(&app),
+        Some(CastState {
+            ability_id: punch.clone(),
+            phase: AbilityPhase::Startup,
+            phase_progress: 0.0,
+        })
+    );
+
+    advance_timeline(app.world_mut(), 2);
+    app.update();
+    assert_eq!(
+        cast_state(&app).map(|s| (s.phase, s.phase_progress)),
+        Some((AbilityPhase::Startup, 0.5))
+    );
 
-    let (ability_entity, active) = find_active_ability(app.world_mut()).unwrap();
-    assert_eq!(active.phase, AbilityPhase::Startup);
+    advance_timeline(app.world_mut(), 2);
+    app.update();
+    assert_eq!(
+        cast_state(&app).map(|s| (s.phase, s.phase_progress)),
+        Some((AbilityPhase::Active, 0.0))
+    );
 
-    // Verify archetype components are present from spawn
-    assert!(app.world().get::<AbilityPhases>(ability_entity).is_some());
-    assert!(app.world().get::<OnTickEffects>(ability_entity).is_some());
-    assert!(app.world().get::<OnHitEffectDefs>(ability_entity).is_some());
-    assert!(app.world().get::<OnInputEffects>(ability_entity).is_some());
+    advance_timeline(app.world_mut(), 5);
+    app.update();
+    assert_eq!(
+        cast_state(&app),
+        Some(CastState {
+            ability_id: punch,
+            phase: AbilityPhase::Active,
+            phase_progress: 0.25,
+        })
+    );
 
-    // Advance through startup to active phase (punch has 4 startup ticks)
-    for _ in 0..4 {
+    for _ in 0..20 {
         advance_timeline(app.world_mut(), 1);
         app.update();
     }
+    assert_eq!(cast_state(&app), None, "removed once the cast ends");
+}
 
-    let (_, active) = find_active_ability(app.world_mut()).unwrap();
-    assert_eq!(active.phase, AbilityPhase::Active);
+#[test]
+fn cast_state_shows_the_lowest_slot_and_skips_unchanged_writes() {
+    let mut app = test_app();
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+    for (def_id, ability_slot) in [("dash", 1), ("punch", 0)] {
+        spawn_test_active_ability(
+            &mut app,
+            ActiveAbility {
+                def_id: AbilityId(def_id.into()),
+                caster,
+                original_caster: caster,
+                target: caster,
+                phase: AbilityPhase::Active,
+                phase_start_tick: Tick(200),
+                ability_slot,
+                depth: 0,
+                landed_hit: false,
+            },
+        );
+    }
+    app.update();
 
-    // MeleeHitbox should have spawned on first Active tick
-    let hitbox_count = app
+    let cast_state = app.world().entity(caster).get_ref::<CastState>().unwrap();
+    assert_eq!(
+        cast_state.ability_id,
+        AbilityId("punch".into()),
+        "slot 0 wins whichever ability spawned first"
+    );
+    let written = cast_state.last_changed();
+
+    app.update();
+    let cast_state = app.world().entity(caster).get_ref::<CastState>().unwrap();
+    assert_eq!(cast_state.ability_id, AbilityId("punch".into()));
+    assert_eq!(
+        cast_state.last_changed(),
+        written,
+        "a tick with the same phase and progress leaves CastState unchanged"
+    );
+}
+
+#[test]
+fn active_abilities_of_returns_only_the_casters_abilities() {
+    let mut app = test_app();
+    let caster = spawn_character(app.world_mut());
+    let other = spawn_character(app.world_mut());
+    let active = |def_id: &str, caster, depth| ActiveAbility {
+        def_id: AbilityId(def_id.into()),
+        caster,
+        original_caster: caster,
+        target: caster,
+        phase: AbilityPhase::Active,
+        phase_start_tick: Tick(200),
+        ability_slot: 0,
+        depth,
+        landed_hit: false,
+    };
+    app.world_mut().spawn(active("dash", caster, 0));
+    app.world_mut().spawn(active("dash_trail", caster, 1));
+    app.world_mut().spawn(active("fireball", other, 0));
+
+    let mut abilities = app
         .world_mut()
-        .query::<&MeleeHitbox>()
+        .run_system_once(move |query: Query<&ActiveAbility>| active_abilities_of(caster, &query))
+        .expect("ActiveAbility query is available");
+    abilities.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        abilities,
+        vec![AbilityId("dash".into()), AbilityId("dash_trail".into())]
+    );
+}
+
+/// Casts `fireball` from `slot` so its projectile spawns on the next update.
+fn cast_fireball(app: &mut App, caster: Entity, slot: u8) {
+    let tick = app.world().resource::<LocalTimeline>().tick();
+    spawn_test_active_ability(
+        app,
+        ActiveAbility {
+            def_id: AbilityId("fireball".into()),
+            caster,
+            original_caster: caster,
+            target: caster,
+            phase: AbilityPhase::Active,
+            phase_start_tick: tick,
+            ability_slot: slot,
+            depth: 0,
+            landed_hit: false,
+        },
+    );
+}
+
+/// Projectile spawns counted against `shooter`'s cap. Expired spawns are
+/// marked with `PredictionDisable` rather than despawned outside prediction.
+fn live_projectiles(app: &mut App, shooter: Entity) -> Vec<Entity> {
+    app.world_mut()
+        .query_filtered::<(Entity, &AbilityProjectileSpawn), Without<PredictionDisable>>()
         .iter(app.world())
-        .count();
+        .filter(|(_, spawn)| spawn.shooter == shooter)
+        .map(|(entity, _)| entity)
+        .collect()
+}
+
+#[test]
+fn projectiles_beyond_the_per_shooter_cap_are_refused() {
+    let mut app = test_app();
+    app.insert_resource(ProjectileLimitConfig { max_per_shooter: 2 });
+    insert_timeline(app.world_mut(), 200);
+    let caster = spawn_character(app.world_mut());
+
+    cast_fireball(&mut app, caster, 0);
+    app.update();
+    advance_timeline(app.world_mut(), 1);
+    cast_fireball(&mut app, caster, 1);
+    cast_fireball(&mut app, caster, 2);
+    app.update();
+    let live = live_projectiles(&mut app, caster);
+    assert_eq!(live.len(), 2, "the third projectile exceeds the cap");
+
+    advance_timeline(app.world_mut(), 1);
+    cast_fireball(&mut app, caster, 3);
+    app.update();
     assert_eq!(
-        hitbox_count, 1,
-        "melee hitbox should spawn on first Active tick"
+        live_projectiles(&mut app, caster).len(),
+        2,
+        "still capped while both projectiles fly"
+    );
+
+    // The first fireball was cast one tick before the second, so only its
+    // bullet reaches the end of its 192-tick flight at tick 392.
+    let (expiring, flying) = if app
+        .world()
+        .get::<AbilityProjectileSpawn>(live[0])
+        .is_some_and(|spawn| spawn.spawn_tick == Tick(200))
+    {
+        (live[0], live[1])
+    } else {
+        (live[1], live[0])
+    };
+    advance_timeline(app.world_mut(), 190);
+    app.update();
+    assert_eq!(
+        read_messages::<AbilityMissEvent>(&app),
+        vec![AbilityMissEvent {
+            caster,
+            ability_id: AbilityId("fireball".into()),
+        }],
+        "the expired bullet missed"
     );
+    assert_eq!(live_projectiles(&mut app, caster), vec![flying]);
+
+    advance_timeline(app.world_mut(), 1);
+    cast_fireball(&mut app, caster, 4);
+    app.update();
+    let after = live_projectiles(&mut app, caster);
+    assert_eq!(after.len(), 2, "an expired projectile frees its slot");
+    assert!(!after.contains(&expiring));
 }
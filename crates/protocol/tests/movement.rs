@@ -0,0 +1,673 @@
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::{LocalTimeline, Tick};
+use protocol::*;
+
+fn physics_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(bevy::asset::AssetPlugin::default());
+    app.add_plugins(bevy::diagnostic::DiagnosticsPlugin);
+    app.add_plugins(bevy::transform::TransformPlugin);
+    app.add_plugins(bevy::mesh::MeshPlugin);
+    app.add_plugins(PhysicsPlugins::default());
+    app.insert_resource(Gravity(Vec3::ZERO));
+    // Run FixedUpdate exactly once per app.update() call
+    app.insert_resource(bevy::time::TimeUpdateStrategy::FixedTimesteps(1));
+    app.finish();
+    app
+}
+
+fn wall_jump_app() -> App {
+    let mut app = physics_app();
+    app.add_systems(
+        FixedUpdate,
+        (detect_wall_contact, airborne_movement).chain(),
+    );
+    app
+}
+
+fn step_up_app() -> App {
+    let mut app = physics_app();
+    app.init_resource::<MovementConfig>();
+    app.add_systems(FixedUpdate, (detect_grounded, step_up_obstacles).chain());
+    app
+}
+
+/// Mirrors the client/server `handle_character_movement`, with the character
+/// always treated as airborne.
+fn airborne_movement(
+    time: Res<Time>,
+    mut query: Query<(
+        &ActionState<PlayerActions>,
+        &ComputedMass,
+        Option<&TouchingWall>,
+        Forces,
+    )>,
+) {
+    for (action_state, mass, wall, mut forces) in &mut query {
+        apply_movement(
+            mass,
+            time.delta_secs(),
+            action_state,
+            MovementConfig::default().move_deadzone,
+            None,
+            false,
+            wall,
+            &[],
+            &mut forces,
+        );
+    }
+}
+
+/// Character at the origin holding Jump.
+fn spawn_jumping_character(world: &mut World) -> Entity {
+    let mut action_state = ActionState::<PlayerActions>::default();
+    action_state.press(&PlayerActions::Jump);
+    world
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Position(Vec3::ZERO),
+            Rotation::default(),
+            action_state,
+        ))
+        .id()
+}
+
+#[test]
+fn wall_jump_pushes_away_from_adjacent_wall() {
+    let mut app = wall_jump_app();
+    let character = spawn_jumping_character(app.world_mut());
+    // Wall face at x = 2.1, just outside the capsule's 2.0 radius.
+    app.world_mut().spawn((
+        RigidBody::Static,
+        Collider::cuboid(1.0, 10.0, 10.0),
+        Position(Vec3::new(2.6, 0.0, 0.0)),
+        terrain_collision_layers(),
+    ));
+
+    // The spatial query only sees the wall after the first physics step.
+    for _ in 0..5 {
+        app.update();
+        if app.world().get::<LinearVelocity>(character).unwrap().y > 0.0 {
+            break;
+        }
+    }
+
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        velocity.x < -1.0,
+        "wall jump should push away from the wall, got {velocity:?}"
+    );
+    assert!(
+        velocity.y > 1.0,
+        "wall jump should push upward, got {velocity:?}"
+    );
+}
+
+#[test]
+fn no_wall_jump_in_open_air() {
+    let mut app = wall_jump_app();
+    let character = spawn_jumping_character(app.world_mut());
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert!(app.world().get::<TouchingWall>(character).is_none());
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert_eq!(velocity, Vec3::ZERO, "no wall means no wall jump");
+}
+
+/// Character standing on a floor at y = 0, steering toward +X, with an obstacle
+/// of `height` whose face sits just past the capsule. Returns the character's
+/// height after a few ticks.
+fn walk_into_obstacle(height: f32) -> f32 {
+    let mut app = step_up_app();
+    let standing_y = CHARACTER_CAPSULE_HEIGHT / 2.0 + CHARACTER_CAPSULE_RADIUS;
+    let mut action_state = ActionState::<PlayerActions>::default();
+    action_state.set_axis_pair(&PlayerActions::Move, Vec2::new(-1.0, 0.0));
+    let character = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Position(Vec3::new(0.0, standing_y, 0.0)),
+            Rotation::default(),
+            action_state,
+        ))
+        .id();
+    app.world_mut().spawn((
+        RigidBody::Static,
+        Collider::cuboid(40.0, 1.0, 40.0),
+        Position(Vec3::new(0.0, -0.5, 0.0)),
+        terrain_collision_layers(),
+    ));
+    app.world_mut().spawn((
+        RigidBody::Static,
+        Collider::cuboid(4.0, height, 4.0),
+        Position(Vec3::new(CHARACTER_CAPSULE_RADIUS + 2.2, height / 2.0, 0.0)),
+        terrain_collision_layers(),
+    ));
+
+    for _ in 0..5 {
+        app.update();
+    }
+    app.world().get::<Position>(character).unwrap().y - standing_y
+}
+
+#[test]
+fn character_steps_up_one_voxel_ledge() {
+    let rise = walk_into_obstacle(1.0);
+    assert!(
+        (rise - 1.0).abs() < 0.05,
+        "character should climb onto the one-voxel step, rose {rise}"
+    );
+}
+
+#[test]
+fn character_blocked_by_two_voxel_wall() {
+    let rise = walk_into_obstacle(2.0);
+    assert!(
+        rise.abs() < 0.05,
+        "two-voxel wall is too tall to step onto, rose {rise}"
+    );
+}
+
+/// Horizontal speed of a grounded, idle character 10 ticks after moving at 10 u/s.
+fn idle_speed_after_ten_ticks(config: MovementConfig) -> f32 {
+    let mut app = physics_app();
+    app.insert_resource(config);
+    app.add_systems(FixedUpdate, apply_ground_drag);
+    let character = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::new(&config, &CharacterDimensions::default()),
+            Position(Vec3::ZERO),
+            Rotation::default(),
+            LinearVelocity(Vec3::new(10.0, 0.0, 0.0)),
+            ActionState::<PlayerActions>::default(),
+            IsGrounded,
+        ))
+        .id();
+
+    for _ in 0..10 {
+        app.update();
+    }
+    app.world()
+        .get::<LinearVelocity>(character)
+        .unwrap()
+        .length()
+}
+
+#[test]
+fn ground_drag_stops_idle_character_faster_than_default() {
+    let default_speed = idle_speed_after_ten_ticks(MovementConfig::default());
+    let drag_speed = idle_speed_after_ten_ticks(MovementConfig {
+        ground_drag: 8.0,
+        ..default()
+    });
+
+    assert!(
+        (default_speed - 10.0).abs() < 0.01,
+        "zero-friction default should keep sliding, got {default_speed}"
+    );
+    assert!(
+        drag_speed < default_speed * 0.5,
+        "ground drag should slow the character, got {drag_speed} vs {default_speed}"
+    );
+}
+
+fn spawn_character_at(world: &mut World, x: f32, velocity: Vec3) -> Entity {
+    world
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Position(Vec3::new(x, 0.0, 0.0)),
+            Rotation::default(),
+            LinearVelocity(velocity),
+        ))
+        .id()
+}
+
+fn position_x(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Position>(entity).unwrap().x
+}
+
+#[test]
+fn overlapping_characters_resolve_apart() {
+    let mut app = physics_app();
+    let contact = 2.0 * CHARACTER_CAPSULE_RADIUS;
+    let a = spawn_character_at(app.world_mut(), 0.0, Vec3::ZERO);
+    let b = spawn_character_at(app.world_mut(), contact - 0.2, Vec3::ZERO);
+
+    for _ in 0..30 {
+        app.update();
+    }
+
+    let gap = position_x(&app, b) - position_x(&app, a);
+    assert!(
+        gap >= contact - 0.05,
+        "characters should be pushed apart, centers {gap} apart"
+    );
+}
+
+/// Mirrors the client/server `handle_character_movement`, grounded and with
+/// the `CharacterContacts` blocking.
+fn contact_movement(
+    time: Res<Time>,
+    config: Res<MovementConfig>,
+    contacts: CharacterContacts,
+    mut query: Query<(Entity, &ActionState<PlayerActions>, &ComputedMass, Forces)>,
+) {
+    for (entity, action_state, mass, mut forces) in &mut query {
+        apply_movement(
+            mass,
+            time.delta_secs(),
+            action_state,
+            config.move_deadzone,
+            None,
+            true,
+            None,
+            &contacts.blocked_directions(entity),
+            &mut forces,
+        );
+    }
+}
+
+/// A character walking toward +X into one standing just ahead of it, both
+/// moved by `contact_movement` under `collision`. Returns how far the standing
+/// character was pushed and the final gap between their centers.
+fn walk_into_character(collision: CharacterCollisionResponse) -> (f32, f32) {
+    let mut app = physics_app();
+    app.insert_resource(MovementConfig {
+        character_collision: collision,
+        ..default()
+    });
+    app.add_systems(FixedUpdate, contact_movement);
+    let contact = 2.0 * CHARACTER_CAPSULE_RADIUS;
+    let pusher = spawn_character_at(app.world_mut(), 0.0, Vec3::ZERO);
+    let mut walking = ActionState::<PlayerActions>::default();
+    walking.set_axis_pair(&PlayerActions::Move, Vec2::new(-1.0, 0.0));
+    app.world_mut().entity_mut(pusher).insert(walking);
+    let standing = spawn_character_at(app.world_mut(), contact + 0.5, Vec3::ZERO);
+    app.world_mut()
+        .entity_mut(standing)
+        .insert(ActionState::<PlayerActions>::default());
+    let standing_x = position_x(&app, standing);
+
+    for _ in 0..60 {
+        app.update();
+    }
+
+    (
+        position_x(&app, standing) - standing_x,
+        position_x(&app, standing) - position_x(&app, pusher),
+    )
+}
+
+#[test]
+fn solid_characters_cannot_shove_each_other() {
+    let (shoved, gap) = walk_into_character(CharacterCollisionResponse::Solid);
+
+    assert!(
+        gap >= 2.0 * CHARACTER_CAPSULE_RADIUS - 0.05,
+        "characters penetrated, centers {gap} apart"
+    );
+    assert!(
+        shoved.abs() < 0.05,
+        "standing character should not be shoved, moved {shoved}"
+    );
+}
+
+#[test]
+fn walking_into_a_character_pushes_it_back_by_default() {
+    assert_eq!(
+        MovementConfig::default().character_collision,
+        CharacterCollisionResponse::PushBack
+    );
+    let (shoved, _) = walk_into_character(CharacterCollisionResponse::PushBack);
+
+    assert!(
+        shoved > 1.0,
+        "a walking character should shove a standing one, moved {shoved}"
+    );
+}
+
+#[test]
+fn ground_ray_origin_tracks_capsule_height() {
+    let tall = CharacterDimensions {
+        radius: 1.0,
+        height: 6.0,
+    };
+    let position = Vec3::new(3.0, 10.0, -2.0);
+
+    assert_eq!(tall.ground_ray_origin(position), Vec3::new(3.0, 7.0, -2.0));
+    assert_eq!(tall.feet_offset(), 4.0);
+    assert_eq!(
+        CharacterDimensions::default().ground_ray_origin(position),
+        position - Vec3::Y * (CHARACTER_CAPSULE_HEIGHT / 2.0)
+    );
+}
+
+#[test]
+fn tall_character_standing_on_floor_is_grounded() {
+    let mut app = physics_app();
+    app.add_systems(FixedUpdate, detect_grounded);
+    let tall = CharacterDimensions {
+        radius: 1.0,
+        height: 8.0,
+    };
+    // Feet rest on the floor 5 units below the center, beyond the 4-unit reach
+    // of the default capsule's ground ray.
+    let character = app
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            tall,
+            CharacterPhysicsBundle::new(&MovementConfig::default(), &tall),
+            Position(Vec3::new(0.0, tall.feet_offset(), 0.0)),
+            Rotation::default(),
+        ))
+        .id();
+    app.world_mut().spawn((
+        RigidBody::Static,
+        Collider::cuboid(40.0, 1.0, 40.0),
+        Position(Vec3::new(0.0, -0.5, 0.0)),
+        terrain_collision_layers(),
+    ));
+
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get::<IsGrounded>(character).is_some());
+}
+
+fn dodge_app() -> App {
+    let mut app = physics_app();
+    app.insert_resource(LocalTimeline::default());
+    app.init_resource::<DodgeConfig>();
+    app.init_resource::<MovementConfig>();
+    app.add_systems(FixedUpdate, (apply_dodge, expire_invulnerability).chain());
+    app
+}
+
+/// Character at the origin steering toward +X.
+fn spawn_dodging_character(world: &mut World) -> Entity {
+    let mut action_state = ActionState::<PlayerActions>::default();
+    action_state.set_axis_pair(&PlayerActions::Move, Vec2::new(-1.0, 0.0));
+    world
+        .spawn((
+            CharacterMarker,
+            CharacterPhysicsBundle::default(),
+            Position(Vec3::ZERO),
+            Rotation::default(),
+            action_state,
+        ))
+        .id()
+}
+
+/// Releases and re-presses Dodge, then runs one tick.
+fn press_dodge(app: &mut App, character: Entity) {
+    let mut action_state = app
+        .world_mut()
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap();
+    action_state.release(&PlayerActions::Dodge);
+    action_state.press(&PlayerActions::Dodge);
+    app.update();
+}
+
+fn release_dodge(app: &mut App, character: Entity) {
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap()
+        .release(&PlayerActions::Dodge);
+}
+
+fn advance_timeline(app: &mut App, delta: i16) {
+    app.world_mut()
+        .resource_mut::<LocalTimeline>()
+        .apply_delta(delta);
+}
+
+#[test]
+fn dodge_bursts_along_input_with_expiring_iframes() {
+    let mut app = dodge_app();
+    let config = *app.world().resource::<DodgeConfig>();
+    let character = spawn_dodging_character(app.world_mut());
+
+    press_dodge(&mut app, character);
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        (velocity - Vec3::X * config.speed).length() < 0.1,
+        "dodge should move along the input at dodge speed, got {velocity:?}"
+    );
+    assert_eq!(
+        app.world().get::<Invulnerable>(character),
+        Some(&Invulnerable {
+            expires_at: Tick(0) + config.invulnerable_ticks as i16
+        })
+    );
+
+    release_dodge(&mut app, character);
+    advance_timeline(&mut app, config.invulnerable_ticks as i16 - 1);
+    app.update();
+    assert!(app.world().get::<Invulnerable>(character).is_some());
+
+    advance_timeline(&mut app, 1);
+    app.update();
+    assert!(
+        app.world().get::<Invulnerable>(character).is_none(),
+        "i-frames should expire"
+    );
+}
+
+#[test]
+fn dodge_is_refused_while_on_cooldown() {
+    let mut app = dodge_app();
+    let config = *app.world().resource::<DodgeConfig>();
+    let character = spawn_dodging_character(app.world_mut());
+
+    press_dodge(&mut app, character);
+    app.world_mut()
+        .get_mut::<LinearVelocity>(character)
+        .unwrap()
+        .0 = Vec3::ZERO;
+
+    advance_timeline(&mut app, config.cooldown_ticks as i16);
+    press_dodge(&mut app, character);
+    assert_eq!(
+        app.world().get::<LinearVelocity>(character).unwrap().0,
+        Vec3::ZERO,
+        "second dodge inside the cooldown should be ignored"
+    );
+
+    advance_timeline(&mut app, 1);
+    press_dodge(&mut app, character);
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        (velocity.x - config.speed).abs() < 0.1,
+        "dodge should be available once the cooldown ends, got {velocity:?}"
+    );
+}
+
+/// Mirrors the client/server `handle_character_movement`.
+fn lockable_movement(
+    time: Res<Time>,
+    locks: MovementLocks,
+    mut query: Query<(
+        Entity,
+        &ActionState<PlayerActions>,
+        &ComputedMass,
+        Option<&ActiveBuffs>,
+        Forces,
+    )>,
+) {
+    for (entity, action_state, mass, buffs, mut forces) in &mut query {
+        if locks.is_locked(entity) {
+            continue;
+        }
+        apply_movement(
+            mass,
+            time.delta_secs(),
+            action_state,
+            MovementConfig::default().move_deadzone,
+            buffs,
+            true,
+            None,
+            &[],
+            &mut forces,
+        );
+    }
+}
+
+#[test]
+fn locked_dash_keeps_its_velocity_against_movement_input() {
+    let mut app = physics_app();
+    app.add_systems(FixedUpdate, lockable_movement);
+    let dash_velocity = Vec3::Z * 15.0;
+    let character = spawn_dodging_character(app.world_mut());
+    app.world_mut()
+        .get_mut::<LinearVelocity>(character)
+        .unwrap()
+        .0 = dash_velocity;
+    let dash = app
+        .world_mut()
+        .spawn((
+            ActiveAbility {
+                def_id: AbilityId("dash".into()),
+                caster: character,
+                original_caster: character,
+                target: character,
+                phase: AbilityPhase::Active,
+                phase_start_tick: Tick(0),
+                ability_slot: 0,
+                depth: 0,
+                landed_hit: false,
+            },
+            protocol::ability::LockMovement,
+        ))
+        .id();
+
+    for _ in 0..5 {
+        app.update();
+    }
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        (velocity - dash_velocity).length() < 1e-3,
+        "steering should not bend a locked dash, got {velocity:?}"
+    );
+
+    app.world_mut()
+        .get_mut::<ActiveAbility>(dash)
+        .unwrap()
+        .phase = AbilityPhase::Recovery;
+    app.update();
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        velocity.x > 1.0,
+        "movement resumes once the dash reaches Recovery, got {velocity:?}"
+    );
+}
+
+#[test]
+fn stunned_character_can_neither_move_nor_dodge() {
+    let mut app = dodge_app();
+    app.add_systems(FixedUpdate, lockable_movement.before(apply_dodge));
+    let character = spawn_dodging_character(app.world_mut());
+    app.world_mut().entity_mut(character).insert(Stunned {
+        expires_tick: Tick(100),
+    });
+
+    for _ in 0..5 {
+        app.update();
+    }
+    press_dodge(&mut app, character);
+
+    assert_eq!(
+        app.world().get::<LinearVelocity>(character).unwrap().0,
+        Vec3::ZERO,
+        "a stun suppresses both steering and the dodge"
+    );
+    assert!(
+        app.world().get::<Invulnerable>(character).is_none(),
+        "a refused dodge grants no i-frames"
+    );
+
+    app.world_mut().entity_mut(character).remove::<Stunned>();
+    release_dodge(&mut app, character);
+    press_dodge(&mut app, character);
+    assert!(
+        app.world().get::<Invulnerable>(character).is_some(),
+        "dodging works again once the stun ends"
+    );
+}
+
+#[test]
+fn speed_buff_raises_top_movement_speed() {
+    let mut app = physics_app();
+    app.add_systems(FixedUpdate, lockable_movement);
+    let character = spawn_dodging_character(app.world_mut());
+    app.world_mut()
+        .entity_mut(character)
+        .insert(ActiveBuffs(vec![ActiveBuff {
+            stat: "speed".into(),
+            multiplier: 1.5,
+            expires_tick: Tick(999),
+            source: AbilityId("test_buff".into()),
+        }]));
+
+    for _ in 0..20 {
+        app.update();
+    }
+    // 1.5x the unbuffed top speed of 15 units/s.
+    let velocity = app.world().get::<LinearVelocity>(character).unwrap().0;
+    assert!(
+        (velocity.x - 22.5).abs() < 1e-3,
+        "speed buff should raise the top speed to 22.5, got {velocity:?}"
+    );
+}
+
+#[test]
+fn stick_drift_inside_the_deadzone_does_not_move_the_character() {
+    let mut app = physics_app();
+    app.add_systems(FixedUpdate, lockable_movement);
+    let character = spawn_dodging_character(app.world_mut());
+    let deadzone = MovementConfig::default().move_deadzone;
+    app.world_mut()
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap()
+        .set_axis_pair(&PlayerActions::Move, Vec2::new(-deadzone * 0.5, 0.0));
+
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(
+        app.world().get::<LinearVelocity>(character).unwrap().0,
+        Vec3::ZERO,
+        "sub-deadzone input should be ignored"
+    );
+}
+
+#[test]
+fn move_deadzone_rescales_input_past_its_edge() {
+    let deadzone = 0.2;
+    assert_eq!(
+        apply_move_deadzone(Vec2::new(0.1, 0.1), deadzone),
+        Vec2::ZERO
+    );
+    let halfway = apply_move_deadzone(Vec2::new(0.0, 0.6), deadzone);
+    assert!(
+        (halfway - Vec2::new(0.0, 0.5)).length() < 1e-5,
+        "60% deflection is halfway between the deadzone edge and full, got {halfway:?}"
+    );
+    let full = apply_move_deadzone(Vec2::new(3.0, 4.0), deadzone);
+    assert!(
+        (full - Vec2::new(0.6, 0.8)).length() < 1e-5,
+        "over-deflection clamps to unit length, got {full:?}"
+    );
+}
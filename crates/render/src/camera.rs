@@ -2,6 +2,10 @@ use avian3d::prelude::Position;
 use bevy::prelude::*;
 use lightyear::prelude::*;
 
+use crate::camera_shake::CameraShake;
+use crate::day_night::Sun;
+use protocol::settings::Settings;
+
 const BASE_OFFSET: Vec3 = Vec3::new(0.0, 18.0, -36.0);
 const BASE_LIGHT_OFFSET: Vec3 = Vec3::new(8.0, 16.0, 8.0);
 const ORBIT_LERP_SPEED: f32 = 20.0;
@@ -53,6 +57,7 @@ pub(crate) fn setup_lighting(mut commands: Commands) {
             ..default()
         },
         Transform::default().looking_to(Vec3::new(-0.5, -1.0, -0.5), Vec3::Y),
+        Sun,
     ));
 }
 
@@ -89,6 +94,9 @@ pub(crate) fn update_camera_orbit(time: Res<Time>, mut query: Query<&mut CameraO
 }
 
 pub(crate) fn follow_player(
+    time: Res<Time>,
+    shake: Res<CameraShake>,
+    settings: Res<Settings>,
     player_query: Query<&Position, With<Controlled>>,
     mut camera_query: Query<(&mut Transform, &CameraOrbitState), With<Camera3d>>,
 ) {
@@ -102,6 +110,14 @@ pub(crate) fn follow_player(
     let rotated_offset = Quat::from_rotation_y(orbit.current_angle) * BASE_OFFSET;
     camera_transform.translation = **player_pos + rotated_offset;
     camera_transform.look_at(**player_pos, Dir3::Y);
+
+    if !settings.camera_shake.enabled {
+        trace!("follow_player: camera shake disabled in settings");
+        return;
+    }
+    let (shake_offset, shake_roll) = shake.offset(time.elapsed_secs());
+    camera_transform.translation += shake_offset;
+    camera_transform.rotate_local_z(shake_roll);
 }
 
 /// Updates light position to follow camera rotation around the player.
@@ -123,3 +139,76 @@ pub(crate) fn update_light_position(
     let rotated_offset = Quat::from_rotation_y(orbit.current_angle) * BASE_LIGHT_OFFSET;
     light_transform.translation = **player_pos + rotated_offset;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::camera_shake::decay_camera_shake;
+    use std::time::Duration;
+
+    fn shake_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+            Duration::from_millis(100),
+        ));
+        app.init_resource::<CameraShake>();
+        app.init_resource::<Settings>();
+        app.add_systems(Update, (decay_camera_shake, follow_player).chain());
+        app.world_mut()
+            .spawn((Controlled, Position(Vec3::new(1.0, 0.0, 2.0))));
+        let camera = app
+            .world_mut()
+            .spawn((
+                Camera3d::default(),
+                Transform::default(),
+                CameraOrbitState::default(),
+            ))
+            .id();
+        app.update();
+        (app, camera)
+    }
+
+    fn follow_translation() -> Vec3 {
+        Vec3::new(1.0, 0.0, 2.0) + BASE_OFFSET
+    }
+
+    #[test]
+    fn trauma_offsets_camera_then_decays_to_follow_position() {
+        let (mut app, camera) = shake_app();
+        let at_rest = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!(at_rest.distance(follow_translation()) < 1e-4);
+
+        app.world_mut()
+            .resource_mut::<CameraShake>()
+            .add_trauma(1.0);
+        app.update();
+        let shaken = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!(
+            shaken.distance(follow_translation()) > 1e-3,
+            "trauma should offset the camera"
+        );
+
+        for _ in 0..20 {
+            app.update();
+        }
+        assert_eq!(app.world().resource::<CameraShake>().trauma, 0.0);
+        let settled = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!(settled.distance(follow_translation()) < 1e-4);
+    }
+
+    #[test]
+    fn disabled_shake_leaves_camera_on_follow_position() {
+        let (mut app, camera) = shake_app();
+        app.world_mut()
+            .resource_mut::<Settings>()
+            .camera_shake
+            .enabled = false;
+        app.world_mut()
+            .resource_mut::<CameraShake>()
+            .add_trauma(1.0);
+        app.update();
+        let translation = app.world().get::<Transform>(camera).unwrap().translation;
+        assert!(translation.distance(follow_translation()) < 1e-4);
+    }
+}
@@ -0,0 +1,94 @@
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::ability::AoEHitbox;
+use protocol::Health;
+
+/// Trauma removed per second; a full-trauma shake settles in one second.
+const TRAUMA_DECAY_PER_SEC: f32 = 1.0;
+/// Camera translation at full trauma, in world units.
+const MAX_SHAKE_OFFSET: f32 = 0.8;
+/// Camera roll at full trauma, in radians.
+const MAX_SHAKE_ROLL: f32 = 0.08;
+/// Base frequency of the shake noise, in Hz.
+const SHAKE_FREQUENCY: f32 = 25.0;
+/// Explosions farther than this from the local player do not shake the camera.
+const EXPLOSION_SHAKE_RADIUS: f32 = 20.0;
+/// Trauma added by an explosion centered on the local player.
+const EXPLOSION_TRAUMA: f32 = 0.6;
+/// Trauma added when the local player loses their entire max health in one hit.
+const FULL_HEALTH_DAMAGE_TRAUMA: f32 = 1.0;
+
+/// Client-side screen shake. Trauma in `[0, 1]` decays over time; the applied
+/// shake scales with trauma squared so small hits stay subtle. Turned off by
+/// [`Settings::camera_shake`](protocol::settings::Settings::camera_shake).
+#[derive(Resource, Debug, Clone, Default)]
+pub struct CameraShake {
+    pub trauma: f32,
+}
+
+impl CameraShake {
+    /// Adds trauma, saturating at 1.
+    pub fn add_trauma(&mut self, amount: f32) {
+        self.trauma = (self.trauma + amount).clamp(0.0, 1.0);
+    }
+
+    /// Translation and roll to layer on top of the follow transform at time `t` seconds.
+    pub fn offset(&self, t: f32) -> (Vec3, f32) {
+        if self.trauma <= 0.0 {
+            return (Vec3::ZERO, 0.0);
+        }
+        let shake = self.trauma * self.trauma;
+        let phase = t * SHAKE_FREQUENCY;
+        let noise = Vec3::new(
+            (phase * 1.0).sin() * (phase * 0.37).cos(),
+            (phase * 1.3 + 1.7).sin() * (phase * 0.51).cos(),
+            (phase * 0.9 + 3.1).sin() * (phase * 0.43).cos(),
+        );
+        let roll = (phase * 1.1 + 5.3).sin() * MAX_SHAKE_ROLL * shake;
+        (noise * MAX_SHAKE_OFFSET * shake, roll)
+    }
+}
+
+pub(crate) fn decay_camera_shake(time: Res<Time>, mut shake: ResMut<CameraShake>) {
+    shake.trauma = (shake.trauma - TRAUMA_DECAY_PER_SEC * time.delta_secs()).max(0.0);
+}
+
+/// Adds trauma proportional to how much health the local player just lost.
+pub(crate) fn add_trauma_on_damage(
+    player_query: Query<&Health, (With<Controlled>, Changed<Health>)>,
+    mut last_health: Local<Option<f32>>,
+    mut shake: ResMut<CameraShake>,
+) {
+    let Ok(health) = player_query.single() else {
+        trace!("add_trauma_on_damage: no single local player with changed health");
+        return;
+    };
+    let previous = last_health.replace(health.current);
+    let Some(previous) = previous else {
+        trace!("add_trauma_on_damage: first health sample for local player");
+        return;
+    };
+    let lost = previous - health.current;
+    if lost > 0.0 && health.max > 0.0 {
+        shake.add_trauma(FULL_HEALTH_DAMAGE_TRAUMA * lost / health.max);
+    }
+}
+
+/// Adds trauma for AoE hitboxes spawning near the local player, falling off with distance.
+pub(crate) fn add_trauma_on_explosion(
+    player_query: Query<&Position, With<Controlled>>,
+    explosions: Query<&Position, Added<AoEHitbox>>,
+    mut shake: ResMut<CameraShake>,
+) {
+    let Ok(player_pos) = player_query.single() else {
+        trace!("add_trauma_on_explosion: no single local player");
+        return;
+    };
+    for explosion_pos in &explosions {
+        let distance = player_pos.0.distance(explosion_pos.0);
+        if distance < EXPLOSION_SHAKE_RADIUS {
+            shake.add_trauma(EXPLOSION_TRAUMA * (1.0 - distance / EXPLOSION_SHAKE_RADIUS));
+        }
+    }
+}
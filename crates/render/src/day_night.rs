@@ -0,0 +1,49 @@
+use bevy::prelude::*;
+use protocol::{WorldTime, WorldTimeConfig};
+
+/// Lux at noon; falls to [`TWILIGHT_ILLUMINANCE`] as the sun reaches the horizon.
+const NOON_ILLUMINANCE: f32 = light_consts::lux::AMBIENT_DAYLIGHT;
+const TWILIGHT_ILLUMINANCE: f32 = 400.0;
+/// Moonlight, kept bright enough to play by.
+const NIGHT_ILLUMINANCE: f32 = 250.0;
+const NOON_COLOR: Color = Color::WHITE;
+const HORIZON_COLOR: Color = Color::srgb(1.0, 0.6, 0.35);
+const NIGHT_COLOR: Color = Color::srgb(0.55, 0.65, 1.0);
+/// Tilts the sun's east-west arc toward +Z so it is never straight overhead.
+const ARC_TILT: f32 = 0.35;
+
+/// Marker for the directional light driven by [`WorldTime`]: the sun by day,
+/// the moon by night.
+#[derive(Component)]
+pub struct Sun;
+
+/// Direction, color and illuminance of the [`Sun`] light `day_fraction` of
+/// the way through a day (see [`WorldTimeConfig::day_fraction`]).
+fn sun_light(day_fraction: f32) -> (Vec3, Color, f32) {
+    let angle = day_fraction * std::f32::consts::TAU;
+    let sun_position = Vec3::new(angle.cos(), angle.sin(), ARC_TILT).normalize();
+    let elevation = angle.sin();
+    if elevation >= 0.0 {
+        let color = HORIZON_COLOR.mix(&NOON_COLOR, elevation);
+        let illuminance = TWILIGHT_ILLUMINANCE.lerp(NOON_ILLUMINANCE, elevation);
+        (-sun_position, color, illuminance)
+    } else {
+        // The moon sits opposite the sun, so it is above the horizon.
+        (sun_position, NIGHT_COLOR, NIGHT_ILLUMINANCE)
+    }
+}
+
+/// Points and tints the [`Sun`] for the current [`WorldTime`]. Purely
+/// cosmetic; the simulation never reads the light.
+pub(crate) fn update_sun(
+    time: Res<WorldTime>,
+    config: Res<WorldTimeConfig>,
+    mut sun_query: Query<(&mut Transform, &mut DirectionalLight), With<Sun>>,
+) {
+    let (direction, color, illuminance) = sun_light(config.day_fraction(*time));
+    for (mut transform, mut light) in &mut sun_query {
+        *transform = Transform::default().looking_to(direction, Vec3::Y);
+        light.color = color;
+        light.illuminance = illuminance;
+    }
+}
@@ -4,17 +4,45 @@ use bevy::render::render_resource::PrimitiveTopology;
 use protocol::billboard::billboard_material::{BillboardExt, BillboardMaterial};
 use protocol::*;
 
+use crate::palette::ColorPalette;
+
 #[derive(Component)]
 pub(crate) struct HealthBarRoot;
 
+/// The fill quad. `displayed` is the health ratio currently drawn, which eases
+/// toward the real ratio; `None` until the first update snaps it into place.
+#[derive(Component, Default)]
+pub(crate) struct HealthBarForeground {
+    displayed: Option<f32>,
+}
+
 #[derive(Component)]
-pub(crate) struct HealthBarForeground;
+pub(crate) struct HealthBarBackground;
 
 const HEALTH_BAR_WIDTH: f32 = 3.0;
 const HEALTH_BAR_HEIGHT: f32 = 0.3;
 const HEALTH_BAR_Y_OFFSET: f32 = 5.0;
-const HEALTH_BAR_FG_NORMAL: Color = Color::srgb(0.1, 0.9, 0.1);
-const HEALTH_BAR_FG_INVULN: Color = Color::srgb(0.2, 0.5, 1.0);
+
+/// How health bar fills follow health changes.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct HealthBarConfig {
+    /// Exponential rate (per second) at which the drawn fill closes the gap to
+    /// the real health ratio. `f32::INFINITY` snaps instantly.
+    pub fill_speed: f32,
+}
+
+impl Default for HealthBarConfig {
+    fn default() -> Self {
+        Self { fill_speed: 10.0 }
+    }
+}
+
+/// Moves `displayed` toward `target` by the fraction of the gap that
+/// `fill_speed` closes in `dt` seconds.
+fn ease_fill(displayed: f32, target: f32, fill_speed: f32, dt: f32) -> f32 {
+    let t = 1.0 - (-fill_speed * dt).exp();
+    displayed + (target - displayed) * t
+}
 
 /// Creates a Z-facing quad centered at origin.
 fn health_bar_quad() -> Mesh {
@@ -40,6 +68,7 @@ pub(crate) fn spawn_health_bar(
     entity: Entity,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<BillboardMaterial>,
+    palette: ColorPalette,
 ) {
     let bg_mesh = meshes.add(health_bar_quad());
     let fg_mesh = meshes.add(health_bar_quad());
@@ -48,7 +77,7 @@ pub(crate) fn spawn_health_bar(
     // transform a Z offset into a camera-dependent direction.
     let bg_material = materials.add(BillboardMaterial {
         base: StandardMaterial {
-            base_color: Color::srgb(0.8, 0.1, 0.1),
+            base_color: palette.health_bar_background(),
             unlit: true,
             double_sided: true,
             cull_mode: None,
@@ -59,7 +88,7 @@ pub(crate) fn spawn_health_bar(
     });
     let fg_material = materials.add(BillboardMaterial {
         base: StandardMaterial {
-            base_color: HEALTH_BAR_FG_NORMAL,
+            base_color: palette.health_bar_foreground(),
             unlit: true,
             double_sided: true,
             cull_mode: None,
@@ -80,12 +109,13 @@ pub(crate) fn spawn_health_bar(
                 ))
                 .with_children(|bar| {
                     bar.spawn((
+                        HealthBarBackground,
                         Mesh3d(bg_mesh),
                         MeshMaterial3d(bg_material),
                         Transform::default(),
                     ));
                     bar.spawn((
-                        HealthBarForeground,
+                        HealthBarForeground::default(),
                         Mesh3d(fg_mesh),
                         MeshMaterial3d(fg_material),
                         Transform::default(),
@@ -99,10 +129,11 @@ pub(crate) fn on_invulnerable_added(
     children_query: Query<&Children>,
     fg_query: Query<&MeshMaterial3d<BillboardMaterial>, With<HealthBarForeground>>,
     mut materials: ResMut<Assets<BillboardMaterial>>,
+    palette: Res<ColorPalette>,
 ) {
     set_fg_color(
         trigger.entity,
-        HEALTH_BAR_FG_INVULN,
+        palette.health_bar_invulnerable(),
         &children_query,
         &fg_query,
         &mut materials,
@@ -114,10 +145,11 @@ pub(crate) fn on_invulnerable_removed(
     children_query: Query<&Children>,
     fg_query: Query<&MeshMaterial3d<BillboardMaterial>, With<HealthBarForeground>>,
     mut materials: ResMut<Assets<BillboardMaterial>>,
+    palette: Res<ColorPalette>,
 ) {
     set_fg_color(
         trigger.entity,
-        HEALTH_BAR_FG_NORMAL,
+        palette.health_bar_foreground(),
         &children_query,
         &fg_query,
         &mut materials,
@@ -149,7 +181,36 @@ fn set_fg_color(
     }
 }
 
-/// Updates fg mesh vertex positions to reflect current health.
+/// Recolors every existing health bar when the player switches palettes.
+pub(crate) fn apply_palette_to_health_bars(
+    palette: Res<ColorPalette>,
+    bar_root_query: Query<(&ChildOf, &Children), With<HealthBarRoot>>,
+    invulnerable_query: Query<(), With<Invulnerable>>,
+    fg_query: Query<&MeshMaterial3d<BillboardMaterial>, With<HealthBarForeground>>,
+    bg_query: Query<&MeshMaterial3d<BillboardMaterial>, With<HealthBarBackground>>,
+    mut materials: ResMut<Assets<BillboardMaterial>>,
+) {
+    for (child_of, children) in &bar_root_query {
+        let fg_color = if invulnerable_query.contains(child_of.parent()) {
+            palette.health_bar_invulnerable()
+        } else {
+            palette.health_bar_foreground()
+        };
+        for child in children {
+            let (handle, color) = match (fg_query.get(*child), bg_query.get(*child)) {
+                (Ok(handle), _) => (handle, fg_color),
+                (_, Ok(handle)) => (handle, palette.health_bar_background()),
+                _ => continue,
+            };
+            if let Some(mat) = materials.get_mut(&handle.0) {
+                mat.base.base_color = color;
+            }
+        }
+    }
+}
+
+/// Updates fg mesh vertex positions to reflect current health, easing the fill
+/// toward it at [`HealthBarConfig::fill_speed`].
 ///
 /// Shrinks the fg quad from the left edge while keeping the right edge fixed,
 /// so the green bar recedes leftward as health decreases. Vertex positions are
@@ -157,9 +218,11 @@ fn set_fg_color(
 /// shader operates in view space — a local-space translation offset would get
 /// rotated by the character's Y-rotation before the shader sees it.
 pub(crate) fn update_health_bars(
+    time: Res<Time>,
+    config: Res<HealthBarConfig>,
     health_query: Query<&Health>,
     bar_root_query: Query<(&ChildOf, &Children), With<HealthBarRoot>>,
-    fg_query: Query<&Mesh3d, With<HealthBarForeground>>,
+    mut fg_query: Query<(&Mesh3d, &mut HealthBarForeground)>,
     mut meshes: ResMut<Assets<Mesh>>,
 ) {
     let hw = HEALTH_BAR_WIDTH / 2.0;
@@ -169,13 +232,17 @@ pub(crate) fn update_health_bars(
         let Ok(health) = health_query.get(child_of.parent()) else {
             continue;
         };
-        let ratio = (health.current / health.max).clamp(0.0, 1.0);
-        let left_x = hw - HEALTH_BAR_WIDTH * ratio;
+        let target = (health.current / health.max).clamp(0.0, 1.0);
 
         for child in children {
-            let Ok(mesh_handle) = fg_query.get(*child) else {
+            let Ok((mesh_handle, mut fg)) = fg_query.get_mut(*child) else {
                 continue;
             };
+            let ratio = fg.displayed.map_or(target, |displayed| {
+                ease_fill(displayed, target, config.fill_speed, time.delta_secs())
+            });
+            fg.displayed = Some(ratio);
+            let left_x = hw - HEALTH_BAR_WIDTH * ratio;
             let Some(mesh) = meshes.get_mut(&mesh_handle.0) else {
                 continue;
             };
@@ -191,3 +258,80 @@ pub(crate) fn update_health_bars(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::mesh::VertexAttributeValues;
+    use bevy::time::TimeUpdateStrategy;
+    use std::time::Duration;
+
+    const FRAME_SECS: f32 = 1.0 / 60.0;
+
+    fn health_bar_app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(AssetPlugin::default());
+        app.init_asset::<Mesh>();
+        app.init_resource::<HealthBarConfig>();
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f32(
+            FRAME_SECS,
+        )));
+        app.add_systems(Update, update_health_bars);
+
+        let mesh = app
+            .world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(health_bar_quad());
+        let character = app.world_mut().spawn(Health::new(100.0)).id();
+        let root = app
+            .world_mut()
+            .spawn((HealthBarRoot, ChildOf(character)))
+            .id();
+        let fg = app
+            .world_mut()
+            .spawn((HealthBarForeground::default(), Mesh3d(mesh), ChildOf(root)))
+            .id();
+        (app, character, fg)
+    }
+
+    /// Fill ratio drawn by the fg mesh, from its left edge.
+    fn drawn_fill(app: &App, fg: Entity) -> f32 {
+        let handle = &app.world().get::<Mesh3d>(fg).unwrap().0;
+        let mesh = app.world().resource::<Assets<Mesh>>().get(handle).unwrap();
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("health bar mesh should have positions");
+        };
+        (HEALTH_BAR_WIDTH / 2.0 - positions[0][0]) / HEALTH_BAR_WIDTH
+    }
+
+    #[test]
+    fn fill_eases_toward_health_ratio() {
+        let (mut app, character, fg) = health_bar_app();
+        app.update();
+        app.update();
+        assert!((drawn_fill(&app, fg) - 1.0).abs() < 1e-5, "starts full");
+
+        app.world_mut()
+            .get_mut::<Health>(character)
+            .unwrap()
+            .apply_damage(50.0);
+        app.update();
+        let fill = drawn_fill(&app, fg);
+        assert!(
+            fill < 1.0 && fill > 0.5 + 1e-3,
+            "one frame moves the fill partway, got {fill}"
+        );
+
+        for _ in 0..60 {
+            app.update();
+        }
+        let fill = drawn_fill(&app, fg);
+        assert!(
+            (fill - 0.5).abs() < 1e-3,
+            "converges on the ratio, got {fill}"
+        );
+    }
+}
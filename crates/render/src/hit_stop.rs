@@ -0,0 +1,81 @@
+use bevy::ecs::message::MessageReader;
+use bevy::prelude::*;
+use lightyear::prelude::Controlled;
+use protocol::settings::Settings;
+use protocol::{AbilityHitEvent, CharacterMarker};
+
+/// Time left on the current hit stop.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq)]
+pub struct HitStop {
+    pub remaining_secs: f32,
+}
+
+impl HitStop {
+    pub fn is_active(&self) -> bool {
+        self.remaining_secs > 0.0
+    }
+}
+
+/// Starts a [`HitStop`] when the local player lands or takes a heavy hit, and
+/// slows character animations while it runs. Client-side only: it slows
+/// character animation playback, never `Time<Virtual>`, so the networked fixed
+/// timestep is untouched. Tuned by [`Settings::hit_stop`].
+pub struct HitStopPlugin;
+
+impl Plugin for HitStopPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        app.init_resource::<HitStop>();
+        app.add_systems(
+            Update,
+            (start_hit_stop, apply_hit_stop_to_animations, tick_hit_stop).chain(),
+        );
+    }
+}
+
+/// Restarts the hit stop on a heavy hit the local player dealt or received.
+/// A rollback replaying the hit just restarts the same short timer.
+fn start_hit_stop(
+    settings: Res<Settings>,
+    mut hits: MessageReader<AbilityHitEvent>,
+    local_query: Query<(), With<Controlled>>,
+    mut hit_stop: ResMut<HitStop>,
+) {
+    let config = settings.hit_stop;
+    for hit in hits.read() {
+        if !config.enabled || hit.damage < config.heavy_hit_damage {
+            continue;
+        }
+        if local_query.contains(hit.caster) || local_query.contains(hit.victim) {
+            hit_stop.remaining_secs = config.duration_secs;
+        }
+    }
+}
+
+/// Sets every character animation's playback speed to the hit-stop time scale
+/// while one runs, and back to normal speed on the frame it ends.
+fn apply_hit_stop_to_animations(
+    settings: Res<Settings>,
+    hit_stop: Res<HitStop>,
+    mut was_active: Local<bool>,
+    mut players: Query<&mut AnimationPlayer, With<CharacterMarker>>,
+) {
+    let active = hit_stop.is_active();
+    if !active && !*was_active {
+        return;
+    }
+    *was_active = active;
+    let config = settings.hit_stop;
+    let speed = if active { config.time_scale } else { 1.0 };
+    for mut player in &mut players {
+        for (_, animation) in player.playing_animations_mut() {
+            animation.set_speed(speed);
+        }
+    }
+}
+
+/// Counts the hit stop down in real time, so it ends on schedule whatever the
+/// animation time scale.
+fn tick_hit_stop(time: Res<Time<Real>>, mut hit_stop: ResMut<HitStop>) {
+    hit_stop.remaining_secs = (hit_stop.remaining_secs - time.delta_secs()).max(0.0);
+}
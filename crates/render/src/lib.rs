@@ -1,11 +1,28 @@
 mod camera;
+mod camera_shake;
+mod day_night;
 mod health_bar;
+mod hit_stop;
+mod palette;
+mod reticle;
+mod settings;
+mod visual_interpolation;
+mod visual_smoothing;
+mod voxel_colors;
 
 pub use camera::CameraOrbitState;
+pub use camera_shake::CameraShake;
+pub use day_night::Sun;
+pub use health_bar::HealthBarConfig;
+pub use hit_stop::{HitStop, HitStopPlugin};
+pub use palette::ColorPalette;
+pub use reticle::{GroundAimPoint, GroundReticle};
+pub use visual_interpolation::VisualInterpolationPlugin;
+pub use visual_smoothing::{VisualSmoothing, VisualSmoothingPlugin};
+pub use voxel_colors::{voxel_vertex_colors, VoxelColorPlugin};
 
 use avian3d::prelude::Position;
 use bevy::prelude::*;
-use lightyear::frame_interpolation::{FrameInterpolate, FrameInterpolationPlugin};
 use lightyear::prelude::*;
 use protocol::billboard::billboard_material::BillboardMaterial;
 use protocol::billboard::shadow_only_material::ShadowOnlyMaterial;
@@ -33,52 +50,66 @@ impl Plugin for RenderPlugin {
         app.add_plugins(bevy::pbr::MaterialPlugin::<SpriteRigMaterial>::default());
         app.add_plugins(bevy::pbr::MaterialPlugin::<ShadowOnlyMaterial>::default());
 
-        app.add_systems(Startup, (camera::setup_camera, camera::setup_lighting));
+        app.init_resource::<CameraShake>();
+        app.init_resource::<ColorPalette>();
+        app.init_resource::<GroundAimPoint>();
+        app.init_resource::<HealthBarConfig>();
+        app.init_resource::<protocol::settings::Settings>();
+        app.init_resource::<WorldTime>();
+        app.init_resource::<WorldTimeConfig>();
+        app.add_systems(
+            Startup,
+            (
+                camera::setup_camera,
+                camera::setup_lighting,
+                reticle::spawn_ground_reticle,
+            ),
+        );
         app.add_systems(
             Update,
             (
+                settings::toggle_settings,
                 camera::handle_camera_rotation_input,
                 camera::update_camera_orbit,
+                camera_shake::add_trauma_on_damage,
+                camera_shake::add_trauma_on_explosion,
+                camera_shake::decay_camera_shake,
                 camera::follow_player,
                 camera::update_light_position,
                 health_bar::update_health_bars,
             )
                 .chain(),
         );
+        app.add_systems(
+            Update,
+            (
+                reticle::update_ground_aim_point,
+                reticle::update_ground_reticle.run_if(resource_exists::<AbilityDefs>),
+            )
+                .chain()
+                .after(camera::follow_player),
+        );
+
+        app.add_systems(
+            Update,
+            health_bar::apply_palette_to_health_bars.run_if(resource_changed::<ColorPalette>),
+        );
+        app.add_systems(Update, palette::tint_character_rigs);
+        app.add_systems(
+            Update,
+            day_night::update_sun.run_if(resource_changed::<WorldTime>),
+        );
 
         app.add_observer(add_health_bars);
         app.add_observer(health_bar::on_invulnerable_added);
         app.add_observer(health_bar::on_invulnerable_removed);
 
         app.add_plugins(sprite_rig::SpriteRigPlugin);
-
-        // FrameInterpolationPlugin for visual smoothing between physics ticks
-        app.add_plugins(FrameInterpolationPlugin::<Position>::default());
-        app.add_plugins(FrameInterpolationPlugin::<avian3d::prelude::Rotation>::default());
-
-        // Add visual interpolation components to predicted entities
-        app.add_observer(add_visual_interpolation_components);
-    }
-}
-
-fn add_visual_interpolation_components(
-    trigger: On<Add, Position>,
-    query: Query<Entity, With<Predicted>>,
-    mut commands: Commands,
-) {
-    if !query.contains(trigger.entity) {
-        return;
+        app.add_plugins(VisualInterpolationPlugin);
+        app.add_plugins(VisualSmoothingPlugin);
+        app.add_plugins(HitStopPlugin);
+        app.add_plugins(VoxelColorPlugin);
     }
-    commands.entity(trigger.entity).insert((
-        FrameInterpolate::<Position> {
-            trigger_change_detection: true,
-            ..default()
-        },
-        FrameInterpolate::<avian3d::prelude::Rotation> {
-            trigger_change_detection: true,
-            ..default()
-        },
-    ));
 }
 
 /// Spawns a health bar for any entity that receives a `Health` component.
@@ -93,6 +124,7 @@ fn add_health_bars(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<BillboardMaterial>>,
+    palette: Res<ColorPalette>,
 ) {
     let entity = trigger.entity;
     if let Ok((pos, rot)) = physics.get(entity) {
@@ -100,5 +132,11 @@ fn add_health_bars(
             .entity(entity)
             .insert(protocol::transform_from_physics(pos, rot));
     }
-    health_bar::spawn_health_bar(&mut commands, entity, &mut *meshes, &mut *materials);
+    health_bar::spawn_health_bar(
+        &mut commands,
+        entity,
+        &mut *meshes,
+        &mut *materials,
+        *palette,
+    );
 }
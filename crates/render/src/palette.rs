@@ -0,0 +1,256 @@
+use bevy::mesh::skinning::SkinnedMesh;
+use bevy::prelude::*;
+use protocol::billboard::sprite_rig_material::SpriteRigMaterial;
+use protocol::{CharacterMarker, ColorComponent, CHARACTER_COLORS};
+
+/// Client-side color scheme for character and health-bar colors.
+/// The colorblind variants are drawn from the Okabe-Ito palette.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorPalette {
+    #[default]
+    Default,
+    Deuteranopia,
+    Protanopia,
+}
+
+const OKABE_ORANGE: Color = Color::srgb(0.90, 0.62, 0.0);
+const OKABE_SKY_BLUE: Color = Color::srgb(0.34, 0.71, 0.91);
+const OKABE_BLUISH_GREEN: Color = Color::srgb(0.0, 0.62, 0.45);
+const OKABE_YELLOW: Color = Color::srgb(0.94, 0.89, 0.26);
+const OKABE_BLUE: Color = Color::srgb(0.0, 0.45, 0.70);
+const OKABE_VERMILLION: Color = Color::srgb(0.84, 0.37, 0.0);
+const OKABE_REDDISH_PURPLE: Color = Color::srgb(0.80, 0.47, 0.65);
+
+const DEUTERANOPIA_CHARACTER_COLORS: [Color; 5] = [
+    OKABE_BLUE,
+    OKABE_ORANGE,
+    OKABE_YELLOW,
+    OKABE_SKY_BLUE,
+    OKABE_REDDISH_PURPLE,
+];
+const PROTANOPIA_CHARACTER_COLORS: [Color; 5] = [
+    OKABE_SKY_BLUE,
+    OKABE_ORANGE,
+    OKABE_YELLOW,
+    OKABE_BLUE,
+    OKABE_BLUISH_GREEN,
+];
+
+impl ColorPalette {
+    /// Color for the character at `index` in the server's join order.
+    pub fn character_color(self, index: usize) -> Color {
+        let colors = match self {
+            ColorPalette::Default => &CHARACTER_COLORS,
+            ColorPalette::Deuteranopia => &DEUTERANOPIA_CHARACTER_COLORS,
+            ColorPalette::Protanopia => &PROTANOPIA_CHARACTER_COLORS,
+        };
+        colors[index % colors.len()]
+    }
+
+    /// Maps a replicated `ColorComponent` color to this palette. Colors that
+    /// aren't one of `CHARACTER_COLORS` are returned unchanged.
+    pub fn remap(self, color: Color) -> Color {
+        CHARACTER_COLORS
+            .iter()
+            .position(|c| *c == color)
+            .map_or(color, |index| self.character_color(index))
+    }
+
+    pub fn health_bar_foreground(self) -> Color {
+        match self {
+            ColorPalette::Default => Color::srgb(0.1, 0.9, 0.1),
+            ColorPalette::Deuteranopia | ColorPalette::Protanopia => OKABE_SKY_BLUE,
+        }
+    }
+
+    pub fn health_bar_background(self) -> Color {
+        match self {
+            ColorPalette::Default => Color::srgb(0.8, 0.1, 0.1),
+            ColorPalette::Deuteranopia => OKABE_VERMILLION,
+            ColorPalette::Protanopia => OKABE_ORANGE,
+        }
+    }
+
+    pub fn health_bar_invulnerable(self) -> Color {
+        match self {
+            ColorPalette::Default => Color::srgb(0.2, 0.5, 1.0),
+            ColorPalette::Deuteranopia | ColorPalette::Protanopia => Color::WHITE,
+        }
+    }
+}
+
+/// Marks a character's rig mesh whose material is its own tinted copy of the
+/// rig's shared material.
+#[derive(Component)]
+pub(crate) struct TintedRig;
+
+/// Tints each character's sprite rig with its replicated `ColorComponent`,
+/// remapped through the active [`ColorPalette`]. The first tint gives the rig
+/// mesh its own copy of the shared rig material; later ones, on a palette or
+/// color change, update that copy.
+pub(crate) fn tint_character_rigs(
+    mut commands: Commands,
+    palette: Res<ColorPalette>,
+    colors: Query<Ref<ColorComponent>, With<CharacterMarker>>,
+    mut rig_meshes: Query<
+        (
+            Entity,
+            &ChildOf,
+            &mut MeshMaterial3d<SpriteRigMaterial>,
+            Has<TintedRig>,
+        ),
+        With<SkinnedMesh>,
+    >,
+    mut materials: ResMut<Assets<SpriteRigMaterial>>,
+) {
+    for (mesh, child_of, mut material, tinted) in &mut rig_meshes {
+        let Ok(color) = colors.get(child_of.parent()) else {
+            trace!("Rig mesh {mesh:?} has no colored character parent, leaving it untinted");
+            continue;
+        };
+        if tinted && !palette.is_changed() && !color.is_changed() {
+            trace!("Rig mesh {mesh:?} tint is up to date");
+            continue;
+        }
+        if !tinted {
+            let Some(shared) = materials.get(&material.0).cloned() else {
+                warn!("Rig mesh {mesh:?} material not loaded, cannot tint it");
+                continue;
+            };
+            material.0 = materials.add(shared);
+            commands.entity(mesh).insert(TintedRig);
+        }
+        if let Some(own) = materials.get_mut(&material.0) {
+            own.base.base_color = palette.remap(color.0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_changes_resolved_character_color() {
+        assert_eq!(
+            ColorPalette::Default.character_color(0),
+            CHARACTER_COLORS[0]
+        );
+        assert_ne!(
+            ColorPalette::Deuteranopia.character_color(0),
+            ColorPalette::Default.character_color(0)
+        );
+        assert_ne!(
+            ColorPalette::Protanopia.character_color(4),
+            ColorPalette::Default.character_color(4)
+        );
+    }
+
+    #[test]
+    fn remap_translates_server_colors_by_index() {
+        let palette = ColorPalette::Deuteranopia;
+        for (index, color) in CHARACTER_COLORS.iter().enumerate() {
+            assert_eq!(palette.remap(*color), palette.character_color(index));
+        }
+        assert_eq!(palette.remap(Color::BLACK), Color::BLACK);
+    }
+
+    #[test]
+    fn colorblind_palettes_change_health_bar_colors() {
+        for palette in [ColorPalette::Deuteranopia, ColorPalette::Protanopia] {
+            assert_ne!(
+                palette.health_bar_foreground(),
+                ColorPalette::Default.health_bar_foreground()
+            );
+            assert_ne!(
+                palette.health_bar_background(),
+                ColorPalette::Default.health_bar_background()
+            );
+            assert_ne!(
+                palette.health_bar_foreground(),
+                palette.health_bar_background(),
+                "{palette:?} keeps the bar's fill and backing distinguishable"
+            );
+        }
+    }
+
+    fn tint_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default()));
+        app.init_asset::<SpriteRigMaterial>();
+        app.init_resource::<ColorPalette>();
+        app.add_systems(Update, tint_character_rigs);
+        app
+    }
+
+    /// Spawns a character colored `CHARACTER_COLORS[index]` with a rig mesh
+    /// child sharing `shared`, returning the mesh.
+    fn spawn_rigged_character(
+        app: &mut App,
+        index: usize,
+        shared: &Handle<SpriteRigMaterial>,
+    ) -> Entity {
+        let character = app
+            .world_mut()
+            .spawn((CharacterMarker, ColorComponent(CHARACTER_COLORS[index])))
+            .id();
+        app.world_mut()
+            .spawn((
+                SkinnedMesh::default(),
+                MeshMaterial3d(shared.clone()),
+                ChildOf(character),
+            ))
+            .id()
+    }
+
+    fn rig_color(app: &App, mesh: Entity) -> Color {
+        let material = app
+            .world()
+            .get::<MeshMaterial3d<SpriteRigMaterial>>(mesh)
+            .expect("rig mesh should keep a material");
+        app.world()
+            .resource::<Assets<SpriteRigMaterial>>()
+            .get(&material.0)
+            .expect("rig material should exist")
+            .base
+            .base_color
+    }
+
+    #[test]
+    fn selecting_a_palette_recolors_character_rigs() {
+        let mut app = tint_app();
+        let shared = app
+            .world_mut()
+            .resource_mut::<Assets<SpriteRigMaterial>>()
+            .add(SpriteRigMaterial::default());
+        let first = spawn_rigged_character(&mut app, 0, &shared);
+        let second = spawn_rigged_character(&mut app, 1, &shared);
+        app.update();
+
+        assert_eq!(rig_color(&app, first), CHARACTER_COLORS[0]);
+        assert_eq!(rig_color(&app, second), CHARACTER_COLORS[1]);
+
+        *app.world_mut().resource_mut::<ColorPalette>() = ColorPalette::Deuteranopia;
+        app.update();
+        assert_eq!(
+            rig_color(&app, first),
+            ColorPalette::Deuteranopia.character_color(0)
+        );
+        assert_eq!(
+            rig_color(&app, second),
+            ColorPalette::Deuteranopia.character_color(1)
+        );
+        let untouched = app
+            .world()
+            .resource::<Assets<SpriteRigMaterial>>()
+            .get(&shared)
+            .expect("shared rig material should exist")
+            .base
+            .base_color;
+        assert_eq!(
+            untouched,
+            Color::WHITE,
+            "the shared rig material is never tinted"
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::*;
+use protocol::ability::{extract_ground_target, slot_to_ability_action};
+use protocol::{AbilityAsset, AbilityDefs, AbilitySlots, DefaultAbilitySlots, PlayerActions};
+
+const RETICLE_RADIUS: f32 = 1.5;
+/// Lifts the reticle off the ground to avoid z-fighting with terrain.
+const RETICLE_HEIGHT_OFFSET: f32 = 0.05;
+const RETICLE_COLOR: Color = Color::srgba(0.3, 0.8, 1.0, 0.5);
+
+/// Where the cursor ray meets the horizontal plane through the local player,
+/// updated each frame. `None` when there is no cursor or camera.
+#[derive(Resource, Default, Debug, Clone, Copy)]
+pub struct GroundAimPoint(pub Option<Vec3>);
+
+/// Marker for the ground targeting reticle entity.
+#[derive(Component)]
+pub struct GroundReticle;
+
+/// Clamps `aim` so its horizontal distance from `origin` is at most `range`.
+pub fn clamp_to_range(origin: Vec3, aim: Vec3, range: f32) -> Vec3 {
+    let offset = Vec2::new(aim.x - origin.x, aim.z - origin.z).clamp_length_max(range);
+    Vec3::new(origin.x + offset.x, aim.y, origin.z + offset.y)
+}
+
+pub(crate) fn spawn_ground_reticle(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn((
+        Name::new("GroundReticle"),
+        GroundReticle,
+        Mesh3d(meshes.add(Annulus::new(RETICLE_RADIUS * 0.8, RETICLE_RADIUS))),
+        MeshMaterial3d(materials.add(StandardMaterial {
+            base_color: RETICLE_COLOR,
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        })),
+        Transform::from_rotation(Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)),
+        Visibility::Hidden,
+    ));
+}
+
+/// Projects the cursor onto the ground plane at the local player's height.
+pub(crate) fn update_ground_aim_point(
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    player_query: Query<&Position, With<Controlled>>,
+    mut aim: ResMut<GroundAimPoint>,
+) {
+    aim.0 = None;
+    let Ok(player_pos) = player_query.single() else {
+        trace!("update_ground_aim_point: no single local player");
+        return;
+    };
+    let Ok((camera, camera_transform)) = camera_query.single() else {
+        trace!("update_ground_aim_point: no single 3D camera");
+        return;
+    };
+    let Some(cursor) = window_query.single().ok().and_then(Window::cursor_position) else {
+        trace!("update_ground_aim_point: cursor is outside the primary window");
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, cursor) else {
+        trace!("update_ground_aim_point: cursor has no world ray");
+        return;
+    };
+    let Some(distance) = ray.intersect_plane(player_pos.0, InfinitePlane3d::new(Vec3::Y)) else {
+        trace!("update_ground_aim_point: cursor ray misses the ground plane");
+        return;
+    };
+    aim.0 = Some(ray.get_point(distance));
+}
+
+/// Shows the reticle at the range-clamped aim point while a ground-targeted
+/// ability's action is held, hiding it otherwise.
+pub(crate) fn update_ground_reticle(
+    aim: Res<GroundAimPoint>,
+    ability_defs: Res<AbilityDefs>,
+    ability_assets: Res<Assets<AbilityAsset>>,
+    default_slots: Res<DefaultAbilitySlots>,
+    player_query: Query<
+        (
+            &Position,
+            &ActionState<PlayerActions>,
+            Option<&AbilitySlots>,
+        ),
+        With<Controlled>,
+    >,
+    mut reticle_query: Query<(&mut Transform, &mut Visibility), With<GroundReticle>>,
+) {
+    let Ok((mut transform, mut visibility)) = reticle_query.single_mut() else {
+        trace!("update_ground_reticle: no single ground reticle");
+        return;
+    };
+    let held_range = player_query
+        .single()
+        .ok()
+        .and_then(|(position, action_state, slots)| {
+            let slots = slots.unwrap_or(&default_slots.0);
+            let range = slots.0.iter().enumerate().find_map(|(slot, ability_id)| {
+                let action = slot_to_ability_action(slot)?;
+                if !action_state.pressed(&action) {
+                    return None;
+                }
+                let asset = ability_assets.get(ability_defs.get(ability_id.as_ref()?)?)?;
+                extract_ground_target(asset).map(|target| target.range)
+            })?;
+            Some((position.0, range))
+        });
+
+    match (held_range, aim.0) {
+        (Some((origin, range)), Some(aim_point)) => {
+            let target = clamp_to_range(origin, aim_point, range);
+            transform.translation = target + Vec3::Y * RETICLE_HEIGHT_OFFSET;
+            *visibility = Visibility::Visible;
+        }
+        _ => *visibility = Visibility::Hidden,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::reflect::PartialReflect;
+    use protocol::ability::GroundTarget;
+    use protocol::AbilityId;
+    use std::collections::HashMap;
+
+    fn reticle_app() -> (App, Entity) {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.add_plugins(AssetPlugin::default());
+        app.init_asset::<AbilityAsset>();
+        app.init_asset::<Mesh>();
+        app.init_asset::<StandardMaterial>();
+        app.init_resource::<GroundAimPoint>();
+        app.init_resource::<DefaultAbilitySlots>();
+
+        let handle = app
+            .world_mut()
+            .resource_mut::<Assets<AbilityAsset>>()
+            .add(AbilityAsset {
+                components: vec![Box::new(GroundTarget { range: 10.0 }).into_partial_reflect()],
+            });
+        app.insert_resource(AbilityDefs {
+            abilities: HashMap::from([(AbilityId("meteor".into()), handle)]),
+        });
+
+        app.add_systems(Startup, spawn_ground_reticle);
+        app.add_systems(Update, update_ground_reticle);
+
+        let player = app
+            .world_mut()
+            .spawn((
+                Controlled,
+                Position(Vec3::ZERO),
+                ActionState::<PlayerActions>::default(),
+                AbilitySlots([Some(AbilityId("meteor".into())), None, None, None, None]),
+            ))
+            .id();
+        (app, player)
+    }
+
+    fn reticle(app: &mut App) -> (Vec3, Visibility) {
+        let (transform, visibility) = app
+            .world_mut()
+            .query_filtered::<(&Transform, &Visibility), With<GroundReticle>>()
+            .single(app.world())
+            .unwrap();
+        (transform.translation, *visibility)
+    }
+
+    #[test]
+    fn clamp_limits_horizontal_distance() {
+        let clamped = clamp_to_range(Vec3::ZERO, Vec3::new(30.0, 2.0, 40.0), 10.0);
+        assert!((Vec2::new(clamped.x, clamped.z).length() - 10.0).abs() < 1e-4);
+        assert_eq!(clamped.y, 2.0);
+        assert_eq!(
+            clamp_to_range(Vec3::ZERO, Vec3::new(3.0, 0.0, 4.0), 10.0),
+            Vec3::new(3.0, 0.0, 4.0)
+        );
+    }
+
+    #[test]
+    fn reticle_shown_at_clamped_aim_while_held() {
+        let (mut app, player) = reticle_app();
+        app.insert_resource(GroundAimPoint(Some(Vec3::new(0.0, 0.0, 25.0))));
+        app.update();
+        assert_eq!(reticle(&mut app).1, Visibility::Hidden);
+
+        app.world_mut()
+            .get_mut::<ActionState<PlayerActions>>(player)
+            .unwrap()
+            .press(&PlayerActions::Ability1);
+        app.update();
+        let (translation, visibility) = reticle(&mut app);
+        assert_eq!(visibility, Visibility::Visible);
+        assert!(translation.distance(Vec3::new(0.0, RETICLE_HEIGHT_OFFSET, 10.0)) < 1e-4);
+
+        app.world_mut()
+            .get_mut::<ActionState<PlayerActions>>(player)
+            .unwrap()
+            .release(&PlayerActions::Ability1);
+        app.update();
+        assert_eq!(reticle(&mut app).1, Visibility::Hidden);
+    }
+}
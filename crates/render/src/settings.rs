@@ -0,0 +1,63 @@
+use bevy::prelude::*;
+use protocol::settings::Settings;
+
+/// Toggles [`Settings::visual_smoothing`] on F6, [`Settings::hit_stop`] on F7
+/// and [`Settings::camera_shake`] on F8.
+pub fn toggle_settings(keys: Res<ButtonInput<KeyCode>>, mut settings: ResMut<Settings>) {
+    if keys.just_pressed(KeyCode::F6) {
+        settings.visual_smoothing.enabled = !settings.visual_smoothing.enabled;
+        info!(
+            "Visual smoothing enabled: {}",
+            settings.visual_smoothing.enabled
+        );
+    }
+    if keys.just_pressed(KeyCode::F7) {
+        settings.hit_stop.enabled = !settings.hit_stop.enabled;
+        info!("Hit stop enabled: {}", settings.hit_stop.enabled);
+    }
+    if keys.just_pressed(KeyCode::F8) {
+        settings.camera_shake.enabled = !settings.camera_shake.enabled;
+        info!("Camera shake enabled: {}", settings.camera_shake.enabled);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(app: &mut App, key: KeyCode) {
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.release(key);
+        keys.clear();
+        keys.press(key);
+        app.update();
+    }
+
+    #[test]
+    fn function_keys_toggle_smoothing_hit_stop_and_camera_shake() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_resource::<Settings>();
+        app.add_systems(Update, toggle_settings);
+        app.update();
+
+        press(&mut app, KeyCode::F6);
+        let settings = *app.world().resource::<Settings>();
+        assert!(settings.visual_smoothing.enabled, "F6 turns smoothing on");
+        assert!(settings.hit_stop.enabled, "F6 leaves hit stop alone");
+
+        press(&mut app, KeyCode::F7);
+        let settings = *app.world().resource::<Settings>();
+        assert!(!settings.hit_stop.enabled, "F7 turns hit stop off");
+        assert!(settings.visual_smoothing.enabled);
+
+        press(&mut app, KeyCode::F6);
+        assert!(!app.world().resource::<Settings>().visual_smoothing.enabled);
+
+        press(&mut app, KeyCode::F8);
+        let settings = *app.world().resource::<Settings>();
+        assert!(!settings.camera_shake.enabled, "F8 turns camera shake off");
+        assert!(!settings.hit_stop.enabled, "F8 leaves hit stop alone");
+    }
+}
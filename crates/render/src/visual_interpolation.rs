@@ -0,0 +1,71 @@
+use avian3d::prelude::{Position, Rotation};
+use bevy::prelude::*;
+use lightyear::frame_interpolation::{FrameInterpolate, FrameInterpolationPlugin};
+use lightyear::prelude::Predicted;
+use protocol::AbilityInterpolation;
+
+/// Smooths predicted `Position`/`Rotation` between fixed ticks. Each frame the
+/// rendered value is blended between the last two ticks' values by
+/// `Time<Fixed>`'s overstep fraction, so motion doesn't step at frame rates
+/// above the tick rate. The tick's real value is restored before the next
+/// `FixedUpdate`. Needs the components' linear interpolation registered, as
+/// `ProtocolPlugin` does by default.
+pub struct VisualInterpolationPlugin;
+
+impl Plugin for VisualInterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FrameInterpolationPlugin::<Position>::default());
+        app.add_plugins(FrameInterpolationPlugin::<Rotation>::default());
+        app.add_observer(add_visual_interpolation_components);
+        app.add_observer(remove_visual_interpolation_from_snapped);
+    }
+}
+
+/// Adds frame interpolation to predicted entities, unless a
+/// [`AbilityInterpolation::Snap`] ability spawned them.
+fn add_visual_interpolation_components(
+    trigger: On<Add, Position>,
+    query: Query<Option<&AbilityInterpolation>, With<Predicted>>,
+    mut commands: Commands,
+) {
+    let Ok(interpolation) = query.get(trigger.entity) else {
+        return;
+    };
+    if interpolation == Some(&AbilityInterpolation::Snap) {
+        trace!(
+            "{:?} spawned by a Snap ability, not smoothing",
+            trigger.entity
+        );
+        return;
+    }
+    commands.entity(trigger.entity).insert((
+        FrameInterpolate::<Position> {
+            trigger_change_detection: true,
+            ..default()
+        },
+        FrameInterpolate::<Rotation> {
+            trigger_change_detection: true,
+            ..default()
+        },
+    ));
+}
+
+/// Strips frame interpolation from entities spawned by a
+/// [`AbilityInterpolation::Snap`] ability, for when the marker lands after
+/// `Position` (e.g. on replication).
+fn remove_visual_interpolation_from_snapped(
+    trigger: On<Add, AbilityInterpolation>,
+    query: Query<&AbilityInterpolation>,
+    mut commands: Commands,
+) {
+    if !matches!(query.get(trigger.entity), Ok(AbilityInterpolation::Snap)) {
+        trace!(
+            "{:?} is smoothed, keeping frame interpolation",
+            trigger.entity
+        );
+        return;
+    }
+    commands
+        .entity(trigger.entity)
+        .remove::<(FrameInterpolate<Position>, FrameInterpolate<Rotation>)>();
+}
@@ -0,0 +1,73 @@
+use avian3d::prelude::{Position, Rotation};
+use bevy::prelude::*;
+use lightyear::frame_interpolation::FrameInterpolationSystems;
+use protocol::settings::Settings;
+use protocol::CharacterMarker;
+
+/// A character's smoothed, rendered pose. Each frame it moves toward the
+/// physics `Position`/`Rotation` and is written to the `Transform`, so a
+/// correction or teleport eases in instead of snapping. Purely cosmetic.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub struct VisualSmoothing {
+    pub translation: Vec3,
+    pub rotation: Quat,
+}
+
+/// Runs [`smooth_character_visuals`] after frame interpolation and before
+/// transform propagation, so the smoothed pose is what gets rendered. Toggled
+/// by [`Settings::visual_smoothing`].
+pub struct VisualSmoothingPlugin;
+
+impl Plugin for VisualSmoothingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Settings>();
+        app.add_systems(Update, toggle_visual_smoothing);
+        app.add_systems(
+            PostUpdate,
+            smooth_character_visuals
+                .after(FrameInterpolationSystems::Interpolate)
+                .before(TransformSystems::Propagate),
+        );
+    }
+}
+
+/// Adds [`VisualSmoothing`] to characters while smoothing is enabled, starting
+/// from their current physics pose, and removes it once disabled.
+fn toggle_visual_smoothing(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    unsmoothed: Query<
+        (Entity, &Position, &Rotation),
+        (With<CharacterMarker>, Without<VisualSmoothing>),
+    >,
+    smoothed: Query<Entity, With<VisualSmoothing>>,
+) {
+    if settings.visual_smoothing.enabled {
+        for (entity, position, rotation) in &unsmoothed {
+            commands.entity(entity).insert(VisualSmoothing {
+                translation: position.0,
+                rotation: rotation.0,
+            });
+        }
+    } else {
+        for entity in &smoothed {
+            trace!("Visual smoothing disabled, removing it from {entity:?}");
+            commands.entity(entity).remove::<VisualSmoothing>();
+        }
+    }
+}
+
+/// Eases each [`VisualSmoothing`] pose toward its physics pose and renders it.
+fn smooth_character_visuals(
+    time: Res<Time>,
+    settings: Res<Settings>,
+    mut query: Query<(&Position, &Rotation, &mut VisualSmoothing, &mut Transform)>,
+) {
+    let blend = 1.0 - (-settings.visual_smoothing.rate * time.delta_secs()).exp();
+    for (position, rotation, mut smoothed, mut transform) in &mut query {
+        smoothed.translation = smoothed.translation.lerp(position.0, blend);
+        smoothed.rotation = smoothed.rotation.slerp(rotation.0, blend);
+        transform.translation = smoothed.translation;
+        transform.rotation = smoothed.rotation;
+    }
+}
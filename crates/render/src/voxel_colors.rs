@@ -0,0 +1,112 @@
+use bevy::mesh::VertexAttributeValues;
+use bevy::prelude::*;
+use protocol::{VoxelMaterialRegistry, DEFAULT_VOXEL_COLOR};
+use voxel_map_engine::prelude::{
+    DefaultVoxelMaterial, RuntimeShape, Shape, VoxelChunk, VoxelMapInstance, WorldVoxel,
+};
+
+/// Tints every voxel chunk mesh face with its material's color from the
+/// [`VoxelMaterialRegistry`], as vertex colors. Client-side only; insert a
+/// customized registry before the plugins to restyle materials.
+pub struct VoxelColorPlugin;
+
+impl Plugin for VoxelColorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<VoxelMaterialRegistry>();
+        app.add_systems(
+            Update,
+            (
+                whiten_default_voxel_material.run_if(resource_added::<DefaultVoxelMaterial>),
+                color_voxel_chunks,
+            ),
+        );
+    }
+}
+
+/// Vertex colors carry each material's color, so the shared chunk material
+/// must not tint them again.
+fn whiten_default_voxel_material(
+    default_material: Res<DefaultVoxelMaterial>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let Some(material) = materials.get_mut(&default_material.0) else {
+        warn!("DefaultVoxelMaterial handle has no StandardMaterial asset");
+        return;
+    };
+    material.base_color = Color::WHITE;
+}
+
+/// Colors chunk meshes as they are spawned or remeshed, from the voxels their
+/// map instance holds for them.
+fn color_voxel_chunks(
+    registry: Res<VoxelMaterialRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    chunks: Query<(&VoxelChunk, &Mesh3d, &ChildOf), Changed<Mesh3d>>,
+    instances: Query<&VoxelMapInstance>,
+) {
+    for (chunk, mesh3d, parent) in &chunks {
+        let Ok(instance) = instances.get(parent.0) else {
+            trace!("Chunk {:?} is not under a voxel map", chunk.position);
+            continue;
+        };
+        let Some(chunk_data) = instance.get_chunk_data(chunk.position) else {
+            trace!("Chunk {:?} has no voxel data to color from", chunk.position);
+            continue;
+        };
+        let voxels = chunk_data.voxels.to_voxels();
+        let Some(mesh) = meshes.get_mut(&mesh3d.0) else {
+            trace!("Chunk {:?} mesh asset not found", chunk.position);
+            continue;
+        };
+        let Some(colors) = voxel_vertex_colors(mesh, &voxels, &instance.shape, &registry) else {
+            warn!(
+                "Chunk {:?} mesh has no float3 positions and normals; leaving it uncolored",
+                chunk.position
+            );
+            continue;
+        };
+        mesh.insert_attribute(Mesh::ATTRIBUTE_COLOR, colors);
+    }
+}
+
+/// Linear vertex colors for a greedy-meshed chunk: each quad takes the color of
+/// the solid voxel whose face it is. `voxels` is the padded array the mesh was
+/// built from, laid out by `shape`. `None` if the mesh lacks float3 positions
+/// or normals.
+pub fn voxel_vertex_colors(
+    mesh: &Mesh,
+    voxels: &[WorldVoxel],
+    shape: &RuntimeShape<u32, 3>,
+    registry: &VoxelMaterialRegistry,
+) -> Option<Vec<[f32; 4]>> {
+    let (
+        Some(VertexAttributeValues::Float32x3(positions)),
+        Some(VertexAttributeValues::Float32x3(normals)),
+    ) = (
+        mesh.attribute(Mesh::ATTRIBUTE_POSITION),
+        mesh.attribute(Mesh::ATTRIBUTE_NORMAL),
+    )
+    else {
+        return None;
+    };
+    let dims = UVec3::from_array(shape.as_array());
+    let colors = positions
+        .chunks_exact(4)
+        .zip(normals.chunks_exact(4))
+        .flat_map(|(quad, quad_normals)| {
+            let center = quad.iter().copied().map(Vec3::from_array).sum::<Vec3>() / 4.0;
+            // Half a voxel behind the face is the voxel that owns it.
+            let inside = (center - Vec3::from_array(quad_normals[0]) * 0.5).floor();
+            let color = if inside.cmpge(Vec3::ZERO).all() && inside.as_uvec3().cmplt(dims).all() {
+                match voxels[shape.linearize(inside.as_uvec3().to_array()) as usize] {
+                    WorldVoxel::Solid(material) => registry.color(material),
+                    WorldVoxel::Air | WorldVoxel::Unset => DEFAULT_VOXEL_COLOR,
+                }
+            } else {
+                DEFAULT_VOXEL_COLOR
+            };
+            [color.to_linear().to_f32_array(); 4]
+        })
+        .collect();
+    Some(colors)
+}
@@ -0,0 +1,99 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use lightyear::prelude::Controlled;
+use protocol::settings::{HitStopSettings, Settings};
+use protocol::{AbilityHitEvent, AbilityId, CharacterMarker, FIXED_TIMESTEP_HZ};
+use render::{HitStop, HitStopPlugin};
+
+#[derive(Resource, Default)]
+struct FixedTicks(u32);
+
+fn hit_stop_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_message::<AbilityHitEvent>();
+    app.add_plugins(HitStopPlugin);
+    app.insert_resource(Time::<Fixed>::from_hz(FIXED_TIMESTEP_HZ));
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / FIXED_TIMESTEP_HZ,
+    )));
+    app.init_resource::<FixedTicks>();
+    app.add_systems(FixedUpdate, |mut ticks: ResMut<FixedTicks>| ticks.0 += 1);
+    app
+}
+
+fn write_hit(app: &mut App, caster: Entity, damage: f32) {
+    let victim = app.world_mut().spawn(CharacterMarker).id();
+    app.world_mut().write_message(AbilityHitEvent {
+        caster,
+        victim,
+        ability_id: AbilityId("punch".into()),
+        damage,
+    });
+}
+
+#[test]
+fn heavy_hit_starts_a_short_hit_stop() {
+    let mut app = hit_stop_app();
+    let player = app.world_mut().spawn((CharacterMarker, Controlled)).id();
+    app.update();
+
+    write_hit(&mut app, player, 10.0);
+    app.update();
+    assert!(
+        !app.world().resource::<HitStop>().is_active(),
+        "a light hit should not stop time"
+    );
+
+    write_hit(&mut app, player, 50.0);
+    app.update();
+    let remaining = app.world().resource::<HitStop>().remaining_secs;
+    let duration = HitStopSettings::default().duration_secs;
+    assert!(
+        remaining > 0.0 && remaining <= duration,
+        "heavy hit should start a hit stop of at most {duration}s, got {remaining}"
+    );
+
+    for _ in 0..10 {
+        app.update();
+    }
+    assert!(!app.world().resource::<HitStop>().is_active());
+}
+
+#[test]
+fn hit_stop_does_not_change_the_fixed_tick_count() {
+    let mut stopped = hit_stop_app();
+    let mut control = hit_stop_app();
+    let player = stopped
+        .world_mut()
+        .spawn((CharacterMarker, Controlled))
+        .id();
+    stopped.update();
+    control.update();
+
+    write_hit(&mut stopped, player, 50.0);
+    for _ in 0..20 {
+        stopped.update();
+        control.update();
+    }
+
+    assert_eq!(
+        stopped.world().resource::<FixedTicks>().0,
+        control.world().resource::<FixedTicks>().0
+    );
+    assert!(control.world().resource::<FixedTicks>().0 > 0);
+}
+
+#[test]
+fn disabled_hit_stop_ignores_heavy_hits() {
+    let mut app = hit_stop_app();
+    app.world_mut().resource_mut::<Settings>().hit_stop.enabled = false;
+    let player = app.world_mut().spawn((CharacterMarker, Controlled)).id();
+    app.update();
+
+    write_hit(&mut app, player, 50.0);
+    app.update();
+    assert!(!app.world().resource::<HitStop>().is_active());
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use lightyear::prelude::client::ClientPlugins;
+use lightyear::prelude::Predicted;
+use protocol::FIXED_TIMESTEP_HZ;
+use render::VisualInterpolationPlugin;
+
+/// Distance the test entity moves along X each fixed tick.
+const STEP: f32 = 1.0;
+
+/// Fixed ticks run so far, and per frame the rendered X with its overstep.
+#[derive(Resource, Default)]
+struct Frames {
+    ticks: u32,
+    rendered: Vec<(u32, f32, f32)>,
+}
+
+fn step_predicted(mut query: Query<&mut Position, With<Predicted>>, mut frames: ResMut<Frames>) {
+    frames.ticks += 1;
+    for mut position in &mut query {
+        position.0.x += STEP;
+    }
+}
+
+fn record_rendered(
+    time: Res<Time<Fixed>>,
+    query: Query<&Position, With<Predicted>>,
+    mut frames: ResMut<Frames>,
+) {
+    let x = query.single().unwrap().0.x;
+    let ticks = frames.ticks;
+    frames.rendered.push((ticks, x, time.overstep_fraction()));
+}
+
+#[test]
+fn rendered_position_blends_between_fixed_ticks() {
+    let tick_duration = Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ);
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(ClientPlugins { tick_duration });
+    app.add_plugins(protocol::ProtocolPlugin);
+    app.add_plugins(VisualInterpolationPlugin);
+    // One and a half ticks per frame, so every other frame ends mid-tick.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(tick_duration * 3 / 2));
+    app.init_resource::<Frames>();
+    app.add_systems(FixedUpdate, step_predicted);
+    app.add_systems(Last, record_rendered);
+
+    app.world_mut().spawn((Predicted, Position(Vec3::ZERO)));
+    for _ in 0..12 {
+        app.update();
+    }
+
+    let frames = app.world().resource::<Frames>();
+    let (ticks, x, overstep) = frames
+        .rendered
+        .iter()
+        .copied()
+        .filter(|(ticks, _, overstep)| *ticks >= 2 && *overstep > 0.1 && *overstep < 0.9)
+        .last()
+        .expect("some frame should end between two ticks");
+    let previous = (ticks - 1) as f32 * STEP;
+    let current = ticks as f32 * STEP;
+    assert!(
+        x > previous && x < current,
+        "rendered x {x} should lie between ticks at {previous} and {current}"
+    );
+    assert!(
+        (x - (previous + overstep * STEP)).abs() < 1e-3,
+        "rendered x {x} should be {overstep} of the way from {previous}"
+    );
+}
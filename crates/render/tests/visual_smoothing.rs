@@ -0,0 +1,104 @@
+use std::time::Duration;
+
+use avian3d::prelude::{Position, Rotation};
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use protocol::settings::{Settings, VisualSmoothingSettings};
+use protocol::CharacterMarker;
+use render::{VisualSmoothing, VisualSmoothingPlugin};
+
+fn smoothing_app(enabled: bool) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(Settings {
+        visual_smoothing: VisualSmoothingSettings {
+            enabled,
+            ..default()
+        },
+        ..default()
+    });
+    app.add_plugins(VisualSmoothingPlugin);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        1.0 / 60.0,
+    )));
+    app
+}
+
+fn spawn_character(app: &mut App) -> Entity {
+    app.world_mut()
+        .spawn((
+            CharacterMarker,
+            Position(Vec3::ZERO),
+            Rotation::default(),
+            Transform::default(),
+        ))
+        .id()
+}
+
+fn rendered_x(app: &App, entity: Entity) -> f32 {
+    app.world().get::<Transform>(entity).unwrap().translation.x
+}
+
+#[test]
+fn smoothed_transform_eases_toward_a_teleport() {
+    let mut app = smoothing_app(true);
+    let character = spawn_character(&mut app);
+    for _ in 0..3 {
+        app.update();
+    }
+    assert!(app.world().get::<VisualSmoothing>(character).is_some());
+
+    app.world_mut().get_mut::<Position>(character).unwrap().0 = Vec3::new(10.0, 0.0, 0.0);
+    app.update();
+    let first = rendered_x(&app, character);
+    assert!(
+        first > 0.0 && first < 10.0,
+        "render transform should lag the teleport, got x = {first}"
+    );
+
+    app.update();
+    let second = rendered_x(&app, character);
+    assert!(
+        second > first && second < 10.0,
+        "render transform should keep easing in, got x = {second} after {first}"
+    );
+
+    for _ in 0..120 {
+        app.update();
+    }
+    assert!(
+        (rendered_x(&app, character) - 10.0).abs() < 1e-3,
+        "render transform should settle on the physics position"
+    );
+}
+
+#[test]
+fn disabled_smoothing_leaves_transform_alone() {
+    let mut app = smoothing_app(false);
+    let character = spawn_character(&mut app);
+    app.world_mut().get_mut::<Position>(character).unwrap().0 = Vec3::new(10.0, 0.0, 0.0);
+    app.update();
+
+    assert!(app.world().get::<VisualSmoothing>(character).is_none());
+    assert_eq!(rendered_x(&app, character), 0.0);
+}
+
+#[test]
+fn turning_smoothing_off_at_runtime_removes_it() {
+    let mut app = smoothing_app(true);
+    let character = spawn_character(&mut app);
+    app.update();
+    app.world_mut().get_mut::<Position>(character).unwrap().0 = Vec3::new(10.0, 0.0, 0.0);
+    app.update();
+    assert!(rendered_x(&app, character) < 10.0);
+
+    app.world_mut()
+        .resource_mut::<Settings>()
+        .visual_smoothing
+        .enabled = false;
+    app.update();
+    assert!(
+        app.world().get::<VisualSmoothing>(character).is_none(),
+        "disabling smoothing removes it from characters"
+    );
+}
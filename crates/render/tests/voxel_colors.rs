@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use protocol::{VoxelMaterialRegistry, DEFAULT_VOXEL_COLOR, LAVA_MATERIAL};
+use render::voxel_vertex_colors;
+use voxel_map_engine::prelude::{mesh_chunk_greedy, RuntimeShape, Shape, WorldVoxel};
+
+#[test]
+fn registry_resolves_material_colors() {
+    let registry = VoxelMaterialRegistry::default();
+    assert_eq!(registry.color(LAVA_MATERIAL), Color::srgb(1.0, 0.35, 0.05));
+    assert_eq!(
+        registry.color(200),
+        DEFAULT_VOXEL_COLOR,
+        "materials without a color fall back to the default"
+    );
+}
+
+#[test]
+fn chunk_mesh_faces_take_their_material_color() {
+    let registry = VoxelMaterialRegistry::default();
+    let shape = RuntimeShape::<u32, 3>::new([18, 18, 18]);
+    let mut voxels = vec![WorldVoxel::Air; shape.usize()];
+    voxels[shape.linearize([4, 4, 4]) as usize] = WorldVoxel::Solid(LAVA_MATERIAL);
+    voxels[shape.linearize([10, 4, 4]) as usize] = WorldVoxel::Solid(200);
+    let mesh = mesh_chunk_greedy(&voxels, &shape).expect("two voxels produce a mesh");
+
+    let colors = voxel_vertex_colors(&mesh, &voxels, &shape, &registry)
+        .expect("greedy meshes have positions and normals");
+
+    let lava = registry.color(LAVA_MATERIAL).to_linear().to_f32_array();
+    let fallback = DEFAULT_VOXEL_COLOR.to_linear().to_f32_array();
+    assert_eq!(
+        colors.len(),
+        2 * 6 * 4,
+        "six quads of four vertices per voxel"
+    );
+    assert_eq!(colors.iter().filter(|c| **c == lava).count(), 24);
+    assert_eq!(colors.iter().filter(|c| **c == fallback).count(), 24);
+}
+
+#[test]
+fn mesh_without_positions_is_not_colored() {
+    let registry = VoxelMaterialRegistry::default();
+    let shape = RuntimeShape::<u32, 3>::new([18, 18, 18]);
+    let voxels = vec![WorldVoxel::Air; shape.usize()];
+    let mesh = Mesh::new(
+        bevy::mesh::PrimitiveTopology::TriangleList,
+        bevy::asset::RenderAssetUsages::default(),
+    );
+
+    assert_eq!(voxel_vertex_colors(&mesh, &voxels, &shape, &registry), None);
+}
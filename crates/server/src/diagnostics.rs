@@ -1,11 +1,20 @@
-//! Server-specific tracy diagnostics (input buffer status, input state).
+//! Server-specific tracy diagnostics (input buffer status, input state) and
+//! state checksum broadcasts.
 
+use avian3d::prelude::Position;
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::ActionState;
 use lightyear::prelude::input::leafwing::LeafwingBuffer;
+use lightyear::prelude::server::ClientOf;
 use lightyear::prelude::*;
-use protocol::diagnostics::plot_action_state;
-use protocol::{CharacterMarker, PlayerActions};
+use protocol::diagnostics::{
+    map_state_checksum, plot_action_state, DiagnosticsChannel, StateChecksum,
+    STATE_CHECKSUM_INTERVAL_TICKS,
+};
+use protocol::map::MapInstanceId;
+use protocol::{CharacterMarker, Health, InputRebroadcastConfig, Minion, PlayerActions};
+use std::collections::HashMap;
 use tracy_client::plot;
 
 /// Server-specific tracy diagnostics.
@@ -14,6 +23,7 @@ pub struct ServerDiagnosticsPlugin;
 impl Plugin for ServerDiagnosticsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(FixedUpdate, plot_server_input_state)
+            .add_systems(FixedLast, broadcast_state_checksum)
             .add_systems(Last, plot_input_buffer_status);
     }
 }
@@ -25,6 +35,69 @@ fn plot_server_input_state(query: Query<&ActionState<PlayerActions>, With<Charac
     }
 }
 
+/// Every `STATE_CHECKSUM_INTERVAL_TICKS` ticks, sends each connected client the
+/// [`map_state_checksum`] of the characters it predicts on its character's map:
+/// all of them while inputs are rebroadcast, otherwise its own character plus
+/// the unowned ones (minions), since other players' characters are interpolated.
+fn broadcast_state_checksum(
+    timeline: Res<LocalTimeline>,
+    rebroadcast: Res<InputRebroadcastConfig>,
+    characters: Query<
+        (&Position, &Health, &MapInstanceId, Option<&ControlledBy>),
+        With<CharacterMarker>,
+    >,
+    players: Query<(&ControlledBy, &MapInstanceId), (With<CharacterMarker>, Without<Minion>)>,
+    clients: Query<(), (With<ClientOf>, With<Connected>)>,
+    mut sender: ServerMultiMessageSender,
+) {
+    let tick = timeline.tick().0;
+    if tick % STATE_CHECKSUM_INTERVAL_TICKS != 0 {
+        return;
+    }
+    let mut targets_by_map: HashMap<&MapInstanceId, EntityHashSet> = HashMap::new();
+    for (controlled_by, map) in &players {
+        if clients.contains(controlled_by.owner) {
+            targets_by_map
+                .entry(map)
+                .or_default()
+                .insert(controlled_by.owner);
+        }
+    }
+    if targets_by_map.is_empty() {
+        trace!("broadcast_state_checksum: no connected players");
+        return;
+    }
+    let state = |(position, health, character_map, _)| (position, health, character_map);
+    for (map, targets) in targets_by_map {
+        if rebroadcast.rebroadcast_inputs {
+            let checksum = StateChecksum {
+                tick,
+                checksum: map_state_checksum(map, characters.iter().map(state)),
+            };
+            sender
+                .send_to_entities::<_, DiagnosticsChannel>(&checksum, &targets)
+                .ok();
+            continue;
+        }
+        for client in targets {
+            let predicted = characters
+                .iter()
+                .filter(|(.., owner)| owner.is_none_or(|owner| owner.owner == client))
+                .map(state);
+            let checksum = StateChecksum {
+                tick,
+                checksum: map_state_checksum(map, predicted),
+            };
+            sender
+                .send_to_entities::<_, DiagnosticsChannel>(
+                    &checksum,
+                    &EntityHashSet::from_iter([client]),
+                )
+                .ok();
+        }
+    }
+}
+
 /// Plots server tick vs input buffer tick range to diagnose tick misalignment.
 fn plot_input_buffer_status(
     timeline: Res<LocalTimeline>,
@@ -1,27 +1,187 @@
 use avian3d::prelude::*;
-use bevy::color::palettes::css;
+use bevy::ecs::entity::EntityHashSet;
 use bevy::prelude::*;
 use leafwing_input_manager::prelude::*;
 use lightyear::connection::client::Connected;
 use lightyear::prelude::server::ClientOf;
 use lightyear::prelude::*;
+use protocol::ability::{GamepadAiming, InputDeviceReport};
 use protocol::vox_model::{VoxModelAsset, VoxModelRegistry};
 use protocol::world_object::{
     ActiveTransformation, DeathEffect, OnDeathEffects, WorldObjectDefRegistry, WorldObjectId,
 };
 use protocol::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 use crate::map::{ClientChunkVisibility, MapLoadState};
+use crate::reconnect::{
+    cache_disconnected_character, evict_expired_disconnects, RecentlyDisconnected,
+};
 use voxel_map_engine::prelude::ChunkTicket;
 
 /// Default spawn position used for respawning and initial player placement.
 pub const DEFAULT_SPAWN_POS: Vec3 = Vec3::new(0.0, 5.0, 0.0);
 
+/// Replication priority of client-owned entities. Lightyear's default group
+/// priority is 1.0, so a player's character wins bandwidth over abilities,
+/// projectiles, minions and world objects. Lightyear prioritizes per group
+/// rather than per receiver, so other clients see the raised priority too.
+pub const CHARACTER_REPLICATION_PRIORITY: f32 = 10.0;
+
+/// Moves each replicated entity that gains a `ControlledBy` owner into its own
+/// replication group at `CHARACTER_REPLICATION_PRIORITY`. Priority follows
+/// ownership, so unowned characters such as minions stay at the default.
+pub fn prioritize_owned_replication(
+    trigger: On<Add, ControlledBy>,
+    replicated: Query<(), With<Replicate>>,
+    mut commands: Commands,
+) {
+    if !replicated.contains(trigger.entity) {
+        trace!("{:?} is owned but not replicated", trigger.entity);
+        return;
+    }
+    commands
+        .entity(trigger.entity)
+        .insert(ReplicationGroup::new_from_entity().set_priority(CHARACTER_REPLICATION_PRIORITY));
+}
+
+/// Base stats a player character spawns with.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStats {
+    pub max_health: f32,
+    /// Size of the `ResourcePool` that `AbilityCost` abilities spend from.
+    #[serde(default = "default_max_resource")]
+    pub max_resource: f32,
+    #[serde(default = "default_resource_regen_per_tick")]
+    pub resource_regen_per_tick: f32,
+}
+
+fn default_max_resource() -> f32 {
+    100.0
+}
+
+/// About 16 resource per second.
+fn default_resource_regen_per_tick() -> f32 {
+    0.25
+}
+
+impl Default for PlayerStats {
+    fn default() -> Self {
+        Self {
+            max_health: 100.0,
+            max_resource: default_max_resource(),
+            resource_regen_per_tick: default_resource_regen_per_tick(),
+        }
+    }
+}
+
+impl PlayerStats {
+    /// The full `ResourcePool` a character with these stats spawns with.
+    pub fn resource_pool(&self) -> ResourcePool {
+        ResourcePool::new(self.max_resource, self.resource_regen_per_tick)
+    }
+}
+
+/// [`PlayerStats`] for each character archetype, applied in `handle_connected`.
+/// Archetypes without an entry in `per_type` use `default`. Loadable from RON
+/// with the server's `--player-stats <path>` flag.
+#[derive(Resource, Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerStatsConfig {
+    #[serde(default)]
+    pub default: PlayerStats,
+    #[serde(default)]
+    pub per_type: HashMap<CharacterType, PlayerStats>,
+}
+
+impl PlayerStatsConfig {
+    /// Stats characters of `character_type` spawn with.
+    pub fn for_type(&self, character_type: CharacterType) -> PlayerStats {
+        self.per_type
+            .get(&character_type)
+            .copied()
+            .unwrap_or(self.default)
+    }
+}
+
+/// Ability presets a new character can cycle between with
+/// `PlayerActions::SwapLoadout`. The first preset is always the global
+/// `DefaultAbilitySlots`, as resolved when the character spawns; `extra`
+/// presets follow it in order.
+#[derive(Resource, Clone, Debug, PartialEq)]
+pub struct StartingLoadouts {
+    pub extra: Vec<AbilitySlots>,
+}
+
+impl Default for StartingLoadouts {
+    /// One ranged alternative to the melee default kit.
+    fn default() -> Self {
+        let slot = |id: &str| Some(AbilityId(id.to_string()));
+        Self {
+            extra: vec![AbilitySlots([
+                slot("fireball"),
+                slot("dash"),
+                slot("shockwave"),
+                slot("barrier"),
+                slot("jump"),
+            ])],
+        }
+    }
+}
+
+impl StartingLoadouts {
+    /// Loadouts a character spawns with, starting on `default_slots`.
+    pub fn for_spawn(&self, default_slots: &AbilitySlots) -> AbilityLoadouts {
+        let mut presets = vec![default_slots.clone()];
+        presets.extend(self.extra.iter().cloned());
+        AbilityLoadouts::new(presets)
+    }
+}
+
+/// Health a newly spawned player starts with: a living cached `restored`
+/// health from a recent disconnect, otherwise full health from `stats`.
+fn initial_health(restored: Option<Health>, stats: PlayerStats) -> Health {
+    restored
+        .filter(|health| !health.is_dead())
+        .unwrap_or_else(|| Health::new(stats.max_health))
+}
+
+/// Written when a client connects, for gameplay hooks such as scoring or chat
+/// announcements. Its character spawns from [`Connected`] in the same frame.
+#[derive(Message, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerJoined {
+    pub peer_id: PeerId,
+    /// The server's `ClientOf` entity for this client.
+    pub client: Entity,
+}
+
+/// Written when a connected client disconnects or its link entity despawns.
+#[derive(Message, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlayerLeft {
+    pub peer_id: PeerId,
+    /// The server's `ClientOf` entity for this client.
+    pub client: Entity,
+}
+
 pub struct ServerGameplayPlugin;
 
 impl Plugin for ServerGameplayPlugin {
     fn build(&self, app: &mut App) {
+        app.init_resource::<PlayerStatsConfig>();
+        app.init_resource::<StartingLoadouts>();
         app.add_observer(handle_connected);
+        app.add_observer(prioritize_owned_replication);
+        app.add_message::<PlayerJoined>();
+        app.add_message::<PlayerLeft>();
+        app.add_observer(announce_player_joined);
+        app.add_observer(announce_player_left);
+        app.init_resource::<RecentlyDisconnected>();
+        app.init_resource::<PracticeMode>();
+        app.init_resource::<PlayerNameLimits>();
+        app.add_observer(cache_disconnected_character);
+        app.add_systems(Update, evict_expired_disconnects);
+        app.add_systems(Startup, protocol::world_time::spawn_world_clock);
+        app.add_systems(FixedUpdate, protocol::world_time::advance_world_time);
         // app.add_systems(OnEnter(AppState::Ready), spawn_dummy_target);
         app.add_systems(
             Update,
@@ -29,11 +189,18 @@ impl Plugin for ServerGameplayPlugin {
                 |query: Query<&MapLoadState>| query.iter().any(|s| *s == MapLoadState::Ready),
             )),
         );
-        // detect_grounded must run before handle_character_movement and
-        // ability_activation so the IsGrounded gate sees fresh state.
+        // detect_grounded and detect_wall_contact must run before
+        // handle_character_movement and ability_activation so the IsGrounded
+        // gate, wall jumps and step-up see fresh state.
         app.add_systems(
             FixedUpdate,
-            (protocol::detect_grounded, handle_character_movement)
+            (
+                (protocol::detect_grounded, protocol::detect_wall_contact),
+                handle_character_movement,
+                protocol::apply_dodge,
+                protocol::step_up_obstacles,
+                protocol::apply_ground_drag,
+            )
                 .chain()
                 .before(protocol::ability::ability_activation),
         );
@@ -56,10 +223,68 @@ impl Plugin for ServerGameplayPlugin {
                         .and(resource_exists::<VoxModelRegistry>),
                 ),
                 process_respawn_timers.after(start_respawn_timer),
-                expire_invulnerability,
+                protocol::expire_invulnerability,
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                sync_ability_manifest,
+                announce_respawns,
+                handle_input_device_reports,
+                handle_player_name_requests,
             ),
         );
-        app.add_systems(Update, sync_ability_manifest);
+    }
+}
+
+/// Sets each requesting client's character `Name` to its sanitized requested name.
+fn handle_player_name_requests(
+    mut commands: Commands,
+    limits: Res<PlayerNameLimits>,
+    mut receivers: Query<(Entity, &mut MessageReceiver<PlayerNameRequest>)>,
+    characters: Query<(Entity, &ControlledBy, &PlayerId), (With<CharacterMarker>, Without<Minion>)>,
+) {
+    for (client_entity, mut receiver) in &mut receivers {
+        for request in receiver.receive() {
+            let Some((character, _, player_id)) = characters
+                .iter()
+                .find(|(_, controlled_by, _)| controlled_by.owner == client_entity)
+            else {
+                trace!("handle_player_name_requests: no character for client {client_entity:?}");
+                continue;
+            };
+            let name = sanitize_player_name(&request.name, player_id.0, &limits);
+            commands.entity(character).insert(Name::new(name));
+        }
+    }
+}
+
+/// Tags or untags each reporting client's character with `GamepadAiming`, which
+/// turns projectile aim assist on for it.
+pub fn handle_input_device_reports(
+    mut commands: Commands,
+    mut receivers: Query<(&RemoteId, &mut MessageReceiver<InputDeviceReport>)>,
+    characters: Query<(Entity, &PlayerId), (With<CharacterMarker>, Without<Minion>)>,
+) {
+    for (remote_id, mut receiver) in &mut receivers {
+        for report in receiver.receive() {
+            let Some((character, _)) = characters
+                .iter()
+                .find(|(_, player_id)| player_id.0 == remote_id.0)
+            else {
+                trace!(
+                    "handle_input_device_reports: no character for player {}",
+                    remote_id.0
+                );
+                continue;
+            };
+            if report.gamepad {
+                commands.entity(character).insert(GamepadAiming);
+            } else {
+                commands.entity(character).remove::<GamepadAiming>();
+            }
+        }
     }
 }
 
@@ -112,13 +337,38 @@ fn sync_ability_manifest(defs: Option<Res<AbilityDefs>>, mut last_len: Local<usi
 //
 fn handle_character_movement(
     time: Res<Time>,
+    config: Res<MovementConfig>,
+    locks: MovementLocks,
+    contacts: CharacterContacts,
     mut query: Query<
-        (&ActionState<PlayerActions>, &ComputedMass, Forces),
+        (
+            Entity,
+            &ActionState<PlayerActions>,
+            &ComputedMass,
+            Has<IsGrounded>,
+            Option<&TouchingWall>,
+            Option<&ActiveBuffs>,
+            Forces,
+        ),
         (With<CharacterMarker>, Without<RespawnTimer>),
     >,
 ) {
-    for (action_state, mass, mut forces) in &mut query {
-        apply_movement(mass, time.delta_secs(), action_state, &mut forces);
+    for (entity, action_state, mass, grounded, wall, buffs, mut forces) in &mut query {
+        if locks.is_locked(entity) {
+            trace!("Movement input ignored for {entity:?}: locked by an ability or stun");
+            continue;
+        }
+        apply_movement(
+            mass,
+            time.delta_secs(),
+            action_state,
+            config.move_deadzone,
+            buffs,
+            grounded,
+            wall,
+            &contacts.blocked_directions(entity),
+            &mut forces,
+        );
     }
 }
 
@@ -139,14 +389,21 @@ fn validate_respawn_points(
 }
 
 /// Starts respawn timers for entities that just died (via DeathEvent).
-/// Skips entities with `OnDeathEffects` — those are handled by `on_death_effects`.
-fn start_respawn_timer(
+/// Skips entities with `OnDeathEffects` — those are handled by `on_death_effects` —
+/// and minions, which `minion_lifetime` despawns instead. In `PracticeMode` the
+/// timer expires immediately.
+pub fn start_respawn_timer(
     mut commands: Commands,
     timeline: Res<LocalTimeline>,
+    practice: Res<PracticeMode>,
     mut events: MessageReader<DeathEvent>,
     query: Query<
         (Option<&RespawnTimerConfig>, Has<OnDeathEffects>),
-        (Without<RespawnTimer>, Without<RespawnPoint>),
+        (
+            Without<RespawnTimer>,
+            Without<RespawnPoint>,
+            Without<Minion>,
+        ),
     >,
 ) {
     let tick = timeline.tick();
@@ -157,9 +414,13 @@ fn start_respawn_timer(
         if has_death_effects {
             continue;
         }
-        let duration = config
-            .map(|c| c.duration_ticks)
-            .unwrap_or(DEFAULT_RESPAWN_TICKS);
+        let duration = if practice.0 {
+            0
+        } else {
+            config
+                .map(|c| c.duration_ticks)
+                .unwrap_or(DEFAULT_RESPAWN_TICKS)
+        };
         commands.entity(event.entity).insert((
             RespawnTimer {
                 expires_at: tick + duration as i16,
@@ -263,10 +524,13 @@ fn tick_active_transformations(
     }
 }
 
-/// Processes expired respawn timers: teleports, heals, grants invulnerability.
-fn process_respawn_timers(
+/// Processes expired respawn timers: teleports, heals, grants invulnerability
+/// for the entity's `RespawnTimerConfig::invulnerable_ticks`. In `PracticeMode`
+/// characters stay where they died instead of teleporting.
+pub fn process_respawn_timers(
     mut commands: Commands,
     timeline: Res<LocalTimeline>,
+    practice: Res<PracticeMode>,
     mut query: Query<
         (
             Entity,
@@ -275,17 +539,18 @@ fn process_respawn_timers(
             &mut Position,
             Option<&mut LinearVelocity>,
             Option<&CharacterMarker>,
+            Option<&RespawnTimerConfig>,
         ),
         Without<RespawnPoint>,
     >,
     respawn_query: Query<&Position, (With<RespawnPoint>, Without<CharacterMarker>)>,
 ) {
     let tick = timeline.tick();
-    for (entity, timer, mut health, mut position, velocity, character) in &mut query {
+    for (entity, timer, mut health, mut position, velocity, character, config) in &mut query {
         if tick < timer.expires_at {
             continue;
         }
-        let respawn_pos = if character.is_some() {
+        let respawn_pos = if character.is_some() && !practice.0 {
             nearest_respawn_pos(&position, &respawn_query)
         } else {
             position.0
@@ -303,8 +568,11 @@ fn process_respawn_timers(
         commands
             .entity(entity)
             .remove::<(RespawnTimer, RigidBodyDisabled, ColliderDisabled)>();
+        let invulnerable_ticks = config
+            .map(|c| c.invulnerable_ticks)
+            .unwrap_or(DEFAULT_RESPAWN_INVULNERABLE_TICKS);
         commands.entity(entity).insert(Invulnerable {
-            expires_at: tick + 128i16,
+            expires_at: tick + invulnerable_ticks as i16,
         });
     }
 }
@@ -324,20 +592,155 @@ fn nearest_respawn_pos(
         .unwrap_or(DEFAULT_SPAWN_POS)
 }
 
-fn expire_invulnerability(
-    mut commands: Commands,
+/// Sends a [`RespawnNotice`] to every connected client when a player's
+/// character starts or finishes its respawn timer. In `PracticeMode` the timer
+/// starts and expires within one tick, so only `Respawned` is sent.
+pub fn announce_respawns(
     timeline: Res<LocalTimeline>,
-    query: Query<(Entity, &Invulnerable)>,
+    died: Query<(&PlayerId, &RespawnTimer), (With<CharacterMarker>, Added<RespawnTimer>)>,
+    mut respawned: RemovedComponents<RespawnTimer>,
+    characters: Query<(&PlayerId, &Position), (With<CharacterMarker>, Without<RespawnTimer>)>,
+    clients: Query<Entity, (With<ClientOf>, With<Connected>)>,
+    mut sender: ServerMultiMessageSender,
 ) {
     let tick = timeline.tick();
-    for (entity, invuln) in &query {
-        if tick >= invuln.expires_at {
-            commands.entity(entity).remove::<Invulnerable>();
-        }
+    let mut notices: Vec<RespawnNotice> = died
+        .iter()
+        .map(|(player, timer)| {
+            let remaining: i16 = timer.expires_at - tick;
+            RespawnNotice::Died {
+                player: player.0,
+                respawn_ticks: remaining.max(0) as u16,
+            }
+        })
+        .collect();
+    notices.extend(respawned.read().filter_map(|entity| {
+        let Ok((player, position)) = characters.get(entity) else {
+            trace!("announce_respawns: {entity:?} is not a living player character");
+            return None;
+        };
+        Some(RespawnNotice::Respawned {
+            player: player.0,
+            position: position.0,
+        })
+    }));
+    if notices.is_empty() {
+        return;
+    }
+
+    let targets: EntityHashSet = clients.iter().collect();
+    if targets.is_empty() {
+        trace!("announce_respawns: no connected clients");
+        return;
+    }
+    for notice in &notices {
+        sender
+            .send_to_entities::<_, CharacterLifecycleChannel>(notice, &targets)
+            .ok();
     }
 }
 
-fn handle_connected(
+/// Writes [`PlayerJoined`] for each newly connected client.
+pub fn announce_player_joined(
+    trigger: On<Add, Connected>,
+    remote_id_query: Query<&RemoteId, With<ClientOf>>,
+    mut joined: MessageWriter<PlayerJoined>,
+) {
+    let Ok(remote_id) = remote_id_query.get(trigger.entity) else {
+        trace!(
+            "announce_player_joined: {:?} is not a ClientOf",
+            trigger.entity
+        );
+        return;
+    };
+    joined.write(PlayerJoined {
+        peer_id: remote_id.0,
+        client: trigger.entity,
+    });
+}
+
+/// Writes [`PlayerLeft`] when a client loses `Connected`, including when its
+/// entity is despawned.
+pub fn announce_player_left(
+    trigger: On<Remove, Connected>,
+    remote_id_query: Query<&RemoteId, With<ClientOf>>,
+    mut left: MessageWriter<PlayerLeft>,
+) {
+    let Ok(remote_id) = remote_id_query.get(trigger.entity) else {
+        trace!(
+            "announce_player_left: {:?} is not a ClientOf",
+            trigger.entity
+        );
+        return;
+    };
+    left.write(PlayerLeft {
+        peer_id: remote_id.0,
+        client: trigger.entity,
+    });
+}
+
+/// Spawns a player character on the overworld, controlled by
+/// `client_entity`: physics, input, replication, prediction or interpolation
+/// as `rebroadcast` allows, `health`, and the `slots` loadout (`None` casts
+/// from `DefaultAbilitySlots`). The character
+/// still needs the client's room and a map transition to become visible.
+/// `handle_connected` spawns through this so tests, bots and respawns share
+/// one code path.
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_character(
+    commands: &mut Commands,
+    client_entity: Entity,
+    peer_id: PeerId,
+    position: Vec3,
+    color: Color,
+    character_type: CharacterType,
+    slots: Option<AbilitySlots>,
+    health: Health,
+    movement_config: &MovementConfig,
+    rebroadcast: &InputRebroadcastConfig,
+    overworld_map: Entity,
+) -> Entity {
+    let dimensions = character_type.dimensions();
+    let (predicted_by, interpolated_by) = rebroadcast.character_sync_targets(peer_id);
+    let mut character = commands.spawn((
+        Name::new("Character"),
+        PlayerId(peer_id),
+        Position(position),
+        Rotation::default(),
+        ActionState::<PlayerActions>::default(),
+        Replicate::to_clients(NetworkTarget::All),
+        NetworkVisibility,
+        PredictionTarget::to_clients(predicted_by),
+        InterpolationTarget::to_clients(interpolated_by),
+        ControlledBy {
+            owner: client_entity,
+            lifetime: Default::default(),
+        },
+        CharacterPhysicsBundle::new(movement_config, &dimensions),
+        ColorComponent(color),
+        CharacterMarker,
+        character_type,
+        dimensions,
+        MapInstanceId::Overworld,
+    ));
+    character.insert((
+        health,
+        HealthRegen::default(),
+        RespawnTimerConfig::default(),
+        AbilityCooldowns::default(),
+        ChunkTicket::player(overworld_map),
+        ClientChunkVisibility::default(),
+    ));
+    if let Some(slots) = slots {
+        character.insert(slots);
+    }
+    character.id()
+}
+
+/// Spawns and places a character for each newly connected client, restoring
+/// its cached state if it reconnected within the grace period, then starts the
+/// client's transition onto the overworld.
+pub fn handle_connected(
     trigger: On<Add, Connected>,
     mut commands: Commands,
     character_query: Query<Entity, (With<CharacterMarker>, Without<DummyTarget>)>,
@@ -350,6 +753,13 @@ fn handle_connected(
         &voxel_map_engine::prelude::MapDimensions,
     )>,
     mut start_senders: Query<&mut MessageSender<protocol::map::MapTransitionStart>>,
+    mut reconnect_cache: ResMut<RecentlyDisconnected>,
+    time: Res<Time>,
+    movement_config: Res<MovementConfig>,
+    player_stats: Res<PlayerStatsConfig>,
+    default_slots: Res<DefaultAbilitySlots>,
+    starting_loadouts: Res<StartingLoadouts>,
+    rebroadcast: Res<InputRebroadcastConfig>,
 ) {
     let client_entity = trigger.entity;
     let peer_id = remote_id_query
@@ -360,49 +770,43 @@ fn handle_connected(
 
     let num_characters = character_query.iter().count();
 
-    let available_colors = [
-        css::LIMEGREEN,
-        css::PINK,
-        css::YELLOW,
-        css::AQUA,
-        css::CRIMSON,
-    ];
-    let color = available_colors[num_characters % available_colors.len()];
+    let color = CHARACTER_COLORS[num_characters % CHARACTER_COLORS.len()];
 
-    let spawn_pos = respawn_query
+    let restored = reconnect_cache.take(peer_id, time.elapsed_secs_f64());
+    if restored.is_some() {
+        info!("Restoring cached character state for reconnecting client {peer_id}");
+    }
+    let default_spawn_pos = respawn_query
         .iter()
         .find(|(_, mid)| **mid == MapInstanceId::Overworld)
         .map(|(p, _)| p.0)
         .unwrap_or(DEFAULT_SPAWN_POS);
-
-    let character_entity = commands
-        .spawn((
-            Name::new("Character"),
-            PlayerId(peer_id),
-            Position(spawn_pos),
-            Rotation::default(),
-            ActionState::<PlayerActions>::default(),
-            Replicate::to_clients(NetworkTarget::All),
-            NetworkVisibility,
-            PredictionTarget::to_clients(NetworkTarget::All),
-            ControlledBy {
-                owner: client_entity,
-                lifetime: Default::default(),
-            },
-            CharacterPhysicsBundle::default(),
-            ColorComponent(color.into()),
-            CharacterMarker,
-            CharacterType::Humanoid,
-            MapInstanceId::Overworld,
-        ))
-        .insert((
-            Health::new(100.0),
-            RespawnTimerConfig::default(),
-            AbilityCooldowns::default(),
-            ChunkTicket::player(registry.get(&MapInstanceId::Overworld)),
-            ClientChunkVisibility::default(),
-        ))
-        .id();
+    // Characters always rejoin on the overworld, so a position cached on
+    // another map is meaningless here.
+    let spawn_pos = restored
+        .as_ref()
+        .filter(|cached| cached.map_id == MapInstanceId::Overworld)
+        .map_or(default_spawn_pos, |cached| cached.position);
+    let character_type = CharacterType::Humanoid;
+    let stats = player_stats.for_type(character_type);
+    let health = initial_health(restored.map(|cached| cached.health), stats);
+    let loadouts = starting_loadouts.for_spawn(&default_slots.0);
+    let character_entity = spawn_character(
+        &mut commands,
+        client_entity,
+        peer_id,
+        spawn_pos,
+        color,
+        character_type,
+        Some(loadouts.presets[loadouts.active].clone()),
+        health,
+        &movement_config,
+        &rebroadcast,
+        registry.get(&MapInstanceId::Overworld),
+    );
+    commands
+        .entity(character_entity)
+        .insert((loadouts, stats.resource_pool()));
 
     // Phase 2 (complete_map_transition) will AddSender when client reports ready
     let room = room_registry.get_or_create(&MapInstanceId::Overworld, &mut commands);
@@ -434,3 +838,64 @@ fn handle_connected(
         readiness_radius: protocol::transition::TRANSITION_READINESS_RADIUS,
     });
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn respawned_character_is_invulnerable_for_configured_ticks() {
+        const INVULNERABLE_TICKS: u16 = 10;
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<PracticeMode>();
+        app.insert_resource(LocalTimeline::default());
+        app.add_systems(
+            Update,
+            (process_respawn_timers, protocol::expire_invulnerability).chain(),
+        );
+        let mut health = Health::new(100.0);
+        health.apply_damage(100.0);
+        let entity = app
+            .world_mut()
+            .spawn((
+                RespawnTimer {
+                    expires_at: Tick(0),
+                },
+                RespawnTimerConfig {
+                    invulnerable_ticks: INVULNERABLE_TICKS,
+                    ..default()
+                },
+                health,
+                Position(Vec3::ZERO),
+            ))
+            .id();
+
+        app.update();
+        assert!(app.world().get::<RespawnTimer>(entity).is_none());
+        assert_eq!(
+            app.world().get::<Invulnerable>(entity),
+            Some(&Invulnerable {
+                expires_at: Tick(INVULNERABLE_TICKS)
+            })
+        );
+
+        app.world_mut()
+            .resource_mut::<LocalTimeline>()
+            .apply_delta(INVULNERABLE_TICKS as i16 - 1);
+        app.update();
+        assert!(
+            app.world().get::<Invulnerable>(entity).is_some(),
+            "still invulnerable one tick before the window ends"
+        );
+
+        app.world_mut()
+            .resource_mut::<LocalTimeline>()
+            .apply_delta(1);
+        app.update();
+        assert!(
+            app.world().get::<Invulnerable>(entity).is_none(),
+            "damageable once the window ends"
+        );
+    }
+}
@@ -1,7 +1,11 @@
-// Library interface for server crate (used by tests)
+// Library interface for the server crate, used by the binary, tests and embedding apps
 pub mod chunk_entities;
+pub mod diagnostics;
 pub mod gameplay;
 pub mod map;
 pub mod persistence;
+pub mod plugins;
+pub mod reconnect;
+pub mod shutdown;
 pub mod transition;
 pub mod world_object;
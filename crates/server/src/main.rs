@@ -1,19 +1,8 @@
-pub mod chunk_entities;
-pub mod diagnostics;
-pub mod gameplay;
-pub mod map;
-pub mod persistence;
-pub mod transition;
-pub mod world_object;
-
 use bevy::prelude::*;
-use diagnostics::ServerDiagnosticsPlugin;
-use gameplay::ServerGameplayPlugin;
-use map::ServerMapPlugin;
-use protocol::diagnostics::SharedDiagnosticsPlugin;
-use protocol::*;
-use server_lightyear::{ServerNetworkConfig, ServerNetworkPlugin};
-use std::time::Duration;
+use protocol::{AbilityAvailability, AbilityId};
+use server::gameplay::PlayerStatsConfig;
+use server::plugins::{GameServerConfig, GameServerPlugins};
+use server_lightyear::ServerNetworkConfig;
 
 fn main() {
     App::new()
@@ -35,22 +24,66 @@ fn main() {
         .add_message::<bevy::asset::AssetEvent<bevy::shader::Shader>>()
         .init_asset::<bevy::image::Image>()
         .add_message::<bevy::asset::AssetEvent<bevy::image::Image>>()
-        .add_plugins(lightyear::prelude::server::ServerPlugins {
-            tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
-        })
-        .add_plugins(SharedGameplayPlugin)
-        .add_plugins(ServerNetworkPlugin {
-            config: ServerNetworkConfig {
-                cert_pem_path: concat!(env!("CARGO_MANIFEST_DIR"), "/../../certificates/cert.pem")
+        .add_plugins(GameServerPlugins {
+            config: GameServerConfig {
+                network: ServerNetworkConfig {
+                    cert_pem_path: concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/../../certificates/cert.pem"
+                    )
                     .into(),
-                key_pem_path: concat!(env!("CARGO_MANIFEST_DIR"), "/../../certificates/key.pem")
+                    key_pem_path: concat!(
+                        env!("CARGO_MANIFEST_DIR"),
+                        "/../../certificates/key.pem"
+                    )
                     .into(),
-                ..Default::default()
+                    ..Default::default()
+                },
+                practice_mode: parse_practice_mode(),
+                abilities: parse_ability_availability(),
+                player_stats: parse_player_stats(),
             },
         })
-        .add_plugins(ServerGameplayPlugin)
-        .add_plugins(ServerMapPlugin)
-        .add_plugins(SharedDiagnosticsPlugin)
-        .add_plugins(ServerDiagnosticsPlugin)
         .run();
 }
+
+/// Practice mode (no cooldowns, instant respawns), from `--practice`.
+fn parse_practice_mode() -> bool {
+    std::env::args().any(|arg| arg == "--practice")
+}
+
+/// Abilities disabled with `--deny-abilities <id,id,...>`; all enabled otherwise.
+fn parse_ability_availability() -> AbilityAvailability {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(list) = args
+        .windows(2)
+        .find(|pair| pair[0] == "--deny-abilities")
+        .map(|pair| &pair[1])
+    else {
+        return AbilityAvailability::All;
+    };
+    AbilityAvailability::Deny(
+        list.split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| AbilityId(id.to_string()))
+            .collect(),
+    )
+}
+
+/// Player stats loaded from the RON file given with `--player-stats <path>`;
+/// defaults otherwise. Panics if the file can't be read or parsed.
+fn parse_player_stats() -> PlayerStatsConfig {
+    let args: Vec<String> = std::env::args().collect();
+    let Some(path) = args
+        .windows(2)
+        .find(|pair| pair[0] == "--player-stats")
+        .map(|pair| &pair[1])
+    else {
+        return PlayerStatsConfig::default();
+    };
+    let ron_data = std::fs::read_to_string(path)
+        .unwrap_or_else(|err| panic!("Failed to read player stats {path}: {err}"));
+    ron::from_str(&ron_data)
+        .unwrap_or_else(|err| panic!("Failed to parse player stats {path}: {err}"))
+}
@@ -6,14 +6,18 @@ use avian3d::prelude::Position;
 use bevy::app::AppExit;
 use bevy::prelude::*;
 use lightyear::prelude::{
-    ControlledBy, MessageReceiver, MessageSender, NetworkVisibility, RemoteId, Room, RoomEvent,
-    RoomTarget, ServerMultiMessageSender,
+    ControlledBy, LocalTimeline, MessageReceiver, MessageSender, NetworkTarget, NetworkVisibility,
+    RemoteId, Replicate, Room, RoomEvent, RoomTarget, ServerMultiMessageSender, Tick,
+};
+use protocol::ability::{
+    AbilityDefs, AbilityEffect, AbilityId, DamageType, EffectTarget, OnHitEffects,
 };
 use protocol::map::{MapSwitchTarget, MapTransitionStart, PlayerMapSwitchRequest};
 use protocol::{
-    CharacterMarker, ChunkChannel, ChunkDataSync, MapInstanceId, MapRegistry, PendingTransition,
-    SectionBlocksUpdate, UnloadColumn, VoxelChannel, VoxelEditAck, VoxelEditBroadcast,
-    VoxelEditReject, VoxelEditRequest, VoxelType,
+    spawn_map_props, CharacterDimensions, CharacterMarker, ChunkChannel, ChunkDataSync,
+    EffectApplier, MapInstanceId, MapProps, MapRegistry, PendingTransition, SectionBlocksUpdate,
+    UnloadColumn, VoxelChannel, VoxelEditAck, VoxelEditBroadcast, VoxelEditReject,
+    VoxelEditRequest, VoxelMaterialRegistry, VoxelType, VoxelWallEvent,
 };
 #[allow(unused_imports)]
 use tracy_client::plot;
@@ -32,7 +36,7 @@ use protocol::map::{MapSaveTarget, SavedEntity, SavedEntityKind};
 use protocol::terrain::TerrainDef;
 use protocol::vox_model::VoxModelRegistry;
 use protocol::world_object::{apply_object_components, WorldObjectDefRegistry};
-use protocol::{AppState, RespawnPoint, TerrainDefRegistry};
+use protocol::{AppState, RespawnPoint, RespawnTimer, TerrainDefRegistry, TrackedAssets};
 use voxel_map_engine::config::WorldObjectSpawn;
 use voxel_map_engine::persistence::fs_chunk::FsChunkStore;
 use voxel_map_engine::persistence::fs_chunk_entities::FsChunkEntitiesStore;
@@ -57,8 +61,11 @@ impl RoomRegistry {
 
 const DEFAULT_OVERWORLD_SEED: u64 = 999;
 const GENERATION_VERSION: u32 = 0;
-const SAVE_DEBOUNCE_SECONDS: f64 = 1.0;
-const MAX_DIRTY_SECONDS: f64 = 5.0;
+/// Upper bound on voxel edits accepted from a single client per tick.
+/// Requests beyond this are rejected so the client rolls its predictions back.
+const MAX_VOXEL_EDITS_PER_CLIENT_PER_TICK: usize = 64;
+/// Largest absolute voxel coordinate a client may edit on any axis.
+const MAX_VOXEL_EDIT_COORDINATE: i32 = 1 << 20;
 
 /// Tracks whether any map has unsaved dirty chunks.
 #[derive(Resource)]
@@ -78,11 +85,46 @@ impl Default for WorldDirtyState {
     }
 }
 
+/// When the world is saved while it has unsaved edits.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct WorldSaveConfig {
+    /// Save once no edit has arrived for this long, batching bursts of edits.
+    pub quiet_period_secs: f64,
+    /// Longest the world stays dirty before an auto-save, even while edits
+    /// keep arriving, so a crash loses at most this much.
+    pub autosave_interval_secs: f64,
+}
+
+impl Default for WorldSaveConfig {
+    fn default() -> Self {
+        Self {
+            quiet_period_secs: 1.0,
+            autosave_interval_secs: 5.0,
+        }
+    }
+}
+
+impl WorldSaveConfig {
+    /// Whether a world in `dirty_state` is due a save at `now` seconds: the
+    /// quiet period has passed since the last edit, or the world has been dirty
+    /// for the auto-save interval.
+    pub fn should_save(&self, dirty_state: &WorldDirtyState, now: f64) -> bool {
+        if !dirty_state.is_dirty {
+            return false;
+        }
+        let time_since_edit = now - dirty_state.last_edit_time;
+        let time_since_first_dirty = dirty_state.first_dirty_time.map(|t| now - t).unwrap_or(0.0);
+        time_since_edit >= self.quiet_period_secs
+            || time_since_first_dirty >= self.autosave_interval_secs
+    }
+}
+
 /// A voxel edit pending broadcast, with context for room-scoped sending.
 pub struct PendingVoxelEdit {
     pub position: IVec3,
     pub voxel: VoxelType,
     /// Client entity that made the edit (excluded from broadcast).
+    /// `Entity::PLACEHOLDER` for server-originated edits.
     pub originator: Entity,
     pub map_id: MapInstanceId,
 }
@@ -93,6 +135,19 @@ pub struct PendingVoxelBroadcasts {
     pub per_chunk: HashMap<IVec3, Vec<PendingVoxelEdit>>,
 }
 
+/// A wall raised by a `VoxelWall` ability, awaiting its revert to air.
+pub struct TemporaryVoxelWall {
+    pub map_id: MapInstanceId,
+    /// Cells the wall actually filled; cells that weren't air are left out.
+    pub cells: Vec<IVec3>,
+    pub material: u8,
+    pub expires_tick: Tick,
+}
+
+/// Ability-placed voxel walls still standing. Server-only.
+#[derive(Resource, Default)]
+pub struct TemporaryVoxelWalls(pub Vec<TemporaryVoxelWall>);
+
 /// Tracks a map entity's load lifecycle.
 #[derive(Component, PartialEq, Eq)]
 pub enum MapLoadState {
@@ -136,6 +191,40 @@ fn init_overworld_entity(
     registry.insert(MapInstanceId::Overworld, map);
 }
 
+/// Handle to the overworld's static props file, loaded before `AppState::Ready`.
+#[derive(Resource)]
+struct OverworldPropsHandle(Handle<MapProps>);
+
+const OVERWORLD_PROPS_PATH: &str = "props/overworld.props.ron";
+
+fn load_overworld_props(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut tracked: ResMut<TrackedAssets>,
+) {
+    let handle = asset_server.load::<MapProps>(OVERWORLD_PROPS_PATH);
+    tracked.add(handle.clone());
+    commands.insert_resource(OverworldPropsHandle(handle));
+}
+
+/// Spawns the overworld's static props once and replicates them to clients on that map.
+fn spawn_overworld_props(
+    mut commands: Commands,
+    handle: Res<OverworldPropsHandle>,
+    props_assets: Res<Assets<MapProps>>,
+) {
+    let Some(props) = props_assets.get(&handle.0) else {
+        warn!("{OVERWORLD_PROPS_PATH} failed to load, overworld has no props");
+        return;
+    };
+    for entity in spawn_map_props(&mut commands, props, &MapInstanceId::Overworld) {
+        commands
+            .entity(entity)
+            .insert((Replicate::to_clients(NetworkTarget::All), NetworkVisibility));
+    }
+    info!("Spawned {} overworld props", props.0.len());
+}
+
 /// Poll async meta loads, configure map entities when meta arrives.
 fn poll_map_meta(
     mut commands: Commands,
@@ -302,8 +391,11 @@ fn build_generator_from_def(
     )
 }
 
-fn save_dirty_chunks_debounced(
+/// Saves dirty chunks, map meta and map entities when
+/// [`WorldSaveConfig::should_save`], then clears the dirty flag.
+pub fn save_dirty_chunks_debounced(
     time: Res<Time>,
+    save_config: Res<WorldSaveConfig>,
     mut dirty_state: ResMut<WorldDirtyState>,
     mut map_query: Query<(
         &mut VoxelMapInstance,
@@ -323,18 +415,7 @@ fn save_dirty_chunks_debounced(
     )>,
     respawn_query: Query<(&Position, &MapInstanceId), With<RespawnPoint>>,
 ) {
-    if !dirty_state.is_dirty {
-        return;
-    }
-
-    let now = time.elapsed_secs_f64();
-    let time_since_edit = now - dirty_state.last_edit_time;
-    let time_since_first_dirty = dirty_state.first_dirty_time.map(|t| now - t).unwrap_or(0.0);
-
-    let should_save =
-        time_since_edit >= SAVE_DEBOUNCE_SECONDS || time_since_first_dirty >= MAX_DIRTY_SECONDS;
-
-    if !should_save {
+    if !save_config.should_save(&dirty_state, time.elapsed_secs_f64()) {
         return;
     }
 
@@ -626,15 +707,28 @@ impl Plugin for ServerMapPlugin {
             .init_resource::<MapRegistry>()
             .init_resource::<RoomRegistry>()
             .init_resource::<WorldDirtyState>()
+            .init_resource::<WorldSaveConfig>()
             .init_resource::<PendingVoxelBroadcasts>()
+            .init_resource::<TemporaryVoxelWalls>()
+            .init_resource::<VoxelMaterialRegistry>()
             .init_resource::<WorldSavePath>()
-            .add_systems(OnEnter(AppState::Ready), init_overworld_entity)
+            .add_systems(Startup, load_overworld_props)
+            .add_systems(
+                OnEnter(AppState::Ready),
+                (init_overworld_entity, spawn_overworld_props),
+            )
             .add_systems(
                 Update,
                 (
                     poll_map_meta.run_if(in_state(AppState::Ready)),
                     poll_map_entities.run_if(in_state(AppState::Ready)),
-                    (handle_voxel_edit_requests, flush_voxel_broadcasts).chain(),
+                    (
+                        handle_voxel_edit_requests,
+                        place_voxel_walls,
+                        revert_expired_voxel_walls,
+                        flush_voxel_broadcasts,
+                    )
+                        .chain(),
                     push_chunks_to_clients,
                     save_dirty_chunks_debounced,
                     handle_map_switch_requests.run_if(resource_exists::<TerrainDefRegistry>),
@@ -652,6 +746,10 @@ impl Plugin for ServerMapPlugin {
                     crate::chunk_entities::save_chunk_entities_periodic,
                 ),
             )
+            .add_systems(
+                FixedUpdate,
+                apply_voxel_hazard_damage.run_if(resource_exists::<AbilityDefs>),
+            )
             .add_systems(
                 Last,
                 (
@@ -686,6 +784,24 @@ fn is_edit_valid(
     if validate_voxel_edit(request, map_entity, voxel_world) {
         return true;
     }
+    send_edit_reject(
+        request,
+        map_entity,
+        client_entity,
+        voxel_world,
+        reject_senders,
+    );
+    false
+}
+
+/// Tells the client its edit was refused so it can roll back the prediction.
+fn send_edit_reject(
+    request: &VoxelEditRequest,
+    map_entity: Entity,
+    client_entity: Entity,
+    voxel_world: &VoxelWorld,
+    reject_senders: &mut Query<&mut MessageSender<VoxelEditReject>>,
+) {
     let current_voxel = voxel_world.get_voxel(map_entity, request.position);
     if let Ok(mut sender) = reject_senders.get_mut(client_entity) {
         sender.send::<VoxelChannel>(VoxelEditReject {
@@ -694,7 +810,6 @@ fn is_edit_valid(
             correct_voxel: current_voxel.into(),
         });
     }
-    false
 }
 
 /// Applies the voxel edit and marks the world dirty.
@@ -753,7 +868,7 @@ pub fn handle_voxel_edit_requests(
     map_registry: Res<MapRegistry>,
 ) {
     for (client_entity, mut receiver) in &mut receivers {
-        for request in receiver.receive() {
+        for (index, request) in receiver.receive().enumerate() {
             let Some((map_entity, player_map_id)) =
                 resolve_player_map(client_entity, &controlled_query, &*map_registry)
             else {
@@ -761,6 +876,21 @@ pub fn handle_voxel_edit_requests(
                 continue;
             };
 
+            if !is_within_edit_budget(index) {
+                warn!(
+                    "Dropping voxel edit {} from client {client_entity:?}: exceeded {MAX_VOXEL_EDITS_PER_CLIENT_PER_TICK} edits this tick",
+                    request.sequence
+                );
+                send_edit_reject(
+                    &request,
+                    map_entity,
+                    client_entity,
+                    &voxel_world,
+                    &mut reject_senders,
+                );
+                continue;
+            }
+
             if !is_edit_valid(
                 &request,
                 map_entity,
@@ -796,16 +926,219 @@ pub fn handle_voxel_edit_requests(
     }
 }
 
+/// Raises the walls requested by `VoxelWall` casts, filling only air cells, and
+/// queues them for broadcast. Walls are temporary, so the map isn't marked dirty.
+pub fn place_voxel_walls(
+    mut wall_events: MessageReader<VoxelWallEvent>,
+    mut walls: ResMut<TemporaryVoxelWalls>,
+    mut pending_broadcasts: ResMut<PendingVoxelBroadcasts>,
+    mut voxel_world: VoxelWorld,
+    map_registry: Res<MapRegistry>,
+) {
+    for event in wall_events.read() {
+        let Some(&map_entity) = map_registry.0.get(&event.map_id) else {
+            warn!("place_voxel_walls: map {:?} not registered", event.map_id);
+            continue;
+        };
+        let Some(chunk_size) = voxel_world.chunk_size(map_entity) else {
+            warn!("place_voxel_walls: map entity {map_entity:?} has no VoxelMapInstance");
+            continue;
+        };
+        let cells: Vec<IVec3> = event
+            .cells
+            .iter()
+            .copied()
+            .filter(|cell| voxel_world.get_voxel(map_entity, *cell) == WorldVoxel::Air)
+            .collect();
+        for &cell in &cells {
+            set_broadcast_voxel(
+                cell,
+                VoxelType::Solid(event.material),
+                map_entity,
+                &event.map_id,
+                chunk_size,
+                &mut voxel_world,
+                &mut pending_broadcasts,
+            );
+        }
+        walls.0.push(TemporaryVoxelWall {
+            map_id: event.map_id.clone(),
+            cells,
+            material: event.material,
+            expires_tick: event.expires_tick,
+        });
+    }
+}
+
+/// Turns expired walls back into air. Cells edited since the wall went up are
+/// left alone.
+pub fn revert_expired_voxel_walls(
+    timeline: Res<LocalTimeline>,
+    mut walls: ResMut<TemporaryVoxelWalls>,
+    mut pending_broadcasts: ResMut<PendingVoxelBroadcasts>,
+    mut voxel_world: VoxelWorld,
+    map_registry: Res<MapRegistry>,
+) {
+    let tick = timeline.tick();
+    let (expired, standing): (Vec<_>, Vec<_>) = std::mem::take(&mut walls.0)
+        .into_iter()
+        .partition(|wall| wall.expires_tick - tick <= 0);
+    walls.0 = standing;
+
+    for wall in expired {
+        let Some(&map_entity) = map_registry.0.get(&wall.map_id) else {
+            trace!("revert_expired_voxel_walls: map {:?} is gone", wall.map_id);
+            continue;
+        };
+        let Some(chunk_size) = voxel_world.chunk_size(map_entity) else {
+            trace!("revert_expired_voxel_walls: map entity {map_entity:?} unloaded");
+            continue;
+        };
+        for cell in wall.cells {
+            if voxel_world.get_voxel(map_entity, cell) != WorldVoxel::Solid(wall.material) {
+                continue;
+            }
+            set_broadcast_voxel(
+                cell,
+                VoxelType::Air,
+                map_entity,
+                &wall.map_id,
+                chunk_size,
+                &mut voxel_world,
+                &mut pending_broadcasts,
+            );
+        }
+    }
+}
+
+/// When a character standing in a hazard next takes damage. Stamped on first
+/// contact and removed once the character steps off.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HazardExposure {
+    pub next_damage_tick: Tick,
+}
+
+/// Damages characters whose capsule base overlaps a hazard voxel: on first
+/// contact, then every `interval_ticks` while they stay on it. Samples the cell
+/// the feet are in and the cell they stand on; the map entity is the damage
+/// source.
+pub fn apply_voxel_hazard_damage(
+    mut commands: Commands,
+    timeline: Res<LocalTimeline>,
+    materials: Res<VoxelMaterialRegistry>,
+    map_registry: Res<MapRegistry>,
+    voxel_world: VoxelWorld,
+    mut characters: Query<
+        (
+            Entity,
+            &Position,
+            &CharacterDimensions,
+            &MapInstanceId,
+            Option<&mut HazardExposure>,
+        ),
+        (With<CharacterMarker>, Without<RespawnTimer>),
+    >,
+    mut applier: EffectApplier,
+) {
+    let tick = timeline.tick();
+    for (entity, position, dimensions, map_id, exposure) in &mut characters {
+        let Some(&map_entity) = map_registry.0.get(map_id) else {
+            trace!("apply_voxel_hazard_damage: map {map_id:?} not registered");
+            continue;
+        };
+        let feet = position.0 - Vec3::Y * dimensions.feet_offset();
+        let hazard = [feet + Vec3::Y * 0.5, feet - Vec3::Y * 0.5]
+            .into_iter()
+            .find_map(|sample| {
+                let cell = sample.floor().as_ivec3();
+                materials.hazard(voxel_world.get_voxel(map_entity, cell))
+            });
+        let Some(hazard) = hazard else {
+            trace!("apply_voxel_hazard_damage: {entity:?} is not on a hazard");
+            if exposure.is_some() {
+                commands.entity(entity).remove::<HazardExposure>();
+            }
+            continue;
+        };
+        let next_damage_tick = tick + hazard.interval_ticks.max(1) as i16;
+        match exposure {
+            Some(exposure) if exposure.next_damage_tick - tick > 0 => {
+                trace!("apply_voxel_hazard_damage: {entity:?} hazard damage not due yet");
+                continue;
+            }
+            Some(mut exposure) => exposure.next_damage_tick = next_damage_tick,
+            None => {
+                commands
+                    .entity(entity)
+                    .insert(HazardExposure { next_damage_tick });
+            }
+        }
+        let effects = OnHitEffects::from_source(
+            map_entity,
+            AbilityId("voxel_hazard".into()),
+            vec![AbilityEffect::Damage {
+                amount: hazard.damage,
+                target: EffectTarget::Victim,
+                damage_type: DamageType::Physical,
+            }],
+        );
+        applier.apply_effects_to(entity, &effects, feet);
+    }
+}
+
+/// Writes a server-originated voxel and queues it for broadcast to every client
+/// in the map's room.
+fn set_broadcast_voxel(
+    position: IVec3,
+    voxel: VoxelType,
+    map_entity: Entity,
+    map_id: &MapInstanceId,
+    chunk_size: u32,
+    voxel_world: &mut VoxelWorld,
+    pending: &mut PendingVoxelBroadcasts,
+) {
+    voxel_world.set_voxel(map_entity, position, WorldVoxel::from(voxel));
+    queue_edit_broadcast(
+        PendingVoxelEdit {
+            position,
+            voxel,
+            originator: Entity::PLACEHOLDER,
+            map_id: map_id.clone(),
+        },
+        chunk_size,
+        pending,
+    );
+}
+
 /// Validates a voxel edit request. Returns false if the edit should be rejected.
 fn validate_voxel_edit(
-    _request: &VoxelEditRequest,
+    request: &VoxelEditRequest,
     _map_entity: Entity,
     _voxel_world: &VoxelWorld,
 ) -> bool {
-    // TODO: Add validation rules as needed (bounds, range, anti-cheat)
+    if !is_edit_position_in_bounds(request.position) {
+        warn!(
+            "Rejecting voxel edit {} at {}: position outside +/-{MAX_VOXEL_EDIT_COORDINATE}",
+            request.sequence, request.position
+        );
+        return false;
+    }
     true
 }
 
+/// Whether the `index`-th edit received from a client this tick may be processed.
+fn is_within_edit_budget(index: usize) -> bool {
+    index < MAX_VOXEL_EDITS_PER_CLIENT_PER_TICK
+}
+
+/// Whether every axis of `position` lies within `MAX_VOXEL_EDIT_COORDINATE`.
+fn is_edit_position_in_bounds(position: IVec3) -> bool {
+    position
+        .to_array()
+        .iter()
+        .all(|axis| axis.unsigned_abs() <= MAX_VOXEL_EDIT_COORDINATE as u32)
+}
+
 /// Drains accumulated voxel edits and broadcasts them to clients in the same room.
 /// Single edits send individual `VoxelEditBroadcast`; 2+ edits in the same chunk
 /// send a batched `SectionBlocksUpdate`. The originating client is excluded.
@@ -1381,4 +1714,27 @@ mod tests {
             "pending should be empty after drain"
         );
     }
+
+    #[test]
+    fn edit_budget_rejects_excess_edits_in_one_tick() {
+        assert!(is_within_edit_budget(0));
+        assert!(is_within_edit_budget(
+            MAX_VOXEL_EDITS_PER_CLIENT_PER_TICK - 1
+        ));
+        assert!(!is_within_edit_budget(MAX_VOXEL_EDITS_PER_CLIENT_PER_TICK));
+    }
+
+    #[test]
+    fn edit_position_bounds() {
+        assert!(is_edit_position_in_bounds(IVec3::new(10, -64, 300)));
+        assert!(is_edit_position_in_bounds(IVec3::splat(
+            -MAX_VOXEL_EDIT_COORDINATE
+        )));
+        assert!(!is_edit_position_in_bounds(IVec3::new(
+            0,
+            MAX_VOXEL_EDIT_COORDINATE + 1,
+            0
+        )));
+        assert!(!is_edit_position_in_bounds(IVec3::new(i32::MIN, 0, 0)));
+    }
 }
@@ -0,0 +1,83 @@
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+use lightyear::prelude::server::ServerPlugins;
+use protocol::diagnostics::SharedDiagnosticsPlugin;
+use protocol::{AbilityAvailability, PracticeMode, SharedGameplayPlugin, FIXED_TIMESTEP_HZ};
+use server_lightyear::{ServerNetworkConfig, ServerNetworkPlugin};
+use std::time::Duration;
+
+use crate::diagnostics::ServerDiagnosticsPlugin;
+use crate::gameplay::{PlayerStatsConfig, ServerGameplayPlugin};
+use crate::map::ServerMapPlugin;
+use crate::shutdown::ServerShutdownPlugin;
+
+/// Settings for [`GameServerPlugins`].
+#[derive(Clone)]
+pub struct GameServerConfig {
+    pub network: ServerNetworkConfig,
+    /// No cooldowns and instant in-place respawns.
+    pub practice_mode: bool,
+    /// Abilities players may cast.
+    pub abilities: AbilityAvailability,
+    /// Stats new player characters spawn with.
+    pub player_stats: PlayerStatsConfig,
+}
+
+impl Default for GameServerConfig {
+    fn default() -> Self {
+        Self {
+            network: ServerNetworkConfig::default(),
+            practice_mode: false,
+            abilities: AbilityAvailability::All,
+            player_stats: PlayerStatsConfig::default(),
+        }
+    }
+}
+
+/// The whole authoritative server: networking, protocol, gameplay, maps,
+/// shutdown handling and diagnostics. Expects the host app to provide
+/// `MinimalPlugins`, `StatesPlugin`, `AssetPlugin`, `TransformPlugin` and the
+/// `Mesh`, `StandardMaterial`, `Shader` and `Image` asset types.
+#[derive(Default)]
+pub struct GameServerPlugins {
+    pub config: GameServerConfig,
+}
+
+impl PluginGroup for GameServerPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(GameServerConfigPlugin {
+                practice_mode: self.config.practice_mode,
+                abilities: self.config.abilities,
+                player_stats: self.config.player_stats,
+            })
+            .add(ServerPlugins {
+                tick_duration: Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+            })
+            .add(SharedGameplayPlugin)
+            .add(ServerNetworkPlugin {
+                config: self.config.network,
+            })
+            .add(ServerGameplayPlugin)
+            .add(ServerMapPlugin)
+            .add(ServerShutdownPlugin)
+            .add(SharedDiagnosticsPlugin)
+            .add(ServerDiagnosticsPlugin)
+    }
+}
+
+/// Inserts the resources [`GameServerConfig`] sets. Added first, so the later
+/// plugins' `init_resource` calls keep these values.
+struct GameServerConfigPlugin {
+    practice_mode: bool,
+    abilities: AbilityAvailability,
+    player_stats: PlayerStatsConfig,
+}
+
+impl Plugin for GameServerConfigPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PracticeMode(self.practice_mode))
+            .insert_resource(self.abilities.clone())
+            .insert_resource(self.player_stats.clone());
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use lightyear::connection::client::Connected;
+use lightyear::prelude::{ControlledBy, PeerId};
+use protocol::{CharacterMarker, DummyTarget, Health, MapInstanceId, PlayerId};
+
+/// How long a disconnected player's character state is kept for a quick reconnect.
+pub const RECONNECT_GRACE_SECONDS: f64 = 30.0;
+
+/// Character state captured when its owning client disconnected.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisconnectedCharacter {
+    pub position: Vec3,
+    pub health: Health,
+    pub map_id: MapInstanceId,
+    pub disconnected_at: f64,
+}
+
+/// Characters of recently disconnected clients, keyed by their `PeerId`.
+/// `handle_connected` consumes an entry to restore state instead of spawning fresh.
+#[derive(Resource, Debug)]
+pub struct RecentlyDisconnected {
+    pub grace_seconds: f64,
+    pub entries: HashMap<PeerId, DisconnectedCharacter>,
+}
+
+impl Default for RecentlyDisconnected {
+    fn default() -> Self {
+        Self {
+            grace_seconds: RECONNECT_GRACE_SECONDS,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+impl RecentlyDisconnected {
+    /// Records `character` for `peer`, replacing any older entry.
+    pub fn insert(&mut self, peer: PeerId, character: DisconnectedCharacter) {
+        self.entries.insert(peer, character);
+    }
+
+    /// Removes and returns the cached state for `peer` if it is still within the grace window.
+    pub fn take(&mut self, peer: PeerId, now: f64) -> Option<DisconnectedCharacter> {
+        let entry = self.entries.remove(&peer)?;
+        if now - entry.disconnected_at > self.grace_seconds {
+            trace!("Reconnect cache entry for {peer} expired, spawning fresh");
+            return None;
+        }
+        Some(entry)
+    }
+
+    /// Drops entries older than the grace window.
+    pub fn evict_expired(&mut self, now: f64) {
+        let grace = self.grace_seconds;
+        self.entries
+            .retain(|_, entry| now - entry.disconnected_at <= grace);
+    }
+}
+
+/// Captures a player character's state right before it is despawned on
+/// disconnect. Characters removed while their owner is still connected are
+/// not cached.
+pub fn cache_disconnected_character(
+    trigger: On<Remove, CharacterMarker>,
+    characters: Query<
+        (&PlayerId, &Position, &Health, &MapInstanceId, &ControlledBy),
+        Without<DummyTarget>,
+    >,
+    connected: Query<(), With<Connected>>,
+    time: Res<Time>,
+    mut cache: ResMut<RecentlyDisconnected>,
+) {
+    let Ok((player_id, position, health, map_id, controlled_by)) = characters.get(trigger.entity)
+    else {
+        trace!(
+            "cache_disconnected_character: {:?} is not a player character",
+            trigger.entity
+        );
+        return;
+    };
+    if connected.contains(controlled_by.owner) {
+        trace!(
+            "cache_disconnected_character: owner of {:?} is still connected",
+            trigger.entity
+        );
+        return;
+    }
+    cache.insert(
+        player_id.0,
+        DisconnectedCharacter {
+            position: position.0,
+            health: health.clone(),
+            map_id: map_id.clone(),
+            disconnected_at: time.elapsed_secs_f64(),
+        },
+    );
+}
+
+/// Periodically clears cache entries whose grace window has passed.
+pub fn evict_expired_disconnects(time: Res<Time>, mut cache: ResMut<RecentlyDisconnected>) {
+    cache.evict_expired(time.elapsed_secs_f64());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached_at(disconnected_at: f64) -> DisconnectedCharacter {
+        DisconnectedCharacter {
+            position: Vec3::new(4.0, 5.0, 6.0),
+            health: Health {
+                current: 40.0,
+                max: 100.0,
+            },
+            map_id: MapInstanceId::Overworld,
+            disconnected_at,
+        }
+    }
+
+    #[test]
+    fn reconnect_within_window_restores_state() {
+        let mut cache = RecentlyDisconnected::default();
+        cache.insert(PeerId::Netcode(7), cached_at(10.0));
+
+        let restored = cache
+            .take(PeerId::Netcode(7), 10.0 + RECONNECT_GRACE_SECONDS - 1.0)
+            .expect("entry within grace window should be restored");
+        assert_eq!(restored.position, Vec3::new(4.0, 5.0, 6.0));
+        assert_eq!(restored.health.current, 40.0);
+        assert!(cache.entries.is_empty(), "entry is consumed on reconnect");
+    }
+
+    #[test]
+    fn reconnect_after_window_spawns_fresh() {
+        let mut cache = RecentlyDisconnected::default();
+        cache.insert(PeerId::Netcode(7), cached_at(10.0));
+
+        assert!(cache
+            .take(PeerId::Netcode(7), 10.0 + RECONNECT_GRACE_SECONDS + 1.0)
+            .is_none());
+    }
+
+    #[test]
+    fn despawned_character_is_cached() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<RecentlyDisconnected>();
+        app.add_observer(cache_disconnected_character);
+
+        let owner = app.world_mut().spawn_empty().id();
+        let character = app
+            .world_mut()
+            .spawn((
+                CharacterMarker,
+                PlayerId(PeerId::Netcode(3)),
+                Position(Vec3::new(1.0, 2.0, 3.0)),
+                Health::new(100.0),
+                MapInstanceId::Overworld,
+                ControlledBy {
+                    owner,
+                    lifetime: Default::default(),
+                },
+            ))
+            .id();
+        app.world_mut().despawn(character);
+
+        let cache = app.world().resource::<RecentlyDisconnected>();
+        let entry = cache
+            .entries
+            .get(&PeerId::Netcode(3))
+            .expect("despawned character should be cached");
+        assert_eq!(entry.position, Vec3::new(1.0, 2.0, 3.0));
+    }
+}
@@ -0,0 +1,51 @@
+//! Graceful shutdown: tells clients the server is closing before the `Last`
+//! schedule's shutdown saves run and the app exits.
+
+use bevy::app::{AppExit, TerminalCtrlCHandlerPlugin};
+use bevy::ecs::entity::EntityHashSet;
+use bevy::prelude::*;
+use lightyear::prelude::server::ClientOf;
+use lightyear::prelude::*;
+use protocol::{ServerClosing, ServerControlChannel};
+
+/// Reason sent to clients in [`ServerClosing`].
+pub const SERVER_CLOSING_REASON: &str = "Server is shutting down";
+
+/// Broadcasts [`ServerClosing`] when the app is asked to exit.
+pub struct ServerShutdownPlugin;
+
+impl Plugin for ServerShutdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            notify_clients_on_shutdown.after(TerminalCtrlCHandlerPlugin::exit_on_flag),
+        );
+    }
+}
+
+/// Sends [`ServerClosing`] to every connected client on `AppExit`. Runs in
+/// `Update` so lightyear sends it in this frame's `PostUpdate`, before the app
+/// exits; the voxel and entity saves still run afterwards in `Last`.
+pub fn notify_clients_on_shutdown(
+    mut exit_reader: MessageReader<AppExit>,
+    clients: Query<Entity, (With<ClientOf>, With<Connected>)>,
+    mut sender: ServerMultiMessageSender,
+) {
+    if exit_reader.is_empty() {
+        return;
+    }
+    exit_reader.clear();
+
+    let targets: EntityHashSet = clients.iter().collect();
+    if targets.is_empty() {
+        trace!("notify_clients_on_shutdown: no connected clients");
+        return;
+    }
+    info!("Notifying {} client(s) of shutdown", targets.len());
+    let closing = ServerClosing {
+        reason: SERVER_CLOSING_REASON.to_string(),
+    };
+    sender
+        .send_to_entities::<_, ServerControlChannel>(&closing, &targets)
+        .ok();
+}
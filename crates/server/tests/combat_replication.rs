@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use avian3d::prelude::{CollidingEntities, Position};
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::ability::{HitTargets, MeleeHitbox, OnHitEffects, TargetMask};
+use protocol::test_utils::TestHarness;
+use protocol::*;
+
+const MAX_WAIT_TICKS: usize = 50;
+
+/// Harness whose server runs the ability and hit-detection pipeline.
+fn combat_harness() -> TestHarness {
+    TestHarness::with_setup(
+        |server| {
+            server.add_plugins(AssetPlugin::default());
+            server.add_plugins(bevy::state::app::StatesPlugin);
+            server.add_plugins(AppStatePlugin);
+            server.add_plugins(AbilityPlugin);
+            // Pre-insert AbilityDefs so the plugin's asset loading no-ops.
+            server.insert_resource(AbilityDefs {
+                abilities: HashMap::new(),
+            });
+            server.insert_resource(DefaultAbilitySlots::default());
+            server
+                .world_mut()
+                .resource_mut::<NextState<AppState>>()
+                .set(AppState::Ready);
+        },
+        |_| {},
+    )
+}
+
+fn client_health(world: &mut World) -> Option<f32> {
+    world
+        .query_filtered::<&Health, (With<CharacterMarker>, With<Replicated>)>()
+        .iter(world)
+        .next()
+        .map(|health| health.current)
+}
+
+#[test]
+fn melee_damage_replicates_to_client_health() {
+    let mut harness = combat_harness();
+
+    let target = harness
+        .server
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            Health::new(100.0),
+            Position(Vec3::new(3.0, 0.0, 0.0)),
+            MapInstanceId::Overworld,
+            Replicate::to_clients(NetworkTarget::All),
+        ))
+        .id();
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| client_health(world) == Some(100.0)),
+        "target should replicate to the client at full health"
+    );
+
+    let caster = harness.server.world_mut().spawn(Position(Vec3::ZERO)).id();
+    let hitbox = harness
+        .server
+        .world_mut()
+        .spawn((
+            MeleeHitbox,
+            OnHitEffects {
+                ability_id: AbilityId("punch".into()),
+                effects: vec![AbilityEffect::Damage {
+                    amount: 25.0,
+                    target: EffectTarget::Victim,
+                    damage_type: DamageType::Physical,
+                }],
+                caster,
+                original_caster: caster,
+                ability_slot: 0,
+                depth: 0,
+            },
+            HitTargets::default(),
+            TargetMask::default(),
+            Position(Vec3::new(3.0, 0.0, 0.0)),
+            CollidingEntities::default(),
+        ))
+        .id();
+    // Simulate physics: the hitbox overlaps the target.
+    harness
+        .server
+        .world_mut()
+        .get_mut::<CollidingEntities>(hitbox)
+        .unwrap()
+        .insert(target);
+
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| client_health(world) == Some(75.0)),
+        "client should observe the server-applied melee damage"
+    );
+    assert_eq!(
+        harness
+            .server
+            .world()
+            .get::<Health>(target)
+            .unwrap()
+            .current,
+        75.0
+    );
+}
+
+fn client_cooldowns(world: &mut World) -> Option<AbilityCooldowns> {
+    world
+        .query_filtered::<&AbilityCooldowns, (With<CharacterMarker>, With<Replicated>)>()
+        .iter(world)
+        .next()
+        .cloned()
+}
+
+#[test]
+fn server_set_cooldown_replicates_to_client() {
+    let mut harness = combat_harness();
+
+    let character = harness
+        .server
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            AbilityCooldowns::default(),
+            Position(Vec3::ZERO),
+            MapInstanceId::Overworld,
+            Replicate::to_clients(NetworkTarget::All),
+        ))
+        .id();
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| {
+            client_cooldowns(world) == Some(AbilityCooldowns::default())
+        }),
+        "character should replicate to the client with no cooldowns"
+    );
+
+    let used = Tick(42);
+    harness
+        .server
+        .world_mut()
+        .get_mut::<AbilityCooldowns>(character)
+        .unwrap()
+        .last_used[2] = Some(used);
+
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| {
+            client_cooldowns(world).is_some_and(|cooldowns| cooldowns.last_used[2] == Some(used))
+        }),
+        "client should observe the server-set cooldown"
+    );
+}
@@ -0,0 +1,25 @@
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::test_utils::TestHarness;
+use protocol::CharacterMarker;
+
+#[test]
+fn replicated_character_appears_on_client() {
+    let mut harness = TestHarness::new();
+
+    harness.server.world_mut().spawn((
+        CharacterMarker,
+        Position(Vec3::new(1.0, 2.0, 3.0)),
+        Replicate::to_clients(NetworkTarget::All),
+    ));
+
+    let appeared = harness.tick_until(50, |world| {
+        world
+            .query_filtered::<(), (With<CharacterMarker>, With<Replicated>)>()
+            .iter(world)
+            .count()
+            == 1
+    });
+    assert!(appeared, "server character should replicate to the client");
+}
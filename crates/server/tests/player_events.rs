@@ -0,0 +1,117 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::ability::{GamepadAiming, InputDeviceChannel, InputDeviceReport};
+use protocol::test_utils::TestHarness;
+use protocol::{CharacterMarker, Minion, PlayerId};
+use server::gameplay::{
+    announce_player_joined, announce_player_left, handle_input_device_reports, PlayerJoined,
+    PlayerLeft,
+};
+
+const MAX_WAIT_TICKS: usize = 50;
+
+/// Connection messages the server has written, in order.
+#[derive(Resource, Default)]
+struct PlayerEvents {
+    joined: Vec<PlayerJoined>,
+    left: Vec<PlayerLeft>,
+}
+
+fn record_player_events(
+    mut joined: MessageReader<PlayerJoined>,
+    mut left: MessageReader<PlayerLeft>,
+    mut events: ResMut<PlayerEvents>,
+) {
+    events.joined.extend(joined.read().copied());
+    events.left.extend(left.read().copied());
+}
+
+#[test]
+fn player_joined_and_left_are_written_on_connect_and_disconnect() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            server.add_message::<PlayerJoined>();
+            server.add_message::<PlayerLeft>();
+            server.add_observer(announce_player_joined);
+            server.add_observer(announce_player_left);
+            server.init_resource::<PlayerEvents>();
+            server.add_systems(Update, record_player_events);
+        },
+        |_| {},
+    );
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let peer_id = harness
+        .server
+        .world()
+        .get::<RemoteId>(client)
+        .expect("connected client should have a RemoteId")
+        .0;
+    let events = harness.server.world().resource::<PlayerEvents>();
+    assert_eq!(events.joined, vec![PlayerJoined { peer_id, client }]);
+    assert!(events.left.is_empty(), "no one has left yet");
+
+    harness.server.world_mut().despawn(client);
+    harness.tick();
+
+    let events = harness.server.world().resource::<PlayerEvents>();
+    assert_eq!(events.left, vec![PlayerLeft { peer_id, client }]);
+    assert_eq!(events.joined.len(), 1, "leaving does not rejoin");
+}
+
+#[test]
+fn input_device_report_tags_the_reporting_players_character() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            server.add_systems(Update, handle_input_device_reports);
+        },
+        |_| {},
+    );
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let peer_id = world
+        .get::<RemoteId>(client)
+        .expect("connected client should have a RemoteId")
+        .0;
+    let character = world.spawn((CharacterMarker, PlayerId(peer_id))).id();
+    let minion = world
+        .spawn((
+            CharacterMarker,
+            PlayerId(peer_id),
+            Minion {
+                owner: character,
+                kind: "imp".into(),
+                expires_tick: Tick(1000),
+            },
+        ))
+        .id();
+
+    let client_entity = harness.clients[0].entity;
+    harness
+        .client()
+        .world_mut()
+        .get_mut::<MessageSender<InputDeviceReport>>(client_entity)
+        .expect("client should have an InputDeviceReport sender")
+        .send::<InputDeviceChannel>(InputDeviceReport { gamepad: true });
+
+    let tagged = (0..MAX_WAIT_TICKS).any(|_| {
+        harness.tick();
+        harness
+            .server
+            .world()
+            .get::<GamepadAiming>(character)
+            .is_some()
+    });
+    assert!(tagged, "the reporting player's character gets aim assist");
+    assert!(
+        harness
+            .server
+            .world()
+            .get::<GamepadAiming>(minion)
+            .is_none(),
+        "the player's minion shares its PlayerId but is not its character"
+    );
+}
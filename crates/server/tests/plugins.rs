@@ -0,0 +1,50 @@
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use protocol::{AppState, DodgeConfig, MapRegistry, MovementConfig, PracticeMode};
+use server::plugins::{GameServerConfig, GameServerPlugins};
+use server_lightyear::ServerNetworkPlugin;
+
+/// Host plugins and asset types `GameServerPlugins` expects, as in `main.rs`.
+fn host_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.add_plugins(AssetPlugin::default());
+    app.add_plugins(bevy::transform::TransformPlugin);
+    app.add_plugins(bevy::scene::ScenePlugin);
+    app.add_message::<AssetEvent<Mesh>>();
+    app.init_asset::<Mesh>();
+    app.init_asset::<StandardMaterial>();
+    app.init_asset::<bevy::shader::Shader>();
+    app.add_message::<AssetEvent<bevy::shader::Shader>>();
+    app.init_asset::<Image>();
+    app.add_message::<AssetEvent<Image>>();
+    app
+}
+
+#[test]
+fn game_server_plugins_initialize_states_and_resources() {
+    let mut app = host_app();
+    app.add_plugins(
+        GameServerPlugins {
+            config: GameServerConfig {
+                practice_mode: true,
+                ..default()
+            },
+        }
+        .build()
+        // Binding real sockets is out of scope for a unit test.
+        .disable::<ServerNetworkPlugin>(),
+    );
+
+    let world = app.world();
+    assert_eq!(
+        world.resource::<PracticeMode>(),
+        &PracticeMode(true),
+        "config is applied, not overwritten by plugin defaults"
+    );
+    assert!(world.contains_resource::<State<AppState>>());
+    assert!(world.contains_resource::<MapRegistry>());
+    assert!(world.contains_resource::<MovementConfig>());
+    assert!(world.contains_resource::<DodgeConfig>());
+}
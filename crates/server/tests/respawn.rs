@@ -0,0 +1,117 @@
+use avian3d::prelude::Position;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::test_utils::TestHarness;
+use protocol::*;
+use server::gameplay::{
+    announce_respawns, process_respawn_timers, start_respawn_timer, DEFAULT_SPAWN_POS,
+};
+
+const RESPAWN_TICKS: u16 = 5;
+const MAX_WAIT_TICKS: usize = 50;
+
+/// `RespawnNotice`s the client has received.
+#[derive(Resource, Default)]
+struct ReceivedNotices(Vec<RespawnNotice>);
+
+fn record_notices(
+    mut receivers: Query<&mut MessageReceiver<RespawnNotice>>,
+    mut received: ResMut<ReceivedNotices>,
+) {
+    for mut receiver in &mut receivers {
+        received.0.extend(receiver.receive());
+    }
+}
+
+#[test]
+fn dead_character_respawns_for_its_owner_and_clients_are_notified() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            server.add_message::<DeathEvent>();
+            server.init_resource::<PracticeMode>();
+            server.add_systems(
+                FixedUpdate,
+                (start_respawn_timer, process_respawn_timers).chain(),
+            );
+            server.add_systems(Update, announce_respawns);
+        },
+        |client| {
+            client.init_resource::<ReceivedNotices>();
+            client.add_systems(Update, record_notices);
+        },
+    );
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let peer_id = harness
+        .server
+        .world()
+        .get::<RemoteId>(client)
+        .expect("connected client should have a RemoteId")
+        .0;
+    let character = harness
+        .server
+        .world_mut()
+        .spawn((
+            CharacterMarker,
+            PlayerId(peer_id),
+            Health::new(100.0),
+            Position(Vec3::new(8.0, 0.0, 0.0)),
+            RespawnTimerConfig {
+                duration_ticks: RESPAWN_TICKS,
+                invulnerable_ticks: 0,
+            },
+            ControlledBy {
+                owner: client,
+                lifetime: Default::default(),
+            },
+            Replicate::to_clients(NetworkTarget::All),
+        ))
+        .id();
+    harness.tick();
+
+    let world = harness.server.world_mut();
+    world
+        .get_mut::<Health>(character)
+        .expect("character should have Health")
+        .apply_damage(100.0);
+    world.write_message(DeathEvent {
+        entity: character,
+        killed_by: None,
+    });
+
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| {
+            world.resource::<ReceivedNotices>().0.len() >= 2
+        }),
+        "client should hear about the death and the respawn"
+    );
+    let notices = &harness.client().world().resource::<ReceivedNotices>().0;
+    assert!(
+        matches!(notices[0], RespawnNotice::Died { player, .. } if player == peer_id),
+        "first notice should be the death, got {notices:?}"
+    );
+    assert_eq!(
+        notices[1],
+        RespawnNotice::Respawned {
+            player: peer_id,
+            position: DEFAULT_SPAWN_POS,
+        }
+    );
+
+    let world = harness.server.world();
+    assert!(world.get::<RespawnTimer>(character).is_none());
+    assert_eq!(
+        world.get::<ControlledBy>(character).map(|c| c.owner),
+        Some(client),
+        "the respawned character stays with its owner"
+    );
+    let health = world
+        .get::<Health>(character)
+        .expect("character should have Health");
+    assert!(!health.is_dead(), "respawn restores health");
+    assert_eq!(
+        world.get::<Position>(character),
+        Some(&Position(DEFAULT_SPAWN_POS))
+    );
+}
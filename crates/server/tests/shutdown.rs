@@ -0,0 +1,113 @@
+use std::sync::Arc;
+
+use bevy::app::AppExit;
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use persistence::{PendingStoreOps, StoreBackend};
+use protocol::map::SavedEntity;
+use protocol::{MapInstanceId, ServerClosing};
+use server::map::{save_world_on_shutdown, WorldDirtyState};
+use server::persistence::fs_map_entities::FsMapEntitiesStore;
+use server::persistence::fs_map_meta::FsMapMetaStore;
+use server::persistence::MapMeta;
+use server::shutdown::{ServerShutdownPlugin, SERVER_CLOSING_REASON};
+use voxel_map_engine::persistence::fs_chunk::FsChunkStore;
+use voxel_map_engine::persistence::{chunk_file_path, ChunkFileEnvelope};
+use voxel_map_engine::prelude::{
+    ChunkData, ChunkStatus, VoxelMapConfig, VoxelMapInstance, WorldVoxel,
+};
+
+/// Padded chunk volume for `chunk_size=16`.
+const PADDED_VOLUME_16: usize = 18 * 18 * 18;
+/// Client updates allowed for the notice to arrive once the server has stopped.
+const MAX_CLIENT_UPDATES: usize = 10;
+
+/// `ServerClosing` notices the client has received.
+#[derive(Resource, Default)]
+struct ReceivedClosing(Vec<ServerClosing>);
+
+fn record_closing(
+    mut receivers: Query<&mut MessageReceiver<ServerClosing>>,
+    mut received: ResMut<ReceivedClosing>,
+) {
+    for mut receiver in &mut receivers {
+        received.0.extend(receiver.receive());
+    }
+}
+
+/// Spawns a persisted overworld map in `map_dir` with one edited voxel, so it
+/// has a dirty chunk to save.
+fn spawn_dirty_map(app: &mut App, map_dir: &std::path::Path) {
+    let map_dir = Arc::new(map_dir.to_path_buf());
+    let mut instance = VoxelMapInstance::new(3, 16);
+    instance.insert_chunk_data(
+        IVec3::ZERO,
+        ChunkData::from_voxels(&vec![WorldVoxel::Air; PADDED_VOLUME_16], ChunkStatus::Full),
+    );
+    instance.set_voxel(IVec3::new(1, 1, 1), WorldVoxel::Solid(1));
+    let mut config = VoxelMapConfig::new(0, 0, 1, true);
+    config.save_dir = Some(map_dir.to_path_buf());
+    app.world_mut().spawn((
+        instance,
+        config,
+        MapInstanceId::Overworld,
+        StoreBackend::new(FsMapMetaStore {
+            map_dir: map_dir.clone(),
+        }),
+        PendingStoreOps::<(), MapMeta>::default(),
+        StoreBackend::new(FsMapEntitiesStore {
+            map_dir: map_dir.clone(),
+        }),
+        PendingStoreOps::<(), Vec<SavedEntity>>::default(),
+        StoreBackend::new(FsChunkStore { map_dir }),
+        PendingStoreOps::<IVec3, ChunkFileEnvelope>::default(),
+    ));
+}
+
+#[test]
+fn shutdown_notifies_clients_before_exit_and_saves_voxels() {
+    let tmp = tempfile::tempdir().unwrap();
+    let map_dir = tmp.path().join("overworld");
+    let mut harness = protocol::test_utils::TestHarness::with_setup(
+        |server| {
+            server.add_plugins(ServerShutdownPlugin);
+            server.insert_resource(WorldDirtyState {
+                is_dirty: true,
+                ..default()
+            });
+            server.add_systems(Last, save_world_on_shutdown);
+        },
+        |client| {
+            client.init_resource::<ReceivedClosing>();
+            client.add_systems(Update, record_closing);
+        },
+    );
+    spawn_dirty_map(&mut harness.server, &map_dir);
+
+    harness.server.world_mut().write_message(AppExit::Success);
+    harness.tick();
+
+    // The server app would exit after this frame, so only the client keeps running.
+    let client = harness.client();
+    for _ in 0..MAX_CLIENT_UPDATES {
+        if !client.world().resource::<ReceivedClosing>().0.is_empty() {
+            break;
+        }
+        client.update();
+    }
+    assert_eq!(
+        client.world().resource::<ReceivedClosing>().0,
+        vec![ServerClosing {
+            reason: SERVER_CLOSING_REASON.to_string(),
+        }],
+        "closing notice should be sent in the exit frame"
+    );
+    assert!(
+        chunk_file_path(&map_dir, IVec3::ZERO).exists(),
+        "dirty chunk should be saved on shutdown"
+    );
+    assert!(
+        map_dir.join("map.meta.bin").exists(),
+        "map meta should be saved"
+    );
+}
@@ -0,0 +1,435 @@
+use avian3d::prelude::*;
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::{ActionState, InputMap};
+use lightyear::connection::client::{Connected, Disconnected};
+use lightyear::prelude::server::ClientOf;
+use lightyear::prelude::*;
+use protocol::map::MapRegistry;
+use protocol::test_utils::TestHarness;
+use protocol::*;
+use server::gameplay::{
+    handle_connected, prioritize_owned_replication, spawn_character, PlayerStats,
+    PlayerStatsConfig, StartingLoadouts, CHARACTER_REPLICATION_PRIORITY,
+};
+use server::map::RoomRegistry;
+use server::reconnect::{cache_disconnected_character, RecentlyDisconnected};
+use std::collections::HashMap;
+use voxel_map_engine::prelude::{ChunkTicket, MapDimensions, VoxelMapConfig};
+
+const MAX_WAIT_TICKS: usize = 50;
+
+/// Registers `handle_connected` and what it needs: an overworld map and the
+/// server resources it reads. Clients that connect get a real character.
+fn connect_setup(server: &mut App) {
+    let overworld = server
+        .world_mut()
+        .spawn((
+            VoxelMapConfig::new(0, 0, 1, false),
+            MapDimensions {
+                chunk_size: 16,
+                column_y_range: (-2, 2),
+                tree_height: 3,
+                bounds: None,
+            },
+        ))
+        .id();
+    let mut registry = MapRegistry::default();
+    registry.insert(MapInstanceId::Overworld, overworld);
+    server.insert_resource(registry);
+    server.init_resource::<RoomRegistry>();
+    server.init_resource::<RecentlyDisconnected>();
+    server.init_resource::<PlayerStatsConfig>();
+    server.init_resource::<StartingLoadouts>();
+    server.insert_resource(DefaultAbilitySlots(AbilitySlots([
+        Some(AbilityId("punch".into())),
+        None,
+        None,
+        None,
+        None,
+    ])));
+    server.add_observer(handle_connected);
+    server.add_observer(prioritize_owned_replication);
+}
+
+/// The character `handle_connected` spawned for `client`.
+fn character_of(world: &mut World, client: Entity) -> Entity {
+    let mut query = world.query::<(Entity, &ControlledBy)>();
+    let characters: Vec<Entity> = query
+        .iter(world)
+        .filter(|(_, controlled_by)| controlled_by.owner == client)
+        .map(|(entity, _)| entity)
+        .collect();
+    assert_eq!(characters.len(), 1, "one character per connected client");
+    characters[0]
+}
+
+#[test]
+fn spawn_character_assembles_a_playable_character() {
+    let mut harness = TestHarness::with_setup(|_| {}, |_| {});
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let peer_id = world
+        .get::<RemoteId>(client)
+        .expect("connected client should have a RemoteId")
+        .0;
+    let map = world.spawn_empty().id();
+    let slots = AbilitySlots([Some(AbilityId("punch".into())), None, None, None, None]);
+    let position = Vec3::new(3.0, 5.0, -2.0);
+    let mut commands = world.commands();
+    let character = spawn_character(
+        &mut commands,
+        client,
+        peer_id,
+        position,
+        Color::WHITE,
+        CharacterType::Humanoid,
+        Some(slots.clone()),
+        Health::new(80.0),
+        &MovementConfig::default(),
+        &InputRebroadcastConfig::default(),
+        map,
+    );
+    world.flush();
+
+    let entity = world.entity(character);
+    assert!(entity.contains::<CharacterMarker>());
+    assert_eq!(entity.get::<PlayerId>(), Some(&PlayerId(peer_id)));
+    assert_eq!(entity.get::<Position>().map(|p| p.0), Some(position));
+    assert!(entity.contains::<RigidBody>(), "physics bundle");
+    assert!(entity.contains::<Collider>(), "physics bundle");
+    assert!(entity.contains::<ActionState<PlayerActions>>(), "input");
+    assert!(entity.contains::<Replicate>(), "replication");
+    assert!(entity.contains::<PredictionTarget>(), "prediction");
+    assert!(entity.contains::<InterpolationTarget>(), "interpolation");
+    assert_eq!(
+        entity
+            .get::<ControlledBy>()
+            .map(|controlled| controlled.owner),
+        Some(client)
+    );
+    assert_eq!(entity.get::<Health>(), Some(&Health::new(80.0)));
+    assert!(entity.contains::<HealthRegen>(), "passive regen");
+    assert_eq!(entity.get::<AbilitySlots>(), Some(&slots));
+    assert!(entity.contains::<AbilityCooldowns>());
+    assert!(entity.contains::<ChunkTicket>());
+    assert_eq!(
+        entity.get::<MapInstanceId>(),
+        Some(&MapInstanceId::Overworld)
+    );
+}
+
+#[test]
+fn connected_clients_own_characters_replicated_at_elevated_priority() {
+    let mut harness = TestHarness::with_setup(connect_setup, |_| {});
+    harness.add_client(|_| {});
+    harness.tick();
+
+    let clients = [harness.clients[0].client_of, harness.clients[1].client_of];
+    let world = harness.server.world_mut();
+    for client in clients {
+        let character = character_of(world, client);
+        let group = world
+            .get::<ReplicationGroup>(character)
+            .expect("an owned character replicates in its own group");
+        assert_eq!(group.priority(), CHARACTER_REPLICATION_PRIORITY);
+    }
+
+    let unowned = world
+        .spawn((CharacterMarker, Replicate::to_clients(NetworkTarget::All)))
+        .id();
+    world.flush();
+    let default_priority = world
+        .get::<ReplicationGroup>(unowned)
+        .map_or(1.0, ReplicationGroup::priority);
+    assert!(
+        default_priority < CHARACTER_REPLICATION_PRIORITY,
+        "an unowned character such as a minion keeps the default priority"
+    );
+}
+
+#[test]
+fn connected_character_can_swap_to_its_starting_loadouts() {
+    let mut harness = TestHarness::with_setup(connect_setup, |_| {});
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let character = character_of(world, client);
+    let default_slots = world.resource::<DefaultAbilitySlots>().0.clone();
+    let alternate = world.resource::<StartingLoadouts>().extra[0].clone();
+    let loadouts = world
+        .get::<AbilityLoadouts>(character)
+        .expect("characters spawn with loadouts");
+    assert_eq!(
+        loadouts.presets,
+        vec![default_slots.clone(), alternate.clone()]
+    );
+    assert_eq!(world.get::<AbilitySlots>(character), Some(&default_slots));
+
+    world
+        .get_mut::<ActionState<PlayerActions>>(character)
+        .unwrap()
+        .press(&PlayerActions::SwapLoadout);
+    world
+        .run_system_once(protocol::ability::swap_loadout)
+        .expect("swap_loadout params are available");
+
+    assert_eq!(
+        world.get::<AbilitySlots>(character),
+        Some(&alternate),
+        "SwapLoadout makes the next preset live"
+    );
+}
+
+#[test]
+fn connected_character_spawns_with_a_full_resource_pool() {
+    let mut harness = TestHarness::with_setup(connect_setup, |_| {});
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let character = character_of(world, client);
+    assert_eq!(
+        world.get::<ResourcePool>(character),
+        Some(&PlayerStats::default().resource_pool()),
+        "AbilityCost abilities need a pool to spend from"
+    );
+}
+
+#[test]
+fn connected_character_spawns_with_its_archetypes_max_health() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            connect_setup(server);
+            server.insert_resource(PlayerStatsConfig {
+                default: PlayerStats {
+                    max_health: 100.0,
+                    ..default()
+                },
+                per_type: HashMap::from([(
+                    CharacterType::Humanoid,
+                    PlayerStats {
+                        max_health: 250.0,
+                        ..default()
+                    },
+                )]),
+            });
+        },
+        |_| {},
+    );
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let character = character_of(world, client);
+    assert_eq!(
+        world.get::<Health>(character),
+        Some(&Health::new(250.0)),
+        "a Humanoid uses its per-type stats over the default"
+    );
+}
+
+/// `connect_setup` plus the observer that caches characters on disconnect.
+fn reconnect_setup(server: &mut App) {
+    connect_setup(server);
+    server.add_observer(cache_disconnected_character);
+}
+
+#[test]
+fn character_despawned_while_its_owner_is_connected_is_not_cached() {
+    let mut harness = TestHarness::with_setup(reconnect_setup, |_| {});
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let character = character_of(world, client);
+    world.despawn(character);
+
+    assert!(
+        world.resource::<RecentlyDisconnected>().entries.is_empty(),
+        "only a disconnect caches the character"
+    );
+}
+
+#[test]
+fn reconnecting_player_gets_their_character_back() {
+    let mut harness = TestHarness::with_setup(reconnect_setup, |_| {});
+    harness.tick();
+
+    let client = harness.clients[0].client_of;
+    let world = harness.server.world_mut();
+    let peer_id = world
+        .get::<RemoteId>(client)
+        .expect("connected client should have a RemoteId")
+        .0;
+    let character = character_of(world, client);
+    let position = Vec3::new(6.0, 2.0, -4.0);
+    let mut wounded = Health::new(100.0);
+    wounded.apply_damage(60.0);
+    world
+        .entity_mut(character)
+        .insert((Position(position), wounded.clone()));
+
+    world
+        .entity_mut(client)
+        .remove::<Connected>()
+        .insert(Disconnected::default());
+    harness.tick();
+
+    let world = harness.server.world_mut();
+    assert!(
+        world.get_entity(character).is_err(),
+        "a session-bound character despawns with its client"
+    );
+    let cached = world
+        .resource::<RecentlyDisconnected>()
+        .entries
+        .get(&peer_id)
+        .expect("the disconnected character should be cached");
+    assert_eq!(cached.position, position);
+    assert_eq!(cached.health, wounded);
+
+    // The same player comes back on a new link.
+    let relinked = world
+        .spawn((
+            ClientOf,
+            RemoteId(peer_id),
+            MessageSender::<protocol::map::MapTransitionStart>::default(),
+        ))
+        .id();
+    world.entity_mut(relinked).insert(Connected);
+    world.flush();
+
+    let restored = character_of(world, relinked);
+    assert_eq!(world.get::<Position>(restored), Some(&Position(position)));
+    assert_eq!(world.get::<Health>(restored), Some(&wounded));
+    assert!(
+        world.resource::<RecentlyDisconnected>().entries.is_empty(),
+        "the cache entry is consumed by the reconnect"
+    );
+}
+
+/// How client `index` sees the character `handle_connected` spawned for
+/// `owner`: `(predicted, interpolated)`.
+fn sync_of(harness: &mut TestHarness, index: usize, owner: PeerId) -> (bool, bool) {
+    let world = harness.clients[index].app.world_mut();
+    let mut query = world.query::<(&PlayerId, Has<Predicted>, Has<Interpolated>)>();
+    query
+        .iter(world)
+        .filter(|(player, ..)| player.0 == owner)
+        .fold((false, false), |(predicted, interpolated), (_, p, i)| {
+            (predicted || p, interpolated || i)
+        })
+}
+
+#[test]
+fn without_input_rebroadcast_remote_characters_are_interpolated() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            connect_setup(server);
+            server.insert_resource(InputRebroadcastConfig {
+                rebroadcast_inputs: false,
+            });
+        },
+        |_| {},
+    );
+    harness.add_client(|_| {});
+    harness.tick();
+
+    let peers: Vec<PeerId> = (0..2)
+        .map(|index| {
+            let client = harness.clients[index].client_of;
+            let world = harness.server.world_mut();
+            let character = character_of(world, client);
+            // Skip the map transition that would admit both into the overworld
+            // room; only the sync targets are under test.
+            world.entity_mut(character).remove::<NetworkVisibility>();
+            world.get::<PlayerId>(character).unwrap().0
+        })
+        .collect();
+
+    let synced = |harness: &mut TestHarness| {
+        [(0, peers[0]), (0, peers[1]), (1, peers[0]), (1, peers[1])]
+            .into_iter()
+            .all(|(index, owner)| sync_of(harness, index, owner) != (false, false))
+    };
+    assert!(
+        (0..MAX_WAIT_TICKS).any(|_| {
+            harness.tick();
+            synced(&mut harness)
+        }),
+        "both characters should reach both clients"
+    );
+    for (index, own, remote) in [(0, peers[0], peers[1]), (1, peers[1], peers[0])] {
+        assert_eq!(
+            sync_of(&mut harness, index, own),
+            (true, false),
+            "client {index} predicts its own character"
+        );
+        assert_eq!(
+            sync_of(&mut harness, index, remote),
+            (false, true),
+            "client {index} interpolates the remote character"
+        );
+    }
+}
+
+#[test]
+fn without_input_rebroadcast_the_owner_still_drives_its_character() {
+    let config = InputRebroadcastConfig {
+        rebroadcast_inputs: false,
+    };
+    let mut harness = TestHarness::server_only_with_config(config, connect_setup);
+    harness.add_client_with_config(config, |_| {});
+    harness.tick();
+
+    let server_character = {
+        let client = harness.clients[0].client_of;
+        let world = harness.server.world_mut();
+        let character = character_of(world, client);
+        world.entity_mut(character).remove::<NetworkVisibility>();
+        character
+    };
+
+    let controlled = |harness: &mut TestHarness| {
+        let world = harness.clients[0].app.world_mut();
+        let mut query = world.query_filtered::<Entity, (With<CharacterMarker>, With<Controlled>)>();
+        query.iter(world).next()
+    };
+    let client_character = (0..MAX_WAIT_TICKS)
+        .find_map(|_| {
+            harness.tick();
+            controlled(&mut harness)
+        })
+        .expect("the owned character should reach its client");
+    // What the client's gameplay plugin does for its controlled character; the
+    // InputMap marks whose inputs are sent.
+    harness.clients[0]
+        .app
+        .world_mut()
+        .entity_mut(client_character)
+        .insert(InputMap::<PlayerActions>::default())
+        .insert_if_new(ActionState::<PlayerActions>::default());
+
+    let jump_reached_server = (0..MAX_WAIT_TICKS).any(|_| {
+        harness.clients[0]
+            .app
+            .world_mut()
+            .get_mut::<ActionState<PlayerActions>>(client_character)
+            .unwrap()
+            .press(&PlayerActions::Jump);
+        harness.tick();
+        harness
+            .server
+            .world()
+            .get::<ActionState<PlayerActions>>(server_character)
+            .is_some_and(|action_state| action_state.pressed(&PlayerActions::Jump))
+    });
+    assert!(
+        jump_reached_server,
+        "the owning client's inputs reach the server without rebroadcast"
+    );
+}
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use avian3d::prelude::Position;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use lightyear::prelude::LocalTimeline;
+use protocol::ability::{AbilityAsset, AbilityDefs, AbilityHitEvent};
+use protocol::{
+    CharacterDimensions, CharacterMarker, DeathEvent, Health, MapInstanceId, MapRegistry,
+    VoxelHazard, VoxelMaterialDef, VoxelMaterialRegistry,
+};
+use server::map::{apply_voxel_hazard_damage, HazardExposure};
+use voxel_map_engine::prelude::{
+    ChunkData, ChunkStatus, FlatGenerator, RuntimeShape, VoxelGenerator, VoxelMapInstance,
+    VoxelWorld, WorldVoxel,
+};
+
+/// Padded chunk volume for `chunk_size=16`.
+const PADDED_VOLUME_16: usize = 18 * 18 * 18;
+const HAZARD_MATERIAL: u8 = 9;
+const SAFE_MATERIAL: u8 = 1;
+const HAZARD_DAMAGE: f32 = 10.0;
+const HAZARD_INTERVAL_TICKS: u16 = 4;
+
+/// App with an all-air overworld chunk, a hazard floor voxel at (2, 0, 2) and a
+/// safe one at (8, 0, 8).
+fn hazard_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(AssetPlugin::default());
+    app.init_asset::<AbilityAsset>();
+    app.insert_resource(AbilityDefs {
+        abilities: HashMap::new(),
+    });
+    app.add_message::<DeathEvent>();
+    app.add_message::<AbilityHitEvent>();
+    app.init_resource::<MapRegistry>();
+    app.insert_resource(LocalTimeline::default());
+    app.insert_resource(VoxelMaterialRegistry {
+        materials: HashMap::from([(
+            HAZARD_MATERIAL,
+            VoxelMaterialDef {
+                hazard: Some(VoxelHazard {
+                    damage: HAZARD_DAMAGE,
+                    interval_ticks: HAZARD_INTERVAL_TICKS,
+                }),
+                ..default()
+            },
+        )]),
+    });
+    app.add_systems(Update, apply_voxel_hazard_damage);
+
+    let mut instance = VoxelMapInstance::new(3, 16);
+    instance.insert_chunk_data(
+        IVec3::ZERO,
+        ChunkData::from_voxels(&vec![WorldVoxel::Air; PADDED_VOLUME_16], ChunkStatus::Full),
+    );
+    let map = app
+        .world_mut()
+        .spawn((
+            instance,
+            VoxelGenerator(Arc::new(FlatGenerator {
+                chunk_size: 16,
+                shape: RuntimeShape::<u32, 3>::new([18, 18, 18]),
+            })),
+        ))
+        .id();
+    app.world_mut()
+        .resource_mut::<MapRegistry>()
+        .insert(MapInstanceId::Overworld, map);
+
+    let mut state = SystemState::<VoxelWorld>::new(app.world_mut());
+    let mut voxel_world = state.get_mut(app.world_mut());
+    voxel_world.set_voxel(map, IVec3::new(2, 0, 2), WorldVoxel::Solid(HAZARD_MATERIAL));
+    voxel_world.set_voxel(map, IVec3::new(8, 0, 8), WorldVoxel::Solid(SAFE_MATERIAL));
+    app
+}
+
+/// Character whose feet rest on top of the voxel at `floor`.
+fn spawn_standing_on(world: &mut World, floor: IVec3) -> Entity {
+    let dimensions = CharacterDimensions::default();
+    let feet = floor.as_vec3() + Vec3::new(0.5, 1.0, 0.5);
+    world
+        .spawn((
+            CharacterMarker,
+            dimensions,
+            Health::new(100.0),
+            Position(feet + Vec3::Y * dimensions.feet_offset()),
+            MapInstanceId::Overworld,
+        ))
+        .id()
+}
+
+#[test]
+fn standing_on_hazard_voxel_deals_periodic_damage() {
+    let mut app = hazard_app();
+    let burning = spawn_standing_on(app.world_mut(), IVec3::new(2, 0, 2));
+    let safe = spawn_standing_on(app.world_mut(), IVec3::new(8, 0, 8));
+
+    // Ticks 0 through 8: damage lands on ticks 0, 4 and 8.
+    let mut burning_health = Vec::new();
+    for _ in 0..=2 * HAZARD_INTERVAL_TICKS {
+        app.update();
+        burning_health.push(app.world().get::<Health>(burning).unwrap().current);
+        app.world_mut()
+            .resource_mut::<LocalTimeline>()
+            .apply_delta(1);
+    }
+
+    assert_eq!(burning_health[0], 100.0 - HAZARD_DAMAGE);
+    assert_eq!(
+        burning_health[HAZARD_INTERVAL_TICKS as usize - 1],
+        100.0 - HAZARD_DAMAGE,
+        "no damage between intervals"
+    );
+    assert_eq!(
+        burning_health.last().copied(),
+        Some(100.0 - 3.0 * HAZARD_DAMAGE)
+    );
+    assert_eq!(
+        app.world().get::<Health>(safe).unwrap().current,
+        100.0,
+        "safe voxels deal no damage"
+    );
+}
+
+/// Runs `ticks` ticks, returning health after each.
+fn health_per_tick(app: &mut App, entity: Entity, ticks: u16) -> Vec<f32> {
+    (0..ticks)
+        .map(|_| {
+            app.update();
+            app.world_mut()
+                .resource_mut::<LocalTimeline>()
+                .apply_delta(1);
+            app.world().get::<Health>(entity).unwrap().current
+        })
+        .collect()
+}
+
+#[test]
+fn hazard_damage_is_timed_from_first_contact() {
+    let mut app = hazard_app();
+    // Tick 65534: off the interval's phase, two ticks before the u16 tick
+    // counter wraps.
+    app.world_mut()
+        .resource_mut::<LocalTimeline>()
+        .apply_delta(-2);
+    let burning = spawn_standing_on(app.world_mut(), IVec3::new(2, 0, 2));
+
+    let health = health_per_tick(&mut app, burning, HAZARD_INTERVAL_TICKS + 1);
+    assert_eq!(
+        health[0],
+        100.0 - HAZARD_DAMAGE,
+        "first contact hurts at once"
+    );
+    assert_eq!(
+        health[HAZARD_INTERVAL_TICKS as usize - 1],
+        100.0 - HAZARD_DAMAGE
+    );
+    assert_eq!(
+        health[HAZARD_INTERVAL_TICKS as usize],
+        100.0 - 2.0 * HAZARD_DAMAGE,
+        "the next hit lands one interval later, across the tick wrap"
+    );
+
+    // Stepping off clears the exposure, so stepping back on hurts at once.
+    app.world_mut().get_mut::<Position>(burning).unwrap().0 += Vec3::new(6.0, 0.0, 6.0);
+    health_per_tick(&mut app, burning, 1);
+    assert!(app.world().get::<HazardExposure>(burning).is_none());
+    app.world_mut().get_mut::<Position>(burning).unwrap().0 -= Vec3::new(6.0, 0.0, 6.0);
+    let health = health_per_tick(&mut app, burning, 1);
+    assert_eq!(health[0], 100.0 - 3.0 * HAZARD_DAMAGE);
+}
@@ -0,0 +1,209 @@
+use std::sync::Arc;
+
+use avian3d::prelude::Position;
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use client::map::{handle_chunk_data_sync, handle_voxel_broadcasts, VoxelPredictionState};
+use lightyear::prelude::*;
+use protocol::test_utils::TestHarness;
+use protocol::{
+    CharacterMarker, MapInstanceId, MapRegistry, VoxelChannel, VoxelEditBroadcast,
+    VoxelEditRequest, VoxelType,
+};
+use server::map::{
+    flush_voxel_broadcasts, handle_voxel_edit_requests, push_chunks_to_clients,
+    ClientChunkVisibility, PendingVoxelBroadcasts, RoomRegistry, WorldDirtyState,
+};
+use voxel_map_engine::prelude::{
+    ChunkData, ChunkStatus, ChunkTicket, FlatGenerator, MapDimensions, RuntimeShape, TicketType,
+    VoxelGenerator, VoxelMapInstance, VoxelWorld, WorldVoxel,
+};
+
+const MAX_WAIT_TICKS: usize = 50;
+const EDIT_POS: IVec3 = IVec3::new(5, 5, 5);
+
+/// Padded chunk volume for `chunk_size=16`.
+const PADDED_VOLUME_16: usize = 18 * 18 * 18;
+
+/// Spawns an overworld map with chunk (0,0,0) loaded as air and registers it.
+fn spawn_map(app: &mut App) -> Entity {
+    let map = spawn_unloaded_map(app);
+    let mut instance = app.world_mut().get_mut::<VoxelMapInstance>(map).unwrap();
+    instance.insert_chunk_data(
+        IVec3::ZERO,
+        ChunkData::from_voxels(&vec![WorldVoxel::Air; PADDED_VOLUME_16], ChunkStatus::Full),
+    );
+    instance.chunk_levels.insert(IVec2::ZERO, 0);
+    map
+}
+
+/// Spawns and registers an overworld map with no chunks loaded, as a client
+/// has before the server streams it any.
+fn spawn_unloaded_map(app: &mut App) -> Entity {
+    let map = app
+        .world_mut()
+        .spawn((
+            VoxelMapInstance::new(3, 16),
+            MapDimensions {
+                chunk_size: 16,
+                column_y_range: (-2, 2),
+                tree_height: 3,
+                bounds: None,
+            },
+            VoxelGenerator(Arc::new(FlatGenerator {
+                chunk_size: 16,
+                shape: RuntimeShape::<u32, 3>::new([18, 18, 18]),
+            })),
+        ))
+        .id();
+    app.world_mut()
+        .resource_mut::<MapRegistry>()
+        .insert(MapInstanceId::Overworld, map);
+    map
+}
+
+fn voxel_at(world: &mut World, pos: IVec3) -> WorldVoxel {
+    let map = world
+        .resource::<MapRegistry>()
+        .get(&MapInstanceId::Overworld);
+    let mut state = SystemState::<VoxelWorld>::new(world);
+    state.get_mut(world).get_voxel(map, pos)
+}
+
+/// Spawns the local player `handle_voxel_broadcasts` applies edits around:
+/// the predicted, controlled character holding a ticket on `map`.
+fn spawn_local_player(app: &mut App, map: Entity) {
+    app.world_mut().spawn((
+        CharacterMarker,
+        Predicted,
+        Controlled,
+        ChunkTicket::player(map),
+    ));
+}
+
+fn server_setup(app: &mut App) {
+    app.add_plugins(RoomPlugin);
+    app.init_resource::<MapRegistry>();
+    app.init_resource::<RoomRegistry>();
+    app.init_resource::<PendingVoxelBroadcasts>();
+    app.init_resource::<WorldDirtyState>();
+    app.add_systems(
+        Update,
+        (
+            (handle_voxel_edit_requests, flush_voxel_broadcasts).chain(),
+            push_chunks_to_clients,
+        )
+            .chain(),
+    );
+}
+
+/// The client's own voxel sync handlers, without the rest of `ClientMapPlugin`
+/// (meshing, input, the `InGame` state gate).
+fn client_setup(app: &mut App) {
+    app.init_resource::<MapRegistry>();
+    app.init_resource::<VoxelPredictionState>();
+    app.add_systems(Update, (handle_voxel_broadcasts, handle_chunk_data_sync));
+}
+
+#[test]
+fn voxel_edit_round_trips_to_second_client() {
+    let mut harness = TestHarness::server_only(server_setup);
+    let editor = harness.add_client(client_setup);
+    let observer = harness.add_client(client_setup);
+
+    let server_map = spawn_map(&mut harness.server);
+    for client in &mut harness.clients {
+        let map = spawn_map(&mut client.app);
+        spawn_local_player(&mut client.app, map);
+    }
+
+    let editor_client_of = harness.clients[editor].client_of;
+    harness.server.world_mut().spawn((
+        CharacterMarker,
+        ControlledBy {
+            owner: editor_client_of,
+            lifetime: Default::default(),
+        },
+        MapInstanceId::Overworld,
+    ));
+    let room = harness.server.world_mut().spawn(Room::default()).id();
+    harness
+        .server
+        .world_mut()
+        .resource_mut::<RoomRegistry>()
+        .0
+        .insert(MapInstanceId::Overworld, room);
+    for client in &harness.clients {
+        harness.server.world_mut().trigger(RoomEvent {
+            room,
+            target: RoomTarget::AddSender(client.client_of),
+        });
+    }
+
+    let editor_entity = harness.clients[editor].entity;
+    harness.clients[editor]
+        .app
+        .world_mut()
+        .get_mut::<MessageSender<VoxelEditRequest>>(editor_entity)
+        .expect("client should have a VoxelEditRequest sender")
+        .send::<VoxelChannel>(VoxelEditRequest {
+            position: EDIT_POS,
+            voxel: VoxelType::Solid(7),
+            sequence: 0,
+        });
+
+    let replicated = harness.tick_until_on(observer, MAX_WAIT_TICKS, |world| {
+        voxel_at(world, EDIT_POS) == WorldVoxel::Solid(7)
+    });
+    assert!(
+        replicated,
+        "second client should receive the edit broadcast"
+    );
+    assert_eq!(
+        voxel_at(harness.server.world_mut(), EDIT_POS),
+        WorldVoxel::Solid(7)
+    );
+    assert_eq!(
+        voxel_at(harness.clients[editor].app.world_mut(), EDIT_POS),
+        WorldVoxel::Air,
+        "the originating client is excluded from the broadcast"
+    );
+
+    // A client joining after the edit never saw the broadcast; it gets the
+    // edited chunk streamed as `ChunkDataSync` once its character holds a
+    // ticket on the map.
+    let late = harness.add_client(client_setup);
+    let late_map = spawn_unloaded_map(&mut harness.clients[late].app);
+    spawn_local_player(&mut harness.clients[late].app, late_map);
+    let loaded = |world: &World| {
+        world
+            .get::<VoxelMapInstance>(late_map)
+            .unwrap()
+            .get_chunk_data(IVec3::ZERO)
+            .is_some()
+    };
+    assert!(!loaded(harness.clients[late].app.world()));
+    let late_client_of = harness.clients[late].client_of;
+    harness.server.world_mut().spawn((
+        CharacterMarker,
+        ControlledBy {
+            owner: late_client_of,
+            lifetime: Default::default(),
+        },
+        MapInstanceId::Overworld,
+        Position(Vec3::ZERO),
+        ChunkTicket::new(server_map, TicketType::Player, 1),
+        ClientChunkVisibility::default(),
+    ));
+
+    let synced = harness.tick_until_on(late, MAX_WAIT_TICKS, |world| {
+        voxel_at(world, EDIT_POS) == WorldVoxel::Solid(7)
+    });
+    assert!(synced, "a late joiner should receive the edited chunk");
+    let world = harness.clients[late].app.world_mut();
+    assert!(
+        loaded(world),
+        "the edit arrives as chunk data, not a generator fallback"
+    );
+    assert_eq!(voxel_at(world, EDIT_POS + IVec3::X), WorldVoxel::Air);
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use lightyear::prelude::{LocalTimeline, Tick};
+use protocol::ability::voxel_wall_cells;
+use protocol::{MapInstanceId, MapRegistry, VoxelWallEvent};
+use server::map::{
+    place_voxel_walls, revert_expired_voxel_walls, PendingVoxelBroadcasts, TemporaryVoxelWalls,
+};
+use voxel_map_engine::prelude::{
+    ChunkData, ChunkStatus, FlatGenerator, RuntimeShape, VoxelGenerator, VoxelMapInstance,
+    VoxelWorld, WorldVoxel,
+};
+
+/// Padded chunk volume for `chunk_size=16`.
+const PADDED_VOLUME_16: usize = 18 * 18 * 18;
+const WALL_MATERIAL: u8 = 4;
+const WALL_DURATION_TICKS: i16 = 10;
+
+fn wall_app() -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_message::<VoxelWallEvent>();
+    app.init_resource::<MapRegistry>();
+    app.init_resource::<TemporaryVoxelWalls>();
+    app.init_resource::<PendingVoxelBroadcasts>();
+    app.insert_resource(LocalTimeline::default());
+    app.add_systems(
+        Update,
+        (place_voxel_walls, revert_expired_voxel_walls).chain(),
+    );
+
+    let mut instance = VoxelMapInstance::new(3, 16);
+    instance.insert_chunk_data(
+        IVec3::ZERO,
+        ChunkData::from_voxels(&vec![WorldVoxel::Air; PADDED_VOLUME_16], ChunkStatus::Full),
+    );
+    let map = app
+        .world_mut()
+        .spawn((
+            instance,
+            VoxelGenerator(Arc::new(FlatGenerator {
+                chunk_size: 16,
+                shape: RuntimeShape::<u32, 3>::new([18, 18, 18]),
+            })),
+        ))
+        .id();
+    app.world_mut()
+        .resource_mut::<MapRegistry>()
+        .insert(MapInstanceId::Overworld, map);
+    app
+}
+
+fn voxel_at(world: &mut World, pos: IVec3) -> WorldVoxel {
+    let map = world
+        .resource::<MapRegistry>()
+        .get(&MapInstanceId::Overworld);
+    let mut state = SystemState::<VoxelWorld>::new(world);
+    state.get_mut(world).get_voxel(map, pos)
+}
+
+fn advance_timeline(app: &mut App, delta: i16) {
+    app.world_mut()
+        .resource_mut::<LocalTimeline>()
+        .apply_delta(delta);
+}
+
+#[test]
+fn voxel_wall_is_placed_then_reverted_after_duration() {
+    let mut app = wall_app();
+    let cells = voxel_wall_cells(Vec3::new(8.0, 1.0, 8.0), Vec3::NEG_Z, 3, 2);
+    assert_eq!(cells.len(), 6, "3 wide by 2 tall");
+    app.world_mut().write_message(VoxelWallEvent {
+        caster: Entity::PLACEHOLDER,
+        map_id: MapInstanceId::Overworld,
+        cells: cells.clone(),
+        material: WALL_MATERIAL,
+        expires_tick: Tick(0) + WALL_DURATION_TICKS,
+    });
+
+    app.update();
+    for &cell in &cells {
+        assert_eq!(
+            voxel_at(app.world_mut(), cell),
+            WorldVoxel::Solid(WALL_MATERIAL),
+            "wall cell {cell} should be placed"
+        );
+    }
+    assert!(
+        !app.world()
+            .resource::<PendingVoxelBroadcasts>()
+            .per_chunk
+            .is_empty(),
+        "placed cells are queued for broadcast"
+    );
+
+    advance_timeline(&mut app, WALL_DURATION_TICKS - 1);
+    app.update();
+    assert_eq!(
+        voxel_at(app.world_mut(), cells[0]),
+        WorldVoxel::Solid(WALL_MATERIAL),
+        "wall stands until its duration elapses"
+    );
+
+    advance_timeline(&mut app, 1);
+    app.update();
+    for &cell in &cells {
+        assert_eq!(
+            voxel_at(app.world_mut(), cell),
+            WorldVoxel::Air,
+            "wall cell {cell} should revert to air"
+        );
+    }
+    assert!(app.world().resource::<TemporaryVoxelWalls>().0.is_empty());
+}
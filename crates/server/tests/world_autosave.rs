@@ -0,0 +1,157 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use persistence::{PendingStoreOps, StoreBackend};
+use protocol::map::SavedEntity;
+use protocol::MapInstanceId;
+use server::map::{save_dirty_chunks_debounced, WorldDirtyState, WorldSaveConfig};
+use server::persistence::fs_map_entities::FsMapEntitiesStore;
+use server::persistence::fs_map_meta::FsMapMetaStore;
+use server::persistence::MapMeta;
+use voxel_map_engine::lifecycle::PendingSaves;
+use voxel_map_engine::prelude::{
+    ChunkData, ChunkStatus, VoxelMapConfig, VoxelMapInstance, WorldVoxel,
+};
+
+/// Padded chunk volume for `chunk_size=16`.
+const PADDED_VOLUME_16: usize = 18 * 18 * 18;
+/// Frame length; exact in binary so elapsed time lands on the interval.
+const STEP_SECS: f64 = 0.25;
+const QUIET_PERIOD_SECS: f64 = 0.5;
+const AUTOSAVE_INTERVAL_SECS: f64 = 1.0;
+const MAX_UPDATES: usize = 20;
+
+/// Edits every frame, so the quiet-period save never triggers.
+fn keep_editing(time: Res<Time>, mut dirty_state: ResMut<WorldDirtyState>) {
+    dirty_state.last_edit_time = time.elapsed_secs_f64();
+}
+
+/// App with a persisted overworld map in `map_dir` whose chunk has an unsaved
+/// edit, made at startup. With `editing`, edits keep arriving every frame.
+fn autosave_app(map_dir: &std::path::Path, editing: bool) -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        STEP_SECS,
+    )));
+    app.insert_resource(WorldSaveConfig {
+        quiet_period_secs: QUIET_PERIOD_SECS,
+        autosave_interval_secs: AUTOSAVE_INTERVAL_SECS,
+    });
+    app.insert_resource(WorldDirtyState {
+        is_dirty: true,
+        last_edit_time: 0.0,
+        first_dirty_time: Some(0.0),
+    });
+    app.add_systems(Update, save_dirty_chunks_debounced);
+    if editing {
+        app.add_systems(Update, keep_editing.before(save_dirty_chunks_debounced));
+    }
+
+    let map_dir = Arc::new(map_dir.to_path_buf());
+    let mut instance = VoxelMapInstance::new(3, 16);
+    instance.insert_chunk_data(
+        IVec3::ZERO,
+        ChunkData::from_voxels(&vec![WorldVoxel::Air; PADDED_VOLUME_16], ChunkStatus::Full),
+    );
+    instance.set_voxel(IVec3::new(1, 1, 1), WorldVoxel::Solid(1));
+    let mut config = VoxelMapConfig::new(0, 0, 1, true);
+    config.save_dir = Some(map_dir.to_path_buf());
+    let map = app
+        .world_mut()
+        .spawn((
+            instance,
+            config,
+            MapInstanceId::Overworld,
+            PendingSaves::default(),
+            StoreBackend::new(FsMapMetaStore {
+                map_dir: map_dir.clone(),
+            }),
+            PendingStoreOps::<(), MapMeta>::default(),
+            StoreBackend::new(FsMapEntitiesStore { map_dir }),
+            PendingStoreOps::<(), Vec<SavedEntity>>::default(),
+        ))
+        .id();
+    (app, map)
+}
+
+/// Updates until the world is saved, asserting it stays dirty before
+/// `expected_save_secs`. Returns the elapsed time of the save.
+fn run_until_saved(app: &mut App, expected_save_secs: f64) -> f64 {
+    for _ in 0..MAX_UPDATES {
+        app.update();
+        let elapsed = app.world().resource::<Time>().elapsed_secs_f64();
+        let is_dirty = app.world().resource::<WorldDirtyState>().is_dirty;
+        if elapsed < expected_save_secs {
+            assert!(is_dirty, "saved too early, at {elapsed}s");
+        }
+        if !is_dirty {
+            return elapsed;
+        }
+    }
+    panic!("world was never saved");
+}
+
+/// The edited chunk is queued and map meta is written.
+fn assert_map_saved(app: &mut App, map: Entity, map_dir: &std::path::Path) {
+    let dirty_state = app.world().resource::<WorldDirtyState>();
+    assert!(
+        !dirty_state.is_dirty,
+        "dirty flag should clear after auto-save"
+    );
+    assert_eq!(dirty_state.first_dirty_time, None);
+
+    let world = app.world_mut();
+    assert!(world
+        .get::<VoxelMapInstance>(map)
+        .unwrap()
+        .dirty_chunks
+        .is_empty());
+    let queued: Vec<IVec3> = world
+        .get::<PendingSaves>(map)
+        .unwrap()
+        .queue
+        .iter()
+        .map(|save| save.position)
+        .collect();
+    assert_eq!(
+        queued,
+        vec![IVec3::ZERO],
+        "edited chunk should be queued for saving"
+    );
+
+    world
+        .get_mut::<PendingStoreOps<(), MapMeta>>(map)
+        .unwrap()
+        .flush();
+    assert!(
+        map_dir.join("map.meta.bin").exists(),
+        "map meta should be saved"
+    );
+}
+
+#[test]
+fn dirty_world_is_saved_after_quiet_period() {
+    let tmp = tempfile::tempdir().unwrap();
+    let map_dir = tmp.path().join("overworld");
+    let (mut app, map) = autosave_app(&map_dir, false);
+
+    let saved_at = run_until_saved(&mut app, QUIET_PERIOD_SECS);
+    assert!(
+        saved_at < AUTOSAVE_INTERVAL_SECS,
+        "quiet period should save before the auto-save interval, saved at {saved_at}s"
+    );
+    assert_map_saved(&mut app, map, &map_dir);
+}
+
+#[test]
+fn dirty_world_is_autosaved_after_interval_despite_continuous_edits() {
+    let tmp = tempfile::tempdir().unwrap();
+    let map_dir = tmp.path().join("overworld");
+    let (mut app, map) = autosave_app(&map_dir, true);
+
+    run_until_saved(&mut app, AUTOSAVE_INTERVAL_SECS);
+    assert_map_saved(&mut app, map, &map_dir);
+}
@@ -0,0 +1,47 @@
+use bevy::prelude::*;
+use protocol::test_utils::TestHarness;
+use protocol::world_time::{advance_world_time, spawn_world_clock, sync_world_time};
+use protocol::WorldTime;
+
+const MAX_WAIT_TICKS: usize = 50;
+
+#[test]
+fn world_time_advances_on_server_and_replicates_to_client() {
+    let mut harness = TestHarness::with_setup(
+        |server| {
+            server.init_resource::<WorldTime>();
+            server.add_systems(Startup, spawn_world_clock);
+            server.add_systems(FixedUpdate, advance_world_time);
+        },
+        |client| {
+            client.init_resource::<WorldTime>();
+            client.add_systems(Update, sync_world_time);
+        },
+    );
+
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| {
+            world.resource::<WorldTime>().ticks > 0
+        }),
+        "replicated world time should reach the client"
+    );
+    let client_ticks = harness.client().world().resource::<WorldTime>().ticks;
+    let server_ticks = harness.server.world().resource::<WorldTime>().ticks;
+    assert!(
+        client_ticks <= server_ticks,
+        "client ({client_ticks}) cannot be ahead of the server ({server_ticks})"
+    );
+
+    harness.tick_n(10);
+    let server_later = harness.server.world().resource::<WorldTime>().ticks;
+    assert!(
+        server_later > server_ticks,
+        "server keeps advancing world time"
+    );
+    assert!(
+        harness.tick_until(MAX_WAIT_TICKS, |world| {
+            world.resource::<WorldTime>().ticks >= server_later
+        }),
+        "client should follow the server's world time"
+    );
+}
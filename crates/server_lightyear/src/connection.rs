@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use lightyear::prelude::server::*;
 use lightyear::prelude::*;
-use protocol::{PRIVATE_KEY, PROTOCOL_ID};
+use protocol::{FIXED_TIMESTEP_HZ, PRIVATE_KEY, PROTOCOL_ID};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
 use std::time::Duration;
@@ -16,6 +16,8 @@ pub struct ServerNetworkConfig {
     pub private_key: [u8; 32],
     pub cert_pem_path: PathBuf,
     pub key_pem_path: PathBuf,
+    /// How often replication updates are sent to each client. `Duration::ZERO`
+    /// sends every tick; non-zero values shorter than one tick are rounded up.
     pub replication_interval: Duration,
 }
 
@@ -47,10 +49,15 @@ impl Default for ServerNetworkPlugin {
 
 impl Plugin for ServerNetworkPlugin {
     fn build(&self, app: &mut App) {
-        let config = self.config.clone();
+        let mut config = self.config.clone();
+        config.replication_interval = validate_replication_interval(
+            config.replication_interval,
+            Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ),
+        );
         app.insert_resource(config.clone());
-        app.register_required_components_with::<ClientOf, ReplicationSender>(|| {
-            ReplicationSender::new(REPLICATION_INTERVAL, SendUpdatesMode::SinceLastAck, false)
+        let replication_interval = config.replication_interval;
+        app.register_required_components_with::<ClientOf, ReplicationSender>(move || {
+            replication_sender(replication_interval)
         });
         app.add_systems(Startup, move |commands: Commands| {
             start_server(commands, config.clone());
@@ -58,6 +65,24 @@ impl Plugin for ServerNetworkPlugin {
     }
 }
 
+/// The sender each connected client replicates through, sending updates every
+/// `interval` (see [`ServerNetworkConfig::replication_interval`]).
+pub fn replication_sender(interval: Duration) -> ReplicationSender {
+    ReplicationSender::new(interval, SendUpdatesMode::SinceLastAck, false)
+}
+
+/// Clamps a non-zero `interval` to at least one `tick_duration`, since the
+/// sender cannot replicate more often than the simulation ticks.
+fn validate_replication_interval(interval: Duration, tick_duration: Duration) -> Duration {
+    if interval.is_zero() || interval >= tick_duration {
+        return interval;
+    }
+    warn!(
+        "replication_interval {interval:?} is shorter than one tick ({tick_duration:?}); using one tick"
+    );
+    tick_duration
+}
+
 fn start_server(mut commands: Commands, config: ServerNetworkConfig) {
     let netcode = crate::netcode::build_netcode_server(&config);
     let webtransport_io = crate::webtransport::build_io(&config);
@@ -77,3 +102,46 @@ fn start_server(mut commands: Commands, config: ServerNetworkConfig) {
         config.bind_addr, config.port
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tick() -> Duration {
+        Duration::from_secs_f64(1.0 / FIXED_TIMESTEP_HZ)
+    }
+
+    #[test]
+    fn interval_shorter_than_tick_is_rounded_up() {
+        assert_eq!(
+            validate_replication_interval(Duration::from_millis(1), tick()),
+            tick()
+        );
+    }
+
+    #[test]
+    fn zero_and_long_intervals_are_kept() {
+        assert_eq!(
+            validate_replication_interval(Duration::ZERO, tick()),
+            Duration::ZERO
+        );
+        assert_eq!(
+            validate_replication_interval(Duration::from_millis(250), tick()),
+            Duration::from_millis(250)
+        );
+    }
+
+    #[test]
+    fn plugin_uses_configured_interval() {
+        let mut app = App::new();
+        app.add_plugins(ServerNetworkPlugin {
+            config: ServerNetworkConfig {
+                replication_interval: Duration::from_millis(250),
+                ..Default::default()
+            },
+        });
+
+        let config = app.world().resource::<ServerNetworkConfig>();
+        assert_eq!(config.replication_interval, Duration::from_millis(250));
+    }
+}
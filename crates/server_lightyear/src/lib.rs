@@ -3,4 +3,4 @@ mod connection;
 mod netcode;
 mod webtransport;
 
-pub use connection::{ServerNetworkConfig, ServerNetworkPlugin};
+pub use connection::{replication_sender, ServerNetworkConfig, ServerNetworkPlugin};
@@ -0,0 +1,71 @@
+use bevy::prelude::*;
+use lightyear::prelude::*;
+use protocol::test_utils::TestHarness;
+use protocol::*;
+use server_lightyear::replication_sender;
+use std::time::Duration;
+
+/// One second of ticks.
+const OBSERVED_TICKS: usize = FIXED_TIMESTEP_HZ as usize;
+const SLOW_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Frames in which the client saw its replicated `Health` change.
+#[derive(Resource, Default)]
+struct HealthUpdates(usize);
+
+fn count_health_updates(changed: Query<(), Changed<Health>>, mut updates: ResMut<HealthUpdates>) {
+    if !changed.is_empty() {
+        updates.0 += 1;
+    }
+}
+
+fn count_updates(client: &mut App) {
+    client.init_resource::<HealthUpdates>();
+    client.add_systems(Update, count_health_updates);
+}
+
+#[test]
+fn replication_interval_limits_how_often_clients_receive_updates() {
+    let mut harness = TestHarness::server_only(|_| {});
+    let every_tick =
+        harness.add_client_with_sender(replication_sender(Duration::ZERO), count_updates);
+    let slow = harness.add_client_with_sender(replication_sender(SLOW_INTERVAL), count_updates);
+    let target = harness
+        .server
+        .world_mut()
+        .spawn((
+            Health::new(1000.0),
+            Replicate::to_clients(NetworkTarget::All),
+        ))
+        .id();
+
+    for _ in 0..OBSERVED_TICKS {
+        harness
+            .server
+            .world_mut()
+            .get_mut::<Health>(target)
+            .expect("target should have Health")
+            .apply_damage(1.0);
+        harness.tick();
+    }
+
+    let updates = |index: usize| {
+        harness.clients[index]
+            .app
+            .world()
+            .resource::<HealthUpdates>()
+            .0
+    };
+    let max_slow_updates =
+        (Duration::from_secs(1).as_millis() / SLOW_INTERVAL.as_millis()) as usize + 1;
+    assert!(
+        updates(slow) <= max_slow_updates,
+        "a {SLOW_INTERVAL:?} interval sends at most {max_slow_updates} updates a second, got {}",
+        updates(slow)
+    );
+    assert!(
+        updates(every_tick) > 2 * max_slow_updates,
+        "a zero interval sends every tick, got {}",
+        updates(every_tick)
+    );
+}
@@ -19,3 +19,31 @@ pub struct CancelButton;
 /// Marker for the map switch toggle button in in-game HUD
 #[derive(Component)]
 pub struct MapSwitchButton;
+
+/// Root of the quit confirmation overlay
+#[derive(Component)]
+pub struct QuitConfirmDialog;
+
+/// Marker for the Yes button in the quit confirmation overlay
+#[derive(Component)]
+pub struct QuitConfirmYesButton;
+
+/// Marker for the No button in the quit confirmation overlay
+#[derive(Component)]
+pub struct QuitConfirmNoButton;
+
+/// Marker for the failure reason text on the connection failed screen
+#[derive(Component)]
+pub struct ConnectionFailureText;
+
+/// Marker for the Reconnect button on the main menu and connection failed screen
+#[derive(Component)]
+pub struct ReconnectButton;
+
+/// Marker for the elapsed/timeout text on the connecting screen
+#[derive(Component)]
+pub struct ConnectingTimerText;
+
+/// Text showing the remaining cooldown of ability slot `.0` in the in-game HUD
+#[derive(Component)]
+pub struct AbilityCooldownLabel(pub usize);
@@ -0,0 +1,109 @@
+use bevy::prelude::*;
+
+use crate::components::{QuitConfirmDialog, QuitConfirmNoButton, QuitConfirmYesButton};
+
+const FOCUSED_BORDER: Color = Color::srgb(1.0, 0.85, 0.2);
+const UNFOCUSED_BORDER: Color = Color::WHITE;
+
+/// Button currently focused by keyboard/gamepad navigation, if any.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MenuFocus(pub Option<Entity>);
+
+/// Position of a button in its screen's keyboard/gamepad navigation order,
+/// lowest first. Buttons without one are skipped by navigation.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FocusOrder(pub u32);
+
+/// Navigation intent for one frame, merged from keyboard and every gamepad.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+struct NavInput {
+    up: bool,
+    down: bool,
+    activate: bool,
+}
+
+fn read_nav_input(keys: &ButtonInput<KeyCode>, gamepads: &Query<&Gamepad>) -> NavInput {
+    let mut nav = NavInput {
+        up: keys.just_pressed(KeyCode::ArrowUp),
+        down: keys.just_pressed(KeyCode::ArrowDown),
+        activate: keys.just_pressed(KeyCode::Enter),
+    };
+    for gamepad in gamepads {
+        nav.up |= gamepad.just_pressed(GamepadButton::DPadUp);
+        nav.down |= gamepad.just_pressed(GamepadButton::DPadDown);
+        nav.activate |= gamepad.just_pressed(GamepadButton::South);
+    }
+    nav
+}
+
+/// Moves focus between the visible menu buttons and presses the focused one.
+/// While the quit dialog is open only its Yes/No buttons are focusable.
+/// Buttons are visited in [`FocusOrder`].
+pub(crate) fn navigate_menu_focus(
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads: Query<&Gamepad>,
+    buttons: Query<
+        (
+            Entity,
+            &FocusOrder,
+            Has<QuitConfirmYesButton>,
+            Has<QuitConfirmNoButton>,
+        ),
+        With<Button>,
+    >,
+    dialog_query: Query<(), With<QuitConfirmDialog>>,
+    mut focus: ResMut<MenuFocus>,
+    mut commands: Commands,
+) {
+    let nav = read_nav_input(&keys, &gamepads);
+    if nav == NavInput::default() {
+        return;
+    }
+
+    let dialog_open = !dialog_query.is_empty();
+    let mut ordered: Vec<(FocusOrder, Entity)> = buttons
+        .iter()
+        .filter(|(_, _, yes, no)| !dialog_open || *yes || *no)
+        .map(|(entity, order, _, _)| (*order, entity))
+        .collect();
+    ordered.sort();
+    let candidates: Vec<Entity> = ordered.into_iter().map(|(_, entity)| entity).collect();
+    if candidates.is_empty() {
+        trace!("Menu navigation input with no focusable buttons");
+        return;
+    }
+
+    let current = focus
+        .0
+        .and_then(|entity| candidates.iter().position(|c| *c == entity));
+    let next = match (current, nav.up, nav.down) {
+        (None, _, _) if nav.up || nav.down => Some(0),
+        (Some(i), true, false) => Some((i + candidates.len() - 1) % candidates.len()),
+        (Some(i), false, true) => Some((i + 1) % candidates.len()),
+        (i, _, _) => i,
+    };
+    focus.set_if_neq(MenuFocus(next.map(|i| candidates[i])));
+
+    if nav.activate {
+        let Some(target) = focus.0.filter(|entity| candidates.contains(entity)) else {
+            trace!("Activate pressed with nothing focused");
+            return;
+        };
+        commands.entity(target).insert(Interaction::Pressed);
+    }
+}
+
+/// Outlines the focused button and restores the default border on the rest.
+pub(crate) fn highlight_focused_button(
+    focus: Res<MenuFocus>,
+    mut buttons: Query<(Entity, &mut BorderColor), With<Button>>,
+) {
+    for (entity, mut border) in &mut buttons {
+        let color = if focus.0 == Some(entity) {
+            FOCUSED_BORDER
+        } else {
+            UNFOCUSED_BORDER
+        };
+        border.set_if_neq(BorderColor::all(color));
+    }
+}
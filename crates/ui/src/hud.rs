@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+
+/// Screen corner the in-game HUD is anchored to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HudAnchor {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl HudAnchor {
+    /// Flex alignment `(justify_content, align_items)` for a full-screen row container.
+    pub fn alignment(self) -> (JustifyContent, AlignItems) {
+        match self {
+            HudAnchor::TopLeft => (JustifyContent::Start, AlignItems::Start),
+            HudAnchor::TopRight => (JustifyContent::End, AlignItems::Start),
+            HudAnchor::BottomLeft => (JustifyContent::Start, AlignItems::End),
+            HudAnchor::BottomRight => (JustifyContent::End, AlignItems::End),
+        }
+    }
+}
+
+/// Player-adjustable HUD layout. Read when the in-game HUD is spawned, so
+/// changes apply the next time the player enters `ClientState::InGame`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct HudConfig {
+    /// Multiplier applied to every HUD pixel size and font size.
+    pub scale: f32,
+    pub anchor: HudAnchor,
+}
+
+impl Default for HudConfig {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            anchor: HudAnchor::default(),
+        }
+    }
+}
+
+impl HudConfig {
+    /// `Val::Px` of `px` scaled by the HUD scale.
+    pub fn px(&self, px: f32) -> Val {
+        Val::Px(px * self.scale)
+    }
+}
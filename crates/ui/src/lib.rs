@@ -1,18 +1,30 @@
 pub mod components;
+pub mod focus;
+pub mod hud;
 pub mod state;
+pub mod vignette;
 
 use bevy::ecs::message::MessageWriter;
 use bevy::prelude::*;
 pub use components::*;
+pub use focus::{FocusOrder, MenuFocus};
+pub use hud::{HudAnchor, HudConfig};
 use lightyear::netcode::Key;
 use lightyear::prelude::{client::*, Controlled, Replicated};
-use lightyear::prelude::{Authentication, MessageSender, Predicted};
+use lightyear::prelude::{
+    Authentication, LocalTimeline, MessageReceiver, MessageSender, Predicted,
+};
+use protocol::ability::extract_phases;
 use protocol::map::{MapChannel, MapSwitchTarget, PlayerMapSwitchRequest};
+use protocol::settings::Settings;
 use protocol::{
-    CharacterMarker, DummyTarget, MapInstanceId, PendingTransition, PRIVATE_KEY, PROTOCOL_ID,
+    ticks_to_secs, AbilityAsset, AbilityCooldowns, AbilityDefs, AbilitySlots, CharacterMarker,
+    DefaultAbilitySlots, DummyTarget, MapInstanceId, PendingTransition, ServerClosing, PRIVATE_KEY,
+    PROTOCOL_ID,
 };
 pub use state::{ClientState, MapTransitionState};
 use std::net::SocketAddr;
+pub use vignette::{LowHealthVignette, LowHealthVignetteConfig};
 
 /// Lightweight client config for UI - mirrors essential fields from client::ClientNetworkConfig
 /// This exists to avoid circular dependency between client and ui crates.
@@ -36,6 +48,36 @@ impl Default for UiClientConfig {
     }
 }
 
+/// Set when the player deliberately disconnects (Cancel / Main Menu) so the
+/// disconnect observer can tell it apart from a network failure. Consumed by
+/// the next `Disconnected`, and cleared whenever a new connection starts.
+#[derive(Resource, Default, Debug)]
+pub struct UserInitiatedDisconnect(pub bool);
+
+/// Why the last connection failed, shown on the `ConnectionFailed` screen.
+/// Cleared when a new connection starts; whoever detects the failure first
+/// (e.g. the connect timeout) sets it, and later generic causes don't overwrite it.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct ConnectionFailure {
+    pub reason: String,
+}
+
+/// How long the connecting screen waits for `Connected` before giving up.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct ConnectTimeout {
+    pub limit_secs: f32,
+}
+
+impl Default for ConnectTimeout {
+    fn default() -> Self {
+        Self { limit_secs: 15.0 }
+    }
+}
+
+/// Seconds spent in the current connection attempt.
+#[derive(Resource, Default, Debug)]
+pub struct ConnectingElapsed(pub f32);
+
 /// Plugin that manages UI and client state
 pub struct UiPlugin;
 
@@ -43,6 +85,32 @@ impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
         // Initialize resources
         app.init_resource::<UiClientConfig>();
+        app.init_resource::<HudConfig>();
+        app.init_resource::<LowHealthVignetteConfig>();
+        app.init_resource::<UserInitiatedDisconnect>();
+        app.init_resource::<ConnectionFailure>();
+        app.init_resource::<Settings>();
+        app.init_resource::<ConnectTimeout>();
+        app.init_resource::<ConnectingElapsed>();
+
+        // Keyboard/gamepad menu navigation. ButtonInput is normally provided by
+        // bevy's InputPlugin, which headless tests don't add.
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_resource::<MenuFocus>();
+        app.add_systems(
+            PreUpdate,
+            focus::navigate_menu_focus
+                .after(bevy::ui::UiSystems::Focus)
+                // In game only the quit dialog is navigable, so menu keys don't
+                // fight gameplay input.
+                .run_if(
+                    not(in_state(ClientState::InGame)).or(any_with_component::<QuitConfirmDialog>),
+                ),
+        );
+        app.add_systems(
+            Update,
+            focus::highlight_focused_button.run_if(resource_changed::<MenuFocus>),
+        );
 
         // Initialize state management
         app.init_state::<ClientState>();
@@ -66,6 +134,7 @@ impl Plugin for UiPlugin {
         );
         app.add_observer(on_client_disconnected);
         app.add_observer(on_client_connected);
+        app.add_systems(Update, receive_server_closing);
 
         // Main menu
         app.add_systems(OnEnter(ClientState::MainMenu), setup_main_menu);
@@ -73,12 +142,32 @@ impl Plugin for UiPlugin {
             Update,
             main_menu_button_interaction.run_if(in_state(ClientState::MainMenu)),
         );
+        app.add_systems(
+            Update,
+            reconnect_button_interaction.run_if(
+                in_state(ClientState::MainMenu).or(in_state(ClientState::ConnectionFailed)),
+            ),
+        );
+
+        // Quit confirmation overlay (main menu and in-game)
+        app.add_systems(Update, quit_confirm_interaction);
 
         // Connecting screen
         app.add_systems(OnEnter(ClientState::Connecting), setup_connecting_screen);
         app.add_systems(
             Update,
-            connecting_screen_interaction.run_if(in_state(ClientState::Connecting)),
+            (connecting_screen_interaction, tick_connect_timeout)
+                .run_if(in_state(ClientState::Connecting)),
+        );
+
+        // Connection failure screen
+        app.add_systems(
+            OnEnter(ClientState::ConnectionFailed),
+            setup_connection_failed_screen,
+        );
+        app.add_systems(
+            Update,
+            connection_failed_interaction.run_if(in_state(ClientState::ConnectionFailed)),
         );
 
         // In-game HUD
@@ -89,21 +178,49 @@ impl Plugin for UiPlugin {
                 ingame_button_interaction,
                 map_switch_button_interaction,
                 update_map_switch_button_label,
+                vignette::update_low_health_vignette,
             )
                 .run_if(in_state(ClientState::InGame)),
         );
+        app.add_systems(
+            Update,
+            update_cooldown_labels
+                .run_if(in_state(ClientState::InGame).and(resource_exists::<AbilityDefs>)),
+        );
 
         trace!("UiPlugin initialized");
     }
 }
 
+/// Starts a connection attempt with a fresh netcode token. Without exactly one
+/// `Client` entity to connect, e.g. before the network plugin has spawned it,
+/// the attempt is abandoned and the menu stays up.
 fn on_entering_connecting_state(
     mut commands: Commands,
     client_query: Query<Entity, With<Client>>,
     config: Res<UiClientConfig>,
+    mut user_disconnect: ResMut<UserInitiatedDisconnect>,
+    mut settings: ResMut<Settings>,
+    mut failure: ResMut<ConnectionFailure>,
+    mut elapsed: ResMut<ConnectingElapsed>,
+    mut next_state: ResMut<NextState<ClientState>>,
 ) {
+    let client_entity = match client_query.single() {
+        Ok(entity) => entity,
+        Err(err) => {
+            warn!("Cannot connect without a single Client entity: {err}");
+            next_state.set(ClientState::MainMenu);
+            return;
+        }
+    };
     trace!("Entering Connecting state, triggering connection...");
-    let client_entity = client_query.single().expect("Client entity should exist");
+    user_disconnect.0 = false;
+    failure.reason.clear();
+    elapsed.0 = 0.0;
+    // Only write on change: every write of `Settings` is persisted.
+    if settings.last_server != Some(config.server_addr) {
+        settings.last_server = Some(config.server_addr);
+    }
 
     // Create fresh authentication with new token
     let auth = Authentication::Manual {
@@ -123,15 +240,45 @@ fn on_entering_connecting_state(
     });
 }
 
+/// Sole router for disconnects: a user-initiated disconnect returns to the
+/// main menu, anything else while connecting or in-game is a failure.
 fn on_client_disconnected(
     _trigger: On<Add, Disconnected>,
     mut next_state: ResMut<NextState<ClientState>>,
     current_state: Res<State<ClientState>>,
+    mut user_disconnect: ResMut<UserInitiatedDisconnect>,
+    mut failure: ResMut<ConnectionFailure>,
+) {
+    let user_initiated = std::mem::take(&mut user_disconnect.0);
+    match current_state.get() {
+        ClientState::MainMenu | ClientState::ConnectionFailed => {
+            trace!("Disconnected while not connected, no transition needed");
+        }
+        ClientState::Connecting | ClientState::InGame if user_initiated => {
+            info!("Client disconnected by user, returning to main menu");
+            next_state.set(ClientState::MainMenu);
+        }
+        ClientState::Connecting | ClientState::InGame => {
+            warn!("Client lost connection to server");
+            if failure.reason.is_empty() {
+                failure.reason = "Lost connection to server".to_string();
+            }
+            next_state.set(ClientState::ConnectionFailed);
+        }
+    }
+}
+
+/// Records a [`ServerClosing`] notice as the failure reason, so the disconnect
+/// that follows shows why the server went away instead of a generic lost connection.
+fn receive_server_closing(
+    mut receivers: Query<&mut MessageReceiver<ServerClosing>>,
+    mut failure: ResMut<ConnectionFailure>,
 ) {
-    // Only transition if not already in MainMenu
-    if *current_state.get() != ClientState::MainMenu {
-        info!("Client disconnected, returning to main menu");
-        next_state.set(ClientState::MainMenu);
+    for mut receiver in &mut receivers {
+        for closing in receiver.receive() {
+            info!("Server closing: {}", closing.reason);
+            failure.reason = closing.reason;
+        }
     }
 }
 
@@ -143,7 +290,7 @@ fn on_client_connected(
     next_state.set(ClientState::InGame);
 }
 
-fn setup_main_menu(mut commands: Commands) {
+fn setup_main_menu(mut commands: Commands, settings: Res<Settings>) {
     trace!("Setting up main menu UI");
 
     commands
@@ -186,6 +333,7 @@ fn setup_main_menu(mut commands: Commands) {
                     BorderColor::all(Color::WHITE),
                     BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
                     ConnectButton,
+                    FocusOrder(0),
                 ))
                 .with_children(|parent| {
                     parent.spawn((
@@ -198,6 +346,10 @@ fn setup_main_menu(mut commands: Commands) {
                     ));
                 });
 
+            if let Some(addr) = settings.last_server {
+                spawn_reconnect_button(parent, addr, FocusOrder(1));
+            }
+
             // Quit Button
             parent
                 .spawn((
@@ -213,6 +365,7 @@ fn setup_main_menu(mut commands: Commands) {
                     BorderColor::all(Color::WHITE),
                     BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
                     QuitButton,
+                    FocusOrder(2),
                 ))
                 .with_children(|parent| {
                     parent.spawn((
@@ -228,8 +381,9 @@ fn setup_main_menu(mut commands: Commands) {
 }
 
 fn main_menu_button_interaction(
+    mut commands: Commands,
     mut next_state: ResMut<NextState<ClientState>>,
-    mut exit_writer: MessageWriter<AppExit>,
+    dialog_query: Query<(), With<QuitConfirmDialog>>,
     connect_query: Query<&Interaction, (Changed<Interaction>, With<ConnectButton>)>,
     quit_query: Query<&Interaction, (Changed<Interaction>, With<QuitButton>)>,
 ) {
@@ -244,13 +398,62 @@ fn main_menu_button_interaction(
     // Handle Quit button
     for interaction in quit_query.iter() {
         if *interaction == Interaction::Pressed {
-            trace!("Quit button pressed");
-            exit_writer.write(AppExit::Success);
+            trace!("Quit button pressed, asking for confirmation");
+            open_quit_confirm_dialog(&mut commands, &dialog_query, ClientState::MainMenu);
+        }
+    }
+}
+
+/// Spawns a Reconnect button labelled with the server it will connect to.
+fn spawn_reconnect_button(parent: &mut ChildSpawnerCommands, addr: SocketAddr, order: FocusOrder) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(320.0),
+                height: Val::Px(65.0),
+                border: UiRect::all(Val::Px(5.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor::all(Color::WHITE),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            ReconnectButton,
+            order,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(format!("Reconnect to {addr}")),
+                TextFont {
+                    font_size: 26.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn reconnect_button_interaction(
+    mut next_state: ResMut<NextState<ClientState>>,
+    mut config: ResMut<UiClientConfig>,
+    settings: Res<Settings>,
+    reconnect_query: Query<&Interaction, (Changed<Interaction>, With<ReconnectButton>)>,
+) {
+    for interaction in reconnect_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
         }
+        let addr = settings
+            .last_server
+            .expect("Reconnect button is only spawned when a last server is known");
+        trace!("Reconnect button pressed, connecting to {addr}");
+        config.server_addr = addr;
+        next_state.set(ClientState::Connecting);
     }
 }
 
-fn setup_connecting_screen(mut commands: Commands) {
+fn setup_connecting_screen(mut commands: Commands, timeout: Res<ConnectTimeout>) {
     trace!("Setting up connecting screen UI");
 
     commands
@@ -278,6 +481,16 @@ fn setup_connecting_screen(mut commands: Commands) {
                 TextColor(Color::WHITE),
             ));
 
+            parent.spawn((
+                ConnectingTimerText,
+                Text::new(connecting_timer_label(0.0, timeout.limit_secs)),
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+
             // Cancel Button
             parent
                 .spawn((
@@ -293,6 +506,7 @@ fn setup_connecting_screen(mut commands: Commands) {
                     BorderColor::all(Color::WHITE),
                     BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
                     CancelButton,
+                    FocusOrder(0),
                 ))
                 .with_children(|parent| {
                     parent.spawn((
@@ -309,6 +523,7 @@ fn setup_connecting_screen(mut commands: Commands) {
 
 fn connecting_screen_interaction(
     mut commands: Commands,
+    mut user_disconnect: ResMut<UserInitiatedDisconnect>,
     mut next_state: ResMut<NextState<ClientState>>,
     client_query: Query<Entity, With<Client>>,
     cancel_query: Query<&Interaction, (Changed<Interaction>, With<CancelButton>)>,
@@ -316,126 +531,254 @@ fn connecting_screen_interaction(
     for interaction in cancel_query.iter() {
         if *interaction == Interaction::Pressed {
             trace!("Cancel button pressed, disconnecting...");
+            disconnect_by_user(
+                &mut commands,
+                &mut user_disconnect,
+                &mut next_state,
+                &client_query,
+            );
+        }
+    }
+}
 
-            let client_entity = client_query.single().expect("Client entity should exist");
-            // Trigger disconnection
+/// Disconnects at the player's request; `on_client_disconnected` then returns
+/// to the main menu. Without a single `Client` there is nothing to disconnect,
+/// so this goes straight to the main menu.
+fn disconnect_by_user(
+    commands: &mut Commands,
+    user_disconnect: &mut UserInitiatedDisconnect,
+    next_state: &mut NextState<ClientState>,
+    client_query: &Query<Entity, With<Client>>,
+) {
+    match client_query.single() {
+        Ok(client_entity) => {
+            user_disconnect.0 = true;
             commands.trigger(Disconnect {
                 entity: client_entity,
             });
-
-            // Return to main menu (observer will also handle this, but explicit is clearer)
+        }
+        Err(err) => {
+            warn!("No single Client entity to disconnect: {err}");
             next_state.set(ClientState::MainMenu);
         }
     }
 }
 
-fn setup_ingame_hud(mut commands: Commands) {
-    trace!("Setting up in-game HUD");
+fn connecting_timer_label(elapsed: f32, limit: f32) -> String {
+    format!("{:.0}s / {:.0}s", elapsed.floor(), limit)
+}
+
+/// Advances the attempt timer and abandons the attempt once it exceeds `ConnectTimeout`.
+fn tick_connect_timeout(
+    mut commands: Commands,
+    time: Res<Time>,
+    timeout: Res<ConnectTimeout>,
+    mut elapsed: ResMut<ConnectingElapsed>,
+    mut failure: ResMut<ConnectionFailure>,
+    mut next_state: ResMut<NextState<ClientState>>,
+    client_query: Query<Entity, With<Client>>,
+    mut timer_text: Query<&mut Text, With<ConnectingTimerText>>,
+) {
+    elapsed.0 += time.delta_secs();
+    for mut text in &mut timer_text {
+        text.0 = connecting_timer_label(elapsed.0, timeout.limit_secs);
+    }
+    if elapsed.0 < timeout.limit_secs {
+        return;
+    }
+
+    warn!("Connection attempt timed out after {}s", timeout.limit_secs);
+    failure.reason = format!("Connection timed out after {:.0}s", timeout.limit_secs);
+    match client_query.single() {
+        Ok(client_entity) => {
+            commands.trigger(Disconnect {
+                entity: client_entity,
+            });
+        }
+        Err(err) => warn!("No single Client entity to disconnect: {err}"),
+    }
+    next_state.set(ClientState::ConnectionFailed);
+}
+
+fn setup_connection_failed_screen(
+    mut commands: Commands,
+    failure: Res<ConnectionFailure>,
+    settings: Res<Settings>,
+) {
+    trace!("Setting up connection failed screen UI");
 
-    // Top-right corner HUD
     commands
         .spawn((
             Node {
                 width: Val::Percent(100.0),
                 height: Val::Percent(100.0),
-                justify_content: JustifyContent::End,
-                align_items: AlignItems::Start,
-                padding: UiRect::all(Val::Px(20.0)),
-                flex_direction: FlexDirection::Row,
-                column_gap: Val::Px(10.0),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
                 ..default()
             },
-            DespawnOnExit(ClientState::InGame),
+            BackgroundColor(Color::srgb(0.1, 0.1, 0.1)),
+            DespawnOnExit(ClientState::ConnectionFailed),
         ))
         .with_children(|parent| {
-            // Map Switch Button
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(150.0),
-                        height: Val::Px(50.0),
-                        border: UiRect::all(Val::Px(3.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    BorderColor::all(Color::WHITE),
-                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
-                    MapSwitchButton,
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Homebase"),
-                        TextFont {
-                            font_size: 24.0,
-                            ..default()
-                        },
-                        TextColor(Color::WHITE),
-                    ));
-                });
+            parent.spawn((
+                Text::new("Connection failed"),
+                TextFont {
+                    font_size: 48.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.4, 0.4)),
+            ));
+
+            parent.spawn((
+                ConnectionFailureText,
+                Text::new(failure.reason.clone()),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+
+            if let Some(addr) = settings.last_server {
+                spawn_reconnect_button(parent, addr, FocusOrder(0));
+            }
 
-            // Main Menu Button
             parent
                 .spawn((
                     Button,
                     Node {
-                        width: Val::Px(150.0),
-                        height: Val::Px(50.0),
-                        border: UiRect::all(Val::Px(3.0)),
+                        width: Val::Px(200.0),
+                        height: Val::Px(65.0),
+                        border: UiRect::all(Val::Px(5.0)),
                         justify_content: JustifyContent::Center,
                         align_items: AlignItems::Center,
                         ..default()
                     },
                     BorderColor::all(Color::WHITE),
-                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+                    BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
                     MainMenuButton,
+                    FocusOrder(1),
                 ))
                 .with_children(|parent| {
                     parent.spawn((
                         Text::new("Main Menu"),
                         TextFont {
-                            font_size: 24.0,
+                            font_size: 33.0,
                             ..default()
                         },
                         TextColor(Color::WHITE),
                     ));
                 });
+        });
+}
 
-            // Quit Button
-            parent
-                .spawn((
-                    Button,
-                    Node {
-                        width: Val::Px(150.0),
-                        height: Val::Px(50.0),
-                        border: UiRect::all(Val::Px(3.0)),
-                        justify_content: JustifyContent::Center,
-                        align_items: AlignItems::Center,
+fn connection_failed_interaction(
+    mut next_state: ResMut<NextState<ClientState>>,
+    main_menu_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuButton>)>,
+) {
+    for interaction in main_menu_query.iter() {
+        if *interaction == Interaction::Pressed {
+            trace!("Main Menu button pressed on failure screen");
+            next_state.set(ClientState::MainMenu);
+        }
+    }
+}
+
+fn setup_ingame_hud(mut commands: Commands, hud: Res<HudConfig>) {
+    trace!("Setting up in-game HUD");
+
+    let (justify_content, align_items) = hud.anchor.alignment();
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content,
+                align_items,
+                padding: UiRect::all(hud.px(20.0)),
+                flex_direction: FlexDirection::Row,
+                column_gap: hud.px(10.0),
+                ..default()
+            },
+            DespawnOnExit(ClientState::InGame),
+        ))
+        .with_children(|parent| {
+            spawn_hud_button(parent, &hud, MapSwitchButton, "Homebase");
+            spawn_hud_button(parent, &hud, MainMenuButton, "Main Menu");
+            spawn_hud_button(parent, &hud, QuitButton, "Quit");
+        });
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::End,
+                padding: UiRect::all(hud.px(20.0)),
+                flex_direction: FlexDirection::Row,
+                column_gap: hud.px(16.0),
+                ..default()
+            },
+            DespawnOnExit(ClientState::InGame),
+        ))
+        .with_children(|parent| {
+            for slot in 0..AbilityCooldowns::default().last_used.len() {
+                parent.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 20.0 * hud.scale,
                         ..default()
                     },
-                    BorderColor::all(Color::WHITE),
-                    BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
-                    QuitButton,
-                ))
-                .with_children(|parent| {
-                    parent.spawn((
-                        Text::new("Quit"),
-                        TextFont {
-                            font_size: 24.0,
-                            ..default()
-                        },
-                        TextColor(Color::WHITE),
-                    ));
-                });
+                    TextColor(Color::WHITE),
+                    AbilityCooldownLabel(slot),
+                ));
+            }
+        });
+}
+
+/// Spawns a labelled in-game HUD button sized by `hud.scale`.
+fn spawn_hud_button(
+    parent: &mut ChildSpawnerCommands,
+    hud: &HudConfig,
+    marker: impl Component,
+    label: &str,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: hud.px(150.0),
+                height: hud.px(50.0),
+                border: UiRect::all(hud.px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor::all(Color::WHITE),
+            BackgroundColor(Color::srgba(0.2, 0.2, 0.2, 0.8)),
+            marker,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 24.0 * hud.scale,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
         });
 }
 
 fn ingame_button_interaction(
     mut commands: Commands,
+    mut user_disconnect: ResMut<UserInitiatedDisconnect>,
     mut next_state: ResMut<NextState<ClientState>>,
-    mut exit_writer: MessageWriter<AppExit>,
     client_query: Query<Entity, With<Client>>,
+    dialog_query: Query<(), With<QuitConfirmDialog>>,
     main_menu_query: Query<&Interaction, (Changed<Interaction>, With<MainMenuButton>)>,
     quit_query: Query<
         &Interaction,
@@ -450,23 +793,126 @@ fn ingame_button_interaction(
     for interaction in main_menu_query.iter() {
         if *interaction == Interaction::Pressed {
             trace!("Main Menu button pressed, disconnecting...");
-
-            let client_entity = client_query.single().expect("Client entity should exist");
-            // Trigger disconnection
-            commands.trigger(Disconnect {
-                entity: client_entity,
-            });
-
-            // Return to main menu (observer will also handle this)
-            next_state.set(ClientState::MainMenu);
+            disconnect_by_user(
+                &mut commands,
+                &mut user_disconnect,
+                &mut next_state,
+                &client_query,
+            );
         }
     }
 
     // Handle Quit button
     for interaction in quit_query.iter() {
         if *interaction == Interaction::Pressed {
-            trace!("Quit button pressed");
-            exit_writer.write(AppExit::Success);
+            trace!("Quit button pressed, asking for confirmation");
+            open_quit_confirm_dialog(&mut commands, &dialog_query, ClientState::InGame);
+        }
+    }
+}
+
+/// Spawns the Yes/No quit confirmation overlay unless one is already open.
+/// The overlay is scoped to `state` so it never outlives the screen that opened it.
+fn open_quit_confirm_dialog(
+    commands: &mut Commands,
+    dialog_query: &Query<(), With<QuitConfirmDialog>>,
+    state: ClientState,
+) {
+    if !dialog_query.is_empty() {
+        trace!("Quit confirmation dialog already open");
+        return;
+    }
+
+    commands
+        .spawn((
+            Node {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                position_type: PositionType::Absolute,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(20.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.7)),
+            GlobalZIndex(50),
+            QuitConfirmDialog,
+            DespawnOnExit(state),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new("Quit the game?"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Row,
+                    column_gap: Val::Px(20.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_dialog_button(parent, QuitConfirmYesButton, "Yes", FocusOrder(0));
+                    spawn_dialog_button(parent, QuitConfirmNoButton, "No", FocusOrder(1));
+                });
+        });
+}
+
+fn spawn_dialog_button(
+    parent: &mut ChildSpawnerCommands,
+    marker: impl Component,
+    label: &str,
+    order: FocusOrder,
+) {
+    parent
+        .spawn((
+            Button,
+            Node {
+                width: Val::Px(150.0),
+                height: Val::Px(55.0),
+                border: UiRect::all(Val::Px(3.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            BorderColor::all(Color::WHITE),
+            BackgroundColor(Color::srgb(0.2, 0.2, 0.2)),
+            marker,
+            order,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text::new(label),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+fn quit_confirm_interaction(
+    mut commands: Commands,
+    mut exit_writer: MessageWriter<AppExit>,
+    dialog_query: Query<Entity, With<QuitConfirmDialog>>,
+    yes_query: Query<&Interaction, (Changed<Interaction>, With<QuitConfirmYesButton>)>,
+    no_query: Query<&Interaction, (Changed<Interaction>, With<QuitConfirmNoButton>)>,
+) {
+    if yes_query.iter().any(|i| *i == Interaction::Pressed) {
+        trace!("Quit confirmed");
+        exit_writer.write(AppExit::Success);
+        return;
+    }
+
+    if no_query.iter().any(|i| *i == Interaction::Pressed) {
+        trace!("Quit cancelled");
+        for dialog in &dialog_query {
+            commands.entity(dialog).despawn();
         }
     }
 }
@@ -542,6 +988,52 @@ fn update_map_switch_button_label(
     }
 }
 
+/// HUD text for one ability slot: its key number and the remaining cooldown,
+/// or `ready`.
+fn cooldown_label(slot: usize, remaining_ticks: u16) -> String {
+    if remaining_ticks == 0 {
+        format!("{}: ready", slot + 1)
+    } else {
+        format!("{}: {:.1}s", slot + 1, ticks_to_secs(remaining_ticks))
+    }
+}
+
+/// Shows each slot's remaining cooldown from the local player's predicted
+/// `AbilityCooldowns`, which rollback corrects to the server's value. Empty
+/// slots show nothing.
+fn update_cooldown_labels(
+    timeline: Res<LocalTimeline>,
+    ability_defs: Res<AbilityDefs>,
+    ability_assets: Res<Assets<AbilityAsset>>,
+    default_slots: Res<DefaultAbilitySlots>,
+    player_query: Query<
+        (&AbilityCooldowns, Option<&AbilitySlots>),
+        (With<Predicted>, With<CharacterMarker>, With<Controlled>),
+    >,
+    mut label_query: Query<(&AbilityCooldownLabel, &mut Text)>,
+) {
+    let Ok((cooldowns, slots)) = player_query.single() else {
+        trace!("update_cooldown_labels: no local player yet");
+        return;
+    };
+    let slots = slots.unwrap_or(&default_slots.0);
+    let tick = timeline.tick();
+    for (label, mut text) in &mut label_query {
+        let cooldown_ticks = slots.0[label.0]
+            .as_ref()
+            .and_then(|id| ability_assets.get(ability_defs.get(id)?))
+            .and_then(extract_phases)
+            .map(|phases| phases.cooldown);
+        text.0 = match cooldown_ticks {
+            Some(cooldown_ticks) => cooldown_label(
+                label.0,
+                cooldowns.remaining_ticks(label.0, tick, cooldown_ticks),
+            ),
+            None => String::new(),
+        };
+    }
+}
+
 /// Marker for the loading screen text entity.
 #[derive(Component)]
 struct LoadingScreenText;
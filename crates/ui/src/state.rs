@@ -10,6 +10,8 @@ pub enum ClientState {
     Connecting,
     /// Connected and in-game
     InGame,
+    /// Connection attempt or session ended by a failure rather than the player
+    ConnectionFailed,
 }
 
 /// Sub-state for map transition flow while in-game
@@ -0,0 +1,100 @@
+use bevy::prelude::*;
+use lightyear::prelude::{Controlled, Predicted};
+use protocol::{CharacterMarker, Health, Minion};
+
+use crate::hud::HudConfig;
+use crate::state::ClientState;
+
+/// When and how strongly the screen edges redden as the local player's health
+/// runs low.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LowHealthVignetteConfig {
+    /// Health ratio below which the vignette appears.
+    pub threshold: f32,
+    /// Vignette alpha at zero health; it fades linearly to nothing at `threshold`.
+    pub max_alpha: f32,
+    /// Border thickness in HUD pixels, scaled by [`HudConfig::scale`].
+    pub width_px: f32,
+}
+
+impl Default for LowHealthVignetteConfig {
+    fn default() -> Self {
+        Self {
+            threshold: 0.3,
+            max_alpha: 0.6,
+            width_px: 48.0,
+        }
+    }
+}
+
+impl LowHealthVignetteConfig {
+    /// Vignette alpha for a health ratio, or `None` at or above the threshold.
+    pub fn alpha(&self, health_ratio: f32) -> Option<f32> {
+        if health_ratio >= self.threshold {
+            return None;
+        }
+        let intensity = 1.0 - health_ratio.max(0.0) / self.threshold;
+        Some(self.max_alpha * intensity)
+    }
+}
+
+/// Full-screen red border shown while the local player's health is low.
+#[derive(Component)]
+pub struct LowHealthVignette;
+
+fn vignette_color(alpha: f32) -> Color {
+    Color::srgba(0.8, 0.0, 0.0, alpha)
+}
+
+/// Spawns, recolors or despawns the [`LowHealthVignette`] to match the local
+/// player's predicted `Health`.
+pub(crate) fn update_low_health_vignette(
+    mut commands: Commands,
+    config: Res<LowHealthVignetteConfig>,
+    hud: Res<HudConfig>,
+    player_query: Query<
+        &Health,
+        (
+            With<Predicted>,
+            With<CharacterMarker>,
+            With<Controlled>,
+            Without<Minion>,
+        ),
+    >,
+    mut vignette_query: Query<(Entity, &mut BorderColor), With<LowHealthVignette>>,
+) {
+    let alpha = match player_query.single() {
+        Ok(health) if health.max > 0.0 => config.alpha(health.current / health.max),
+        Ok(_) => None,
+        Err(_) => {
+            trace!("update_low_health_vignette: no local player yet");
+            None
+        }
+    };
+    let existing = vignette_query.single_mut().ok();
+
+    match (alpha, existing) {
+        (Some(alpha), Some((_, mut border))) => {
+            *border = BorderColor::all(vignette_color(alpha));
+        }
+        (Some(alpha), None) => {
+            commands.spawn((
+                LowHealthVignette,
+                Node {
+                    position_type: PositionType::Absolute,
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    border: UiRect::all(hud.px(config.width_px)),
+                    ..default()
+                },
+                BorderColor::all(vignette_color(alpha)),
+                GlobalZIndex(50),
+                DespawnOnExit(ClientState::InGame),
+            ));
+        }
+        (None, Some((entity, _))) => {
+            commands.entity(entity).despawn();
+        }
+        (None, None) => {}
+    }
+}
@@ -1,7 +1,8 @@
 use bevy::prelude::*;
+use bevy::reflect::PartialReflect;
 use bevy::state::app::StatesPlugin;
 use lightyear::prelude::client::*;
-use lightyear::prelude::{Controlled, Predicted};
+use lightyear::prelude::{Controlled, LocalTimeline, Predicted, Tick};
 use protocol::transition::ClientTransitionState;
 use protocol::*;
 use std::time::Duration;
@@ -125,7 +126,7 @@ fn test_ingame_state_spawns_hud() {
 }
 
 #[test]
-fn test_disconnection_returns_to_main_menu() {
+fn test_network_disconnection_shows_failure_screen() {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
     app.add_plugins(StatesPlugin);
@@ -160,7 +161,27 @@ fn test_disconnection_returns_to_main_menu() {
         .insert(Disconnected::default());
     app.update();
 
-    // Verify returned to MainMenu
+    // A disconnect the player didn't ask for is a failure
+    let state = app.world().resource::<State<ClientState>>();
+    assert_eq!(*state.get(), ClientState::ConnectionFailed);
+}
+
+#[test]
+fn entering_connecting_without_client_stays_in_main_menu() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.update();
+
+    // No Client entity has been spawned.
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::Connecting);
+    app.update();
+    app.update();
+
     let state = app.world().resource::<State<ClientState>>();
     assert_eq!(*state.get(), ClientState::MainMenu);
 }
@@ -342,3 +363,439 @@ fn test_state_cleanup() {
         "Connecting UI should exist"
     );
 }
+
+fn ingame_quit_button_size(hud: HudConfig) -> (Val, Val) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.insert_resource(hud);
+
+    app.world_mut()
+        .spawn((Name::new("Test Client"), Client::default()));
+
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::InGame);
+    app.update();
+
+    let node = app
+        .world_mut()
+        .query_filtered::<&Node, With<QuitButton>>()
+        .single(app.world())
+        .expect("In-game Quit button should exist");
+    (node.width, node.height)
+}
+
+#[test]
+fn hud_scale_resizes_ingame_buttons_proportionally() {
+    let (base_width, base_height) = ingame_quit_button_size(HudConfig::default());
+    let (scaled_width, scaled_height) = ingame_quit_button_size(HudConfig {
+        scale: 2.0,
+        ..default()
+    });
+
+    let (Val::Px(base_width), Val::Px(base_height)) = (base_width, base_height) else {
+        panic!("HUD buttons should be sized in pixels");
+    };
+    assert_eq!(scaled_width, Val::Px(base_width * 2.0));
+    assert_eq!(scaled_height, Val::Px(base_height * 2.0));
+}
+
+fn press<M: Component>(app: &mut App) {
+    let button = app
+        .world_mut()
+        .query_filtered::<Entity, With<M>>()
+        .single(app.world())
+        .expect("button should exist");
+    app.world_mut()
+        .entity_mut(button)
+        .insert(Interaction::Pressed);
+    app.update();
+}
+
+#[test]
+fn quit_opens_confirmation_and_only_yes_exits() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.update();
+
+    press::<QuitButton>(&mut app);
+    let mut dialog_query = app
+        .world_mut()
+        .query_filtered::<Entity, With<QuitConfirmDialog>>();
+    assert_eq!(
+        dialog_query.iter(app.world()).count(),
+        1,
+        "Quit should open the confirmation dialog"
+    );
+    assert!(app.should_exit().is_none(), "Quit alone must not exit");
+
+    press::<QuitConfirmNoButton>(&mut app);
+    assert_eq!(
+        dialog_query.iter(app.world()).count(),
+        0,
+        "No should close the dialog"
+    );
+    assert!(app.should_exit().is_none(), "No must not exit");
+
+    press::<QuitButton>(&mut app);
+    press::<QuitConfirmYesButton>(&mut app);
+    assert_eq!(app.should_exit(), Some(AppExit::Success));
+}
+
+fn connecting_app() -> (App, Entity) {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+
+    let client = app
+        .world_mut()
+        .spawn((Name::new("Test Client"), Client::default()))
+        .id();
+    app.update();
+
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::Connecting);
+    app.update();
+    (app, client)
+}
+
+#[test]
+fn user_cancel_disconnect_returns_to_main_menu() {
+    let (mut app, client) = connecting_app();
+
+    press::<CancelButton>(&mut app);
+    assert!(app.world().resource::<UserInitiatedDisconnect>().0);
+
+    // The transport reports the disconnect the Cancel button requested
+    app.world_mut()
+        .entity_mut(client)
+        .insert(Disconnected::default());
+    app.update();
+
+    let state = app.world().resource::<State<ClientState>>();
+    assert_eq!(*state.get(), ClientState::MainMenu);
+    assert!(!app.world().resource::<UserInitiatedDisconnect>().0);
+}
+
+#[test]
+fn unexpected_disconnect_while_connecting_shows_failure_screen() {
+    let (mut app, client) = connecting_app();
+
+    app.world_mut()
+        .entity_mut(client)
+        .insert(Disconnected::default());
+    app.update();
+
+    let state = app.world().resource::<State<ClientState>>();
+    assert_eq!(*state.get(), ClientState::ConnectionFailed);
+    let mut reason_query = app
+        .world_mut()
+        .query_filtered::<&Text, With<ConnectionFailureText>>();
+    assert_eq!(
+        reason_query.iter(app.world()).count(),
+        1,
+        "Failure screen should show a reason"
+    );
+}
+
+#[test]
+fn reconnect_after_disconnect_reuses_last_server() {
+    let (mut app, client) = connecting_app();
+    let attempted = app.world().resource::<UiClientConfig>().server_addr;
+    assert_eq!(
+        app.world()
+            .resource::<protocol::settings::Settings>()
+            .last_server,
+        Some(attempted)
+    );
+
+    app.world_mut()
+        .entity_mut(client)
+        .insert(Disconnected::default());
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<ClientState>>().get(),
+        ClientState::ConnectionFailed
+    );
+
+    app.world_mut().resource_mut::<UiClientConfig>().server_addr = "10.0.0.1:6000".parse().unwrap();
+    press::<ReconnectButton>(&mut app);
+    app.update();
+
+    assert_eq!(
+        *app.world().resource::<State<ClientState>>().get(),
+        ClientState::Connecting
+    );
+    assert_eq!(
+        app.world().resource::<UiClientConfig>().server_addr,
+        attempted,
+        "Reconnect should target the stored address"
+    );
+}
+
+#[test]
+fn connecting_past_timeout_shows_failure_screen() {
+    let (mut app, _client) = connecting_app();
+    app.insert_resource(ConnectTimeout { limit_secs: 3.0 });
+    app.insert_resource(bevy::time::TimeUpdateStrategy::ManualDuration(
+        Duration::from_secs(1),
+    ));
+
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<ClientState>>().get(),
+        ClientState::Connecting,
+        "Should still be connecting before the timeout"
+    );
+
+    for _ in 0..5 {
+        app.update();
+    }
+
+    assert_eq!(
+        *app.world().resource::<State<ClientState>>().get(),
+        ClientState::ConnectionFailed
+    );
+    assert!(app
+        .world()
+        .resource::<ConnectionFailure>()
+        .reason
+        .contains("timed out"));
+}
+
+fn tap_key(app: &mut App, key: KeyCode) {
+    app.world_mut()
+        .resource_mut::<ButtonInput<KeyCode>>()
+        .press(key);
+    app.update();
+    let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+    keys.release(key);
+    keys.clear();
+}
+
+fn single_entity<M: Component>(app: &mut App) -> Entity {
+    app.world_mut()
+        .query_filtered::<Entity, With<M>>()
+        .single(app.world())
+        .expect("exactly one matching entity")
+}
+
+#[test]
+fn keyboard_navigation_moves_focus_and_activates_focused_button() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.update();
+
+    let connect = single_entity::<ConnectButton>(&mut app);
+    let quit = single_entity::<QuitButton>(&mut app);
+
+    tap_key(&mut app, KeyCode::ArrowDown);
+    assert_eq!(app.world().resource::<MenuFocus>().0, Some(connect));
+    tap_key(&mut app, KeyCode::ArrowDown);
+    assert_eq!(app.world().resource::<MenuFocus>().0, Some(quit));
+    tap_key(&mut app, KeyCode::ArrowUp);
+    tap_key(&mut app, KeyCode::ArrowUp);
+    assert_eq!(
+        app.world().resource::<MenuFocus>().0,
+        Some(quit),
+        "Focus should wrap around"
+    );
+
+    tap_key(&mut app, KeyCode::Enter);
+    single_entity::<QuitConfirmDialog>(&mut app);
+    assert!(app.should_exit().is_none());
+
+    // Only the dialog's buttons are focusable while it is open
+    tap_key(&mut app, KeyCode::ArrowDown);
+    let yes = single_entity::<QuitConfirmYesButton>(&mut app);
+    assert_eq!(app.world().resource::<MenuFocus>().0, Some(yes));
+    tap_key(&mut app, KeyCode::Enter);
+    assert_eq!(app.should_exit(), Some(AppExit::Success));
+}
+
+#[test]
+fn gamepad_drives_the_in_game_quit_dialog() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.world_mut()
+        .spawn((Name::new("Test Client"), Client::default()));
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::InGame);
+    app.update();
+    let gamepad = app.world_mut().spawn(Gamepad::default()).id();
+
+    let tap_button = |app: &mut App, button: GamepadButton| {
+        app.world_mut()
+            .get_mut::<Gamepad>(gamepad)
+            .unwrap()
+            .digital_mut()
+            .press(button);
+        app.update();
+        let mut pad = app.world_mut().get_mut::<Gamepad>(gamepad).unwrap();
+        pad.digital_mut().release(button);
+        pad.digital_mut().clear();
+    };
+
+    tap_button(&mut app, GamepadButton::DPadDown);
+    assert_eq!(
+        app.world().resource::<MenuFocus>().0,
+        None,
+        "HUD buttons are not navigable during play"
+    );
+
+    press::<QuitButton>(&mut app);
+    let yes = single_entity::<QuitConfirmYesButton>(&mut app);
+    let no = single_entity::<QuitConfirmNoButton>(&mut app);
+    tap_button(&mut app, GamepadButton::DPadDown);
+    assert_eq!(app.world().resource::<MenuFocus>().0, Some(yes));
+    tap_button(&mut app, GamepadButton::DPadDown);
+    assert_eq!(app.world().resource::<MenuFocus>().0, Some(no));
+    tap_button(&mut app, GamepadButton::DPadUp);
+    assert_eq!(
+        app.world().resource::<MenuFocus>().0,
+        Some(yes),
+        "buttons follow their FocusOrder"
+    );
+    tap_button(&mut app, GamepadButton::South);
+    assert_eq!(app.should_exit(), Some(AppExit::Success));
+}
+
+fn cooldown_label_text(app: &mut App, slot: usize) -> String {
+    app.world_mut()
+        .query::<(&AbilityCooldownLabel, &Text)>()
+        .iter(app.world())
+        .find(|(label, _)| label.0 == slot)
+        .map(|(_, text)| text.0.clone())
+        .expect("HUD should have a label for every slot")
+}
+
+#[test]
+fn cooldown_labels_show_predicted_player_cooldowns() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.add_plugins(AssetPlugin::default());
+    app.init_asset::<AbilityAsset>();
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+
+    let handle = app
+        .world_mut()
+        .resource_mut::<Assets<AbilityAsset>>()
+        .add(AbilityAsset {
+            components: vec![Box::new(AbilityPhases {
+                startup: 0,
+                active: 1,
+                recovery: 0,
+                cooldown: 64,
+            })
+            .into_partial_reflect()],
+        });
+    app.insert_resource(AbilityDefs {
+        abilities: [(AbilityId("punch".into()), handle)].into(),
+    });
+    app.insert_resource(DefaultAbilitySlots(AbilitySlots([
+        Some(AbilityId("punch".into())),
+        Some(AbilityId("punch".into())),
+        None,
+        None,
+        None,
+    ])));
+    let mut timeline = LocalTimeline::default();
+    timeline.apply_delta(100);
+    app.insert_resource(timeline);
+
+    app.world_mut()
+        .spawn((Name::new("Test Client"), Client::default()));
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::InGame);
+    app.update();
+
+    let mut cooldowns = AbilityCooldowns::default();
+    cooldowns.last_used[0] = Some(Tick(67));
+    app.world_mut()
+        .spawn((CharacterMarker, Predicted, Controlled, cooldowns));
+    app.update();
+
+    assert_eq!(cooldown_label_text(&mut app, 0), "1: 0.5s");
+    assert_eq!(cooldown_label_text(&mut app, 1), "2: ready");
+    assert_eq!(
+        cooldown_label_text(&mut app, 2),
+        "",
+        "empty slots show nothing"
+    );
+}
+
+fn vignette_alpha(app: &mut App) -> Option<f32> {
+    app.world_mut()
+        .query_filtered::<&BorderColor, With<LowHealthVignette>>()
+        .iter(app.world())
+        .next()
+        .map(|border| border.top.alpha())
+}
+
+#[test]
+fn low_health_vignette_intensifies_as_health_drops() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins);
+    app.add_plugins(StatesPlugin);
+    app.init_resource::<ClientTransitionState>();
+    app.add_plugins(UiPlugin);
+    app.insert_resource(LowHealthVignetteConfig {
+        threshold: 0.5,
+        max_alpha: 0.8,
+        ..default()
+    });
+
+    app.world_mut()
+        .spawn((Name::new("Test Client"), Client::default()));
+    app.world_mut()
+        .resource_mut::<NextState<ClientState>>()
+        .set(ClientState::InGame);
+    app.update();
+
+    let player = app
+        .world_mut()
+        .spawn((CharacterMarker, Predicted, Controlled, Health::new(100.0)))
+        .id();
+    let alpha_at = |app: &mut App, current: f32| {
+        app.world_mut().get_mut::<Health>(player).unwrap().current = current;
+        app.update();
+        app.update();
+        vignette_alpha(app)
+    };
+
+    assert_eq!(alpha_at(&mut app, 80.0), None, "absent above the threshold");
+    let quarter = alpha_at(&mut app, 25.0).expect("shown below the threshold");
+    assert!(
+        (quarter - 0.4).abs() < 1e-5,
+        "half intensity, got {quarter}"
+    );
+    let tenth = alpha_at(&mut app, 10.0).expect("shown below the threshold");
+    assert!(
+        (tenth - 0.64).abs() < 1e-5,
+        "lower health is stronger, got {tenth}"
+    );
+    assert_eq!(
+        alpha_at(&mut app, 60.0),
+        None,
+        "removed once health recovers"
+    );
+}